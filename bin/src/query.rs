@@ -2,12 +2,12 @@ use {
     crate::prompt::print_json_pretty,
     anyhow::ensure,
     clap::{Parser, Subcommand},
-    grug_jmt::Proof,
     grug_sdk::Client,
-    grug_types::{Addr, Binary, Hash},
+    grug_types::{Addr, Binary, Hash, TxStateDiff},
+    prost::Message,
     serde::Serialize,
     serde_json::Value,
-    std::{fs::File, io::Write, path::PathBuf},
+    std::{collections::BTreeMap, fs::File, io::Write, path::PathBuf},
 };
 
 #[derive(Parser)]
@@ -56,6 +56,18 @@ enum SubCmd {
         /// Maximum number of items to display
         limit: Option<u32>,
     },
+    /// Query a token's metadata
+    Metadata {
+        /// Token denomination
+        denom: String,
+    },
+    /// Enumerate all tokens' metadata
+    Metadatas {
+        /// Start after this token denomination
+        start_after: Option<String>,
+        /// Maximum number of items to display
+        limit: Option<u32>,
+    },
     /// Query a Wasm binary code by hash
     Code { hash: Hash },
     /// Enumerate hashes of all Wasm byte codes
@@ -84,6 +96,20 @@ enum SubCmd {
         /// The raw key in hex encoding
         key_hex: String,
     },
+    /// Enumerate raw key-value pairs in a contract store
+    WasmScan {
+        /// Contract address
+        contract: Addr,
+        /// Lower bound (inclusive) of the raw keys in hex encoding
+        #[arg(long)]
+        min_hex: Option<String>,
+        /// Upper bound (exclusive) of the raw keys in hex encoding
+        #[arg(long)]
+        max_hex: Option<String>,
+        /// Maximum number of items to display
+        #[arg(long)]
+        limit: Option<u32>,
+    },
     /// Call a contract's query entry point
     WasmSmart {
         /// Contract address
@@ -104,6 +130,11 @@ enum SubCmd {
         /// Transaction hash
         hash: String,
     },
+    /// Get the state changes made by a transaction, if the node records them
+    StateDiff {
+        /// Transaction hash
+        hash: String,
+    },
     /// Get block by height
     Block {
         /// Block height [default: latest]
@@ -143,6 +174,16 @@ impl QueryCmd {
                     .await?;
                 print_json_pretty(res)
             },
+            SubCmd::Metadata { denom } => {
+                let res = client.query_metadata(denom, self.height).await?;
+                print_json_pretty(res)
+            },
+            SubCmd::Metadatas { start_after, limit } => {
+                let res = client
+                    .query_metadatas(start_after, limit, self.height)
+                    .await?;
+                print_json_pretty(res)
+            },
             SubCmd::Code { hash } => {
                 // we will be writing the wasm byte code to $(pwd)/${hash}.wasm
                 // first check if the file already exists. throw if it does
@@ -181,6 +222,24 @@ impl QueryCmd {
                 let res = client.query_wasm_raw(contract, key, self.height).await?;
                 print_json_pretty(res)
             },
+            SubCmd::WasmScan {
+                contract,
+                min_hex,
+                max_hex,
+                limit,
+            } => {
+                let min = min_hex.map(hex::decode).transpose()?.map(Binary::from);
+                let max = max_hex.map(hex::decode).transpose()?.map(Binary::from);
+                let res = client
+                    .query_wasm_scan(contract, min, max, limit, self.height)
+                    .await?;
+                // print keys and values in hex, same as they're inputted
+                let res = res
+                    .into_iter()
+                    .map(|(k, v)| (hex::encode(k), hex::encode(v)))
+                    .collect::<BTreeMap<_, _>>();
+                print_json_pretty(res)
+            },
             SubCmd::WasmSmart { contract, msg } => {
                 // the input should be a JSON string, e.g. `{"config":{}}`
                 let msg: Value = serde_json::from_str(&msg)?;
@@ -194,7 +253,7 @@ impl QueryCmd {
                 struct PrintableQueryStoreResponse {
                     key: String,
                     value: Option<String>,
-                    proof: Option<Proof>,
+                    proof: Option<String>,
                 }
 
                 let key = hex::decode(&key_hex)?;
@@ -203,13 +262,29 @@ impl QueryCmd {
                 print_json_pretty(PrintableQueryStoreResponse {
                     key: key_hex,
                     value: value.map(hex::encode),
-                    proof,
+                    proof: proof.map(|proof| hex::encode(proof.encode_to_vec())),
                 })
             },
             SubCmd::Tx { hash } => {
                 let res = client.tx(&hash).await?;
                 print_json_pretty(res)
             },
+            SubCmd::StateDiff { hash } => {
+                let res = client.tx(&hash).await?;
+                // the node puts the JSON-encoded state diff in the `info` field
+                // of the tx result, if it's started with `--state-diffs`
+                ensure!(
+                    !res.tx_result.info.is_empty(),
+                    "state diff not found; the node may not be recording state diffs"
+                );
+                let diff: TxStateDiff = serde_json::from_str(&res.tx_result.info)?;
+                if diff.is_empty() {
+                    println!("No state changes");
+                } else {
+                    print!("{diff}");
+                }
+                Ok(())
+            },
             SubCmd::Block { height } => {
                 let res = client.block_result(height).await?;
                 print_json_pretty(res)
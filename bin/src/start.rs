@@ -9,13 +9,17 @@ pub struct StartCmd {
     /// Buffer size for reading chunks of incoming data from client
     #[arg(long, default_value = "1048576")]
     read_buf_size: usize,
+
+    /// Record the state changes made by each transaction [default: false]
+    #[arg(long, default_value_t = false)]
+    state_diffs: bool,
 }
 
 impl StartCmd {
     pub async fn run(self, data_dir: PathBuf) -> anyhow::Result<()> {
         let db = DiskDb::open(data_dir)?;
         let vm = WasmVm::new();
-        let app = App::new(db, vm);
+        let app = App::new(db, vm).with_state_diffs(self.state_diffs);
 
         Ok(app.start_abci_server(self.read_buf_size, self.abci_addr)?)
     }
@@ -1,7 +1,7 @@
 use {
     crate::prompt::{confirm, print_json_pretty, read_password},
     anyhow::anyhow,
-    clap::{Parser, Subcommand},
+    clap::{Parser, Subcommand, ValueEnum},
     colored::Colorize,
     grug_sdk::{Client, SigningKey, SigningOptions},
     grug_types::{from_json_slice, Addr, Binary, Coins, Config, Hash, Message},
@@ -32,10 +32,28 @@ pub struct TxCmd {
     #[arg(long)]
     sequence: Option<u32>,
 
+    /// Simulate the transaction instead of broadcasting it, and print a trace
+    /// of the contract calls it makes
+    #[arg(long)]
+    trace: bool,
+
+    /// Format in which to print the trace
+    #[arg(long, value_enum, default_value_t = TraceFormat::Json, requires = "trace")]
+    trace_format: TraceFormat,
+
     #[command(subcommand)]
     subcmd: SubCmd,
 }
 
+#[derive(Clone, Copy, ValueEnum)]
+enum TraceFormat {
+    /// The transaction's result and its call tree as JSON
+    Json,
+    /// Folded stacks, which can be turned into a flamegraph by tools such as
+    /// `flamegraph.pl` or `inferno-flamegraph`
+    Folded,
+}
+
 #[derive(Subcommand)]
 enum SubCmd {
     /// Update the chain-level configurations
@@ -156,8 +174,20 @@ impl TxCmd {
             sequence: self.sequence,
         };
 
-        // broadcast transaction
         let client = Client::connect(&self.node)?;
+
+        // simulate the transaction and print the trace, if requested
+        if self.trace {
+            let tx = client.create_and_sign_tx(vec![msg], &sign_opts).await?;
+            let res = client.simulate(&tx).await?;
+            match self.trace_format {
+                TraceFormat::Json => print_json_pretty(res)?,
+                TraceFormat::Folded => print!("{}", res.trace.to_folded()),
+            }
+            return Ok(());
+        }
+
+        // broadcast transaction
         let maybe_res = client
             .send_tx_with_confirmation(vec![msg], &sign_opts, |tx| {
                 print_json_pretty(tx)?;
@@ -1,12 +1,24 @@
 use {
-    crate::{BALANCES_BY_ADDR, BALANCES_BY_DENOM, SUPPLIES},
-    grug::{Addr, Coins, MutableCtx, Number, Response, StdResult, Storage, Uint128},
-    std::collections::HashMap,
+    crate::{
+        Allowance, DenomInfo, ALLOWANCES, BALANCES_BY_ADDR, BALANCES_BY_DENOM, DENOMS, METADATAS,
+        NAMESPACES, SUPPLIES,
+    },
+    anyhow::{anyhow, bail, ensure},
+    grug::{
+        Addr, Coins, DenomMetadata, MutableCtx, Number, QuerierWrapper, Response, StdError,
+        StdResult, Storage, Timestamp, Uint128,
+    },
+    std::collections::{BTreeMap, HashMap},
 };
 
+/// The namespace of denoms created with `ExecuteMsg::CreateDenom`, of the form
+/// `factory/{creator}/{subdenom}`. It can't be assigned to anyone.
+pub const FACTORY_NAMESPACE: &str = "factory";
+
 pub fn initialize(
     storage: &mut dyn Storage,
     initial_balances: impl IntoIterator<Item = (Addr, Coins)>,
+    namespaces: BTreeMap<String, Addr>,
 ) -> StdResult<Response> {
     // Need to make sure there are no duplicate address in initial balances.
     // We don't need to dedup denoms however. If there's duplicate denoms, the
@@ -25,6 +37,11 @@ pub fn initialize(
         SUPPLIES.save(storage, &denom, &amount)?;
     }
 
+    for (namespace, owner) in namespaces {
+        validate_namespace(&namespace)?;
+        NAMESPACES.save(storage, &namespace, &owner)?;
+    }
+
     Ok(Response::new())
 }
 
@@ -45,14 +62,141 @@ fn accumulate_supply(
     Ok(())
 }
 
-/// Mint tokens of specified denom and amount to an account.
+fn validate_namespace(namespace: &str) -> StdResult<()> {
+    if namespace.is_empty() || namespace.contains('/') || namespace == FACTORY_NAMESPACE {
+        return Err(StdError::generic_err(format!(
+            "invalid namespace `{namespace}`: must be non-empty, not contain `/`, and not be \
+             `{FACTORY_NAMESPACE}`"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Create a new denom `factory/{sender}/{subdenom}`.
 ///
-/// NOTE: This demo contract doesn't implement any gatekeeping for minting,
-/// meaning _any_ account can mint _any_ token of _any_ amount.
+/// The subdenom may consist of alphanumeric characters, `.`, `-`, `_`, and `/`,
+/// so that it can't be confused with the delimiters of coins' string format.
+pub fn create_denom(
+    ctx: MutableCtx,
+    subdenom: String,
+    admin: Option<Addr>,
+    max_supply: Option<Uint128>,
+) -> anyhow::Result<Response> {
+    ensure!(
+        !subdenom.is_empty()
+            && subdenom
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '/')),
+        "invalid subdenom `{subdenom}`"
+    );
+
+    let denom = format!("{FACTORY_NAMESPACE}/{}/{subdenom}", ctx.sender);
+    ensure!(
+        !DENOMS.has(ctx.storage, &denom),
+        "denom `{denom}` already exists"
+    );
+
+    let admin = admin.unwrap_or_else(|| ctx.sender.clone());
+    DENOMS.save(ctx.storage, &denom, &DenomInfo {
+        admin: Some(admin.clone()),
+        max_supply,
+    })?;
+
+    let mut res = Response::new()
+        .add_attribute("method", "create_denom")
+        .add_attribute("creator", ctx.sender)
+        .add_attribute("denom", denom)
+        .add_attribute("admin", admin);
+    if let Some(max_supply) = max_supply {
+        res = res.add_attribute("max_supply", max_supply);
+    }
+
+    Ok(res)
+}
+
+pub fn change_admin(
+    ctx: MutableCtx,
+    denom: String,
+    new_admin: Option<Addr>,
+) -> anyhow::Result<Response> {
+    let mut info = DENOMS
+        .may_load(ctx.storage, &denom)?
+        .ok_or_else(|| anyhow!("denom `{denom}` wasn't created with the token factory"))?;
+
+    ensure!(
+        info.admin.as_ref() == Some(&ctx.sender),
+        "sender `{}` isn't the admin of denom `{denom}`",
+        ctx.sender
+    );
+
+    info.admin = new_admin;
+    DENOMS.save(ctx.storage, &denom, &info)?;
+
+    let mut res = Response::new()
+        .add_attribute("method", "change_admin")
+        .add_attribute("denom", denom);
+    if let Some(new_admin) = info.admin {
+        res = res.add_attribute("new_admin", new_admin);
+    }
+
+    Ok(res)
+}
+
+pub fn set_namespace(
+    ctx: MutableCtx,
+    namespace: String,
+    owner: Option<Addr>,
+) -> anyhow::Result<Response> {
+    ensure!(
+        ctx.querier.query_info()?.config.owner.as_ref() == Some(&ctx.sender),
+        "only the chain's owner can assign namespaces"
+    );
+
+    validate_namespace(&namespace)?;
+
+    let mut res = Response::new()
+        .add_attribute("method", "set_namespace")
+        .add_attribute("namespace", &namespace);
+    if let Some(owner) = owner {
+        NAMESPACES.save(ctx.storage, &namespace, &owner)?;
+        res = res.add_attribute("owner", owner);
+    } else {
+        NAMESPACES.remove(ctx.storage, &namespace);
+    }
+
+    Ok(res)
+}
+
+pub fn set_metadata(
+    ctx: MutableCtx,
+    denom: String,
+    metadata: DenomMetadata,
+) -> anyhow::Result<Response> {
+    assert_minter(ctx.storage, ctx.querier, &ctx.sender, &denom)?;
+
+    METADATAS.save(ctx.storage, &denom, &metadata)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_metadata")
+        .add_attribute("denom", denom))
+}
+
+/// Mint tokens of specified denom and amount to an account.
 ///
-/// Apparently, this is not intended for using in production.
-pub fn mint(ctx: MutableCtx, to: Addr, denom: String, amount: Uint128) -> StdResult<Response> {
-    increase_supply(ctx.storage, &denom, amount)?;
+/// Only the account returned by `minter` can mint a denom. Tokens created with
+/// the token factory can't be minted beyond their supply caps.
+pub fn mint(ctx: MutableCtx, to: Addr, denom: String, amount: Uint128) -> anyhow::Result<Response> {
+    let info = assert_minter(ctx.storage, ctx.querier, &ctx.sender, &denom)?;
+
+    let supply = increase_supply(ctx.storage, &denom, amount)?.unwrap_or_default();
+    if let Some(max_supply) = info.and_then(|info| info.max_supply) {
+        ensure!(
+            supply <= max_supply,
+            "minting would exceed the max supply of denom `{denom}`: {supply} > {max_supply}"
+        );
+    }
+
     increase_balance(ctx.storage, &to, &denom, amount)?;
 
     Ok(Response::new()
@@ -64,11 +208,23 @@ pub fn mint(ctx: MutableCtx, to: Addr, denom: String, amount: Uint128) -> StdRes
 
 /// Burn tokens of specified denom and amount from an account.
 ///
-/// NOTE: This demo contract doesn't implement any gatekeeping for burning,
-/// meaning _any_ account can mint _any_ token of _any_ amount.
-///
-/// Apparently, this is not intended for using in production.
-pub fn burn(ctx: MutableCtx, from: Addr, denom: String, amount: Uint128) -> StdResult<Response> {
+/// Only the account returned by `minter` can burn a denom, and only from its
+/// own account. To burn someone else's tokens, they must be transferred to the
+/// minter first, e.g. sent along with a message, as ICS-20 does.
+pub fn burn(
+    ctx: MutableCtx,
+    from: Addr,
+    denom: String,
+    amount: Uint128,
+) -> anyhow::Result<Response> {
+    assert_minter(ctx.storage, ctx.querier, &ctx.sender, &denom)?;
+
+    ensure!(
+        from == ctx.sender,
+        "tokens can only be burned from the sender's own account: sender {}, from {from}",
+        ctx.sender
+    );
+
     decrease_supply(ctx.storage, &denom, amount)?;
     decrease_balance(ctx.storage, &from, &denom, amount)?;
 
@@ -79,6 +235,48 @@ pub fn burn(ctx: MutableCtx, from: Addr, denom: String, amount: Uint128) -> StdR
         .add_attribute("amount", amount))
 }
 
+/// Return the account that can mint and burn the given denom, if any:
+///
+/// - for `factory/{creator}/{subdenom}`, the denom's admin;
+/// - for `{namespace}/...`, where the namespace has been assigned, its owner,
+///   e.g. the ICS-20 contract for `ibc/{hash}`;
+/// - otherwise, i.e. for native denoms, the chain's owner (governance).
+///
+/// For factory denoms, the denom's info is returned as well.
+pub fn minter(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    denom: &str,
+) -> anyhow::Result<(Option<Addr>, Option<DenomInfo>)> {
+    match denom.split_once('/') {
+        Some((FACTORY_NAMESPACE, _)) => {
+            let info = DENOMS
+                .may_load(storage, denom)?
+                .ok_or_else(|| anyhow!("denom `{denom}` doesn't exist"))?;
+            Ok((info.admin.clone(), Some(info)))
+        },
+        Some((namespace, _)) if NAMESPACES.has(storage, namespace) => {
+            Ok((Some(NAMESPACES.load(storage, namespace)?), None))
+        },
+        _ => Ok((querier.query_info()?.config.owner, None)),
+    }
+}
+
+fn assert_minter(
+    storage: &dyn Storage,
+    querier: QuerierWrapper,
+    sender: &Addr,
+    denom: &str,
+) -> anyhow::Result<Option<DenomInfo>> {
+    let (minter, info) = minter(storage, querier, denom)?;
+
+    if minter.as_ref() != Some(sender) {
+        bail!("sender `{sender}` isn't allowed to mint or burn denom `{denom}`");
+    }
+
+    Ok(info)
+}
+
 /// Transfer tokens from one account to another.
 pub fn transfer(
     storage: &mut dyn Storage,
@@ -98,6 +296,108 @@ pub fn transfer(
         .add_attribute("coins", coins.to_string()))
 }
 
+pub fn approve(
+    ctx: MutableCtx,
+    spender: Addr,
+    denom: String,
+    amount: Uint128,
+    expires: Option<Timestamp>,
+) -> anyhow::Result<Response> {
+    ensure!(ctx.sender != spender, "can't approve oneself as spender");
+
+    if let Some(expires) = expires {
+        ensure!(
+            expires > ctx.block.timestamp,
+            "expiration must be in the future"
+        );
+    }
+
+    let key = (ctx.sender.clone(), spender.clone(), denom.clone());
+    if amount.is_zero() {
+        ALLOWANCES.remove(ctx.storage, key)?;
+    } else {
+        ALLOWANCES.save(ctx.storage, key, &Allowance { amount, expires })?;
+    }
+
+    let mut res = Response::new()
+        .add_attribute("method", "approve")
+        .add_attribute("owner", ctx.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("denom", denom)
+        .add_attribute("amount", amount);
+    if let Some(expires) = expires {
+        res = res.add_attribute("expires", expires.nanos());
+    }
+
+    Ok(res)
+}
+
+pub fn revoke_allowance(ctx: MutableCtx, spender: Addr, denom: String) -> anyhow::Result<Response> {
+    let key = (ctx.sender.clone(), spender.clone(), denom.clone());
+    ensure!(
+        ALLOWANCES.has(ctx.storage, key.clone()),
+        "no allowance to revoke"
+    );
+
+    ALLOWANCES.remove(ctx.storage, key)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "revoke_allowance")
+        .add_attribute("owner", ctx.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("denom", denom))
+}
+
+/// Transfer coins out of the owner's account on behalf of the owner, spending
+/// the allowances given to the sender.
+///
+/// The transfer emits the same attributes as a regular transfer, plus the
+/// spender.
+pub fn transfer_from(
+    ctx: MutableCtx,
+    owner: Addr,
+    to: Addr,
+    coins: Coins,
+) -> anyhow::Result<Response> {
+    for coin in &coins {
+        let key = (owner.clone(), ctx.sender.clone(), coin.denom.clone());
+        let mut allowance = ALLOWANCES
+            .may_load(ctx.storage, key.clone())?
+            .ok_or_else(|| {
+                anyhow!(
+                    "sender `{}` has no allowance of denom `{}` from `{owner}`",
+                    ctx.sender,
+                    coin.denom
+                )
+            })?;
+
+        if let Some(expires) = allowance.expires {
+            ensure!(
+                ctx.block.timestamp < expires,
+                "allowance of denom `{}` from `{owner}` has expired",
+                coin.denom
+            );
+        }
+
+        allowance.amount = allowance.amount.checked_sub(*coin.amount).map_err(|_| {
+            anyhow!(
+                "insufficient allowance of denom `{}` from `{owner}`: {} < {}",
+                coin.denom,
+                allowance.amount,
+                coin.amount
+            )
+        })?;
+
+        if allowance.amount.is_zero() {
+            ALLOWANCES.remove(ctx.storage, key)?;
+        } else {
+            ALLOWANCES.save(ctx.storage, key, &allowance)?;
+        }
+    }
+
+    Ok(transfer(ctx.storage, &owner, &to, &coins)?.add_attribute("spender", ctx.sender))
+}
+
 /// Increase the total supply of a token by the given amount.
 /// Return the total supply value after the increase.
 fn increase_supply(
@@ -165,3 +465,440 @@ fn decrease_balance(
     BALANCES_BY_ADDR.update(storage, (address, denom), action)?;
     BALANCES_BY_DENOM.update(storage, (denom, address), action)
 }
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            query_allowance, query_allowances_by_owner, query_allowances_by_spender, query_balance,
+        },
+        grug::{
+            BlockInfo, Config, Hash, InfoResponse, MockApi, MockCaller, MockStorage, Permission,
+            Permissions, Querier, QueryRequest, QueryResponse, Timestamp, Uint64,
+        },
+        std::collections::BTreeSet,
+    };
+
+    const OWNER: Addr = Addr::mock(1);
+
+    const ICS20: Addr = Addr::mock(2);
+
+    const ALICE: Addr = Addr::mock(3);
+
+    const BOB: Addr = Addr::mock(4);
+
+    /// A querier that only answers `QueryRequest::Info`, with `OWNER` as the
+    /// chain's owner.
+    struct InfoQuerier;
+
+    impl Querier for InfoQuerier {
+        fn query_chain(&self, req: QueryRequest) -> StdResult<QueryResponse> {
+            let QueryRequest::Info {} = req else {
+                return Err(StdError::generic_err("unexpected query"));
+            };
+
+            Ok(QueryResponse::Info(InfoResponse {
+                chain_id: "dev-1".to_string(),
+                config: Config {
+                    owner: Some(OWNER),
+                    bank: Addr::mock(0),
+                    begin_blockers: vec![],
+                    end_blockers: vec![],
+                    permissions: Permissions {
+                        upload: Permission::Everybody,
+                        instantiate: Permission::Everybody,
+                        create_client: Permission::Everybody,
+                        create_connection: Permission::Everybody,
+                        create_channel: Permission::Everybody,
+                    },
+                    allowed_clients: BTreeSet::new(),
+                },
+                last_finalized_block: BlockInfo {
+                    height: Uint64::new(1),
+                    timestamp: Timestamp::from_seconds(1),
+                    hash: Hash::ZERO,
+                },
+            }))
+        }
+    }
+
+    fn ctx<'a>(storage: &'a mut dyn Storage, sender: &Addr) -> MutableCtx<'a> {
+        MutableCtx {
+            storage,
+            api: &MockApi,
+            querier: QuerierWrapper::new(&InfoQuerier),
+            caller: &MockCaller,
+            chain_id: "dev-1".to_string(),
+            block: BlockInfo {
+                height: Uint64::new(2),
+                timestamp: Timestamp::from_seconds(2),
+                hash: Hash::ZERO,
+            },
+            contract: Addr::mock(0),
+            sender: sender.clone(),
+            funds: Coins::new_empty(),
+        }
+    }
+
+    fn setup() -> MockStorage {
+        let mut storage = MockStorage::new();
+        initialize(
+            &mut storage,
+            BTreeMap::new(),
+            BTreeMap::from([("ibc".to_string(), ICS20)]),
+        )
+        .unwrap();
+        storage
+    }
+
+    fn balance(storage: &MockStorage, address: &Addr, denom: &str) -> Uint128 {
+        query_balance(storage, address.clone(), denom.to_string())
+            .unwrap()
+            .amount
+    }
+
+    #[test]
+    fn factory_denoms_work() {
+        let mut storage = setup();
+        let denom = format!("factory/{ALICE}/foo");
+
+        create_denom(
+            ctx(&mut storage, &ALICE),
+            "foo".to_string(),
+            None,
+            Some(Uint128::new(100)),
+        )
+        .unwrap();
+
+        // the same denom can't be created twice
+        let err = create_denom(ctx(&mut storage, &ALICE), "foo".to_string(), None, None);
+        assert!(err.unwrap_err().to_string().contains("already exists"));
+
+        // only the admin can mint, and not beyond the supply cap
+        let err = mint(ctx(&mut storage, &BOB), BOB, denom.clone(), Uint128::new(1));
+        assert!(err.unwrap_err().to_string().contains("isn't allowed"));
+
+        mint(
+            ctx(&mut storage, &ALICE),
+            BOB,
+            denom.clone(),
+            Uint128::new(60),
+        )
+        .unwrap();
+        assert_eq!(balance(&storage, &BOB, &denom), Uint128::new(60));
+
+        let err = mint(
+            ctx(&mut storage, &ALICE),
+            BOB,
+            denom.clone(),
+            Uint128::new(41),
+        );
+        assert!(err.unwrap_err().to_string().contains("max supply"));
+
+        // after the admin role is handed over, only the new admin can burn
+        change_admin(ctx(&mut storage, &ALICE), denom.clone(), Some(BOB)).unwrap();
+
+        let err = burn(
+            ctx(&mut storage, &ALICE),
+            BOB,
+            denom.clone(),
+            Uint128::new(10),
+        );
+        assert!(err.unwrap_err().to_string().contains("isn't allowed"));
+
+        burn(
+            ctx(&mut storage, &BOB),
+            BOB,
+            denom.clone(),
+            Uint128::new(10),
+        )
+        .unwrap();
+        assert_eq!(balance(&storage, &BOB, &denom), Uint128::new(50));
+
+        // once the role is renounced, no one can mint
+        change_admin(ctx(&mut storage, &BOB), denom.clone(), None).unwrap();
+
+        let err = mint(ctx(&mut storage, &BOB), BOB, denom.clone(), Uint128::new(1));
+        assert!(err.unwrap_err().to_string().contains("isn't allowed"));
+
+        // denoms that weren't created can't be minted
+        let err = mint(
+            ctx(&mut storage, &BOB),
+            BOB,
+            format!("factory/{BOB}/foo"),
+            Uint128::new(1),
+        );
+        assert!(err.unwrap_err().to_string().contains("doesn't exist"));
+    }
+
+    #[test]
+    fn namespaces_and_native_denoms_work() {
+        let mut storage = setup();
+
+        // only the namespace's owner can mint denoms in it
+        mint(
+            ctx(&mut storage, &ICS20),
+            ALICE,
+            "ibc/ABCD".to_string(),
+            Uint128::new(5),
+        )
+        .unwrap();
+
+        let err = mint(
+            ctx(&mut storage, &ALICE),
+            ALICE,
+            "ibc/ABCD".to_string(),
+            Uint128::new(5),
+        );
+        assert!(err.unwrap_err().to_string().contains("isn't allowed"));
+
+        // native denoms, and those in namespaces that aren't assigned, belong
+        // to the chain's owner
+        for denom in ["ugrug", "gamm/pool/1"] {
+            let err = mint(
+                ctx(&mut storage, &ICS20),
+                ALICE,
+                denom.to_string(),
+                Uint128::new(5),
+            );
+            assert!(err.unwrap_err().to_string().contains("isn't allowed"));
+
+            mint(
+                ctx(&mut storage, &OWNER),
+                ALICE,
+                denom.to_string(),
+                Uint128::new(5),
+            )
+            .unwrap();
+            assert_eq!(balance(&storage, &ALICE, denom), Uint128::new(5));
+        }
+
+        // only the chain's owner can assign namespaces, and not the factory one
+        let err = set_namespace(ctx(&mut storage, &ALICE), "gamm".to_string(), Some(ALICE));
+        assert!(err
+            .unwrap_err()
+            .to_string()
+            .contains("only the chain's owner"));
+
+        let err = set_namespace(
+            ctx(&mut storage, &OWNER),
+            "factory".to_string(),
+            Some(ALICE),
+        );
+        assert!(err.unwrap_err().to_string().contains("invalid namespace"));
+
+        set_namespace(ctx(&mut storage, &OWNER), "gamm".to_string(), Some(ALICE)).unwrap();
+
+        let err = burn(
+            ctx(&mut storage, &OWNER),
+            ALICE,
+            "gamm/pool/1".to_string(),
+            Uint128::new(5),
+        );
+        assert!(err.unwrap_err().to_string().contains("isn't allowed"));
+
+        burn(
+            ctx(&mut storage, &ALICE),
+            ALICE,
+            "gamm/pool/1".to_string(),
+            Uint128::new(5),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn burning_only_works_from_own_account() {
+        let mut storage = setup();
+        let denom = "ibc/ABCD".to_string();
+
+        for to in [ALICE, ICS20] {
+            mint(
+                ctx(&mut storage, &ICS20),
+                to,
+                denom.clone(),
+                Uint128::new(5),
+            )
+            .unwrap();
+        }
+
+        // the minter can't burn tokens out of someone else's account
+        let err = burn(
+            ctx(&mut storage, &ICS20),
+            ALICE,
+            denom.clone(),
+            Uint128::new(5),
+        );
+        assert!(err.unwrap_err().to_string().contains("own account"));
+        assert_eq!(balance(&storage, &ALICE, &denom), Uint128::new(5));
+
+        burn(
+            ctx(&mut storage, &ICS20),
+            ICS20,
+            denom.clone(),
+            Uint128::new(5),
+        )
+        .unwrap();
+        assert_eq!(balance(&storage, &ICS20, &denom), Uint128::new(0));
+    }
+
+    #[test]
+    fn setting_metadata_works() {
+        let mut storage = setup();
+        let metadata = DenomMetadata {
+            name: "Grug".to_string(),
+            symbol: "GRUG".to_string(),
+            decimals: 6,
+        };
+
+        let err = set_metadata(
+            ctx(&mut storage, &ALICE),
+            "ugrug".to_string(),
+            metadata.clone(),
+        );
+        assert!(err.unwrap_err().to_string().contains("isn't allowed"));
+
+        set_metadata(
+            ctx(&mut storage, &OWNER),
+            "ugrug".to_string(),
+            metadata.clone(),
+        )
+        .unwrap();
+        assert_eq!(METADATAS.load(&storage, "ugrug").unwrap(), metadata);
+    }
+
+    #[test]
+    fn allowances_work() {
+        let mut storage = setup();
+        let carol = Addr::mock(5);
+        mint(
+            ctx(&mut storage, &OWNER),
+            ALICE,
+            "ugrug".to_string(),
+            Uint128::new(100),
+        )
+        .unwrap();
+        mint(
+            ctx(&mut storage, &OWNER),
+            ALICE,
+            "uatom".to_string(),
+            Uint128::new(100),
+        )
+        .unwrap();
+
+        approve(
+            ctx(&mut storage, &ALICE),
+            BOB,
+            "ugrug".to_string(),
+            Uint128::new(30),
+            None,
+        )
+        .unwrap();
+        approve(
+            ctx(&mut storage, &ALICE),
+            carol.clone(),
+            "uatom".to_string(),
+            Uint128::new(10),
+            Some(Timestamp::from_seconds(3)),
+        )
+        .unwrap();
+
+        // allowances can be enumerated both by owner and by spender
+        let by_owner = query_allowances_by_owner(&storage, ALICE, None, None).unwrap();
+        assert_eq!(
+            by_owner
+                .iter()
+                .map(|allowance| (&allowance.spender, allowance.denom.as_str()))
+                .collect::<Vec<_>>(),
+            [(&BOB, "ugrug"), (&carol, "uatom")]
+        );
+
+        let by_spender = query_allowances_by_spender(&storage, BOB, None, None).unwrap();
+        assert_eq!(by_spender.len(), 1);
+        assert_eq!(by_spender[0].owner, ALICE);
+        assert_eq!(by_spender[0].amount, Uint128::new(30));
+
+        // the spender can transfer up to the allowance, which is then reduced
+        let res = transfer_from(
+            ctx(&mut storage, &BOB),
+            ALICE,
+            carol.clone(),
+            Coins::new_one("ugrug", 20_u128),
+        )
+        .unwrap();
+        let attrs = res
+            .attributes
+            .iter()
+            .map(|attr| (attr.key.as_str(), attr.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(attrs[0], ("method", "send"));
+        assert!(attrs.contains(&("spender", BOB.to_string().as_str())));
+
+        assert_eq!(balance(&storage, &carol, "ugrug"), Uint128::new(20));
+        assert_eq!(
+            query_allowance(&storage, ALICE, BOB, "ugrug".to_string())
+                .unwrap()
+                .amount,
+            Uint128::new(10)
+        );
+
+        let err = transfer_from(
+            ctx(&mut storage, &BOB),
+            ALICE,
+            BOB,
+            Coins::new_one("ugrug", 11_u128),
+        );
+        assert!(err
+            .unwrap_err()
+            .to_string()
+            .contains("insufficient allowance"));
+
+        // allowances are per denom
+        let err = transfer_from(
+            ctx(&mut storage, &BOB),
+            ALICE,
+            BOB,
+            Coins::new_one("uatom", 1_u128),
+        );
+        assert!(err.unwrap_err().to_string().contains("no allowance"));
+
+        // spending the whole allowance removes it
+        transfer_from(
+            ctx(&mut storage, &BOB),
+            ALICE,
+            BOB,
+            Coins::new_one("ugrug", 10_u128),
+        )
+        .unwrap();
+        assert!(query_allowances_by_spender(&storage, BOB, None, None)
+            .unwrap()
+            .is_empty());
+
+        // expired allowances can't be used
+        let mut expired = ctx(&mut storage, &carol);
+        expired.block.timestamp = Timestamp::from_seconds(3);
+        let err = transfer_from(
+            expired,
+            ALICE,
+            carol.clone(),
+            Coins::new_one("uatom", 1_u128),
+        );
+        assert!(err.unwrap_err().to_string().contains("expired"));
+
+        // revoked allowances are removed from both indexes
+        revoke_allowance(
+            ctx(&mut storage, &ALICE),
+            carol.clone(),
+            "uatom".to_string(),
+        )
+        .unwrap();
+        assert!(query_allowances_by_owner(&storage, ALICE, None, None)
+            .unwrap()
+            .is_empty());
+        assert!(query_allowances_by_spender(&storage, carol, None, None)
+            .unwrap()
+            .is_empty());
+    }
+}
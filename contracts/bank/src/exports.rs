@@ -1,7 +1,10 @@
 use {
     crate::{
-        burn, initialize, mint, query_balance, query_balances, query_holders, query_supplies,
-        query_supply, transfer, ExecuteMsg, InstantiateMsg, QueryMsg,
+        approve, burn, change_admin, create_denom, initialize, mint, query_allowance,
+        query_allowances_by_owner, query_allowances_by_spender, query_balance, query_balances,
+        query_denom, query_denoms, query_holders, query_metadata, query_metadatas,
+        query_namespaces, query_supplies, query_supply, revoke_allowance, set_metadata,
+        set_namespace, transfer, transfer_from, ExecuteMsg, InstantiateMsg, QueryMsg,
     },
     anyhow::bail,
     grug::{
@@ -12,7 +15,7 @@ use {
 
 #[grug_export]
 pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> StdResult<Response> {
-    initialize(ctx.storage, msg.initial_balances)
+    initialize(ctx.storage, msg.initial_balances, msg.namespaces)
 }
 
 #[grug_export]
@@ -23,14 +26,30 @@ pub fn receive(_ctx: MutableCtx) -> anyhow::Result<Response> {
 }
 
 #[grug_export]
-pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
     match msg {
+        ExecuteMsg::CreateDenom {
+            subdenom,
+            admin,
+            max_supply,
+        } => create_denom(ctx, subdenom, admin, max_supply),
+        ExecuteMsg::ChangeAdmin { denom, new_admin } => change_admin(ctx, denom, new_admin),
+        ExecuteMsg::SetNamespace { namespace, owner } => set_namespace(ctx, namespace, owner),
+        ExecuteMsg::SetMetadata { denom, metadata } => set_metadata(ctx, denom, metadata),
         ExecuteMsg::Mint { to, denom, amount } => mint(ctx, to, denom, amount),
         ExecuteMsg::Burn {
             from,
             denom,
             amount,
         } => burn(ctx, from, denom, amount),
+        ExecuteMsg::Approve {
+            spender,
+            denom,
+            amount,
+            expires,
+        } => approve(ctx, spender, denom, amount, expires),
+        ExecuteMsg::RevokeAllowance { spender, denom } => revoke_allowance(ctx, spender, denom),
+        ExecuteMsg::TransferFrom { owner, to, coins } => transfer_from(ctx, owner, to, coins),
     }
 }
 
@@ -42,6 +61,38 @@ pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
             start_after,
             limit,
         } => to_json_value(&query_holders(ctx.storage, denom, start_after, limit)?),
+        QueryMsg::Denom { denom } => to_json_value(&query_denom(ctx.storage, denom)?),
+        QueryMsg::Denoms { start_after, limit } => {
+            to_json_value(&query_denoms(ctx.storage, start_after, limit)?)
+        },
+        QueryMsg::Allowance {
+            owner,
+            spender,
+            denom,
+        } => to_json_value(&query_allowance(ctx.storage, owner, spender, denom)?),
+        QueryMsg::AllowancesByOwner {
+            owner,
+            start_after,
+            limit,
+        } => to_json_value(&query_allowances_by_owner(
+            ctx.storage,
+            owner,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::AllowancesBySpender {
+            spender,
+            start_after,
+            limit,
+        } => to_json_value(&query_allowances_by_spender(
+            ctx.storage,
+            spender,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Namespaces { start_after, limit } => {
+            to_json_value(&query_namespaces(ctx.storage, start_after, limit)?)
+        },
     }
 }
 
@@ -66,5 +117,11 @@ pub fn bank_query(ctx: ImmutableCtx, msg: BankQuery) -> StdResult<BankQueryRespo
         BankQuery::Supplies { start_after, limit } => {
             query_supplies(ctx.storage, start_after, limit).map(BankQueryResponse::Supplies)
         },
+        BankQuery::Metadata { denom } => {
+            query_metadata(ctx.storage, denom).map(BankQueryResponse::Metadata)
+        },
+        BankQuery::Metadatas { start_after, limit } => {
+            query_metadatas(ctx.storage, start_after, limit).map(BankQueryResponse::Metadatas)
+        },
     }
 }
@@ -1,6 +1,11 @@
 use {
-    crate::{BALANCES_BY_ADDR, BALANCES_BY_DENOM, SUPPLIES},
-    grug::{Addr, Bound, Coin, Coins, NumberConst, Order, StdResult, Storage, Uint128},
+    crate::{
+        AllowanceResponse, DenomInfo, ALLOWANCES, BALANCES_BY_ADDR, BALANCES_BY_DENOM, DENOMS,
+        METADATAS, NAMESPACES, SUPPLIES,
+    },
+    grug::{
+        Addr, Bound, Coin, Coins, DenomMetadata, NumberConst, Order, StdResult, Storage, Uint128,
+    },
     std::collections::BTreeMap,
 };
 
@@ -68,3 +73,128 @@ pub fn query_holders(
         .take(limit as usize)
         .collect()
 }
+
+pub fn query_metadata(storage: &dyn Storage, denom: String) -> StdResult<Option<DenomMetadata>> {
+    METADATAS.may_load(storage, &denom)
+}
+
+pub fn query_metadatas(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<String, DenomMetadata>> {
+    let start = start_after
+        .as_ref()
+        .map(|denom| Bound::Exclusive(denom.as_str()));
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+    METADATAS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit as usize)
+        .collect()
+}
+
+pub fn query_denom(storage: &dyn Storage, denom: String) -> StdResult<DenomInfo> {
+    DENOMS.load(storage, &denom)
+}
+
+pub fn query_denoms(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<String, DenomInfo>> {
+    let start = start_after
+        .as_ref()
+        .map(|denom| Bound::Exclusive(denom.as_str()));
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+    DENOMS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit as usize)
+        .collect()
+}
+
+pub fn query_namespaces(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<String, Addr>> {
+    let start = start_after
+        .as_ref()
+        .map(|namespace| Bound::Exclusive(namespace.as_str()));
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+    NAMESPACES
+        .range(storage, start, None, Order::Ascending)
+        .take(limit as usize)
+        .collect()
+}
+
+pub fn query_allowance(
+    storage: &dyn Storage,
+    owner: Addr,
+    spender: Addr,
+    denom: String,
+) -> StdResult<AllowanceResponse> {
+    let allowance =
+        ALLOWANCES.may_load(storage, (owner.clone(), spender.clone(), denom.clone()))?;
+    Ok(AllowanceResponse {
+        owner,
+        spender,
+        denom,
+        amount: allowance
+            .as_ref()
+            .map(|allowance| allowance.amount)
+            .unwrap_or(Uint128::ZERO),
+        expires: allowance.and_then(|allowance| allowance.expires),
+    })
+}
+
+pub fn query_allowances_by_owner(
+    storage: &dyn Storage,
+    owner: Addr,
+    start_after: Option<(Addr, String)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<AllowanceResponse>> {
+    let start = start_after.map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+    ALLOWANCES
+        .prefix(owner.clone())
+        .range(storage, start, None, Order::Ascending)
+        .take(limit as usize)
+        .map(|res| {
+            let ((spender, denom), allowance) = res?;
+            Ok(AllowanceResponse {
+                owner: owner.clone(),
+                spender,
+                denom,
+                amount: allowance.amount,
+                expires: allowance.expires,
+            })
+        })
+        .collect()
+}
+
+pub fn query_allowances_by_spender(
+    storage: &dyn Storage,
+    spender: Addr,
+    start_after: Option<(Addr, String)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<AllowanceResponse>> {
+    let start = start_after.map(|(owner, denom)| Bound::Exclusive((owner, spender.clone(), denom)));
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+    ALLOWANCES
+        .idx
+        .spender
+        .prefix(spender)
+        .range(storage, start, None, Order::Ascending)
+        .take(limit as usize)
+        .map(|res| {
+            let ((owner, spender, denom), allowance) = res?;
+            Ok(AllowanceResponse {
+                owner,
+                spender,
+                denom,
+                amount: allowance.amount,
+                expires: allowance.expires,
+            })
+        })
+        .collect()
+}
@@ -1,4 +1,7 @@
-use grug::{Addr, Map, Uint128};
+use {
+    crate::{Allowance, DenomInfo},
+    grug::{Addr, DenomMetadata, Index, IndexList, IndexedMap, Map, MultiIndex, Uint128},
+};
 
 /// Total supplies of tokens, indexed by denoms.
 pub const SUPPLIES: Map<&str, Uint128> = Map::new("s");
@@ -8,3 +11,31 @@ pub const BALANCES_BY_ADDR: Map<(&Addr, &str), Uint128> = Map::new("bu");
 
 /// Token balances, indexed first by denoms, then by user addresses.
 pub const BALANCES_BY_DENOM: Map<(&str, &Addr), Uint128> = Map::new("bd");
+
+/// Denoms created with `ExecuteMsg::CreateDenom`, indexed by denoms.
+pub const DENOMS: Map<&str, DenomInfo> = Map::new("d");
+
+/// Owners of denom namespaces, indexed by namespaces.
+pub const NAMESPACES: Map<&str, Addr> = Map::new("n");
+
+/// Token metadata, indexed by denoms.
+pub const METADATAS: Map<&str, DenomMetadata> = Map::new("m");
+
+/// Allowances, indexed by owners, spenders, and denoms.
+pub const ALLOWANCES: IndexedMap<(Addr, Addr, String), Allowance, AllowanceIndexes> =
+    IndexedMap::new("a", AllowanceIndexes {
+        spender: MultiIndex::new(|(_, spender, _), _| spender.clone(), "a", "a__spender"),
+    });
+
+pub struct AllowanceIndexes<'a> {
+    pub spender: MultiIndex<'a, (Addr, Addr, String), Addr, Allowance>,
+}
+
+impl<'a> IndexList<(Addr, Addr, String), Allowance> for AllowanceIndexes<'a> {
+    fn get_indexes(
+        &self,
+    ) -> Box<dyn Iterator<Item = &'_ dyn Index<(Addr, Addr, String), Allowance>> + '_> {
+        let v: Vec<&dyn Index<(Addr, Addr, String), Allowance>> = vec![&self.spender];
+        Box::new(v.into_iter())
+    }
+}
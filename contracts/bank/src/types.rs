@@ -1,27 +1,101 @@
 use {
-    grug::{grug_derive, Addr, Coins, Uint128},
+    grug::{grug_derive, Addr, Coins, DenomMetadata, Timestamp, Uint128},
     std::collections::BTreeMap,
 };
 
 #[grug_derive(serde)]
 pub struct InstantiateMsg {
     pub initial_balances: BTreeMap<Addr, Coins>,
+    /// Denom namespaces to assign at genesis, e.g. `ibc` to the ICS-20
+    /// contract. See `ExecuteMsg::SetNamespace`.
+    pub namespaces: BTreeMap<String, Addr>,
+}
+
+/// A denom created with `ExecuteMsg::CreateDenom`.
+#[grug_derive(serde, borsh)]
+pub struct DenomInfo {
+    /// The account that can mint and burn the token, and change its metadata.
+    /// `None` if the admin has renounced the role, in which case the supply
+    /// can no longer change.
+    pub admin: Option<Addr>,
+    /// The maximum total supply, if capped.
+    pub max_supply: Option<Uint128>,
+}
+
+/// An amount of a denom that a spender is allowed to transfer out of an owner's
+/// account with `ExecuteMsg::TransferFrom`.
+#[grug_derive(serde, borsh)]
+pub struct Allowance {
+    pub amount: Uint128,
+    /// The allowance can no longer be used as of this time, if set.
+    pub expires: Option<Timestamp>,
+}
+
+#[grug_derive(serde)]
+pub struct AllowanceResponse {
+    pub owner: Addr,
+    pub spender: Addr,
+    pub denom: String,
+    pub amount: Uint128,
+    pub expires: Option<Timestamp>,
 }
 
 #[grug_derive(serde)]
 pub enum ExecuteMsg {
+    /// Create a new denom `factory/{sender}/{subdenom}`, with the sender as
+    /// its admin unless specified otherwise.
+    CreateDenom {
+        subdenom: String,
+        admin: Option<Addr>,
+        max_supply: Option<Uint128>,
+    },
+    /// Change the admin of a denom created with `CreateDenom`, or renounce the
+    /// role by setting none. Only callable by the current admin.
+    ChangeAdmin {
+        denom: String,
+        new_admin: Option<Addr>,
+    },
+    /// Assign a denom namespace, i.e. all denoms of the form `{namespace}/...`,
+    /// to an account, which can then mint and burn tokens of those denoms, or
+    /// revoke it by setting none. Only callable by the chain's owner.
+    SetNamespace {
+        namespace: String,
+        owner: Option<Addr>,
+    },
+    /// Set a denom's metadata. Only callable by whoever can mint the denom.
+    SetMetadata {
+        denom: String,
+        metadata: DenomMetadata,
+    },
     /// Mint a token of the specified amount to a user.
     Mint {
         to: Addr,
         denom: String,
         amount: Uint128,
     },
-    /// Burn a token of the specified amount from a user.
+    /// Burn a token of the specified amount from the sender's own account.
+    /// Only callable by whoever can mint the denom.
     Burn {
         from: Addr,
         denom: String,
         amount: Uint128,
     },
+    /// Allow the spender to transfer up to the given amount of a denom out of
+    /// the sender's account, replacing any existing allowance.
+    Approve {
+        spender: Addr,
+        denom: String,
+        amount: Uint128,
+        expires: Option<Timestamp>,
+    },
+    /// Remove the spender's allowance of a denom from the sender's account.
+    RevokeAllowance { spender: Addr, denom: String },
+    /// Transfer coins out of the owner's account, using the allowances that the
+    /// owner has given the sender.
+    ///
+    /// Unlike with `Message::Transfer`, the recipient's `receive` entry point
+    /// isn't called.
+    TransferFrom { owner: Addr, to: Addr, coins: Coins },
 }
 
 #[grug_derive(serde)]
@@ -33,4 +107,42 @@ pub enum QueryMsg {
         start_after: Option<Addr>,
         limit: Option<u32>,
     },
+    /// A denom created with `ExecuteMsg::CreateDenom`.
+    /// Returns: `DenomInfo`.
+    Denom { denom: String },
+    /// Enumerate all denoms created with `ExecuteMsg::CreateDenom`.
+    /// Returns: `BTreeMap<String, DenomInfo>`.
+    Denoms {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// The allowance that the owner has given the spender in a denom.
+    /// Returns: `AllowanceResponse`, with a zero amount if there's none.
+    Allowance {
+        owner: Addr,
+        spender: Addr,
+        denom: String,
+    },
+    /// Enumerate the allowances that an owner has given, ordered by spender
+    /// and denom.
+    /// Returns: `Vec<AllowanceResponse>`.
+    AllowancesByOwner {
+        owner: Addr,
+        start_after: Option<(Addr, String)>,
+        limit: Option<u32>,
+    },
+    /// Enumerate the allowances that a spender has been given, ordered by owner
+    /// and denom.
+    /// Returns: `Vec<AllowanceResponse>`.
+    AllowancesBySpender {
+        spender: Addr,
+        start_after: Option<(Addr, String)>,
+        limit: Option<u32>,
+    },
+    /// Enumerate all assigned denom namespaces and their owners.
+    /// Returns: `BTreeMap<String, Addr>`.
+    Namespaces {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
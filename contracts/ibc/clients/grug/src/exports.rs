@@ -0,0 +1,81 @@
+use {
+    crate::{verify_membership, verify_non_membership},
+    grug::{grug_export, IbcClientQuery, IbcClientQueryResponse, ImmutableCtx, Response, SudoCtx},
+    grug_ibc_client_tendermint::{
+        create_client, query_state, query_status, query_timestamp_at_height, submit_misbehavior,
+        update_client, ClientState, ConsensusState, Header, Misbehavior,
+    },
+};
+
+#[grug_export]
+pub fn ibc_client_create(
+    ctx: SudoCtx,
+    client_state: ClientState,
+    consensus_state: ConsensusState,
+) -> anyhow::Result<Response> {
+    create_client(ctx, client_state, consensus_state)
+}
+
+#[grug_export]
+pub fn ibc_client_update(ctx: SudoCtx, header: Header) -> anyhow::Result<Response> {
+    update_client(ctx, header)
+}
+
+#[grug_export]
+pub fn ibc_client_misbehavior(ctx: SudoCtx, misbehavior: Misbehavior) -> anyhow::Result<Response> {
+    submit_misbehavior(ctx, misbehavior)
+}
+
+#[grug_export]
+pub fn ibc_client_query(
+    ctx: ImmutableCtx,
+    msg: IbcClientQuery,
+) -> anyhow::Result<IbcClientQueryResponse> {
+    match msg {
+        IbcClientQuery::Status {} => query_status(ctx)
+            .map(IbcClientQueryResponse::Status)
+            .map_err(Into::into),
+        IbcClientQuery::State {} => query_state(ctx)
+            .map(IbcClientQueryResponse::State)
+            .map_err(Into::into),
+        IbcClientQuery::VerifyMembership {
+            height,
+            delay_time_period,
+            delay_block_period,
+            prefix,
+            key,
+            value,
+            proof,
+        } => verify_membership(
+            ctx,
+            height,
+            delay_time_period,
+            delay_block_period,
+            &prefix,
+            &key,
+            &value,
+            proof,
+        )
+        .map(IbcClientQueryResponse::VerifyMembership),
+        IbcClientQuery::VerifyNonMembership {
+            height,
+            delay_time_period,
+            delay_block_period,
+            prefix,
+            key,
+            proof,
+        } => verify_non_membership(
+            ctx,
+            height,
+            delay_time_period,
+            delay_block_period,
+            &prefix,
+            &key,
+            proof,
+        )
+        .map(IbcClientQueryResponse::VerifyNonMembership),
+        IbcClientQuery::TimestampAtHeight { height } => query_timestamp_at_height(ctx, height)
+            .map(IbcClientQueryResponse::TimestampAtHeight)
+            .map_err(Into::into),
+    }
+}
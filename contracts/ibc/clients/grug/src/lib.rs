@@ -0,0 +1,7 @@
+#[cfg(not(feature = "library"))]
+mod exports;
+mod query;
+
+#[cfg(not(feature = "library"))]
+pub use crate::exports::*;
+pub use crate::query::*;
@@ -0,0 +1,254 @@
+use {
+    anyhow::anyhow,
+    grug::{from_json_value, Hash, ImmutableCtx, Json},
+    grug_ibc_client_tendermint::{load_root, CLIENT_STATE},
+    grug_jmt::{verify_proof, Proof},
+};
+
+/// Verify a `grug_jmt` proof of the key-value pair in the counterparty's state,
+/// at the given height.
+///
+/// Grug chains use CometBFT for consensus, so headers are verified the same way
+/// as in the Tendermint client. However, the app hash is the root of the
+/// counterparty's Merkle tree, not of a Cosmos SDK multistore, so proofs are
+/// over raw storage keys, i.e. the connection's commitment prefix (typically
+/// `ibc`, the namespace under which the host keeps connection and channel ends)
+/// followed by the key.
+pub fn verify_membership(
+    ctx: ImmutableCtx,
+    height: u64,
+    delay_time_period: u64,
+    delay_block_period: u64,
+    prefix: &[u8],
+    key: &[u8],
+    value: &[u8],
+    proof: Json,
+) -> anyhow::Result<bool> {
+    let (root, key_hash) = load_root_and_hash_key(
+        &ctx,
+        height,
+        delay_time_period,
+        delay_block_period,
+        prefix,
+        key,
+    )?;
+    let value_hash = Hash::from_slice(ctx.api.sha2_256(value));
+    let proof: Proof = from_json_value(proof)?;
+
+    Ok(verify_proof(&root, &key_hash, Some(&value_hash), &proof).is_ok())
+}
+
+/// Verify a `grug_jmt` proof of the key's absence from the counterparty's
+/// state, at the given height.
+pub fn verify_non_membership(
+    ctx: ImmutableCtx,
+    height: u64,
+    delay_time_period: u64,
+    delay_block_period: u64,
+    prefix: &[u8],
+    key: &[u8],
+    proof: Json,
+) -> anyhow::Result<bool> {
+    let (root, key_hash) = load_root_and_hash_key(
+        &ctx,
+        height,
+        delay_time_period,
+        delay_block_period,
+        prefix,
+        key,
+    )?;
+    let proof: Proof = from_json_value(proof)?;
+
+    Ok(verify_proof(&root, &key_hash, None, &proof).is_ok())
+}
+
+fn load_root_and_hash_key(
+    ctx: &ImmutableCtx,
+    height: u64,
+    delay_time_period: u64,
+    delay_block_period: u64,
+    prefix: &[u8],
+    key: &[u8],
+) -> anyhow::Result<(Hash, Hash)> {
+    let client_state = CLIENT_STATE.load(ctx.storage)?;
+    let root = load_root(
+        ctx,
+        &client_state,
+        height,
+        delay_time_period,
+        delay_block_period,
+    )?;
+    let root = Hash::try_from(root.as_ref())
+        .map_err(|_| anyhow!("app hash is not a valid root hash: {root}"))?;
+    let raw_key = [prefix, key].concat();
+    let key_hash = Hash::from_slice(ctx.api.sha2_256(&raw_key));
+
+    Ok((root, key_hash))
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        grug::{
+            from_json_slice, hash, to_json_value, Addr, Batch, BlockInfo, IbcClientResponseData,
+            MockApi, MockCaller, MockQuerier, MockStorage, Op, QuerierWrapper, SudoCtx, Timestamp,
+            Uint64,
+        },
+        grug_ibc_client_tendermint::{create_client, ClientState, ConsensusState, Fraction},
+        grug_jmt::MerkleTree,
+    };
+
+    /// The namespace under which the host keeps the IBC state, i.e. its
+    /// commitment prefix. Keys within it are ICS-24 paths.
+    const IBC_NAMESPACE: &[u8] = b"ibc";
+
+    fn block() -> BlockInfo {
+        BlockInfo {
+            height: Uint64::new(100),
+            timestamp: Timestamp::from_seconds(1_000),
+            hash: Hash::ZERO,
+        }
+    }
+
+    fn raw_key(key: &[u8]) -> Vec<u8> {
+        [IBC_NAMESPACE, key].concat()
+    }
+
+    #[test]
+    fn verifying_jmt_proofs_works() {
+        // The counterparty's state, with a connection end under the IBC
+        // namespace, and something else in a contract's storage.
+        let mut counterparty = MockStorage::new();
+        let tree = MerkleTree::default();
+        let batch = Batch::from([
+            (
+                raw_key(b"connections/connection-0"),
+                Op::Insert(b"connection-end".to_vec()),
+            ),
+            (
+                [b"wasm", Addr::mock(3).as_ref(), b"larry"].concat(),
+                Op::Insert(b"rocks".to_vec()),
+            ),
+        ]);
+        let root = tree
+            .apply_raw(&mut counterparty, 0, 1, &batch)
+            .unwrap()
+            .unwrap();
+
+        let mut storage = MockStorage::new();
+        let res = create_client(
+            SudoCtx {
+                storage: &mut storage,
+                api: &MockApi,
+                querier: QuerierWrapper::new(&MockQuerier),
+                caller: &MockCaller,
+                chain_id: "dev-1".to_string(),
+                block: block(),
+                contract: Addr::mock(1),
+            },
+            ClientState {
+                chain_id: "counterparty".to_string(),
+                trust_level: Fraction::TWO_THIRDS,
+                trusting_period: 100,
+                unbonding_period: 150,
+                max_clock_drift: 10,
+                latest_height: 1,
+                frozen_height: None,
+            },
+            ConsensusState {
+                timestamp: Timestamp::from_seconds(990),
+                root: root.into_vec().into(),
+                next_validators_hash: Hash::ZERO.into_vec().into(),
+            },
+        )
+        .unwrap();
+
+        // the host reads the consensus height from the response data
+        let data: IbcClientResponseData = from_json_slice(res.data.unwrap()).unwrap();
+        assert_eq!(data.consensus_height, 1);
+
+        let ctx = || ImmutableCtx {
+            storage: &storage,
+            api: &MockApi,
+            querier: QuerierWrapper::new(&MockQuerier),
+            chain_id: "dev-1".to_string(),
+            block: block(),
+            contract: Addr::mock(1),
+        };
+        let prove = |key: &[u8]| {
+            let proof = tree.prove(&counterparty, &hash(raw_key(key)), 1).unwrap();
+            to_json_value(&proof).unwrap()
+        };
+
+        let proof = prove(b"connections/connection-0");
+        let verify = |value: &[u8]| {
+            verify_membership(
+                ctx(),
+                1,
+                0,
+                0,
+                IBC_NAMESPACE,
+                b"connections/connection-0",
+                value,
+                proof.clone(),
+            )
+            .unwrap()
+        };
+        assert!(verify(b"connection-end"));
+        assert!(!verify(b"fake-connection-end"));
+
+        // the proof is for the key under the IBC namespace, not for the same key
+        // in a contract's storage
+        let proof = prove(b"larry");
+        assert!(!verify_membership(
+            ctx(),
+            1,
+            0,
+            0,
+            IBC_NAMESPACE,
+            b"larry",
+            b"rocks",
+            proof.clone()
+        )
+        .unwrap());
+        assert!(verify_non_membership(ctx(), 1, 0, 0, IBC_NAMESPACE, b"larry", proof).unwrap());
+
+        // nor is it under any other prefix, so a connection whose counterparty
+        // prefix doesn't match the counterparty's layout can't be proven
+        let proof = prove(b"connections/connection-0");
+        assert!(!verify_membership(
+            ctx(),
+            1,
+            0,
+            0,
+            b"wasm",
+            b"connections/connection-0",
+            b"connection-end",
+            proof
+        )
+        .unwrap());
+
+        let proof = prove(b"channelEnds/ports/transfer/channels/channel-0");
+        let verify = |key: &[u8]| {
+            verify_non_membership(ctx(), 1, 0, 0, IBC_NAMESPACE, key, proof.clone()).unwrap()
+        };
+        assert!(verify(b"channelEnds/ports/transfer/channels/channel-0"));
+        assert!(!verify(b"connections/connection-0"));
+
+        // proofs can't be verified before the delay period has passed
+        let err = verify_non_membership(
+            ctx(),
+            1,
+            0,
+            1,
+            IBC_NAMESPACE,
+            b"channelEnds/ports/transfer/channels/channel-0",
+            prove(b"channelEnds/ports/transfer/channels/channel-0"),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("delay block period"));
+    }
+}
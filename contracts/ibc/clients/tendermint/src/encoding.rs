@@ -0,0 +1,215 @@
+//! Tendermint's canonical encodings of headers, validator sets and votes,
+//! which are needed to compute the hashes and signatures it commits to.
+//!
+//! These mirror the Protobuf encodings used by CometBFT v0.34–v0.38.
+
+use {
+    crate::{BlockId, LightHeader, Validator},
+    grug::{Api, Timestamp},
+    prost::Message,
+};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+/// The `SignedMsgType` of precommit votes, which are what commits consist of.
+const PRECOMMIT_TYPE: i32 = 2;
+
+mod proto {
+    use prost::Message;
+
+    #[derive(Message)]
+    pub struct Consensus {
+        #[prost(uint64, tag = "1")]
+        pub block: u64,
+        #[prost(uint64, tag = "2")]
+        pub app: u64,
+    }
+
+    #[derive(Message)]
+    pub struct Timestamp {
+        #[prost(int64, tag = "1")]
+        pub seconds: i64,
+        #[prost(int32, tag = "2")]
+        pub nanos: i32,
+    }
+
+    #[derive(Message)]
+    pub struct PartSetHeader {
+        #[prost(uint32, tag = "1")]
+        pub total: u32,
+        #[prost(bytes = "vec", tag = "2")]
+        pub hash: Vec<u8>,
+    }
+
+    // `CanonicalBlockID` has the same encoding as `BlockID`.
+    #[derive(Message)]
+    pub struct BlockId {
+        #[prost(bytes = "vec", tag = "1")]
+        pub hash: Vec<u8>,
+        #[prost(message, optional, tag = "2")]
+        pub part_set_header: Option<PartSetHeader>,
+    }
+
+    #[derive(Message)]
+    pub struct CanonicalVote {
+        #[prost(int32, tag = "1")]
+        pub r#type: i32,
+        #[prost(sfixed64, tag = "2")]
+        pub height: i64,
+        #[prost(sfixed64, tag = "3")]
+        pub round: i64,
+        #[prost(message, optional, tag = "4")]
+        pub block_id: Option<BlockId>,
+        #[prost(message, optional, tag = "5")]
+        pub timestamp: Option<Timestamp>,
+        #[prost(string, tag = "6")]
+        pub chain_id: String,
+    }
+
+    #[derive(Message)]
+    pub struct PublicKey {
+        #[prost(bytes = "vec", tag = "1")]
+        pub ed25519: Vec<u8>,
+    }
+
+    #[derive(Message)]
+    pub struct SimpleValidator {
+        #[prost(message, optional, tag = "1")]
+        pub pub_key: Option<PublicKey>,
+        #[prost(int64, tag = "2")]
+        pub voting_power: i64,
+    }
+
+    // Wrappers that Tendermint uses to encode primitive header fields.
+
+    #[derive(Message)]
+    pub struct StringValue {
+        #[prost(string, tag = "1")]
+        pub value: String,
+    }
+
+    #[derive(Message)]
+    pub struct Int64Value {
+        #[prost(int64, tag = "1")]
+        pub value: i64,
+    }
+
+    #[derive(Message)]
+    pub struct BytesValue {
+        #[prost(bytes = "vec", tag = "1")]
+        pub value: Vec<u8>,
+    }
+}
+
+/// Compute the hash of a header, which is what validators sign.
+pub fn hash_header(api: &dyn Api, header: &LightHeader) -> [u8; 32] {
+    let bytes = |value: &[u8]| {
+        proto::BytesValue {
+            value: value.to_vec(),
+        }
+        .encode_to_vec()
+    };
+
+    merkle_root(api, &[
+        proto::Consensus {
+            block: header.version.block,
+            app: header.version.app,
+        }
+        .encode_to_vec(),
+        proto::StringValue {
+            value: header.chain_id.clone(),
+        }
+        .encode_to_vec(),
+        proto::Int64Value {
+            value: header.height as i64,
+        }
+        .encode_to_vec(),
+        encode_timestamp(header.time).encode_to_vec(),
+        encode_block_id(&header.last_block_id).encode_to_vec(),
+        bytes(&header.last_commit_hash),
+        bytes(&header.data_hash),
+        bytes(&header.validators_hash),
+        bytes(&header.next_validators_hash),
+        bytes(&header.consensus_hash),
+        bytes(&header.app_hash),
+        bytes(&header.last_results_hash),
+        bytes(&header.evidence_hash),
+        bytes(&header.proposer_address),
+    ])
+}
+
+/// Compute the hash of a validator set, which headers commit to.
+pub fn hash_validator_set(api: &dyn Api, validators: &[Validator]) -> [u8; 32] {
+    let items = validators
+        .iter()
+        .map(|validator| {
+            proto::SimpleValidator {
+                pub_key: Some(proto::PublicKey {
+                    ed25519: validator.pub_key.to_vec(),
+                }),
+                voting_power: validator.voting_power as i64,
+            }
+            .encode_to_vec()
+        })
+        .collect::<Vec<_>>();
+
+    merkle_root(api, &items)
+}
+
+/// Compute the address of a validator from its Ed25519 public key.
+pub fn validator_address(api: &dyn Api, pub_key: &[u8]) -> [u8; 20] {
+    let mut address = [0; 20];
+    address.copy_from_slice(&api.sha2_256(pub_key)[..20]);
+    address
+}
+
+/// Generate the bytes that a validator signs when precommitting a block.
+pub fn vote_sign_bytes(
+    chain_id: &str,
+    height: u64,
+    round: u32,
+    block_id: &BlockId,
+    timestamp: Timestamp,
+) -> Vec<u8> {
+    proto::CanonicalVote {
+        r#type: PRECOMMIT_TYPE,
+        height: height as i64,
+        round: round as i64,
+        block_id: Some(encode_block_id(block_id)),
+        timestamp: Some(encode_timestamp(timestamp)),
+        chain_id: chain_id.to_string(),
+    }
+    .encode_length_delimited_to_vec()
+}
+
+fn encode_timestamp(timestamp: Timestamp) -> proto::Timestamp {
+    proto::Timestamp {
+        seconds: (timestamp.nanos() / NANOS_PER_SECOND) as i64,
+        nanos: (timestamp.nanos() % NANOS_PER_SECOND) as i32,
+    }
+}
+
+fn encode_block_id(block_id: &BlockId) -> proto::BlockId {
+    proto::BlockId {
+        hash: block_id.hash.to_vec(),
+        part_set_header: Some(proto::PartSetHeader {
+            total: block_id.part_set_header.total,
+            hash: block_id.part_set_header.hash.to_vec(),
+        }),
+    }
+}
+
+/// Compute the root of Tendermint's simple Merkle tree (RFC 6962) of the items.
+fn merkle_root(api: &dyn Api, items: &[Vec<u8>]) -> [u8; 32] {
+    match items.len() {
+        0 => api.sha2_256(&[]),
+        1 => api.sha2_256(&[&[0], items[0].as_slice()].concat()),
+        len => {
+            // The largest power of two strictly smaller than the length.
+            let split = 1 << (usize::BITS - (len - 1).leading_zeros() - 1);
+            let left = merkle_root(api, &items[..split]);
+            let right = merkle_root(api, &items[split..]);
+            api.sha2_256(&[&[1], left.as_slice(), right.as_slice()].concat())
+        },
+    }
+}
@@ -0,0 +1,1012 @@
+use {
+    crate::{
+        check_misbehavior, is_expired, revision_number, verify_header, ClientState, ConsensusState,
+        Header, Misbehavior, Processed, CLIENT_STATE, CONSENSUS_STATES, PROCESSED,
+    },
+    anyhow::{bail, ensure},
+    grug::{
+        to_json_vec, Bound, IbcClientResponseData, Order, Response, StdResult, Storage, SudoCtx,
+        Timestamp,
+    },
+};
+
+pub fn create_client(
+    mut ctx: SudoCtx,
+    client_state: ClientState,
+    consensus_state: ConsensusState,
+) -> anyhow::Result<Response> {
+    let trust_level = &client_state.trust_level;
+    ensure!(
+        trust_level.denominator > 0
+            && trust_level.numerator * 3 >= trust_level.denominator
+            && trust_level.numerator <= trust_level.denominator,
+        "trust level must be within [1/3, 1]"
+    );
+
+    ensure!(
+        client_state.trusting_period > 0
+            && client_state.trusting_period < client_state.unbonding_period,
+        "trusting period must be positive and shorter than the unbonding period"
+    );
+
+    ensure!(!client_state.chain_id.is_empty(), "chain id can't be empty");
+
+    // Heights on our side don't have revision numbers, so only counterparties
+    // whose revision number is zero are supported. Their heights are then the
+    // same with or without revision numbers.
+    ensure!(
+        revision_number(&client_state.chain_id) == Some(0),
+        "chain id `{}` has a nonzero revision number, which isn't supported",
+        client_state.chain_id
+    );
+
+    ensure!(
+        client_state.latest_height > 0,
+        "latest height can't be zero"
+    );
+
+    ensure!(
+        client_state.frozen_height.is_none(),
+        "can't create a frozen client"
+    );
+
+    ensure!(
+        !is_expired(
+            &client_state,
+            consensus_state.timestamp,
+            ctx.block.timestamp
+        ),
+        "consensus state has expired"
+    );
+
+    save_consensus_state(&mut ctx, client_state.latest_height, &consensus_state)?;
+    CLIENT_STATE.save(ctx.storage, &client_state)?;
+
+    response(client_state.latest_height)
+}
+
+pub fn update_client(mut ctx: SudoCtx, header: Header) -> anyhow::Result<Response> {
+    let mut client_state = CLIENT_STATE.load(ctx.storage)?;
+    ensure!(
+        client_state.frozen_height.is_none(),
+        "client is frozen and can't be updated"
+    );
+
+    let trusted = CONSENSUS_STATES.load(ctx.storage, header.trusted_height)?;
+    verify_header(
+        ctx.api,
+        &client_state,
+        &trusted,
+        &header,
+        ctx.block.timestamp,
+    )?;
+
+    let signed_header = header.signed_header.header;
+    let height = signed_header.height;
+    let consensus_state = ConsensusState {
+        timestamp: signed_header.time,
+        root: signed_header.app_hash,
+        next_validators_hash: signed_header.next_validators_hash,
+    };
+
+    // A valid header that conflicts with a consensus state we already have is
+    // proof of misbehavior. Freeze the client instead of updating it.
+    if let Some(existing) = CONSENSUS_STATES.may_load(ctx.storage, height)? {
+        if existing != consensus_state {
+            return freeze(ctx.storage, client_state, height);
+        }
+
+        // Already have this exact consensus state; nothing to do.
+        return response(height);
+    }
+
+    // So is a valid header that breaks the monotonicity of block times, i.e.
+    // whose time isn't between those of the consensus states right before and
+    // after it. Same as ibc-go, which checks this on every update.
+    if !is_time_monotonic(ctx.storage, height, consensus_state.timestamp)? {
+        return freeze(ctx.storage, client_state, height);
+    }
+
+    save_consensus_state(&mut ctx, height, &consensus_state)?;
+
+    if height > client_state.latest_height {
+        client_state.latest_height = height;
+        CLIENT_STATE.save(ctx.storage, &client_state)?;
+    }
+
+    response(height)
+}
+
+pub fn submit_misbehavior(ctx: SudoCtx, misbehavior: Misbehavior) -> anyhow::Result<Response> {
+    let mut client_state = CLIENT_STATE.load(ctx.storage)?;
+    if client_state.frozen_height.is_some() {
+        bail!("client is already frozen");
+    }
+
+    // Both headers must be valid on their own, i.e. signed by validators that
+    // the client trusts.
+    for header in [&misbehavior.header_1, &misbehavior.header_2] {
+        let trusted = CONSENSUS_STATES.load(ctx.storage, header.trusted_height)?;
+        verify_header(
+            ctx.api,
+            &client_state,
+            &trusted,
+            header,
+            ctx.block.timestamp,
+        )?;
+    }
+
+    check_misbehavior(ctx.api, &misbehavior.header_1, &misbehavior.header_2)?;
+
+    let height = misbehavior.header_1.signed_header.header.height;
+    client_state.frozen_height = Some(height);
+    CLIENT_STATE.save(ctx.storage, &client_state)?;
+
+    response(height)
+}
+
+/// Freeze the client at the height of a header that proves misbehavior.
+fn freeze(
+    storage: &mut dyn Storage,
+    mut client_state: ClientState,
+    height: u64,
+) -> anyhow::Result<Response> {
+    client_state.frozen_height = Some(height);
+    CLIENT_STATE.save(storage, &client_state)?;
+
+    Ok(response(height)?.add_attribute("frozen", true))
+}
+
+/// Whether the timestamp is after that of the consensus state right before the
+/// given height, and before that of the one right after it, if there is one.
+fn is_time_monotonic(storage: &dyn Storage, height: u64, timestamp: Timestamp) -> StdResult<bool> {
+    let prev = CONSENSUS_STATES
+        .range(
+            storage,
+            None,
+            Some(Bound::Exclusive(height)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?;
+    if prev.is_some_and(|(_, prev)| prev.timestamp >= timestamp) {
+        return Ok(false);
+    }
+
+    let next = CONSENSUS_STATES
+        .range(
+            storage,
+            Some(Bound::Exclusive(height)),
+            None,
+            Order::Ascending,
+        )
+        .next()
+        .transpose()?;
+
+    Ok(next.map_or(true, |(_, next)| next.timestamp > timestamp))
+}
+
+/// The response of the client's entry points, with the consensus height that
+/// the host includes in its events as data.
+fn response(consensus_height: u64) -> anyhow::Result<Response> {
+    let data = IbcClientResponseData { consensus_height };
+    Ok(Response::new().set_data(to_json_vec(&data)?))
+}
+
+fn save_consensus_state(
+    ctx: &mut SudoCtx,
+    height: u64,
+    consensus_state: &ConsensusState,
+) -> StdResult<()> {
+    CONSENSUS_STATES.save(ctx.storage, height, consensus_state)?;
+    PROCESSED.save(ctx.storage, height, &Processed {
+        time: ctx.block.timestamp,
+        height: ctx.block.height.number(),
+    })
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            hash_header, hash_validator_set, query_state, query_status, validator_address,
+            verify_commit, verify_membership, verify_non_membership, vote_sign_bytes, BlockId,
+            BlockIdFlag, Commit, CommitSig, Fraction, LightHeader, PartSetHeader, SignedHeader,
+            Validator, Version,
+        },
+        ed25519_dalek::{Signer, SigningKey},
+        grug::{
+            from_json_slice, from_json_value, to_json_value, Addr, Api, Binary, BlockInfo, Hash,
+            IbcClientStatus, ImmutableCtx, Json, MockApi, MockCaller, MockQuerier, MockStorage,
+            QuerierWrapper, Timestamp, Uint64,
+        },
+        grug_ics23::{
+            apply_inner, apply_leaf, iavl_spec, tendermint_spec, CommitmentProof, ExistenceProof,
+            HashOp, InnerOp, MerkleProof, NonExistenceProof, Proof,
+        },
+        prost::Message,
+    };
+
+    const CHAIN_ID: &str = "testchain";
+
+    const GENESIS_TIME: Timestamp = Timestamp::from_seconds(1_700_000_000);
+
+    const TRUSTING_PERIOD: u64 = 14 * 24 * 60 * 60;
+
+    // ---------------------------------- fixtures ---------------------------------
+
+    fn signing_keys(seeds: &[u8]) -> Vec<SigningKey> {
+        seeds
+            .iter()
+            .map(|seed| SigningKey::from_bytes(&[*seed; 32]))
+            .collect()
+    }
+
+    fn validators(keys: &[SigningKey]) -> Vec<Validator> {
+        keys.iter()
+            .map(|key| {
+                let pub_key = key.verifying_key().to_bytes();
+                Validator {
+                    address: validator_address(&MockApi, &pub_key).to_vec().into(),
+                    pub_key: pub_key.to_vec().into(),
+                    voting_power: 10,
+                }
+            })
+            .collect()
+    }
+
+    /// Generate a header at the given height, signed by the given signers, who
+    /// must be among the block's validators.
+    fn header(
+        height: u64,
+        app_hash: &[u8],
+        keys: &[SigningKey],
+        next_keys: &[SigningKey],
+        signers: &[SigningKey],
+        trusted_height: u64,
+        trusted_keys: &[SigningKey],
+    ) -> Header {
+        let time = GENESIS_TIME.plus_seconds(height * 5);
+        header_at(
+            height,
+            time,
+            app_hash,
+            keys,
+            next_keys,
+            signers,
+            trusted_height,
+            trusted_keys,
+        )
+    }
+
+    /// Same as [`header`], but with the given block time.
+    fn header_at(
+        height: u64,
+        time: Timestamp,
+        app_hash: &[u8],
+        keys: &[SigningKey],
+        next_keys: &[SigningKey],
+        signers: &[SigningKey],
+        trusted_height: u64,
+        trusted_keys: &[SigningKey],
+    ) -> Header {
+        let validator_set = validators(keys);
+        let light_header = LightHeader {
+            version: Version { block: 11, app: 0 },
+            chain_id: CHAIN_ID.to_string(),
+            height,
+            time,
+            last_block_id: BlockId {
+                hash: Binary::empty(),
+                part_set_header: PartSetHeader {
+                    total: 0,
+                    hash: Binary::empty(),
+                },
+            },
+            last_commit_hash: Binary::empty(),
+            data_hash: Binary::empty(),
+            validators_hash: hash_validator_set(&MockApi, &validator_set).to_vec().into(),
+            next_validators_hash: hash_validator_set(&MockApi, &validators(next_keys))
+                .to_vec()
+                .into(),
+            consensus_hash: Binary::empty(),
+            app_hash: app_hash.to_vec().into(),
+            last_results_hash: Binary::empty(),
+            evidence_hash: Binary::empty(),
+            proposer_address: validator_set[0].address.clone(),
+        };
+        let block_id = BlockId {
+            hash: hash_header(&MockApi, &light_header).to_vec().into(),
+            part_set_header: PartSetHeader {
+                total: 1,
+                hash: vec![0; 32].into(),
+            },
+        };
+        let signatures = keys
+            .iter()
+            .zip(&validator_set)
+            .map(|(key, validator)| {
+                let timestamp = time.plus_seconds(1);
+                if signers.iter().any(|signer| signer == key) {
+                    let sign_bytes = vote_sign_bytes(CHAIN_ID, height, 0, &block_id, timestamp);
+                    CommitSig {
+                        block_id_flag: BlockIdFlag::Commit,
+                        validator_address: validator.address.clone(),
+                        timestamp,
+                        signature: key.sign(&sign_bytes).to_bytes().to_vec().into(),
+                    }
+                } else {
+                    CommitSig {
+                        block_id_flag: BlockIdFlag::Absent,
+                        validator_address: validator.address.clone(),
+                        timestamp,
+                        signature: Binary::empty(),
+                    }
+                }
+            })
+            .collect();
+
+        Header {
+            signed_header: SignedHeader {
+                header: light_header,
+                commit: Commit {
+                    height,
+                    round: 0,
+                    block_id,
+                    signatures,
+                },
+            },
+            validator_set,
+            trusted_height,
+            trusted_validators: validators(trusted_keys),
+        }
+    }
+
+    fn block(now: Timestamp) -> BlockInfo {
+        BlockInfo {
+            height: Uint64::new(100),
+            timestamp: now,
+            hash: Hash::ZERO,
+        }
+    }
+
+    fn sudo_ctx(storage: &mut MockStorage, now: Timestamp) -> SudoCtx {
+        SudoCtx {
+            storage,
+            api: &MockApi,
+            querier: QuerierWrapper::new(&MockQuerier),
+            caller: &MockCaller,
+            chain_id: "dev-1".to_string(),
+            block: block(now),
+            contract: Addr::mock(1),
+        }
+    }
+
+    fn immutable_ctx(storage: &MockStorage, now: Timestamp) -> ImmutableCtx {
+        ImmutableCtx {
+            storage,
+            api: &MockApi,
+            querier: QuerierWrapper::new(&MockQuerier),
+            chain_id: "dev-1".to_string(),
+            block: block(now),
+            contract: Addr::mock(1),
+        }
+    }
+
+    /// Create a client at height 10, trusting the given validators.
+    fn setup(keys: &[SigningKey], root: &[u8]) -> MockStorage {
+        let mut storage = MockStorage::new();
+        let now = GENESIS_TIME.plus_seconds(60);
+        create_client(
+            sudo_ctx(&mut storage, now),
+            client_state(CHAIN_ID),
+            consensus_state(keys, root),
+        )
+        .unwrap();
+        storage
+    }
+
+    /// The state of a client at height 10.
+    fn client_state(chain_id: &str) -> ClientState {
+        ClientState {
+            chain_id: chain_id.to_string(),
+            trust_level: Fraction {
+                numerator: 1,
+                denominator: 3,
+            },
+            trusting_period: TRUSTING_PERIOD,
+            unbonding_period: TRUSTING_PERIOD * 3 / 2,
+            max_clock_drift: 10,
+            latest_height: 10,
+            frozen_height: None,
+        }
+    }
+
+    fn consensus_state(keys: &[SigningKey], root: &[u8]) -> ConsensusState {
+        ConsensusState {
+            timestamp: GENESIS_TIME.plus_seconds(50),
+            root: root.to_vec().into(),
+            next_validators_hash: hash_validator_set(&MockApi, &validators(keys))
+                .to_vec()
+                .into(),
+        }
+    }
+
+    // ----------------------------------- tests -----------------------------------
+
+    #[test]
+    fn updating_client_works() {
+        let keys = signing_keys(&[1, 2, 3, 4]);
+        let mut storage = setup(&keys, b"root-10");
+        let now = GENESIS_TIME.plus_seconds(1_000);
+
+        // adjacent header, signed by 3 out of 4 validators
+        let header_11 = header(11, b"root-11", &keys, &keys, &keys[..3], 10, &keys);
+        update_client(sudo_ctx(&mut storage, now), header_11).unwrap();
+        assert_eq!(CLIENT_STATE.load(&storage).unwrap().latest_height, 11);
+        assert_eq!(
+            CONSENSUS_STATES.load(&storage, 11).unwrap().root,
+            Binary::from(b"root-11".to_vec())
+        );
+
+        // 2 out of 4 is not more than 2/3
+        let header_12 = header(12, b"root-12", &keys, &keys, &keys[..2], 11, &keys);
+        let err = update_client(sudo_ctx(&mut storage, now), header_12).unwrap_err();
+        assert!(err.to_string().contains("insufficient voting power"));
+
+        // skip to height 20, where half of the validators have been replaced.
+        // 2 out of the 4 trusted validators have signed, more than 1/3.
+        let new_keys = signing_keys(&[1, 2, 5, 6]);
+        let header_20 = header(
+            20,
+            b"root-20",
+            &new_keys,
+            &new_keys,
+            &new_keys[..3],
+            11,
+            &keys,
+        );
+        update_client(sudo_ctx(&mut storage, now), header_20).unwrap();
+        assert_eq!(CLIENT_STATE.load(&storage).unwrap().latest_height, 20);
+
+        // skipping from height 20 to 30, where only 1 of the trusted validators
+        // remains, isn't enough
+        let newer_keys = signing_keys(&[1, 7, 8, 9]);
+        let header_30 = header(
+            30,
+            b"root-30",
+            &newer_keys,
+            &newer_keys,
+            &newer_keys,
+            20,
+            &new_keys,
+        );
+        let err = update_client(sudo_ctx(&mut storage, now), header_30).unwrap_err();
+        assert!(err.to_string().contains("insufficient voting power"));
+
+        // a forged signature fails verification
+        let mut header_21 = header(
+            21, b"root-21", &new_keys, &new_keys, &new_keys, 20, &new_keys,
+        );
+        header_21.signed_header.commit.signatures[0].signature = vec![0; 64].into();
+        assert!(update_client(sudo_ctx(&mut storage, now), header_21).is_err());
+
+        // a header for another chain is rejected
+        let mut header_21 = header(
+            21, b"root-21", &new_keys, &new_keys, &new_keys, 20, &new_keys,
+        );
+        header_21.signed_header.header.chain_id = "osmosis-1".to_string();
+        let err = update_client(sudo_ctx(&mut storage, now), header_21).unwrap_err();
+        assert!(err.to_string().contains("chain id mismatch"));
+
+        // a header from too far in the future is rejected
+        let now = GENESIS_TIME.plus_seconds(100);
+        let header_22 = header(
+            22, b"root-22", &new_keys, &new_keys, &new_keys, 20, &new_keys,
+        );
+        let err = update_client(sudo_ctx(&mut storage, now), header_22).unwrap_err();
+        assert!(err.to_string().contains("future"));
+    }
+
+    #[test]
+    fn nonzero_revisions_are_rejected() {
+        for (chain_id, expect) in [
+            ("testchain", Some(0)),
+            ("testchain-0", Some(0)),
+            ("testchain-01", Some(0)),
+            ("testchain--1", Some(0)),
+            ("-1", Some(0)),
+            ("test-chain", Some(0)),
+            ("cosmoshub-4", Some(4)),
+            ("test-chain-12", Some(12)),
+            ("testchain-99999999999999999999", None),
+        ] {
+            assert_eq!(revision_number(chain_id), expect, "{chain_id}");
+        }
+
+        let keys = signing_keys(&[1, 2, 3, 4]);
+        let mut storage = MockStorage::new();
+        let err = create_client(
+            sudo_ctx(&mut storage, GENESIS_TIME.plus_seconds(60)),
+            client_state("cosmoshub-4"),
+            consensus_state(&keys, b"root-10"),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("nonzero revision number"));
+    }
+
+    #[test]
+    fn client_expires_and_freezes() {
+        let keys = signing_keys(&[1, 2, 3, 4]);
+        let mut storage = setup(&keys, b"root-10");
+        let now = GENESIS_TIME.plus_seconds(1_000);
+
+        let status = query_status(immutable_ctx(&storage, now)).unwrap();
+        assert_eq!(status, IbcClientStatus::Active);
+
+        // once the trusting period has passed, the client expires, and can no
+        // longer be updated
+        let later = GENESIS_TIME.plus_seconds(TRUSTING_PERIOD + 50);
+        let status = query_status(immutable_ctx(&storage, later)).unwrap();
+        assert_eq!(status, IbcClientStatus::Expired);
+
+        let header_11 = header(11, b"root-11", &keys, &keys, &keys, 10, &keys);
+        let err = update_client(sudo_ctx(&mut storage, later), header_11).unwrap_err();
+        assert!(err.to_string().contains("expired"));
+
+        // two headers for different blocks at the same height is not
+        // misbehavior, unless both are signed by the trusted validators
+        let header_1 = header(11, b"root-11", &keys, &keys, &keys, 10, &keys);
+        let mut header_2 = header(11, b"evil-11", &keys, &keys, &keys[..1], 10, &keys);
+        let err = submit_misbehavior(sudo_ctx(&mut storage, now), Misbehavior {
+            header_1: header_1.clone(),
+            header_2: header_2.clone(),
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("insufficient voting power"));
+
+        // two headers for the same block are not misbehavior either
+        let err = submit_misbehavior(sudo_ctx(&mut storage, now), Misbehavior {
+            header_1: header_1.clone(),
+            header_2: header_1.clone(),
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("not misbehavior"));
+
+        header_2 = header(11, b"evil-11", &keys, &keys, &keys, 10, &keys);
+        submit_misbehavior(sudo_ctx(&mut storage, now), Misbehavior {
+            header_1: header_1.clone(),
+            header_2,
+        })
+        .unwrap();
+
+        let status = query_status(immutable_ctx(&storage, now)).unwrap();
+        assert_eq!(status, IbcClientStatus::Frozen);
+
+        let err = update_client(sudo_ctx(&mut storage, now), header_1).unwrap_err();
+        assert!(err.to_string().contains("frozen"));
+    }
+
+    #[test]
+    fn conflicting_update_freezes_client() {
+        let keys = signing_keys(&[1, 2, 3, 4]);
+        let mut storage = setup(&keys, b"root-10");
+        let now = GENESIS_TIME.plus_seconds(1_000);
+
+        let header_11 = header(11, b"root-11", &keys, &keys, &keys, 10, &keys);
+        update_client(sudo_ctx(&mut storage, now), header_11.clone()).unwrap();
+
+        // submitting the same header again is a no-op
+        update_client(sudo_ctx(&mut storage, now), header_11).unwrap();
+        let status = query_status(immutable_ctx(&storage, now)).unwrap();
+        assert_eq!(status, IbcClientStatus::Active);
+
+        let evil_11 = header(11, b"evil-11", &keys, &keys, &keys, 10, &keys);
+        let res = update_client(sudo_ctx(&mut storage, now), evil_11).unwrap();
+        assert!(res.attributes.iter().any(|attr| attr.key == "frozen"));
+
+        let status = query_status(immutable_ctx(&storage, now)).unwrap();
+        assert_eq!(status, IbcClientStatus::Frozen);
+    }
+
+    #[test]
+    fn non_monotonic_time_freezes_client() {
+        let keys = signing_keys(&[1, 2, 3, 4]);
+        let now = GENESIS_TIME.plus_seconds(1_000);
+
+        // a header between two consensus states, whose time is after that of
+        // the next one
+        let mut storage = setup(&keys, b"root-10");
+        let header_20 = header(20, b"root-20", &keys, &keys, &keys, 10, &keys);
+        update_client(sudo_ctx(&mut storage, now), header_20).unwrap();
+
+        let time = GENESIS_TIME.plus_seconds(20 * 5);
+        let header_15 = header_at(15, time, b"root-15", &keys, &keys, &keys, 10, &keys);
+        let res = update_client(sudo_ctx(&mut storage, now), header_15).unwrap();
+        assert!(res.attributes.iter().any(|attr| attr.key == "frozen"));
+        assert_eq!(CLIENT_STATE.load(&storage).unwrap().frozen_height, Some(15));
+        assert!(!CONSENSUS_STATES.has(&storage, 15));
+
+        // a header after the latest consensus state, verified against an
+        // earlier one, whose time is before that of the latest one
+        let mut storage = setup(&keys, b"root-10");
+        let header_20 = header(20, b"root-20", &keys, &keys, &keys, 10, &keys);
+        update_client(sudo_ctx(&mut storage, now), header_20).unwrap();
+
+        let time = GENESIS_TIME.plus_seconds(90);
+        let header_25 = header_at(25, time, b"root-25", &keys, &keys, &keys, 10, &keys);
+        let res = update_client(sudo_ctx(&mut storage, now), header_25).unwrap();
+        assert!(res.attributes.iter().any(|attr| attr.key == "frozen"));
+        assert_eq!(CLIENT_STATE.load(&storage).unwrap().latest_height, 20);
+
+        let status = query_status(immutable_ctx(&storage, now)).unwrap();
+        assert_eq!(status, IbcClientStatus::Frozen);
+
+        // a header between two consensus states, with a time between theirs
+        let mut storage = setup(&keys, b"root-10");
+        let header_20 = header(20, b"root-20", &keys, &keys, &keys, 10, &keys);
+        update_client(sudo_ctx(&mut storage, now), header_20).unwrap();
+        let header_15 = header(15, b"root-15", &keys, &keys, &keys, 10, &keys);
+        let res = update_client(sudo_ctx(&mut storage, now), header_15).unwrap();
+        assert!(res.attributes.iter().all(|attr| attr.key != "frozen"));
+        assert!(CONSENSUS_STATES.has(&storage, 15));
+    }
+
+    #[test]
+    fn verifying_proofs_works() {
+        // An IAVL tree with a single key, as the `ibc` store of a multistore
+        // that also has a `bank` store.
+        let iavl_leaf = iavl_spec().leaf_spec.unwrap();
+        let mut leaf = iavl_leaf.clone();
+        leaf.prefix = vec![0, 2, 2, 2];
+        let ibc_proof = ExistenceProof {
+            key: b"connections/connection-0".to_vec(),
+            value: b"connection-end".to_vec(),
+            leaf: Some(leaf),
+            path: vec![],
+        };
+        let ibc_root = apply_leaf(
+            ibc_proof.leaf.as_ref().unwrap(),
+            &ibc_proof.key,
+            &ibc_proof.value,
+        )
+        .unwrap();
+
+        let store_leaf = tendermint_spec().leaf_spec.unwrap();
+        let bank_root = MockApi.sha2_256(b"bank");
+        let bank_hash = apply_leaf(&store_leaf, b"bank", &bank_root).unwrap();
+        let store_proof = ExistenceProof {
+            key: b"ibc".to_vec(),
+            value: ibc_root,
+            leaf: Some(store_leaf),
+            path: vec![InnerOp {
+                hash: HashOp::Sha256.into(),
+                prefix: [&[1], bank_hash.as_slice()].concat(),
+                suffix: vec![],
+            }],
+        };
+        let leaf_hash = apply_leaf(
+            store_proof.leaf.as_ref().unwrap(),
+            &store_proof.key,
+            &store_proof.value,
+        )
+        .unwrap();
+        let root = apply_inner(&store_proof.path[0], &leaf_hash).unwrap();
+
+        let keys = signing_keys(&[1, 2, 3, 4]);
+        let storage = setup(&keys, &root);
+        let now = GENESIS_TIME.plus_seconds(1_000);
+        let encode = |first: Proof| {
+            let proof = MerkleProof {
+                proofs: vec![CommitmentProof { proof: Some(first) }, CommitmentProof {
+                    proof: Some(Proof::Exist(store_proof.clone())),
+                }],
+            };
+            to_json_value(&Binary::from(proof.encode_to_vec())).unwrap()
+        };
+
+        let proof = encode(Proof::Exist(ibc_proof.clone()));
+        let verify = |value: &[u8]| {
+            verify_membership(
+                immutable_ctx(&storage, now),
+                10,
+                0,
+                0,
+                b"ibc",
+                b"connections/connection-0",
+                value,
+                proof.clone(),
+            )
+            .unwrap()
+        };
+        assert!(verify(b"connection-end"));
+        assert!(!verify(b"fake-connection-end"));
+
+        // the store proof is for the `ibc` store, not any other
+        assert!(!verify_membership(
+            immutable_ctx(&storage, now),
+            10,
+            0,
+            0,
+            b"bank",
+            b"connections/connection-0",
+            b"connection-end",
+            proof.clone(),
+        )
+        .unwrap());
+
+        let proof = encode(Proof::Nonexist(NonExistenceProof {
+            key: b"channelEnds/ports/transfer/channels/channel-0".to_vec(),
+            left: None,
+            right: Some(ibc_proof),
+        }));
+        let verify = |key: &[u8]| {
+            verify_non_membership(
+                immutable_ctx(&storage, now),
+                10,
+                0,
+                0,
+                b"ibc",
+                key,
+                proof.clone(),
+            )
+            .unwrap()
+        };
+        assert!(verify(b"channelEnds/ports/transfer/channels/channel-0"));
+        assert!(!verify(b"connections/connection-0"));
+
+        // proofs can't be verified before the delay period has passed
+        let err = verify_membership(
+            immutable_ctx(&storage, now),
+            10,
+            10_000 * 1_000_000_000,
+            0,
+            b"ibc",
+            b"connections/connection-0",
+            b"connection-end",
+            proof,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("delay time period"));
+
+        let state = query_state(immutable_ctx(&storage, now)).unwrap();
+        let consensus_state: ConsensusState = from_json_slice(&state.consensus_state).unwrap();
+        assert_eq!(consensus_state.root.as_ref(), root.as_slice());
+    }
+
+    // ----------------------------- cometbft fixtures -----------------------------
+
+    fn read_fixture(name: &str) -> Json {
+        let path = format!("{}/testdata/{name}", env!("CARGO_MANIFEST_DIR"));
+        from_json_slice(std::fs::read(path).unwrap()).unwrap()
+    }
+
+    fn parse_hex(json: &Json) -> Binary {
+        hex::decode(json.as_str().unwrap()).unwrap().into()
+    }
+
+    fn parse_base64(json: &Json) -> Binary {
+        from_json_value(json.clone()).unwrap()
+    }
+
+    fn parse_u64(json: &Json) -> u64 {
+        json.as_str().unwrap().parse().unwrap()
+    }
+
+    /// Parse a time in RFC 3339 format, in UTC, which is how CometBFT formats
+    /// them in JSON.
+    fn parse_time(json: &Json) -> Timestamp {
+        let (date, time) = json
+            .as_str()
+            .unwrap()
+            .trim_end_matches('Z')
+            .split_once('T')
+            .unwrap();
+        let (time, fraction) = time.split_once('.').unwrap_or((time, "0"));
+        let numbers = |s: &str, sep: char| -> Vec<i64> {
+            s.split(sep).map(|number| number.parse().unwrap()).collect()
+        };
+        let [year, month, day] = numbers(date, '-')[..] else {
+            panic!("invalid date: {date}");
+        };
+        let [hour, minute, second] = numbers(time, ':')[..] else {
+            panic!("invalid time: {time}");
+        };
+
+        // Days since the Unix epoch, using the algorithm of `days_from_civil`
+        // from http://howardhinnant.github.io/date_algorithms.html
+        let year = if month <= 2 {
+            year - 1
+        } else {
+            year
+        };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146_097 + day_of_era - 719_468;
+
+        let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second;
+        let nanos: u64 = format!("{fraction:0<9}").parse().unwrap();
+
+        Timestamp::from_seconds(seconds as u64).plus_nanos(nanos)
+    }
+
+    fn parse_block_id(json: &Json) -> BlockId {
+        // The RPC calls the part set header `parts`.
+        let parts = json.get("parts").unwrap_or(&json["part_set_header"]);
+        BlockId {
+            hash: parse_hex(&json["hash"]),
+            part_set_header: PartSetHeader {
+                total: parts["total"].as_u64().unwrap() as u32,
+                hash: parse_hex(&parts["hash"]),
+            },
+        }
+    }
+
+    fn parse_header(json: &Json) -> LightHeader {
+        LightHeader {
+            version: Version {
+                block: parse_u64(&json["version"]["block"]),
+                app: parse_u64(&json["version"]["app"]),
+            },
+            chain_id: json["chain_id"].as_str().unwrap().to_string(),
+            height: parse_u64(&json["height"]),
+            time: parse_time(&json["time"]),
+            last_block_id: parse_block_id(&json["last_block_id"]),
+            last_commit_hash: parse_hex(&json["last_commit_hash"]),
+            data_hash: parse_hex(&json["data_hash"]),
+            validators_hash: parse_hex(&json["validators_hash"]),
+            next_validators_hash: parse_hex(&json["next_validators_hash"]),
+            consensus_hash: parse_hex(&json["consensus_hash"]),
+            app_hash: parse_hex(&json["app_hash"]),
+            last_results_hash: parse_hex(&json["last_results_hash"]),
+            evidence_hash: parse_hex(&json["evidence_hash"]),
+            proposer_address: parse_hex(&json["proposer_address"]),
+        }
+    }
+
+    fn parse_signed_header(json: &Json) -> SignedHeader {
+        let commit = &json["commit"];
+        let signatures = commit["signatures"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|sig| CommitSig {
+                block_id_flag: match sig["block_id_flag"].as_u64().unwrap() {
+                    1 => BlockIdFlag::Absent,
+                    2 => BlockIdFlag::Commit,
+                    3 => BlockIdFlag::Nil,
+                    flag => panic!("invalid block id flag: {flag}"),
+                },
+                validator_address: parse_hex(&sig["validator_address"]),
+                timestamp: parse_time(&sig["timestamp"]),
+                signature: parse_base64(&sig["signature"]),
+            })
+            .collect();
+
+        SignedHeader {
+            header: parse_header(&json["header"]),
+            commit: Commit {
+                height: parse_u64(&commit["height"]),
+                round: commit["round"].as_u64().unwrap() as u32,
+                block_id: parse_block_id(&commit["block_id"]),
+                signatures,
+            },
+        }
+    }
+
+    fn parse_validators(json: &Json) -> Vec<Validator> {
+        json.as_array()
+            .unwrap()
+            .iter()
+            .map(|validator| Validator {
+                address: parse_hex(&validator["address"]),
+                pub_key: parse_base64(&validator["pub_key"]["value"]),
+                voting_power: parse_u64(&validator["power"]),
+            })
+            .collect()
+    }
+
+    /// The signed header of block 10 of a CometBFT chain, and the validators
+    /// that signed it.
+    fn cometbft_block() -> (SignedHeader, Vec<Validator>) {
+        let commit = read_fixture("commit_at_height_10.json");
+        let genesis = read_fixture("genesis.json");
+        (
+            parse_signed_header(&commit["result"]["signed_header"]),
+            parse_validators(&genesis["result"]["genesis"]["validators"]),
+        )
+    }
+
+    #[test]
+    fn header_hashes_match_cometbft() {
+        let header = parse_header(&read_fixture("header_with_known_hash.json"));
+        assert_eq!(
+            hex::encode_upper(hash_header(&MockApi, &header)),
+            "F30A71F2409FB15AACAEDB6CC122DFA2525BEE9CAE521721B06BFDCA291B8D56"
+        );
+
+        // the commit is for the block of the header's hash
+        let (signed_header, _) = cometbft_block();
+        assert_eq!(
+            hash_header(&MockApi, &signed_header.header).as_slice(),
+            signed_header.commit.block_id.hash.as_ref()
+        );
+    }
+
+    #[test]
+    fn validator_set_hashes_match_cometbft() {
+        let (signed_header, validators) = cometbft_block();
+        assert_eq!(
+            hash_validator_set(&MockApi, &validators).as_slice(),
+            signed_header.header.validators_hash.as_ref()
+        );
+
+        for validator in &validators {
+            assert_eq!(
+                validator_address(&MockApi, &validator.pub_key).as_slice(),
+                validator.address.as_ref()
+            );
+        }
+    }
+
+    #[test]
+    fn commit_signatures_match_cometbft() {
+        let (signed_header, validators) = cometbft_block();
+        let chain_id = &signed_header.header.chain_id;
+        verify_commit(
+            &MockApi,
+            chain_id,
+            &signed_header.commit,
+            &validators,
+            &Fraction::TWO_THIRDS,
+        )
+        .unwrap();
+
+        // a signature over anything else doesn't verify, e.g. a different
+        // timestamp
+        let mut commit = signed_header.commit.clone();
+        commit.signatures[0].timestamp = commit.signatures[0].timestamp.plus_nanos(1);
+        assert!(verify_commit(
+            &MockApi,
+            chain_id,
+            &commit,
+            &validators,
+            &Fraction::TWO_THIRDS
+        )
+        .is_err());
+
+        // the client accepts the header, when updated from the block before
+        let time = signed_header.header.time;
+        let now = time.plus_seconds(60);
+        let mut storage = MockStorage::new();
+        create_client(
+            sudo_ctx(&mut storage, now),
+            ClientState {
+                latest_height: 9,
+                ..client_state(chain_id)
+            },
+            ConsensusState {
+                timestamp: Timestamp::from_nanos(time.nanos() - 1_000_000_000),
+                root: b"root-9".to_vec().into(),
+                next_validators_hash: hash_validator_set(&MockApi, &validators).to_vec().into(),
+            },
+        )
+        .unwrap();
+
+        let app_hash = signed_header.header.app_hash.clone();
+        update_client(sudo_ctx(&mut storage, now), Header {
+            signed_header,
+            validator_set: validators.clone(),
+            trusted_height: 9,
+            trusted_validators: validators,
+        })
+        .unwrap();
+        assert_eq!(CLIENT_STATE.load(&storage).unwrap().latest_height, 10);
+        assert_eq!(CONSENSUS_STATES.load(&storage, 10).unwrap().root, app_hash);
+    }
+}
@@ -1 +1,12 @@
+mod encoding;
+mod execute;
+#[cfg(not(feature = "library"))]
+mod exports;
+mod query;
+mod state;
+mod types;
+mod verify;
 
+#[cfg(not(feature = "library"))]
+pub use crate::exports::*;
+pub use crate::{encoding::*, execute::*, query::*, state::*, types::*, verify::*};
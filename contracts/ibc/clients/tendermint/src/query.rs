@@ -0,0 +1,134 @@
+use {
+    crate::{is_expired, ClientState, CLIENT_STATE, CONSENSUS_STATES, PROCESSED},
+    anyhow::ensure,
+    grug::{
+        from_json_value, to_json_vec, Binary, IbcClientStateResponse, IbcClientStatus,
+        ImmutableCtx, Json, StdResult, Timestamp,
+    },
+    grug_ics23::{sdk_specs, verify_merkle_membership, verify_merkle_non_membership, MerkleProof},
+};
+
+pub fn query_status(ctx: ImmutableCtx) -> StdResult<IbcClientStatus> {
+    let client_state = CLIENT_STATE.load(ctx.storage)?;
+    if client_state.frozen_height.is_some() {
+        return Ok(IbcClientStatus::Frozen);
+    }
+
+    let latest = CONSENSUS_STATES.load(ctx.storage, client_state.latest_height)?;
+    if is_expired(&client_state, latest.timestamp, ctx.block.timestamp) {
+        return Ok(IbcClientStatus::Expired);
+    }
+
+    Ok(IbcClientStatus::Active)
+}
+
+pub fn query_state(ctx: ImmutableCtx) -> StdResult<IbcClientStateResponse> {
+    let client_state = CLIENT_STATE.load(ctx.storage)?;
+    let consensus_state = CONSENSUS_STATES.load(ctx.storage, client_state.latest_height)?;
+    Ok(IbcClientStateResponse {
+        client_state: to_json_vec(&client_state)?.into(),
+        consensus_state: to_json_vec(&consensus_state)?.into(),
+    })
+}
+
+/// The counterparty's block time at the given height, as recorded in the
+/// consensus state.
+pub fn query_timestamp_at_height(ctx: ImmutableCtx, height: u64) -> StdResult<Timestamp> {
+    CONSENSUS_STATES
+        .load(ctx.storage, height)
+        .map(|consensus_state| consensus_state.timestamp)
+}
+
+/// Verify an ibc-go `MerkleProof` of the key-value pair in the counterparty's
+/// IBC store, at the given height.
+///
+/// The prefix is the name of the store in the counterparty's multistore where
+/// IBC state is kept, typically `ibc`. The proof is expected to be the
+/// Protobuf-encoded `MerkleProof`, as returned by the counterparty's
+/// `/store/{prefix}/key` ABCI query.
+pub fn verify_membership(
+    ctx: ImmutableCtx,
+    height: u64,
+    delay_time_period: u64,
+    delay_block_period: u64,
+    prefix: &[u8],
+    key: &[u8],
+    value: &[u8],
+    proof: Json,
+) -> anyhow::Result<bool> {
+    let client_state = CLIENT_STATE.load(ctx.storage)?;
+    let root = load_root(
+        &ctx,
+        &client_state,
+        height,
+        delay_time_period,
+        delay_block_period,
+    )?;
+    let proof = decode_proof(proof)?;
+    let keys = [prefix, key];
+
+    Ok(verify_merkle_membership(&sdk_specs(), &root, &proof, &keys, value).is_ok())
+}
+
+/// Verify an ibc-go `MerkleProof` of the key's absence from the counterparty's
+/// IBC store, at the given height.
+pub fn verify_non_membership(
+    ctx: ImmutableCtx,
+    height: u64,
+    delay_time_period: u64,
+    delay_block_period: u64,
+    prefix: &[u8],
+    key: &[u8],
+    proof: Json,
+) -> anyhow::Result<bool> {
+    let client_state = CLIENT_STATE.load(ctx.storage)?;
+    let root = load_root(
+        &ctx,
+        &client_state,
+        height,
+        delay_time_period,
+        delay_block_period,
+    )?;
+    let proof = decode_proof(proof)?;
+    let keys = [prefix, key];
+
+    Ok(verify_merkle_non_membership(&sdk_specs(), &root, &proof, &keys).is_ok())
+}
+
+/// Load the root of the consensus state at the given height, making sure the
+/// client isn't frozen, and the delay periods have passed since the consensus
+/// state was saved.
+///
+/// The delay time period is in nanoseconds, as in ibc-go.
+pub fn load_root(
+    ctx: &ImmutableCtx,
+    client_state: &ClientState,
+    height: u64,
+    delay_time_period: u64,
+    delay_block_period: u64,
+) -> anyhow::Result<Binary> {
+    ensure!(
+        client_state.frozen_height.is_none(),
+        "client is frozen; proofs can't be verified"
+    );
+
+    let consensus_state = CONSENSUS_STATES.load(ctx.storage, height)?;
+    let processed = PROCESSED.load(ctx.storage, height)?;
+
+    ensure!(
+        processed.time.plus_nanos(delay_time_period) <= ctx.block.timestamp,
+        "delay time period has not passed"
+    );
+
+    ensure!(
+        processed.height + delay_block_period <= ctx.block.height.number(),
+        "delay block period has not passed"
+    );
+
+    Ok(consensus_state.root)
+}
+
+fn decode_proof(proof: Json) -> anyhow::Result<MerkleProof> {
+    let bytes: Binary = from_json_value(proof)?;
+    Ok(MerkleProof::decode_bytes(&bytes)?)
+}
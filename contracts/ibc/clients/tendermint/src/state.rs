@@ -0,0 +1,12 @@
+use {
+    crate::{ClientState, ConsensusState, Processed},
+    grug::{Item, Map},
+};
+
+pub const CLIENT_STATE: Item<ClientState> = Item::new("client_state");
+
+/// Consensus states, indexed by counterparty block height.
+pub const CONSENSUS_STATES: Map<u64, ConsensusState> = Map::new("consensus_state");
+
+/// When each consensus state was saved, indexed by counterparty block height.
+pub const PROCESSED: Map<u64, Processed> = Map::new("processed");
@@ -0,0 +1,163 @@
+use grug::{grug_derive, Binary, Timestamp};
+
+// ---------------------------------- client -----------------------------------
+
+/// A fraction, used to express the trust level of a client.
+#[grug_derive(serde, borsh)]
+pub struct Fraction {
+    pub numerator: u64,
+    pub denominator: u64,
+}
+
+impl Fraction {
+    pub const TWO_THIRDS: Self = Self {
+        numerator: 2,
+        denominator: 3,
+    };
+}
+
+#[grug_derive(serde, borsh)]
+pub struct ClientState {
+    pub chain_id: String,
+    /// The fraction of the trusted validator set's voting power that must have
+    /// signed a non-adjacent header for it to be accepted. Must be within
+    /// `[1/3, 1]`; typically `1/3`.
+    pub trust_level: Fraction,
+    /// For how long, in seconds, a consensus state can be trusted after its
+    /// timestamp. Must be shorter than the unbonding period.
+    pub trusting_period: u64,
+    /// The counterparty chain's unbonding period, in seconds.
+    pub unbonding_period: u64,
+    /// How far ahead of our block time, in seconds, a header's time is allowed
+    /// to be.
+    pub max_clock_drift: u64,
+    /// The greatest height the client has a consensus state of.
+    ///
+    /// Heights are block heights on the counterparty chain. Revision numbers
+    /// are not supported: the chain ID must be one whose revision number is
+    /// zero, per [`revision_number`](crate::revision_number).
+    pub latest_height: u64,
+    /// The height of the header that proved misbehavior, if the client has
+    /// been frozen.
+    pub frozen_height: Option<u64>,
+}
+
+#[grug_derive(serde, borsh)]
+pub struct ConsensusState {
+    /// The counterparty block's time.
+    pub timestamp: Timestamp,
+    /// The counterparty block's app hash, against which proofs are verified.
+    pub root: Binary,
+    /// Hash of the validator set that is to sign the next block.
+    pub next_validators_hash: Binary,
+}
+
+/// Our chain's block time and height when a consensus state was saved, which
+/// is needed to enforce the delay periods of IBC connections.
+#[grug_derive(serde, borsh)]
+pub struct Processed {
+    pub time: Timestamp,
+    pub height: u64,
+}
+
+/// A header submitted to update the client.
+#[grug_derive(serde)]
+pub struct Header {
+    pub signed_header: SignedHeader,
+    /// The validator set that signed the header.
+    pub validator_set: Vec<Validator>,
+    /// The height of the consensus state that the header is verified against.
+    pub trusted_height: u64,
+    /// The validator set committed to in the trusted consensus state as the
+    /// next validator set.
+    pub trusted_validators: Vec<Validator>,
+}
+
+/// Evidence that two conflicting headers were signed by the counterparty's
+/// validators, either at the same height, or violating the monotonicity of
+/// block times.
+#[grug_derive(serde)]
+pub struct Misbehavior {
+    pub header_1: Header,
+    pub header_2: Header,
+}
+
+// -------------------------------- tendermint ---------------------------------
+
+#[grug_derive(serde)]
+pub struct Version {
+    pub block: u64,
+    pub app: u64,
+}
+
+#[grug_derive(serde)]
+pub struct PartSetHeader {
+    pub total: u32,
+    pub hash: Binary,
+}
+
+#[grug_derive(serde)]
+pub struct BlockId {
+    pub hash: Binary,
+    pub part_set_header: PartSetHeader,
+}
+
+/// A Tendermint block header.
+#[grug_derive(serde)]
+pub struct LightHeader {
+    pub version: Version,
+    pub chain_id: String,
+    pub height: u64,
+    pub time: Timestamp,
+    pub last_block_id: BlockId,
+    pub last_commit_hash: Binary,
+    pub data_hash: Binary,
+    pub validators_hash: Binary,
+    pub next_validators_hash: Binary,
+    pub consensus_hash: Binary,
+    pub app_hash: Binary,
+    pub last_results_hash: Binary,
+    pub evidence_hash: Binary,
+    pub proposer_address: Binary,
+}
+
+#[grug_derive(serde)]
+pub enum BlockIdFlag {
+    /// The validator didn't vote.
+    Absent,
+    /// The validator voted for the block.
+    Commit,
+    /// The validator voted for nil.
+    Nil,
+}
+
+#[grug_derive(serde)]
+pub struct CommitSig {
+    pub block_id_flag: BlockIdFlag,
+    pub validator_address: Binary,
+    pub timestamp: Timestamp,
+    pub signature: Binary,
+}
+
+#[grug_derive(serde)]
+pub struct Commit {
+    pub height: u64,
+    pub round: u32,
+    pub block_id: BlockId,
+    pub signatures: Vec<CommitSig>,
+}
+
+#[grug_derive(serde)]
+pub struct SignedHeader {
+    pub header: LightHeader,
+    pub commit: Commit,
+}
+
+/// A validator with an Ed25519 public key.
+#[grug_derive(serde)]
+pub struct Validator {
+    /// First 20 bytes of the SHA-256 hash of the public key.
+    pub address: Binary,
+    pub pub_key: Binary,
+    pub voting_power: u64,
+}
@@ -0,0 +1,246 @@
+use {
+    crate::{
+        hash_header, hash_validator_set, validator_address, vote_sign_bytes, BlockIdFlag,
+        ClientState, Commit, ConsensusState, Fraction, Header, Validator,
+    },
+    anyhow::{bail, ensure},
+    grug::{Api, Timestamp},
+    std::collections::BTreeSet,
+};
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+/// Whether a consensus state of the given timestamp can no longer be trusted.
+pub fn is_expired(client_state: &ClientState, timestamp: Timestamp, now: Timestamp) -> bool {
+    timestamp.plus_seconds(client_state.trusting_period) <= now
+}
+
+/// Return the revision number of a chain, which ibc-go derives from its chain
+/// ID: the number following the last dash if the chain ID is of the format
+/// `{name}-{number}`, such as `cosmoshub-4`, and zero otherwise.
+///
+/// `None` if the number doesn't fit in a `u64`.
+pub fn revision_number(chain_id: &str) -> Option<u64> {
+    let Some((name, number)) = chain_id.rsplit_once('-') else {
+        return Some(0);
+    };
+
+    if name.is_empty()
+        || name.ends_with(['-', '\n'])
+        || number.is_empty()
+        || number.starts_with('0')
+        || !number.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Some(0);
+    }
+
+    number.parse().ok()
+}
+
+/// Verify a header against a trusted consensus state, following the Tendermint
+/// light client protocol:
+///
+/// - if the header is adjacent to the trusted one, i.e. immediately follows it,
+///   its validator set must be the trusted state's next validator set, and it
+///   must have been signed by more than 2/3 of it;
+/// - otherwise ("skipping" verification), it must have been signed by at least
+///   the trust level of the trusted validators, in addition to more than 2/3 of
+///   its own validator set.
+pub fn verify_header(
+    api: &dyn Api,
+    client_state: &ClientState,
+    trusted: &ConsensusState,
+    header: &Header,
+    now: Timestamp,
+) -> anyhow::Result<()> {
+    let signed_header = &header.signed_header.header;
+    let commit = &header.signed_header.commit;
+
+    ensure!(
+        signed_header.chain_id == client_state.chain_id,
+        "chain id mismatch: expecting {}, got {}",
+        client_state.chain_id,
+        signed_header.chain_id
+    );
+
+    ensure!(
+        signed_header.height > header.trusted_height,
+        "header height {} must be greater than trusted height {}",
+        signed_header.height,
+        header.trusted_height
+    );
+
+    ensure!(
+        !is_expired(client_state, trusted.timestamp, now),
+        "trusted consensus state at height {} has expired",
+        header.trusted_height
+    );
+
+    ensure!(
+        signed_header.time > trusted.timestamp,
+        "header time must be after the trusted consensus state's time"
+    );
+
+    ensure!(
+        signed_header.time.nanos() < now.nanos() + client_state.max_clock_drift * NANOS_PER_SECOND,
+        "header time is too far in the future"
+    );
+
+    ensure!(
+        trusted.next_validators_hash.as_ref()
+            == hash_validator_set(api, &header.trusted_validators),
+        "trusted validators don't match the trusted consensus state"
+    );
+
+    ensure!(
+        signed_header.validators_hash.as_ref() == hash_validator_set(api, &header.validator_set),
+        "validator set doesn't match the header"
+    );
+
+    ensure!(
+        commit.height == signed_header.height,
+        "commit height {} doesn't match header height {}",
+        commit.height,
+        signed_header.height
+    );
+
+    ensure!(
+        commit.block_id.hash.as_ref() == hash_header(api, signed_header),
+        "commit is not for the header"
+    );
+
+    if signed_header.height == header.trusted_height + 1 {
+        ensure!(
+            signed_header.validators_hash == trusted.next_validators_hash,
+            "adjacent header's validator set doesn't match the trusted next validator set"
+        );
+    } else {
+        verify_commit(
+            api,
+            &client_state.chain_id,
+            commit,
+            &header.trusted_validators,
+            &client_state.trust_level,
+        )?;
+    }
+
+    verify_commit(
+        api,
+        &client_state.chain_id,
+        commit,
+        &header.validator_set,
+        &Fraction::TWO_THIRDS,
+    )
+}
+
+/// Verify that validators of more than the given fraction of the validator
+/// set's total voting power have signed the commit.
+///
+/// Signatures from validators not in the set are ignored, so this can be used
+/// to check a commit against a validator set other than the one that produced
+/// the block, as is done in skipping verification.
+pub fn verify_commit(
+    api: &dyn Api,
+    chain_id: &str,
+    commit: &Commit,
+    validators: &[Validator],
+    threshold: &Fraction,
+) -> anyhow::Result<()> {
+    let total_power = validators
+        .iter()
+        .map(|validator| validator.voting_power as u128)
+        .sum::<u128>();
+    let is_enough = |power: u128| {
+        power * threshold.denominator as u128 > total_power * threshold.numerator as u128
+    };
+
+    let mut seen = BTreeSet::new();
+    let mut tallied_power = 0;
+    let mut msgs = vec![];
+    let mut sigs = vec![];
+    let mut pks = vec![];
+
+    for sig in &commit.signatures {
+        if sig.block_id_flag != BlockIdFlag::Commit {
+            continue;
+        }
+
+        let Some(validator) = validators
+            .iter()
+            .find(|validator| validator.address == sig.validator_address)
+        else {
+            continue;
+        };
+
+        ensure!(
+            validator.address.as_ref() == validator_address(api, &validator.pub_key),
+            "validator address {} doesn't match its public key",
+            validator.address
+        );
+
+        if !seen.insert(&validator.address) {
+            bail!("double vote from validator {}", validator.address);
+        }
+
+        msgs.push(vote_sign_bytes(
+            chain_id,
+            commit.height,
+            commit.round,
+            &commit.block_id,
+            sig.timestamp,
+        ));
+        sigs.push(sig.signature.as_ref());
+        pks.push(validator.pub_key.as_ref());
+        tallied_power += validator.voting_power as u128;
+
+        // No need to verify more signatures than necessary.
+        if is_enough(tallied_power) {
+            break;
+        }
+    }
+
+    ensure!(
+        is_enough(tallied_power),
+        "insufficient voting power: got {tallied_power}, total {total_power}, need more than {}/{}",
+        threshold.numerator,
+        threshold.denominator
+    );
+
+    let msgs = msgs.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    api.ed25519_batch_verify(&msgs, &sigs, &pks)?;
+
+    Ok(())
+}
+
+/// Check whether two headers, each of which is valid on its own, together
+/// prove that the counterparty chain has misbehaved.
+///
+/// This is the case if they are either for the same height but different
+/// blocks, or if the higher header isn't later than the lower one in time.
+pub fn check_misbehavior(
+    api: &dyn Api,
+    header_1: &Header,
+    header_2: &Header,
+) -> anyhow::Result<()> {
+    let header_1 = &header_1.signed_header.header;
+    let header_2 = &header_2.signed_header.header;
+
+    ensure!(
+        header_1.height >= header_2.height,
+        "header 1 height must be greater than or equal to header 2 height"
+    );
+
+    if header_1.height == header_2.height {
+        ensure!(
+            hash_header(api, header_1) != hash_header(api, header_2),
+            "headers are for the same block; this is not misbehavior"
+        );
+    } else {
+        ensure!(
+            header_1.time <= header_2.time,
+            "headers are monotonic in time; this is not misbehavior"
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use grug::{grug_derive, hash};
+
+/// The trace of a denom that has been sent through one or more IBC channels.
+///
+/// The path consists of the port and channel IDs of each hop, the last one
+/// first, e.g. the trace `transfer/channel-1/transfer/channel-0/uatom` has the
+/// path `transfer/channel-1/transfer/channel-0` and the base denom `uatom`.
+#[grug_derive(serde, borsh)]
+pub struct DenomTrace {
+    pub path: String,
+    pub base_denom: String,
+}
+
+impl DenomTrace {
+    /// Split a full denom trace into the path and the base denom.
+    ///
+    /// Since base denoms may themselves contain slashes (e.g. those created by
+    /// the token factory), the path is taken as the longest prefix made of port
+    /// and channel ID pairs, where channel IDs are of the form `channel-{n}`.
+    pub fn parse(full_path: &str) -> Self {
+        let segments = full_path.split('/').collect::<Vec<_>>();
+
+        let mut i = 0;
+        while i + 2 < segments.len() && segments[i + 1].starts_with("channel-") {
+            i += 2;
+        }
+
+        Self {
+            path: segments[..i].join("/"),
+            base_denom: segments[i..].join("/"),
+        }
+    }
+
+    pub fn full_path(&self) -> String {
+        if self.path.is_empty() {
+            self.base_denom.clone()
+        } else {
+            format!("{}/{}", self.path, self.base_denom)
+        }
+    }
+
+    /// The hash of the full trace, in uppercase hex, as used in the name of the
+    /// voucher denom.
+    pub fn hash(&self) -> String {
+        hash(self.full_path()).to_string().to_uppercase()
+    }
+
+    /// The name of the denom on this chain: the base denom if the tokens are
+    /// native to this chain, or `ibc/{hash}` if they are IBC vouchers.
+    pub fn local_denom(&self) -> String {
+        if self.path.is_empty() {
+            self.base_denom.clone()
+        } else {
+            format!("ibc/{}", self.hash())
+        }
+    }
+
+    /// Return whether the last hop of the tokens was through the given port
+    /// and channel.
+    pub fn has_prefix(&self, port_id: &str, channel_id: &str) -> bool {
+        let prefix = format!("{port_id}/{channel_id}");
+        self.path == prefix || self.path.starts_with(&format!("{prefix}/"))
+    }
+
+    /// Add a hop through the given port and channel to the trace.
+    pub fn add_prefix(&self, port_id: &str, channel_id: &str) -> Self {
+        let prefix = format!("{port_id}/{channel_id}");
+        Self {
+            path: if self.path.is_empty() {
+                prefix
+            } else {
+                format!("{prefix}/{}", self.path)
+            },
+            base_denom: self.base_denom.clone(),
+        }
+    }
+
+    /// Remove the last hop from the trace. The caller must have checked that
+    /// the trace has a prefix, using [`DenomTrace::has_prefix`].
+    pub fn remove_prefix(&self) -> Self {
+        let mut segments = self.path.splitn(3, '/').skip(2);
+        Self {
+            path: segments.next().unwrap_or_default().to_string(),
+            base_denom: self.base_denom.clone(),
+        }
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_denom_traces() {
+        for (full_path, path, base_denom) in [
+            ("uatom", "", "uatom"),
+            ("transfer/channel-0/uatom", "transfer/channel-0", "uatom"),
+            (
+                "transfer/channel-1/transfer/channel-0/uatom",
+                "transfer/channel-1/transfer/channel-0",
+                "uatom",
+            ),
+            ("factory/0x1234/ufoo", "", "factory/0x1234/ufoo"),
+            (
+                "transfer/channel-0/factory/0x1234/ufoo",
+                "transfer/channel-0",
+                "factory/0x1234/ufoo",
+            ),
+            // the base denom can't be empty
+            ("transfer/channel-0", "", "transfer/channel-0"),
+        ] {
+            let trace = DenomTrace::parse(full_path);
+            assert_eq!(trace, DenomTrace {
+                path: path.into(),
+                base_denom: base_denom.into(),
+            });
+            assert_eq!(trace.full_path(), full_path);
+        }
+    }
+
+    #[test]
+    fn adding_and_removing_prefixes() {
+        let trace = DenomTrace::parse("uatom");
+        assert_eq!(trace.local_denom(), "uatom");
+        assert!(!trace.has_prefix("transfer", "channel-0"));
+
+        let trace = trace.add_prefix("transfer", "channel-0");
+        assert_eq!(trace.full_path(), "transfer/channel-0/uatom");
+        // this matches the denom in ibc-go
+        assert_eq!(
+            trace.local_denom(),
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+        );
+
+        let trace = trace.add_prefix("transfer", "channel-1");
+        assert!(trace.has_prefix("transfer", "channel-1"));
+        assert!(!trace.has_prefix("transfer", "channel-0"));
+        assert!(!trace.has_prefix("transfer", "channel-10"));
+
+        let trace = trace.remove_prefix();
+        assert_eq!(trace.full_path(), "transfer/channel-0/uatom");
+        assert_eq!(trace.remove_prefix(), DenomTrace::parse("uatom"));
+    }
+}
@@ -0,0 +1,276 @@
+use {
+    crate::{DenomTrace, FungibleTokenPacketData, DENOM_TRACES, ESCROWS},
+    anyhow::{anyhow, ensure},
+    grug::{
+        from_json_slice, to_json_value, to_json_vec, Addr, Binary, Coins, IbcAcknowledgement,
+        IbcChannelConnectMsg, IbcChannelEnd, IbcChannelOpenMsg, IbcChannelOrder, IbcPacket,
+        IbcTimeout, Message, MutableCtx, Number, QuerierWrapper, Response, StdResult, Storage,
+        SudoCtx, Uint128,
+    },
+    grug_bank::ExecuteMsg as BankExecuteMsg,
+    std::str::FromStr,
+};
+
+/// The acknowledgement data of a successfully received packet, as defined by
+/// ICS-20.
+const SUCCESS_ACK: &[u8] = &[1];
+
+/// The version of the ICS-20 protocol that this contract implements. Channels
+/// must use it, and be unordered.
+pub const ICS20_VERSION: &str = "ics20-1";
+
+pub fn open_channel(msg: IbcChannelOpenMsg) -> anyhow::Result<Response> {
+    let (channel_id, channel) = match msg {
+        IbcChannelOpenMsg::Init {
+            channel_id,
+            channel,
+        } => (channel_id, channel),
+        IbcChannelOpenMsg::Try {
+            channel_id,
+            channel,
+            counterparty_version,
+        } => {
+            ensure!(
+                counterparty_version == ICS20_VERSION,
+                "invalid counterparty version: expecting `{ICS20_VERSION}`, got `{counterparty_version}`"
+            );
+            (channel_id, channel)
+        },
+    };
+
+    validate_channel(&channel)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "open_channel")
+        .add_attribute("channel_id", channel_id))
+}
+
+pub fn connect_channel(msg: IbcChannelConnectMsg) -> anyhow::Result<Response> {
+    let (IbcChannelConnectMsg::Ack {
+        channel_id,
+        channel,
+    }
+    | IbcChannelConnectMsg::Confirm {
+        channel_id,
+        channel,
+    }) = msg;
+
+    // On `Ack`, the version is the one the counterparty agreed to, which may
+    // differ from what was proposed in `Init`.
+    validate_channel(&channel)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "connect_channel")
+        .add_attribute("channel_id", channel_id))
+}
+
+fn validate_channel(channel: &IbcChannelEnd) -> anyhow::Result<()> {
+    ensure!(
+        channel.ordering == IbcChannelOrder::Unordered,
+        "invalid channel ordering: expecting `Unordered`, got `{:?}`",
+        channel.ordering
+    );
+    ensure!(
+        channel.version == ICS20_VERSION,
+        "invalid channel version: expecting `{ICS20_VERSION}`, got `{}`",
+        channel.version
+    );
+    Ok(())
+}
+
+pub fn transfer(
+    ctx: MutableCtx,
+    channel_id: String,
+    receiver: String,
+    timeout: IbcTimeout,
+    memo: Option<String>,
+) -> anyhow::Result<Response> {
+    let coin = ctx.funds.one_coin()?;
+    let (denom, amount) = (coin.denom.clone(), *coin.amount);
+
+    let trace = match denom.strip_prefix("ibc/") {
+        Some(hash) => DENOM_TRACES
+            .may_load(ctx.storage, hash)?
+            .ok_or_else(|| anyhow!("unknown IBC denom `{denom}`"))?,
+        None => DenomTrace::parse(&denom),
+    };
+
+    // If the tokens last came from the counterparty through this same channel,
+    // they're going back to where they came from, so the vouchers are burned.
+    // Otherwise, this chain is the source, and the tokens are held in escrow
+    // until they come back.
+    let port_id = ctx.contract.to_string();
+    let mut res = Response::new();
+    if trace.has_prefix(&port_id, &channel_id) {
+        res = res.add_message(bank_msg(ctx.querier, BankExecuteMsg::Burn {
+            from: ctx.contract.clone(),
+            denom: denom.clone(),
+            amount,
+        })?);
+    } else {
+        increase_escrow(ctx.storage, &channel_id, &denom, amount)?;
+    }
+
+    let data = FungibleTokenPacketData {
+        amount,
+        denom: trace.full_path(),
+        memo,
+        receiver: receiver.clone(),
+        sender: ctx.sender.to_string(),
+    };
+
+    Ok(res
+        .add_message(Message::SendPacket {
+            channel_id: channel_id.clone(),
+            data: to_json_vec(&data)?.into(),
+            timeout,
+        })
+        .add_attribute("method", "transfer")
+        .add_attribute("channel_id", channel_id)
+        .add_attribute("sender", ctx.sender)
+        .add_attribute("receiver", receiver)
+        .add_attribute("denom", denom)
+        .add_attribute("amount", amount))
+}
+
+pub fn receive_packet(ctx: SudoCtx, packet: IbcPacket) -> anyhow::Result<Response> {
+    let data: FungibleTokenPacketData = from_json_slice(&packet.data)?;
+    let receiver = Addr::from_str(&data.receiver)?;
+
+    ensure!(!data.amount.is_zero(), "amount can't be zero");
+
+    // If the tokens last left this chain through the channel they're coming
+    // back from, they're released from escrow. Otherwise, the counterparty is
+    // the source, and vouchers are minted.
+    let trace = DenomTrace::parse(&data.denom);
+    let (res, denom) = if trace.has_prefix(&packet.source_port, &packet.source_channel) {
+        let denom = trace.remove_prefix().local_denom();
+
+        decrease_escrow(
+            ctx.storage,
+            &packet.destination_channel,
+            &denom,
+            data.amount,
+        )?;
+
+        let res = Response::new().add_message(Message::Transfer {
+            to: receiver.clone(),
+            coins: Coins::new_one(denom.clone(), data.amount),
+        });
+
+        (res, denom)
+    } else {
+        let trace = trace.add_prefix(&packet.destination_port, &packet.destination_channel);
+        let denom = trace.local_denom();
+
+        let hash = trace.hash();
+        if !DENOM_TRACES.has(ctx.storage, &hash) {
+            DENOM_TRACES.save(ctx.storage, &hash, &trace)?;
+        }
+
+        let res = Response::new().add_message(bank_msg(ctx.querier, BankExecuteMsg::Mint {
+            to: receiver.clone(),
+            denom: denom.clone(),
+            amount: data.amount,
+        })?);
+
+        (res, denom)
+    };
+
+    Ok(res
+        .set_data(SUCCESS_ACK.to_vec())
+        .add_attribute("method", "receive_packet")
+        .add_attribute("sender", data.sender)
+        .add_attribute("receiver", receiver)
+        .add_attribute("denom", denom)
+        .add_attribute("amount", data.amount))
+}
+
+pub fn acknowledge_packet(
+    ctx: SudoCtx,
+    packet: IbcPacket,
+    acknowledgement: Binary,
+) -> anyhow::Result<Response> {
+    match from_json_slice(&acknowledgement)? {
+        IbcAcknowledgement::Result(_) => Ok(Response::new()
+            .add_attribute("method", "acknowledge_packet")
+            .add_attribute("success", true)),
+        IbcAcknowledgement::Error(err) => Ok(refund(ctx, &packet)?
+            .add_attribute("method", "acknowledge_packet")
+            .add_attribute("success", false)
+            .add_attribute("error", err)),
+    }
+}
+
+pub fn timeout_packet(ctx: SudoCtx, packet: IbcPacket) -> anyhow::Result<Response> {
+    Ok(refund(ctx, &packet)?.add_attribute("method", "timeout_packet"))
+}
+
+/// Give the tokens of a packet that failed to be received back to the sender,
+/// reverting what was done when sending it: the vouchers are minted again if
+/// they were burned, or the tokens are released from escrow.
+fn refund(ctx: SudoCtx, packet: &IbcPacket) -> anyhow::Result<Response> {
+    let data: FungibleTokenPacketData = from_json_slice(&packet.data)?;
+    let sender = Addr::from_str(&data.sender)?;
+
+    let trace = DenomTrace::parse(&data.denom);
+    let denom = trace.local_denom();
+    let msg = if trace.has_prefix(&packet.source_port, &packet.source_channel) {
+        bank_msg(ctx.querier, BankExecuteMsg::Mint {
+            to: sender.clone(),
+            denom: denom.clone(),
+            amount: data.amount,
+        })?
+    } else {
+        decrease_escrow(ctx.storage, &packet.source_channel, &denom, data.amount)?;
+        Message::Transfer {
+            to: sender.clone(),
+            coins: Coins::new_one(denom.clone(), data.amount),
+        }
+    };
+
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("refund_receiver", sender)
+        .add_attribute("refund_denom", denom)
+        .add_attribute("refund_amount", data.amount))
+}
+
+/// Mint or burn vouchers through the bank contract. For the bank to accept this,
+/// the `ibc` denom namespace must be assigned to this contract.
+fn bank_msg(querier: QuerierWrapper, msg: BankExecuteMsg) -> StdResult<Message> {
+    Ok(Message::Execute {
+        contract: querier.query_info()?.config.bank,
+        msg: to_json_value(&msg)?,
+        funds: Coins::new_empty(),
+    })
+}
+
+fn increase_escrow(
+    storage: &mut dyn Storage,
+    channel_id: &str,
+    denom: &str,
+    amount: Uint128,
+) -> StdResult<Option<Uint128>> {
+    ESCROWS.update(storage, (channel_id, denom), |escrow| {
+        let escrow = escrow.unwrap_or_default().checked_add(amount)?;
+        Ok(Some(escrow))
+    })
+}
+
+fn decrease_escrow(
+    storage: &mut dyn Storage,
+    channel_id: &str,
+    denom: &str,
+    amount: Uint128,
+) -> StdResult<Option<Uint128>> {
+    ESCROWS.update(storage, (channel_id, denom), |escrow| {
+        let escrow = escrow.unwrap_or_default().checked_sub(amount)?;
+        // if the escrow is reduced to zero, delete it, to save disk space
+        if escrow.is_zero() {
+            Ok(None)
+        } else {
+            Ok(Some(escrow))
+        }
+    })
+}
@@ -0,0 +1,79 @@
+use {
+    crate::{
+        acknowledge_packet, connect_channel, open_channel, query_denom_trace, query_denom_traces,
+        query_escrow, receive_packet, timeout_packet, transfer, ExecuteMsg, QueryMsg,
+    },
+    anyhow::bail,
+    grug::{
+        grug_export, to_json_value, Binary, Empty, IbcChannelConnectMsg, IbcChannelOpenMsg,
+        IbcPacket, ImmutableCtx, Json, MutableCtx, Response, StdResult, SudoCtx,
+    },
+};
+
+#[grug_export]
+pub fn instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+#[grug_export]
+pub fn receive(_ctx: MutableCtx) -> anyhow::Result<Response> {
+    // Tokens are only expected to be sent along with a transfer. Throw an error
+    // to revert the transfer.
+    bail!("tokens can only be sent to this contract through `ExecuteMsg::Transfer`");
+}
+
+#[grug_export]
+pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
+    match msg {
+        ExecuteMsg::Transfer {
+            channel_id,
+            receiver,
+            timeout,
+            memo,
+        } => transfer(ctx, channel_id, receiver, timeout, memo),
+    }
+}
+
+#[grug_export]
+pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
+    match msg {
+        QueryMsg::DenomTrace { hash } => to_json_value(&query_denom_trace(ctx.storage, hash)?),
+        QueryMsg::DenomTraces { start_after, limit } => {
+            to_json_value(&query_denom_traces(ctx.storage, start_after, limit)?)
+        },
+        QueryMsg::Escrow {
+            channel_id,
+            start_after,
+            limit,
+        } => to_json_value(&query_escrow(ctx.storage, channel_id, start_after, limit)?),
+    }
+}
+
+#[grug_export]
+pub fn ibc_channel_open(_ctx: SudoCtx, msg: IbcChannelOpenMsg) -> anyhow::Result<Response> {
+    open_channel(msg)
+}
+
+#[grug_export]
+pub fn ibc_channel_connect(_ctx: SudoCtx, msg: IbcChannelConnectMsg) -> anyhow::Result<Response> {
+    connect_channel(msg)
+}
+
+#[grug_export]
+pub fn ibc_packet_receive(ctx: SudoCtx, packet: IbcPacket) -> anyhow::Result<Response> {
+    receive_packet(ctx, packet)
+}
+
+#[grug_export]
+pub fn ibc_packet_ack(
+    ctx: SudoCtx,
+    packet: IbcPacket,
+    acknowledgement: Binary,
+) -> anyhow::Result<Response> {
+    acknowledge_packet(ctx, packet, acknowledgement)
+}
+
+#[grug_export]
+pub fn ibc_packet_timeout(ctx: SudoCtx, packet: IbcPacket) -> anyhow::Result<Response> {
+    timeout_packet(ctx, packet)
+}
@@ -0,0 +1,11 @@
+mod denom;
+mod execute;
+#[cfg(not(feature = "library"))]
+mod exports;
+mod query;
+mod state;
+mod types;
+
+#[cfg(not(feature = "library"))]
+pub use crate::exports::*;
+pub use crate::{denom::*, execute::*, query::*, state::*, types::*};
@@ -0,0 +1,39 @@
+use {
+    crate::{DenomTrace, DENOM_TRACES, ESCROWS},
+    grug::{Bound, Coins, Order, StdResult, Storage},
+    std::collections::BTreeMap,
+};
+
+pub const DEFAULT_PAGE_LIMIT: u32 = 30;
+
+pub fn query_denom_trace(storage: &dyn Storage, hash: String) -> StdResult<DenomTrace> {
+    DENOM_TRACES.load(storage, &hash)
+}
+
+pub fn query_denom_traces(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<BTreeMap<String, DenomTrace>> {
+    let start = start_after.as_deref().map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
+    DENOM_TRACES
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+pub fn query_escrow(
+    storage: &dyn Storage,
+    channel_id: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Coins> {
+    let start = start_after.as_deref().map(Bound::Exclusive);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
+    let mut iter = ESCROWS
+        .prefix(&channel_id)
+        .range(storage, start, None, Order::Ascending)
+        .take(limit);
+    Coins::from_iter_unchecked(&mut iter)
+}
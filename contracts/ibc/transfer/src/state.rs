@@ -0,0 +1,16 @@
+use {
+    crate::DenomTrace,
+    grug::{Map, Uint128},
+};
+
+/// Traces of the IBC voucher denoms minted by this contract, indexed by the
+/// hashes in their `ibc/{hash}` names.
+pub const DENOM_TRACES: Map<&str, DenomTrace> = Map::new("dt");
+
+/// Amounts of tokens held in escrow, indexed first by the channels they were
+/// sent through, then by denoms.
+///
+/// Tokens escrowed for one channel can only be released by packets coming from
+/// that same channel, so that a misbehaving counterparty can't drain tokens
+/// sent to other chains.
+pub const ESCROWS: Map<(&str, &str), Uint128> = Map::new("e");
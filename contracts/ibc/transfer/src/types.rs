@@ -0,0 +1,52 @@
+use grug::{grug_derive, IbcTimeout, Uint128};
+
+/// The data of an ICS-20 packet.
+///
+/// The fields are in alphabetical order, so that the JSON encoding matches the
+/// one of ibc-go, which sorts keys.
+#[grug_derive(serde)]
+pub struct FungibleTokenPacketData {
+    pub amount: Uint128,
+    /// The full trace of the denom being sent, as seen by the sending chain,
+    /// e.g. `transfer/channel-0/uatom`.
+    pub denom: String,
+    pub memo: Option<String>,
+    pub receiver: String,
+    pub sender: String,
+}
+
+#[grug_derive(serde)]
+pub enum ExecuteMsg {
+    /// Send the tokens attached to the message to a receiver on the
+    /// counterparty chain through the given channel.
+    ///
+    /// Exactly one denom must be attached. Tokens native to this chain are
+    /// held in escrow until they come back; vouchers of tokens coming from the
+    /// counterparty are burned.
+    Transfer {
+        channel_id: String,
+        receiver: String,
+        timeout: IbcTimeout,
+        memo: Option<String>,
+    },
+}
+
+#[grug_derive(serde)]
+pub enum QueryMsg {
+    /// Query the trace of an IBC voucher denom, by the hash in its name.
+    /// Returns: [`DenomTrace`](crate::DenomTrace)
+    DenomTrace { hash: String },
+    /// Enumerate the traces of all IBC voucher denoms.
+    /// Returns: `BTreeMap<String, DenomTrace>`
+    DenomTraces {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Query the tokens held in escrow for the given channel.
+    /// Returns: `Coins`
+    Escrow {
+        channel_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
@@ -1,8 +1,11 @@
 use {
-    crate::{App, AppError, AppResult, Db, Vm},
-    grug_types::{Attribute, BlockInfo, Event, Hash, Timestamp, Uint64, GENESIS_BLOCK_HASH},
+    crate::{App, AppError, Db, TxOutcome, Vm},
+    grug_types::{
+        to_json_string, to_json_vec, Attribute, BlockInfo, Event, Hash, Timestamp, Uint64,
+        GENESIS_BLOCK_HASH,
+    },
     prost::bytes::Bytes,
-    std::{any::type_name, net::ToSocketAddrs},
+    std::net::ToSocketAddrs,
     tendermint_abci::{Application, Error as ABCIError, ServerBuilder},
     tendermint_proto::{
         abci::{
@@ -125,7 +128,7 @@ where
                 Ok((value, proof)) => {
                     let proof_ops = proof.map(|proof| ProofOps {
                         ops: vec![ProofOp {
-                            r#type: type_name::<DB::Proof>().into(),
+                            r#type: DB::ICS23_PROOF_TYPE.into(),
                             key: req.data.into(),
                             data: proof,
                         }],
@@ -145,10 +148,23 @@ where
                     ..Default::default()
                 },
             },
+            "/simulate" => match self.do_simulate_raw(&req.data) {
+                Ok(res) => ResponseQuery {
+                    code: 0,
+                    value: res.into(),
+                    ..Default::default()
+                },
+                Err(err) => ResponseQuery {
+                    code: 1,
+                    codespace: "simulate".into(),
+                    log: err.to_string(),
+                    ..Default::default()
+                },
+            },
             unknown => ResponseQuery {
                 code: 1,
                 codespace: "app".into(),
-                log: format!("unknown path `{unknown}`; must be `/app` or `/store`"),
+                log: format!("unknown path `{unknown}`; must be `/app`, `/store`, or `/simulate`"),
                 ..Default::default()
             },
         }
@@ -174,18 +190,44 @@ fn from_tm_hash(bytes: Bytes) -> Hash {
         .expect("incorrect block hash length")
 }
 
-fn to_tm_tx_result(tx_result: AppResult<Vec<Event>>) -> ExecTxResult {
-    match tx_result {
-        Ok(events) => ExecTxResult {
+fn to_tm_tx_result(outcome: TxOutcome) -> ExecTxResult {
+    // the state diff, if tracked, goes to the `info` field. unlike `data`, this
+    // field isn't included in the block's results hash, so it's ok for nodes to
+    // disagree on it (i.e. some nodes track state diffs while others don't).
+    let info = outcome
+        .state_diff
+        .map(|diff| {
+            to_json_string(&diff).unwrap_or_else(|err| {
+                panic!("failed to serialize state diff: {err}");
+            })
+        })
+        .unwrap_or_default();
+
+    match outcome.result {
+        // the data returned by each message goes to the `data` field as a JSON
+        // array, in the same order as the messages.
+        Ok(res) => ExecTxResult {
             code: 0,
-            events: events.into_iter().map(to_tm_event).collect(),
+            data: to_json_vec(&res.data)
+                .unwrap_or_else(|err| {
+                    panic!("failed to serialize tx data: {err}");
+                })
+                .into(),
+            events: res.events.into_iter().map(to_tm_event).collect(),
+            info,
             ..Default::default()
         },
-        Err(err) => ExecTxResult {
-            code: 1,                     // TODO: custom error code
-            codespace: "tx".to_string(), // TODO: custom error codespace
-            log: err.to_string(),
-            ..Default::default()
+        // the code and codespace tell which party is at fault. the log contains
+        // the full structured error as JSON, so that clients can decode it.
+        Err(err) => {
+            let err = err.into_execution_error();
+            ExecTxResult {
+                code: err.category.code(),
+                codespace: err.category.as_str().to_string(),
+                log: to_json_string(&err).unwrap_or_else(|_| err.to_string()),
+                info,
+                ..Default::default()
+            }
         },
     }
 }
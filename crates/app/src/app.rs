@@ -2,16 +2,23 @@
 use tracing::{debug, info};
 use {
     crate::{
-        do_after_block, do_after_tx, do_before_block, do_before_tx, do_execute, do_instantiate,
-        do_migrate, do_set_config, do_transfer, do_upload, query_account, query_accounts,
-        query_balance, query_balances, query_code, query_codes, query_info, query_supplies,
-        query_supply, query_wasm_raw, query_wasm_smart, AppError, AppResult, Buffer, Db, Shared,
-        Vm, CHAIN_ID, CONFIG, LAST_FINALIZED_BLOCK,
+        decode_tx_state_diff, do_acknowledge_packet, do_after_block, do_after_tx, do_before_block,
+        do_before_tx, do_chan_open_ack, do_chan_open_confirm, do_chan_open_init, do_chan_open_try,
+        do_conn_open_ack, do_conn_open_confirm, do_conn_open_init, do_conn_open_try,
+        do_create_client, do_execute, do_instantiate, do_migrate, do_recv_packet, do_send_packet,
+        do_set_config, do_submit_misbehavior, do_timeout_packet, do_transfer, do_update_client,
+        do_upload, query_account, query_accounts, query_balance, query_balances, query_channel,
+        query_client, query_code, query_codes, query_connection, query_info, query_metadata,
+        query_metadatas, query_supplies, query_supply, query_wasm_raw, query_wasm_scan,
+        query_wasm_smart, record_trace, AppError, AppResult, Db, TxStore, Vm, CHAIN_ID, CONFIG,
+        LAST_FINALIZED_BLOCK,
     },
     grug_types::{
-        from_json_slice, hash, to_json_vec, Addr, BlockInfo, Event, GenesisState, Hash, Message,
-        Permission, QueryRequest, QueryResponse, StdResult, Storage, Tx, GENESIS_SENDER,
+        from_json_slice, hash, to_json_vec, Addr, BlockInfo, Event, GenericResult, GenesisState,
+        Hash, Message, MsgResponse, Permission, QueryRequest, QueryResponse, SimulateResponse,
+        StdResult, Storage, Trace, Tx, TxResponse, TxStateDiff, GENESIS_SENDER,
     },
+    prost::Message as _,
 };
 
 /// The ABCI application.
@@ -22,14 +29,48 @@ use {
 pub struct App<DB, VM> {
     db: DB,
     vm: VM,
+    /// Whether to record the state changes made by each transaction, and
+    /// return them in `do_finalize_block`. Off by default.
+    track_state_diffs: bool,
 }
 
 impl<DB, VM> App<DB, VM> {
     pub fn new(db: DB, vm: VM) -> Self {
-        Self { db, vm }
+        Self {
+            db,
+            vm,
+            track_state_diffs: false,
+        }
+    }
+
+    /// Enable or disable recording the state changes made by each transaction.
+    ///
+    /// This is useful for debugging and for indexers, but it involves reading
+    /// the old value of every key written, so it's not recommended for nodes
+    /// that don't need it. It doesn't otherwise affect execution: in particular,
+    /// it takes no savepoint, so transactions can nest submessages equally deep
+    /// either way.
+    pub fn with_state_diffs(mut self, enabled: bool) -> Self {
+        self.track_state_diffs = enabled;
+        self
     }
 }
 
+/// The outcome of processing a single transaction in `do_finalize_block`.
+#[derive(Debug)]
+pub struct TxOutcome {
+    /// Events emitted and data returned if the transaction succeeded, or the
+    /// error if it failed.
+    pub result: AppResult<TxResponse>,
+    /// State changes made by the transaction, in the order they were made, for
+    /// each stage of the transaction. `None` if state diff tracking isn't
+    /// enabled.
+    ///
+    /// Note that a failed transaction may still have made changes, e.g. in the
+    /// sender account's `before_tx` call.
+    pub state_diff: Option<TxStateDiff>,
+}
+
 impl<DB, VM> App<DB, VM>
 where
     DB: Db,
@@ -52,7 +93,7 @@ where
         block: BlockInfo,
         genesis_state: GenesisState,
     ) -> AppResult<Hash> {
-        let mut buffer = Shared::new(Buffer::new(self.db.state_storage(None), None));
+        let mut store = TxStore::new(Box::new(self.db.state_storage(None)));
 
         // make sure the block height during InitChain is zero. this is necessary
         // to ensure that block height always matches the BaseStore version.
@@ -64,9 +105,9 @@ where
         }
 
         // save the config and genesis block. some genesis messages may need it
-        CHAIN_ID.save(&mut buffer, &chain_id)?;
-        CONFIG.save(&mut buffer, &genesis_state.config)?;
-        LAST_FINALIZED_BLOCK.save(&mut buffer, &block)?;
+        CHAIN_ID.save(&mut store, &chain_id)?;
+        CONFIG.save(&mut store, &genesis_state.config)?;
+        LAST_FINALIZED_BLOCK.save(&mut store, &block)?;
 
         // loop through genesis messages and execute each one.
         // it's expected that genesis messages should all successfully execute.
@@ -78,7 +119,7 @@ where
 
             process_msg(
                 self.vm.clone(),
-                Box::new(buffer.clone()),
+                store.clone(),
                 block.clone(),
                 GENESIS_SENDER,
                 msg,
@@ -86,7 +127,7 @@ where
         }
 
         // persist the state changes to disk
-        let (_, pending) = buffer.disassemble().disassemble();
+        let (_, pending) = store.disassemble();
         let (version, root_hash) = self.db.flush_and_commit(pending)?;
 
         // BaseStore version should be 0
@@ -113,7 +154,7 @@ where
         &self,
         block: BlockInfo,
         raw_txs: Vec<impl AsRef<[u8]>>,
-    ) -> AppResult<(Hash, Vec<Event>, Vec<AppResult<Vec<Event>>>)> {
+    ) -> AppResult<(Hash, Vec<Event>, Vec<TxOutcome>)> {
         let txs = raw_txs
             .into_iter()
             .map(|raw_tx| {
@@ -130,13 +171,17 @@ where
         &self,
         block: BlockInfo,
         txs: Vec<(Hash, Tx)>,
-    ) -> AppResult<(Hash, Vec<Event>, Vec<AppResult<Vec<Event>>>)> {
-        let mut buffer = Shared::new(Buffer::new(self.db.state_storage(None), None));
+    ) -> AppResult<(Hash, Vec<Event>, Vec<TxOutcome>)> {
+        let mut store = TxStore::new(Box::new(self.db.state_storage(None)));
         let mut events = vec![];
         let mut tx_results = vec![];
 
-        let cfg = CONFIG.load(&buffer)?;
-        let last_finalized_block = LAST_FINALIZED_BLOCK.load(&buffer)?;
+        if self.track_state_diffs {
+            store.track_changes();
+        }
+
+        let cfg = CONFIG.load(&store)?;
+        let last_finalized_block = LAST_FINALIZED_BLOCK.load(&store)?;
 
         // make sure the new block height is exactly the last finalized height
         // plus one. this ensures that block height always matches the BaseStore
@@ -162,7 +207,7 @@ where
             // TODO: we need to think whether this is the desired behavior
             events.extend(do_before_block(
                 self.vm.clone(),
-                Box::new(buffer.share()),
+                store.clone(),
                 block.clone(),
                 contract,
             )?);
@@ -173,12 +218,14 @@ where
             #[cfg(feature = "tracing")]
             debug!(idx = _idx, tx_hash = ?_tx_hash, "Processing transaction");
 
-            tx_results.push(process_tx(
-                self.vm.clone(),
-                buffer.share(),
-                block.clone(),
-                tx,
-            ));
+            // discard the changes logged before the tx, e.g. by begin blockers
+            // or the previous tx, so that the log only contains the tx's own.
+            store.take_change_log();
+
+            let result = process_tx(self.vm.clone(), store.clone(), block.clone(), tx, false);
+            let state_diff = store.take_change_log().map(decode_tx_state_diff);
+
+            tx_results.push(TxOutcome { result, state_diff });
         }
 
         // call end blockers
@@ -195,7 +242,7 @@ where
             // TODO: we need to think whether this is the desired behavior
             events.extend(do_after_block(
                 self.vm.clone(),
-                Box::new(buffer.share()),
+                store.clone(),
                 block.clone(),
                 contract,
             )?);
@@ -206,11 +253,11 @@ where
         // note that we do this *after* the transactions have been executed, so
         // if a contract queries the last committed block during the execution,
         // it gets the previous block, not the current one.
-        LAST_FINALIZED_BLOCK.save(&mut buffer, &block)?;
+        LAST_FINALIZED_BLOCK.save(&mut store, &block)?;
 
         // flush the state changes to the DB, but keep it in memory, not persist
         // to disk yet. it will be done in the ABCI `Commit` call.
-        let (_, batch) = buffer.disassemble().disassemble();
+        let (_, batch) = store.disassemble();
         let (version, root_hash) = self.db.flush_but_not_commit(batch)?;
 
         // block height should match the DB version
@@ -261,6 +308,37 @@ where
         Ok((version, root_hash))
     }
 
+    pub fn do_simulate_raw(&self, raw_tx: &[u8]) -> AppResult<Vec<u8>> {
+        let tx = from_json_slice(raw_tx)?;
+        let (result, trace) = self.do_simulate(tx)?;
+        Ok(to_json_vec(&SimulateResponse {
+            result: match result {
+                Ok(res) => GenericResult::Ok(res),
+                Err(err) => GenericResult::Err(err.into_execution_error()),
+            },
+            trace,
+        })?)
+    }
+
+    /// Execute a transaction against the latest committed state without
+    /// persisting any change, and return its result along with a trace of the
+    /// calls it made.
+    ///
+    /// The sender account's `before_tx` and `after_tx` methods are called in
+    /// simulate mode, so the transaction doesn't need to be properly signed.
+    ///
+    /// The outer result is an error if the simulation can't be carried out,
+    /// e.g. if the chain hasn't started; the inner one if the transaction
+    /// fails.
+    pub fn do_simulate(&self, tx: Tx) -> AppResult<(AppResult<TxResponse>, Trace)> {
+        let store = TxStore::new(Box::new(self.db.state_storage(None)));
+        let block = LAST_FINALIZED_BLOCK.load(&store)?;
+
+        Ok(record_trace(|| {
+            process_tx(self.vm.clone(), store, block, tx, true)
+        }))
+    }
+
     pub fn do_query_app_raw(&self, raw_req: &[u8], height: u64, prove: bool) -> AppResult<Vec<u8>> {
         let req = from_json_slice(raw_req)?;
         let res = self.do_query_app(req, height, prove)?;
@@ -297,7 +375,8 @@ where
     /// Performs a raw query of the app's underlying key-value store.
     /// Returns two values:
     /// - the value corresponding to the given key; `None` if the key doesn't exist;
-    /// - the Merkle proof; `None` if a proof is not requested (`prove` is false).
+    /// - the Protobuf-encoded ICS-23 commitment proof; `None` if a proof is not
+    ///   requested (`prove` is false).
     pub fn do_query_store(
         &self,
         key: &[u8],
@@ -313,7 +392,7 @@ where
         };
 
         let proof = if prove {
-            Some(to_json_vec(&self.db.prove(key, version)?)?)
+            Some(self.db.ics23_prove(key, version)?.encode_to_vec())
         } else {
             None
         };
@@ -324,77 +403,120 @@ where
     }
 }
 
-fn process_tx<S, VM>(vm: VM, storage: S, block: BlockInfo, tx: Tx) -> AppResult<Vec<Event>>
+fn process_tx<VM>(
+    vm: VM,
+    store: TxStore,
+    block: BlockInfo,
+    tx: Tx,
+    simulate: bool,
+) -> AppResult<TxResponse>
 where
-    S: Storage + Clone + 'static,
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
     let mut events = vec![];
 
-    // create buffer storage for this tx
-    let buffer = Shared::new(Buffer::new(storage, None));
-
     // call the sender account's `before_tx` method.
     // if this fails, abort, discard uncommitted state changes.
-    events.extend(do_before_tx(
-        vm.clone(),
-        Box::new(buffer.share()),
-        block.clone(),
-        &tx,
-    )?);
+    let savepoint = store.savepoint()?;
+    match do_before_tx(vm.clone(), store.clone(), block.clone(), &tx, simulate) {
+        Ok(before_tx_events) => events.extend(before_tx_events),
+        Err(err) => {
+            store.rollback_to(savepoint);
+            return Err(err);
+        },
+    }
 
     // update the account state. as long as authentication succeeds, regardless
     // of whether the message are successful, we update account state. if auth
     // fails, we don't update account state.
-    buffer.write_access().commit();
+    store.release(savepoint);
 
     // now that the tx is authenticated, we loop through the messages and
-    // execute them one by one.
-    // if any one of the msgs fails, the entire tx fails; abort, discard
+    // execute them one by one, then call the sender account's `after_tx`
+    // method.
+    // if any one of these fails, the entire tx fails; abort, discard
     // uncommitted changes (the changes from the before_tx call earlier are
     // persisted)
+    let savepoint = store.savepoint()?;
+    match process_msgs_and_after_tx(vm, store.clone(), block, &tx, simulate) {
+        Ok(res) => {
+            // all messages succeeded. commit the state changes
+            store.release(savepoint);
+            events.extend(res.events);
+            Ok(TxResponse {
+                events,
+                data: res.data,
+            })
+        },
+        Err(err) => {
+            store.rollback_to(savepoint);
+            Err(err)
+        },
+    }
+}
+
+fn process_msgs_and_after_tx<VM>(
+    vm: VM,
+    store: TxStore,
+    block: BlockInfo,
+    tx: &Tx,
+    simulate: bool,
+) -> AppResult<TxResponse>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let mut res = TxResponse::default();
+
     for (_idx, msg) in tx.msgs.iter().enumerate() {
         #[cfg(feature = "tracing")]
         debug!(idx = _idx, "Processing message");
 
-        events.extend(process_msg(
+        // if state diffs are tracked, each message's changes go to a section of
+        // the change log of their own. same for `after_tx` below.
+        store.start_section();
+
+        let msg_res = process_msg(
             vm.clone(),
-            Box::new(buffer.share()),
+            store.clone(),
             block.clone(),
             tx.sender.clone(),
             msg.clone(),
-        )?);
+        )?;
+        res.events.extend(msg_res.events);
+        res.data.push(msg_res.data);
     }
 
-    // call the sender account's `after_tx` method.
-    // if this fails, abort, discard uncommitted state changes from messages.
-    // state changes from `before_tx` are always kept.
-    events.extend(do_after_tx(vm, Box::new(buffer.share()), block, &tx)?);
+    store.start_section();
+    res.events
+        .extend(do_after_tx(vm, store, block, tx, simulate)?);
 
-    // all messages succeeded. commit the state changes
-    buffer.write_access().commit();
-
-    Ok(events)
+    Ok(res)
 }
 
 pub fn process_msg<VM>(
     vm: VM,
-    mut storage: Box<dyn Storage>,
+    mut storage: TxStore,
     block: BlockInfo,
     sender: Addr,
     msg: Message,
-) -> AppResult<Vec<Event>>
+) -> AppResult<MsgResponse>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
     match msg {
-        Message::SetConfig { new_cfg } => do_set_config(&mut storage, &sender, &new_cfg),
+        Message::SetConfig { new_cfg } => {
+            do_set_config(&mut storage, &sender, &new_cfg).map(MsgResponse::from_events)
+        },
         Message::Transfer { to, coins } => {
             do_transfer(vm, storage, block, sender.clone(), to, coins, true)
+                .map(MsgResponse::from_events)
+        },
+        Message::Upload { code } => {
+            do_upload(&vm, &mut storage, &sender, code.into()).map(MsgResponse::from_events)
         },
-        Message::Upload { code } => do_upload(&mut storage, &sender, code.into()),
         Message::Instantiate {
             code_hash,
             msg,
@@ -414,6 +536,201 @@ where
             new_code_hash,
             msg,
         } => do_migrate(vm, storage, block, contract, sender, new_code_hash, &msg),
+        Message::CreateClient {
+            code_hash,
+            client_state,
+            consensus_state,
+            salt,
+        } => do_create_client(
+            vm,
+            storage,
+            block,
+            sender,
+            code_hash,
+            &client_state,
+            &consensus_state,
+            salt,
+        ),
+        Message::UpdateClient { client_id, header } => {
+            do_update_client(vm, storage, block, client_id, &header)
+        },
+        Message::SubmitMisbehavior {
+            client_id,
+            misbehavior,
+        } => do_submit_misbehavior(vm, storage, block, client_id, &misbehavior),
+        Message::ConnOpenInit {
+            client_id,
+            counterparty,
+            delay_period,
+        } => do_conn_open_init(
+            vm,
+            storage,
+            block,
+            &sender,
+            client_id,
+            counterparty,
+            delay_period,
+        )
+        .map(MsgResponse::from_events),
+        Message::ConnOpenTry {
+            client_id,
+            counterparty,
+            counterparty_versions,
+            delay_period,
+            proof_height,
+            proof_init,
+        } => do_conn_open_try(
+            vm,
+            storage,
+            block,
+            client_id,
+            counterparty,
+            counterparty_versions,
+            delay_period,
+            proof_height,
+            proof_init,
+        )
+        .map(MsgResponse::from_events),
+        Message::ConnOpenAck {
+            connection_id,
+            counterparty_connection_id,
+            version,
+            proof_height,
+            proof_try,
+        } => do_conn_open_ack(
+            vm,
+            storage,
+            block,
+            connection_id,
+            counterparty_connection_id,
+            version,
+            proof_height,
+            proof_try,
+        )
+        .map(MsgResponse::from_events),
+        Message::ConnOpenConfirm {
+            connection_id,
+            proof_height,
+            proof_ack,
+        } => do_conn_open_confirm(vm, storage, block, connection_id, proof_height, proof_ack)
+            .map(MsgResponse::from_events),
+        Message::ChanOpenInit {
+            port_id,
+            connection_id,
+            counterparty_port_id,
+            ordering,
+            version,
+        } => do_chan_open_init(
+            vm,
+            storage,
+            block,
+            &sender,
+            port_id,
+            connection_id,
+            counterparty_port_id,
+            ordering,
+            version,
+        )
+        .map(MsgResponse::from_events),
+        Message::ChanOpenTry {
+            port_id,
+            connection_id,
+            counterparty,
+            ordering,
+            version,
+            counterparty_version,
+            proof_height,
+            proof_init,
+        } => do_chan_open_try(
+            vm,
+            storage,
+            block,
+            &sender,
+            port_id,
+            connection_id,
+            counterparty,
+            ordering,
+            version,
+            counterparty_version,
+            proof_height,
+            proof_init,
+        )
+        .map(MsgResponse::from_events),
+        Message::ChanOpenAck {
+            port_id,
+            channel_id,
+            counterparty_channel_id,
+            counterparty_version,
+            proof_height,
+            proof_try,
+        } => do_chan_open_ack(
+            vm,
+            storage,
+            block,
+            port_id,
+            channel_id,
+            counterparty_channel_id,
+            counterparty_version,
+            proof_height,
+            proof_try,
+        )
+        .map(MsgResponse::from_events),
+        Message::ChanOpenConfirm {
+            port_id,
+            channel_id,
+            proof_height,
+            proof_ack,
+        } => do_chan_open_confirm(
+            vm,
+            storage,
+            block,
+            port_id,
+            channel_id,
+            proof_height,
+            proof_ack,
+        )
+        .map(MsgResponse::from_events),
+        Message::SendPacket {
+            channel_id,
+            data,
+            timeout,
+        } => do_send_packet(vm, storage, block, sender, channel_id, data, timeout),
+        Message::RecvPacket {
+            packet,
+            proof_height,
+            proof_commitment,
+        } => do_recv_packet(vm, storage, block, packet, proof_height, proof_commitment)
+            .map(MsgResponse::from_events),
+        Message::Acknowledgement {
+            packet,
+            acknowledgement,
+            proof_height,
+            proof_acked,
+        } => do_acknowledge_packet(
+            vm,
+            storage,
+            block,
+            packet,
+            acknowledgement,
+            proof_height,
+            proof_acked,
+        )
+        .map(MsgResponse::from_events),
+        Message::Timeout {
+            packet,
+            next_sequence_recv,
+            proof_height,
+            proof_unreceived,
+        } => do_timeout_packet(
+            vm,
+            storage,
+            block,
+            packet,
+            next_sequence_recv,
+            proof_height,
+            proof_unreceived,
+        )
+        .map(MsgResponse::from_events),
     }
 }
 
@@ -444,6 +761,12 @@ where
         QueryRequest::Supplies { start_after, limit } => {
             query_supplies(vm, storage, block, start_after, limit).map(QueryResponse::Supplies)
         },
+        QueryRequest::Metadata { denom } => {
+            query_metadata(vm, storage, block, denom).map(QueryResponse::Metadata)
+        },
+        QueryRequest::Metadatas { start_after, limit } => {
+            query_metadatas(vm, storage, block, start_after, limit).map(QueryResponse::Metadatas)
+        },
         QueryRequest::Code { hash } => query_code(&storage, hash).map(QueryResponse::Code),
         QueryRequest::Codes { start_after, limit } => {
             query_codes(&storage, start_after, limit).map(QueryResponse::Codes)
@@ -457,9 +780,25 @@ where
         QueryRequest::WasmRaw { contract, key } => {
             query_wasm_raw(storage, contract, key).map(QueryResponse::WasmRaw)
         },
+        QueryRequest::WasmScan {
+            contract,
+            min,
+            max,
+            limit,
+        } => query_wasm_scan(storage, contract, min, max, limit).map(QueryResponse::WasmScan),
         QueryRequest::WasmSmart { contract, msg } => {
             query_wasm_smart(vm, storage, block, contract, msg).map(QueryResponse::WasmSmart)
         },
+        QueryRequest::Client { client_id } => {
+            query_client(vm, storage, block, client_id).map(QueryResponse::Client)
+        },
+        QueryRequest::Connection { connection_id } => {
+            query_connection(&storage, connection_id).map(QueryResponse::Connection)
+        },
+        QueryRequest::Channel {
+            port_id,
+            channel_id,
+        } => query_channel(&storage, port_id, channel_id).map(QueryResponse::Channel),
     }
 }
 
@@ -1,7 +1,8 @@
 use {
-    grug_types::{Batch, Op, Order, Record, Storage},
+    grug_types::{prefix_upper_bound, Batch, Change, Op, Order, Record, Storage},
     std::{
         cmp::Ordering,
+        collections::BTreeMap,
         iter::{self, Peekable},
         mem,
         ops::Bound,
@@ -10,12 +11,82 @@ use {
 
 /// A key-value storage with an in-memory write buffer.
 ///
+/// All pending ops live in a single ordered change-set, regardless of how many
+/// savepoints are open. Nesting is tracked by an undo log per savepoint rather
+/// than by stacking buffers on top of each other, so reads are always one
+/// lookup into the change-set plus at most one into the base store, and scans
+/// are always a single merge of two iterators.
+///
 /// Adapted from cw-multi-test:
 /// <https://github.com/CosmWasm/cw-multi-test/blob/v0.19.0/src/transactions.rs#L170-L253>
 #[derive(Clone)]
 pub struct Buffer<S: Clone> {
     base: S,
     pending: Batch,
+    /// Undo logs of the open savepoints, the innermost one last, each along
+    /// with the generation of the savepoint it belongs to.
+    ///
+    /// Each log maps a key to the pending op it had (or `None` if it had none)
+    /// when it was first touched after the savepoint was created. Rolling back
+    /// restores these; releasing merges them into the enclosing savepoint.
+    savepoints: Vec<OpenSavepoint>,
+    /// The generation to be given to the next savepoint.
+    next_generation: u64,
+    /// The changes made so far, in the order they were made, if tracking is
+    /// enabled with [`Buffer::track_changes`].
+    log: Option<ChangeLog>,
+}
+
+type UndoLog = BTreeMap<Vec<u8>, Option<Op>>;
+
+#[derive(Clone)]
+struct OpenSavepoint {
+    generation: u64,
+    undo_log: UndoLog,
+    /// The length of the change log, and its number of sections, when the
+    /// savepoint was created.
+    log_position: (usize, usize),
+}
+
+/// The changes made to a [`Buffer`], as `(key, old value, new value)`, in the
+/// order they were made.
+///
+/// The log is split into sections by [`Buffer::start_section`], e.g. one for
+/// each message of a transaction. Changes that are rolled back are removed
+/// from the log, along with sections started after the savepoint.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ChangeLog {
+    changes: Vec<Change>,
+    /// Index of the first change of each section but the first.
+    section_starts: Vec<usize>,
+}
+
+impl ChangeLog {
+    /// Return the changes of each section, in order. There is always at least
+    /// one section, which may be empty.
+    pub fn into_sections(mut self) -> Vec<Vec<Change>> {
+        let mut sections = Vec::with_capacity(self.section_starts.len() + 1);
+        for start in self.section_starts.into_iter().rev() {
+            sections.push(self.changes.split_off(start));
+        }
+        sections.push(self.changes);
+        sections.reverse();
+        sections
+    }
+}
+
+/// A handle to a savepoint created by [`Buffer::savepoint`].
+///
+/// Rolling back to or releasing a savepoint also rolls back or releases all
+/// savepoints created after it.
+///
+/// Besides its depth in the stack of open savepoints, a handle carries a
+/// generation that is unique within the buffer, so that a handle to a closed
+/// savepoint isn't mistaken for a newer one later opened at the same depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Savepoint {
+    depth: usize,
+    generation: u64,
 }
 
 impl<S: Clone> Buffer<S> {
@@ -24,29 +95,239 @@ impl<S: Clone> Buffer<S> {
         Self {
             base,
             pending: pending.unwrap_or_default(),
+            savepoints: vec![],
+            next_generation: 0,
+            log: None,
         }
     }
 
+    /// Start recording the changes made to the buffer from now on. See
+    /// [`Buffer::take_change_log`].
+    pub fn track_changes(&mut self) {
+        self.log.get_or_insert_with(ChangeLog::default);
+    }
+
+    /// Start a new section of the change log. Does nothing if changes aren't
+    /// tracked.
+    pub fn start_section(&mut self) {
+        if let Some(log) = &mut self.log {
+            log.section_starts.push(log.changes.len());
+        }
+    }
+
+    /// Return the changes recorded so far and clear the log, or `None` if
+    /// changes aren't tracked.
+    ///
+    /// Panics if there are open savepoints, since rolling them back would no
+    /// longer be reflected in the returned log.
+    pub fn take_change_log(&mut self) -> Option<ChangeLog> {
+        assert!(
+            self.savepoints.is_empty(),
+            "can't take the change log with open savepoints"
+        );
+
+        self.log.as_mut().map(mem::take)
+    }
+
     /// Comsume self, do not flush, just return the underlying store and the
-    /// pending ops.
+    /// pending ops. Open savepoints are discarded, i.e. the pending ops made
+    /// after them are kept.
     pub fn disassemble(self) -> (S, Batch) {
         (self.base, self.pending)
     }
+
+    /// Mark the current state of the buffer, so that ops made from now on can
+    /// later be discarded with [`Buffer::rollback_to`].
+    pub fn savepoint(&mut self) -> Savepoint {
+        let savepoint = Savepoint {
+            depth: self.savepoints.len(),
+            generation: self.next_generation,
+        };
+        let log_position = self
+            .log
+            .as_ref()
+            .map_or((0, 0), |log| (log.changes.len(), log.section_starts.len()));
+        self.savepoints.push(OpenSavepoint {
+            generation: savepoint.generation,
+            undo_log: UndoLog::new(),
+            log_position,
+        });
+        self.next_generation += 1;
+        savepoint
+    }
+
+    /// Return the number of currently open savepoints.
+    pub fn depth(&self) -> usize {
+        self.savepoints.len()
+    }
+
+    /// Discard all ops made since the savepoint was created, and close it
+    /// along with all savepoints created after it.
+    ///
+    /// Panics if the savepoint has already been closed.
+    pub fn rollback_to(&mut self, savepoint: Savepoint) {
+        self.assert_open(savepoint);
+
+        // Undo the innermost logs first, so that if a key appears in multiple
+        // logs, the op from the outermost one (the oldest) is what remains.
+        while self.savepoints.len() > savepoint.depth {
+            let OpenSavepoint {
+                undo_log,
+                log_position,
+                ..
+            } = self.savepoints.pop().unwrap();
+            for (key, op) in undo_log {
+                match op {
+                    Some(op) => self.pending.insert(key, op),
+                    None => self.pending.remove(&key),
+                };
+            }
+            if let Some(log) = &mut self.log {
+                log.changes.truncate(log_position.0);
+                log.section_starts.truncate(log_position.1);
+            }
+        }
+    }
+
+    /// Keep all ops made since the savepoint was created, and close it along
+    /// with all savepoints created after it.
+    ///
+    /// Panics if the savepoint has already been closed.
+    pub fn release(&mut self, savepoint: Savepoint) {
+        self.assert_open(savepoint);
+
+        // Fold the closed logs into the enclosing savepoint's, if any, so that
+        // it can still be rolled back. Where both logs contain the same key,
+        // the enclosing log's entry is older and takes precedence.
+        while self.savepoints.len() > savepoint.depth {
+            let OpenSavepoint { undo_log, .. } = self.savepoints.pop().unwrap();
+            if let Some(parent) = self.savepoints.last_mut() {
+                for (key, op) in undo_log {
+                    parent.undo_log.entry(key).or_insert(op);
+                }
+            }
+        }
+    }
+
+    fn assert_open(&self, savepoint: Savepoint) {
+        assert!(
+            self.savepoints
+                .get(savepoint.depth)
+                .is_some_and(|open| open.generation == savepoint.generation),
+            "savepoint {} is already closed; open savepoints: {}",
+            savepoint.generation,
+            self.savepoints.len()
+        );
+    }
 }
 
 impl<S: Storage + Clone> Buffer<S> {
     /// Flush pending ops to the underlying store.
+    ///
+    /// Panics if there are open savepoints, since they could no longer be
+    /// rolled back afterwards.
     pub fn commit(&mut self) {
+        assert!(
+            self.savepoints.is_empty(),
+            "can't commit with open savepoints"
+        );
+
         let pending = mem::take(&mut self.pending);
-        self.base.flush(pending);
+        self.base.write_batch(pending);
     }
 
     /// Consume self, flush pending ops to the underlying store, return the
     /// underlying store.
+    ///
+    /// Panics if there are open savepoints, same as [`Buffer::commit`].
     pub fn consume(mut self) -> S {
-        self.base.flush(self.pending);
+        self.commit();
         self.base
     }
+
+    /// Return the changes that the pending ops would make to the underlying
+    /// store, as a log of `(key, old value, new value)` ordered by keys.
+    ///
+    /// Old values are read from the underlying store. Ops that don't actually
+    /// change the value (e.g. deleting a non-existent key) are omitted.
+    pub fn changes(&self) -> Vec<Change> {
+        self.pending
+            .iter()
+            .filter_map(|(key, op)| {
+                let old = self.base.read(key);
+                let new = op.as_ref().into_option().cloned();
+                make_change(key, old, new)
+            })
+            .collect()
+    }
+
+    /// Return the changes made since the savepoint was created, in the same
+    /// format as [`Buffer::changes`].
+    ///
+    /// Panics if the savepoint has already been closed.
+    pub fn changes_since(&self, savepoint: Savepoint) -> Vec<Change> {
+        self.assert_open(savepoint);
+
+        // For each key touched since the savepoint, find the op it had before
+        // it was first touched. If a key appears in several logs, the one in
+        // the outermost log is the oldest.
+        let mut prev_ops = BTreeMap::new();
+        for open in &self.savepoints[savepoint.depth..] {
+            for (key, op) in &open.undo_log {
+                prev_ops.entry(key).or_insert(op);
+            }
+        }
+
+        prev_ops
+            .into_iter()
+            .filter_map(|(key, op)| {
+                let old = match op {
+                    Some(Op::Insert(value)) => Some(value.clone()),
+                    Some(Op::Delete) => None,
+                    None => self.base.read(key),
+                };
+                let new = self.read(key);
+                make_change(key, old, new)
+            })
+            .collect()
+    }
+
+    /// Insert a pending op, first recording the key's previous op in the
+    /// innermost undo log if it isn't already there, and the change it makes
+    /// in the change log if changes are tracked.
+    fn insert_op(&mut self, key: Vec<u8>, op: Op) {
+        if let Some(open) = self.savepoints.last_mut() {
+            if !open.undo_log.contains_key(&key) {
+                open.undo_log
+                    .insert(key.clone(), self.pending.get(&key).cloned());
+            }
+        }
+
+        if self.log.is_some() {
+            let old = self.read(&key);
+            let new = op.as_ref().into_option().cloned();
+            if let (Some(log), Some(change)) = (&mut self.log, make_change(&key, old, new)) {
+                log.changes.push(change);
+            }
+        }
+
+        self.pending.insert(key, op);
+    }
+
+    fn pending_range<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = (&'a Vec<u8>, &'a Op)> + 'a> {
+        let min = min.map_or(Bound::Unbounded, |bytes| Bound::Included(bytes.to_vec()));
+        let max = max.map_or(Bound::Unbounded, |bytes| Bound::Excluded(bytes.to_vec()));
+        let pending_raw = self.pending.range((min, max));
+        match order {
+            Order::Ascending => Box::new(pending_raw),
+            Order::Descending => Box::new(pending_raw.rev()),
+        }
+    }
 }
 
 impl<S: Storage + Clone> Storage for Buffer<S> {
@@ -71,14 +352,7 @@ impl<S: Storage + Clone> Storage for Buffer<S> {
         }
 
         let base = self.base.scan(min, max, order);
-
-        let min = min.map_or(Bound::Unbounded, |bytes| Bound::Included(bytes.to_vec()));
-        let max = max.map_or(Bound::Unbounded, |bytes| Bound::Excluded(bytes.to_vec()));
-        let pending_raw = self.pending.range((min, max));
-        let pending: Box<dyn Iterator<Item = _>> = match order {
-            Order::Ascending => Box::new(pending_raw),
-            Order::Descending => Box::new(pending_raw.rev()),
-        };
+        let pending = self.pending_range(min, max, order);
 
         Box::new(Merged::new(base, pending, order))
     }
@@ -89,10 +363,16 @@ impl<S: Storage + Clone> Storage for Buffer<S> {
         max: Option<&[u8]>,
         order: Order,
     ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
-        // Currently we simply iterate both keys and values, and discard the
-        // values. This isn't efficient.
-        // TODO: optimize this
-        Box::new(self.scan(min, max, order).map(|(k, _)| k))
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                return Box::new(iter::empty());
+            }
+        }
+
+        let base = self.base.scan_keys(min, max, order);
+        let pending = self.pending_range(min, max, order);
+
+        Box::new(Merged::new(base, pending, order))
     }
 
     fn scan_values<'a>(
@@ -101,42 +381,139 @@ impl<S: Storage + Clone> Storage for Buffer<S> {
         max: Option<&[u8]>,
         order: Order,
     ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        // Merging requires the keys of the base store's records, so we can't
+        // use `self.base.scan_values` here.
         Box::new(self.scan(min, max, order).map(|(_, v)| v))
     }
 
     fn write(&mut self, key: &[u8], value: &[u8]) {
-        self.pending
-            .insert(key.to_vec(), Op::Insert(value.to_vec()));
+        self.insert_op(key.to_vec(), Op::Insert(value.to_vec()));
     }
 
     fn remove(&mut self, key: &[u8]) {
-        self.pending.insert(key.to_vec(), Op::Delete);
+        self.insert_op(key.to_vec(), Op::Delete);
     }
 
     fn remove_range(&mut self, min: Option<&[u8]>, max: Option<&[u8]>) {
         // Find all keys within the bounds and mark them all as to be deleted.
         // We use `self.scan_keys` here, which scans both the base and pending.
         // We have to collect the iterator, because the iterator holds an
-        //
-        // immutable reference to `self`, but `self.pending.extend` requires a
+        // immutable reference to `self`, but `self.insert_op` requires a
         // mutable reference, which can't coexist.
-        let deletes = self
+        let keys = self
             .scan_keys(min, max, Order::Ascending)
-            .map(|key| (key, Op::Delete))
             .collect::<Vec<_>>();
-        self.pending.extend(deletes);
+        for key in keys {
+            self.insert_op(key, Op::Delete);
+        }
+    }
+
+    fn has(&self, key: &[u8]) -> bool {
+        match self.pending.get(key) {
+            Some(Op::Insert(_)) => true,
+            Some(Op::Delete) => false,
+            None => self.base.has(key),
+        }
+    }
+
+    fn read_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        // Resolve as many keys as possible from the pending ops, and only read
+        // the rest from the base store, in one call.
+        let mut values = Vec::with_capacity(keys.len());
+        let mut missing = Vec::new();
+        for (index, key) in keys.iter().enumerate() {
+            match self.pending.get(*key) {
+                Some(Op::Insert(value)) => values.push(Some(value.clone())),
+                Some(Op::Delete) => values.push(None),
+                None => {
+                    values.push(None);
+                    missing.push(index);
+                },
+            }
+        }
+
+        if !missing.is_empty() {
+            let missing_keys = missing.iter().map(|index| keys[*index]).collect::<Vec<_>>();
+            let base_values = self.base.read_many(&missing_keys);
+            for (index, value) in missing.into_iter().zip(base_values) {
+                values[index] = value;
+            }
+        }
+
+        values
+    }
+
+    // Forward the prefix to the base store instead of turning it into bounds
+    // here, so that a base store that overrides `scan_prefix` (e.g. a
+    // `StorageProvider`, which namespaces the prefix) gets to use its own.
+    fn scan_prefix<'a>(
+        &'a self,
+        prefix: &[u8],
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        let max = prefix_upper_bound(prefix);
+        let base = self.base.scan_prefix(prefix, order);
+        let pending = self.pending_range(Some(prefix), max.as_deref(), order);
+
+        Box::new(Merged::new(base, pending, order))
+    }
+
+    fn write_batch(&mut self, batch: Batch) {
+        if self.savepoints.is_empty() && self.log.is_none() {
+            // if we do a.extend(b), while a and b have common keys, the values
+            // in b are chosen. this is exactly what we want.
+            self.pending.extend(batch);
+        } else {
+            for (key, op) in batch {
+                self.insert_op(key, op);
+            }
+        }
+    }
+}
+
+fn make_change(key: &[u8], old: Option<Vec<u8>>, new: Option<Vec<u8>>) -> Option<Change> {
+    if old == new {
+        return None;
+    }
+
+    Some(Change {
+        key: key.to_vec().into(),
+        old: old.map(Into::into),
+        new: new.map(Into::into),
+    })
+}
+
+/// An item yielded by a [`Merged`] iterator: either a full record, or only a
+/// key.
+trait MergeItem {
+    fn key(&self) -> &[u8];
+
+    fn from_pending(key: &[u8], value: &[u8]) -> Self;
+}
+
+impl MergeItem for Record {
+    fn key(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn from_pending(key: &[u8], value: &[u8]) -> Self {
+        (key.to_vec(), value.to_vec())
+    }
+}
+
+impl MergeItem for Vec<u8> {
+    fn key(&self) -> &[u8] {
+        self
     }
 
-    fn flush(&mut self, batch: Batch) {
-        // if we do a.extend(b), while a and b have common keys, the values in b
-        // are chosen. this is exactly what we want.
-        self.pending.extend(batch);
+    fn from_pending(key: &[u8], _value: &[u8]) -> Self {
+        key.to_vec()
     }
 }
 
 struct Merged<'a, B, P>
 where
-    B: Iterator<Item = Record>,
+    B: Iterator,
     P: Iterator<Item = (&'a Vec<u8>, &'a Op)>,
 {
     base: Peekable<B>,
@@ -146,7 +523,7 @@ where
 
 impl<'a, B, P> Merged<'a, B, P>
 where
-    B: Iterator<Item = Record>,
+    B: Iterator,
     P: Iterator<Item = (&'a Vec<u8>, &'a Op)>,
 {
     pub fn new(base: B, pending: P, order: Order) -> Self {
@@ -156,44 +533,45 @@ where
             order,
         }
     }
-
-    fn take_pending(&mut self) -> Option<Record> {
-        let (key, op) = self.pending.next()?;
-        match op {
-            Op::Insert(value) => Some((key.clone(), value.clone())),
-            Op::Delete => self.next(),
-        }
-    }
 }
 
 impl<'a, B, P> Iterator for Merged<'a, B, P>
 where
-    B: Iterator<Item = Record>,
+    B: Iterator,
+    B::Item: MergeItem,
     P: Iterator<Item = (&'a Vec<u8>, &'a Op)>,
 {
-    type Item = Record;
+    type Item = B::Item;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match (self.base.peek(), self.pending.peek()) {
-            (Some((base_key, _)), Some((pending_key, _))) => {
-                let ordering_raw = base_key.cmp(pending_key);
-                let ordering = match self.order {
-                    Order::Ascending => ordering_raw,
-                    Order::Descending => ordering_raw.reverse(),
-                };
+        // Loop instead of recursing when skipping deleted keys, so that a long
+        // run of deletes doesn't overflow the stack.
+        loop {
+            let ordering = match (self.base.peek(), self.pending.peek()) {
+                (Some(base_item), Some((pending_key, _))) => {
+                    let ordering_raw = base_item.key().cmp(pending_key.as_slice());
+                    match self.order {
+                        Order::Ascending => ordering_raw,
+                        Order::Descending => ordering_raw.reverse(),
+                    }
+                },
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => return None,
+            };
 
-                match ordering {
-                    Ordering::Less => self.base.next(),
-                    Ordering::Equal => {
-                        self.base.next();
-                        self.take_pending()
-                    },
-                    Ordering::Greater => self.take_pending(),
-                }
-            },
-            (None, Some(_)) => self.take_pending(),
-            (Some(_), None) => self.base.next(),
-            (None, None) => None,
+            match ordering {
+                Ordering::Less => return self.base.next(),
+                // The pending op overrides the base record.
+                Ordering::Equal => {
+                    self.base.next();
+                },
+                Ordering::Greater => {},
+            }
+
+            if let (key, Op::Insert(value)) = self.pending.next()? {
+                return Some(B::Item::from_pending(key, value));
+            }
         }
     }
 }
@@ -202,7 +580,11 @@ where
 
 #[cfg(test)]
 mod tests {
-    use {super::*, grug_types::MockStorage};
+    use {
+        super::*,
+        grug_types::MockStorage,
+        proptest::{option, prelude::*, sample::Index},
+    };
 
     // illustration of this test case:
     //
@@ -250,5 +632,418 @@ mod tests {
         assert_eq!(collect_records(&buffer, Order::Descending), merged);
     }
 
-    // TODO: add fuzz test
+    #[test]
+    fn read_many_works() {
+        let (buffer, _) = make_test_case();
+        let keys: &[&[u8]] = &[&[1], &[2], &[3], &[6], &[7], &[9]];
+        assert_eq!(buffer.read_many(keys), vec![
+            Some(vec![1]),
+            None,
+            Some(vec![3]),
+            Some(vec![255]),
+            None,
+            None,
+        ]);
+        assert!(buffer.has(&[3]));
+        assert!(!buffer.has(&[7]));
+    }
+
+    #[test]
+    fn changes_works() {
+        let (mut buffer, _) = make_test_case();
+        // deleting a non-existent key doesn't count as a change
+        buffer.remove(&[9]);
+
+        assert_eq!(buffer.changes(), vec![
+            Change {
+                key: vec![2].into(),
+                old: Some(vec![2].into()),
+                new: None,
+            },
+            Change {
+                key: vec![3].into(),
+                old: None,
+                new: Some(vec![3].into()),
+            },
+            Change {
+                key: vec![6].into(),
+                old: Some(vec![6].into()),
+                new: Some(vec![255].into()),
+            },
+            Change {
+                key: vec![7].into(),
+                old: Some(vec![7].into()),
+                new: None,
+            },
+            Change {
+                key: vec![8].into(),
+                old: None,
+                new: Some(vec![8].into()),
+            },
+        ]);
+    }
+
+    #[test]
+    fn scan_prefix_works() {
+        let mut base = MockStorage::new();
+        base.write(b"aa", b"0");
+        base.write(b"abd", b"4");
+        base.write(b"abe", b"5");
+
+        let mut buffer = Buffer::new(base, None);
+        buffer.write(b"a", b"1");
+        buffer.write(b"ab", b"2");
+        buffer.write(b"abc", b"3");
+        buffer.write(b"b", b"6");
+        buffer.remove(b"abe");
+
+        let records = buffer
+            .scan_prefix(b"ab", Order::Ascending)
+            .collect::<Vec<_>>();
+        assert_eq!(records, vec![
+            (b"ab".to_vec(), b"2".to_vec()),
+            (b"abc".to_vec(), b"3".to_vec()),
+            (b"abd".to_vec(), b"4".to_vec()),
+        ]);
+
+        let records = buffer
+            .scan_prefix(b"ab", Order::Descending)
+            .collect::<Vec<_>>();
+        assert_eq!(records, vec![
+            (b"abd".to_vec(), b"4".to_vec()),
+            (b"abc".to_vec(), b"3".to_vec()),
+            (b"ab".to_vec(), b"2".to_vec()),
+        ]);
+    }
+
+    #[test]
+    fn savepoints_work() {
+        let (mut buffer, merged) = make_test_case();
+
+        let outer = buffer.savepoint();
+        buffer.write(&[1], &[100]);
+        buffer.remove(&[3]);
+
+        let inner = buffer.savepoint();
+        buffer.write(&[1], &[200]);
+        buffer.remove_range(Some(&[4]), Some(&[6]));
+        assert_eq!(buffer.depth(), 2);
+
+        // releasing the inner savepoint keeps its ops, but they can still be
+        // rolled back with the outer one
+        buffer.release(inner);
+        assert_eq!(buffer.depth(), 1);
+        assert_eq!(buffer.read(&[1]), Some(vec![200]));
+        assert_eq!(buffer.read(&[4]), None);
+
+        buffer.rollback_to(outer);
+        assert_eq!(buffer.depth(), 0);
+        assert_eq!(collect_records(&buffer, Order::Ascending), merged);
+    }
+
+    #[test]
+    fn changes_since_works() {
+        let (mut buffer, _) = make_test_case();
+
+        let outer = buffer.savepoint();
+        buffer.write(&[1], &[100]);
+        buffer.write(&[3], &[30]);
+
+        let inner = buffer.savepoint();
+        buffer.write(&[1], &[200]);
+        buffer.remove(&[8]);
+        // writing back the original value doesn't count as a change
+        buffer.write(&[3], &[3]);
+
+        assert_eq!(buffer.changes_since(inner), vec![
+            Change {
+                key: vec![1].into(),
+                old: Some(vec![100].into()),
+                new: Some(vec![200].into()),
+            },
+            Change {
+                key: vec![3].into(),
+                old: Some(vec![30].into()),
+                new: Some(vec![3].into()),
+            },
+            Change {
+                key: vec![8].into(),
+                old: Some(vec![8].into()),
+                new: None,
+            },
+        ]);
+        assert_eq!(buffer.changes_since(outer), vec![
+            Change {
+                key: vec![1].into(),
+                old: Some(vec![1].into()),
+                new: Some(vec![200].into()),
+            },
+            Change {
+                key: vec![8].into(),
+                old: Some(vec![8].into()),
+                new: None,
+            },
+        ]);
+    }
+
+    #[test]
+    fn change_log_works() {
+        let (mut buffer, _) = make_test_case();
+        buffer.track_changes();
+
+        let change = |key: u8, old: Option<u8>, new: Option<u8>| Change {
+            key: vec![key].into(),
+            old: old.map(|v| vec![v].into()),
+            new: new.map(|v| vec![v].into()),
+        };
+
+        // changes are logged in the order they're made, including repeated
+        // changes to the same key
+        buffer.write(&[3], &[30]);
+        buffer.write(&[1], &[10]);
+        buffer.write(&[3], &[31]);
+
+        // changes rolled back are dropped from the log, as are the sections
+        // started after the savepoint
+        let savepoint = buffer.savepoint();
+        buffer.start_section();
+        buffer.remove(&[8]);
+        buffer.rollback_to(savepoint);
+
+        let savepoint = buffer.savepoint();
+        buffer.start_section();
+        buffer.remove(&[1]);
+        // deleting a non-existent key doesn't count as a change
+        buffer.remove(&[2]);
+        buffer.release(savepoint);
+
+        assert_eq!(buffer.take_change_log().unwrap().into_sections(), [
+            vec![
+                change(3, Some(3), Some(30)),
+                change(1, Some(1), Some(10)),
+                change(3, Some(30), Some(31)),
+            ],
+            vec![change(1, Some(10), None)],
+        ]);
+
+        // the log is cleared once taken
+        assert_eq!(buffer.take_change_log().unwrap().into_sections(), [vec![]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "savepoint 1 is already closed")]
+    fn closed_savepoint_panics() {
+        let mut buffer = Buffer::new(MockStorage::new(), None);
+        let outer = buffer.savepoint();
+        let inner = buffer.savepoint();
+        buffer.rollback_to(outer);
+        buffer.release(inner);
+    }
+
+    #[test]
+    #[should_panic(expected = "savepoint 0 is already closed")]
+    fn stale_savepoint_at_same_depth_panics() {
+        let mut buffer = Buffer::new(MockStorage::new(), None);
+        let stale = buffer.savepoint();
+        buffer.release(stale);
+
+        // the new savepoint is at the same depth as the stale one, which must
+        // not be able to roll it back
+        let _fresh = buffer.savepoint();
+        buffer.write(&[1], &[1]);
+        buffer.rollback_to(stale);
+    }
+
+    #[test]
+    #[should_panic(expected = "can't commit with open savepoints")]
+    fn consuming_with_open_savepoints_panics() {
+        let mut buffer = Buffer::new(MockStorage::new(), None);
+        buffer.savepoint();
+        buffer.consume();
+    }
+
+    #[derive(Debug, Clone)]
+    enum Action {
+        Write(Vec<u8>, Vec<u8>),
+        Remove(Vec<u8>),
+        RemoveRange(Option<Vec<u8>>, Option<Vec<u8>>),
+        WriteBatch(Batch),
+        Savepoint,
+        RollbackTo(Index),
+        Release(Index),
+        Scan(Option<Vec<u8>>, Option<Vec<u8>>, Order),
+    }
+
+    // Keys are drawn from a small domain, so that actions often touch the same
+    // keys, and some keys are prefixes of others.
+    fn key() -> impl Strategy<Value = Vec<u8>> {
+        prop::collection::vec(0u8..4, 1..3)
+    }
+
+    fn value() -> impl Strategy<Value = Vec<u8>> {
+        any::<u8>().prop_map(|byte| vec![byte])
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        option::of(value()).prop_map(|value| match value {
+            Some(value) => Op::Insert(value),
+            None => Op::Delete,
+        })
+    }
+
+    fn order() -> impl Strategy<Value = Order> {
+        prop_oneof![Just(Order::Ascending), Just(Order::Descending)]
+    }
+
+    fn action() -> impl Strategy<Value = Action> {
+        prop_oneof![
+            4 => (key(), value()).prop_map(|(k, v)| Action::Write(k, v)),
+            2 => key().prop_map(Action::Remove),
+            1 => (option::of(key()), option::of(key()))
+                .prop_map(|(min, max)| Action::RemoveRange(min, max)),
+            1 => prop::collection::btree_map(key(), op(), 0..4).prop_map(Action::WriteBatch),
+            2 => Just(Action::Savepoint),
+            1 => any::<Index>().prop_map(Action::RollbackTo),
+            1 => any::<Index>().prop_map(Action::Release),
+            2 => (option::of(key()), option::of(key()), order())
+                .prop_map(|(min, max, order)| Action::Scan(min, max, order)),
+        ]
+    }
+
+    fn reference_scan(
+        reference: &BTreeMap<Vec<u8>, Vec<u8>>,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Vec<Record> {
+        if let (Some(min), Some(max)) = (min, max) {
+            if min > max {
+                return vec![];
+            }
+        }
+
+        let min = min.map_or(Bound::Unbounded, |bytes| Bound::Included(bytes.to_vec()));
+        let max = max.map_or(Bound::Unbounded, |bytes| Bound::Excluded(bytes.to_vec()));
+        let range = reference
+            .range((min, max))
+            .map(|(k, v)| (k.clone(), v.clone()));
+        match order {
+            Order::Ascending => range.collect(),
+            Order::Descending => range.rev().collect(),
+        }
+    }
+
+    fn assert_matches(buffer: &Buffer<MockStorage>, reference: &BTreeMap<Vec<u8>, Vec<u8>>) {
+        for order in [Order::Ascending, Order::Descending] {
+            let expected = reference_scan(reference, None, None, order);
+            let keys = expected.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>();
+            let values = expected.iter().map(|(_, v)| v.clone()).collect::<Vec<_>>();
+            assert_eq!(collect_records(buffer, order), expected);
+            assert_eq!(
+                buffer.scan_keys(None, None, order).collect::<Vec<_>>(),
+                keys
+            );
+            assert_eq!(
+                buffer.scan_values(None, None, order).collect::<Vec<_>>(),
+                values
+            );
+        }
+
+        for a in 0..4 {
+            for key in [vec![a], vec![a, 0], vec![a, 3]] {
+                assert_eq!(buffer.read(&key), reference.get(&key).cloned());
+                assert_eq!(buffer.has(&key), reference.contains_key(&key));
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn fuzz_against_btree_map(
+            initial in prop::collection::btree_map(key(), value(), 0..10),
+            actions in prop::collection::vec(action(), 0..64),
+        ) {
+            let mut base = MockStorage::new();
+            for (key, value) in &initial {
+                base.write(key, value);
+            }
+
+            let mut buffer = Buffer::new(base, None);
+            let mut reference = initial;
+            // The handle and the reference state at each open savepoint.
+            let mut savepoints = Vec::<(Savepoint, BTreeMap<_, _>)>::new();
+
+            for action in actions {
+                match action {
+                    Action::Write(key, value) => {
+                        buffer.write(&key, &value);
+                        reference.insert(key, value);
+                    },
+                    Action::Remove(key) => {
+                        buffer.remove(&key);
+                        reference.remove(&key);
+                    },
+                    Action::RemoveRange(min, max) => {
+                        buffer.remove_range(min.as_deref(), max.as_deref());
+                        reference.retain(|key, _| {
+                            let in_range = min.as_ref().map_or(true, |min| key >= min)
+                                && max.as_ref().map_or(true, |max| key < max);
+                            !in_range
+                        });
+                    },
+                    Action::WriteBatch(batch) => {
+                        for (key, op) in &batch {
+                            match op {
+                                Op::Insert(value) => reference.insert(key.clone(), value.clone()),
+                                Op::Delete => reference.remove(key),
+                            };
+                        }
+                        buffer.write_batch(batch);
+                    },
+                    Action::Savepoint => {
+                        savepoints.push((buffer.savepoint(), reference.clone()));
+                    },
+                    Action::RollbackTo(index) => {
+                        if !savepoints.is_empty() {
+                            let index = index.index(savepoints.len());
+                            let (savepoint, snapshot) = savepoints[index].clone();
+                            buffer.rollback_to(savepoint);
+                            reference = snapshot;
+                            savepoints.truncate(index);
+                        }
+                    },
+                    Action::Release(index) => {
+                        if !savepoints.is_empty() {
+                            let index = index.index(savepoints.len());
+                            buffer.release(savepoints[index].0);
+                            savepoints.truncate(index);
+                        }
+                    },
+                    Action::Scan(min, max, order) => {
+                        let records = buffer
+                            .scan(min.as_deref(), max.as_deref(), order)
+                            .collect::<Vec<_>>();
+                        let expected = reference_scan(&reference, min.as_deref(), max.as_deref(), order);
+                        prop_assert_eq!(records, expected);
+                    },
+                }
+
+                prop_assert_eq!(buffer.depth(), savepoints.len());
+                assert_matches(&buffer, &reference);
+            }
+
+            // Once all savepoints are released, committing must leave the base
+            // store in the same state as the reference.
+            if let Some((savepoint, _)) = savepoints.first() {
+                buffer.release(*savepoint);
+            }
+            buffer.commit();
+            let (base, pending) = buffer.disassemble();
+            prop_assert!(pending.is_empty());
+            prop_assert_eq!(
+                collect_records(&base, Order::Ascending),
+                reference.into_iter().collect::<Vec<_>>()
+            );
+        }
+    }
 }
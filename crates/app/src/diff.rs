@@ -0,0 +1,101 @@
+use {
+    crate::{ChangeLog, CONTRACT_NAMESPACE},
+    grug_types::{Addr, Change, Hash, StateDiff, TxStateDiff},
+};
+
+/// Decode the change log of a transaction into a state diff for each of its
+/// stages.
+///
+/// The log is expected to have one section for `before_tx`, followed by one
+/// for each message and one for `after_tx`, as started by `process_tx`. If the
+/// messages failed, only the first section remains.
+pub fn decode_tx_state_diff(log: ChangeLog) -> TxStateDiff {
+    let mut sections = log.into_sections().into_iter().map(decode_state_diff);
+    let before_tx = sections.next().unwrap_or_default();
+    let mut msgs = sections.collect::<Vec<_>>();
+    let after_tx = msgs.pop().unwrap_or_default();
+
+    TxStateDiff {
+        before_tx,
+        msgs,
+        after_tx,
+    }
+}
+
+/// Decode a list of changes, such as a section of a `ChangeLog`, into a state
+/// diff, grouping the changes by the contract substores they belong to. The
+/// order of the changes within each group is kept.
+///
+/// A key belongs to a contract's substore if it's prefixed by the contract
+/// namespace followed by the contract's address (see `StorageProvider`). Such
+/// keys are trimmed to be relative to the substore. All other keys are
+/// considered chain-level.
+pub fn decode_state_diff(changes: Vec<Change>) -> StateDiff {
+    let mut diff = StateDiff::default();
+
+    for change in changes {
+        let Some((contract, key)) = split_contract_key(&change.key) else {
+            diff.chain.push(change);
+            continue;
+        };
+
+        diff.contracts.entry(contract).or_default().push(Change {
+            key: key.to_vec().into(),
+            old: change.old,
+            new: change.new,
+        });
+    }
+
+    diff
+}
+
+fn split_contract_key(key: &[u8]) -> Option<(Addr, &[u8])> {
+    let rest = key.strip_prefix(CONTRACT_NAMESPACE)?;
+    if rest.len() < Hash::LENGTH {
+        return None;
+    }
+
+    let (addr, key) = rest.split_at(Hash::LENGTH);
+    let addr = Addr::try_from(addr).ok()?;
+
+    Some((addr, key))
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, grug_types::concat, std::collections::BTreeMap};
+
+    #[test]
+    fn decoding_state_diff_works() {
+        let contract = Addr::mock(1);
+        let contract_key = concat(&concat(CONTRACT_NAMESPACE, contract.as_ref()), b"larry");
+
+        let changes = vec![
+            Change {
+                key: b"chain_id".to_vec().into(),
+                old: None,
+                new: Some(b"dev-1".to_vec().into()),
+            },
+            Change {
+                key: contract_key.into(),
+                old: Some(b"foo".to_vec().into()),
+                new: Some(b"bar".to_vec().into()),
+            },
+        ];
+
+        assert_eq!(decode_state_diff(changes), StateDiff {
+            chain: vec![Change {
+                key: b"chain_id".to_vec().into(),
+                old: None,
+                new: Some(b"dev-1".to_vec().into()),
+            }],
+            contracts: BTreeMap::from([(contract, vec![Change {
+                key: b"larry".to_vec().into(),
+                old: Some(b"foo".to_vec().into()),
+                new: Some(b"bar".to_vec().into()),
+            }])]),
+        });
+    }
+}
@@ -1,5 +1,5 @@
 use {
-    grug_types::{Addr, Hash, StdError},
+    grug_types::{Addr, ErrorCategory, ExecutionError, Hash, IbcClientStatus, StdError},
     thiserror::Error,
 };
 
@@ -8,8 +8,8 @@ pub enum AppError {
     #[error(transparent)]
     Std(#[from] StdError),
 
-    #[error("VM error: {0}")]
-    Vm(String),
+    #[error(transparent)]
+    Execution(#[from] ExecutionError),
 
     #[error("DB error: {0}")]
     Db(String),
@@ -41,8 +41,114 @@ pub enum AppError {
     #[error("account with address `{address}` already exists")]
     AccountExists { address: Addr },
 
+    #[error("too many nested savepoints! max depth: {max}")]
+    SavepointDepthExceeded { max: usize },
+
+    #[error("invalid event `{ty}`: {reason}")]
+    InvalidEvent { ty: String, reason: String },
+
+    #[error("contract `{contract}` is in the middle of a synchronous call and can't be reentered")]
+    Reentrancy { contract: Addr },
+
     #[error("code hash is not allowed as IBC client: `{code_hash}`")]
     NotAllowedClient { code_hash: Hash },
+
+    #[error("IBC client returned an invalid response: {reason}")]
+    InvalidClientResponse { reason: String },
+
+    #[error("IBC client `{client_id}` is not active! status: {status:?}")]
+    ClientNotActive {
+        client_id: Addr,
+        status: IbcClientStatus,
+    },
+
+    #[error("IBC connection `{connection_id}` not found")]
+    ConnectionNotFound { connection_id: String },
+
+    #[error("IBC connection `{connection_id}` is in an incorrect state! expecting: {expect}, actual: {actual}")]
+    IncorrectConnectionState {
+        connection_id: String,
+        expect: String,
+        actual: String,
+    },
+
+    #[error("counterparty {kind} ID is not set")]
+    CounterpartyIdNotSet { kind: &'static str },
+
+    #[error("IBC connection version is not supported: {version}")]
+    UnsupportedConnectionVersion { version: String },
+
+    #[error("IBC channel `{channel_id}` not found for port `{port_id}`")]
+    ChannelNotFound { port_id: Addr, channel_id: String },
+
+    #[error("IBC channel `{channel_id}` is in an incorrect state! expecting: {expect}, actual: {actual}")]
+    IncorrectChannelState {
+        channel_id: String,
+        expect: String,
+        actual: String,
+    },
+
+    #[error("IBC channel ordering `{ordering}` is not supported by connection `{connection_id}`")]
+    UnsupportedChannelOrdering {
+        connection_id: String,
+        ordering: String,
+    },
+
+    #[error("IBC packet timeout height and timestamp can't both be unset")]
+    EmptyPacketTimeout,
+
+    #[error("IBC packet doesn't match the channel's counterparty! port: `{port_id}`, channel: `{channel_id}`")]
+    IncorrectPacketCounterparty { port_id: String, channel_id: String },
+
+    #[error("IBC packet sequence is incorrect! expecting: {expect}, actual: {actual}")]
+    IncorrectPacketSequence { expect: u64, actual: u64 },
+
+    #[error("IBC packet with sequence {sequence} has timed out")]
+    PacketTimedOut { sequence: u64 },
+
+    #[error("IBC packet with sequence {sequence} has not timed out")]
+    PacketNotTimedOut { sequence: u64 },
+
+    #[error("IBC packet with sequence {sequence} has already been received")]
+    PacketAlreadyReceived { sequence: u64 },
+
+    #[error("IBC packet with sequence {sequence} doesn't match any commitment")]
+    PacketCommitmentNotFound { sequence: u64 },
+
+    #[error("failed to verify proof of IBC state at path `{path}`")]
+    ProofVerificationFailed { path: String },
+}
+
+impl AppError {
+    /// Return the category of the error, i.e. which party is at fault.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            AppError::Execution(err) => err.category,
+            AppError::Std(StdError::Serialize { .. } | StdError::Deserialize { .. }) => {
+                ErrorCategory::Deserialize
+            },
+            AppError::Std(StdError::DataNotFound { .. }) => ErrorCategory::NotFound,
+            AppError::InvalidEvent { .. } | AppError::InvalidClientResponse { .. } => {
+                ErrorCategory::Contract
+            },
+            _ => ErrorCategory::Host,
+        }
+    }
+
+    /// Convert the error into a structured execution error, which can be
+    /// passed to contracts and clients.
+    pub fn into_execution_error(self) -> ExecutionError {
+        match self {
+            AppError::Execution(err) => err,
+            err => ExecutionError::new(err.category(), err),
+        }
+    }
+
+    /// Attribute the error to a call to the given contract and entry point,
+    /// unless it's already been attributed to one.
+    pub fn attribute(self, contract: &Addr, entry_point: &str) -> Self {
+        AppError::Execution(self.into_execution_error().attribute(contract, entry_point))
+    }
 }
 
 pub type AppResult<T> = core::result::Result<T, AppError>;
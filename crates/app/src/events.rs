@@ -4,6 +4,63 @@
 // attribute that impersonates state machine attributes in order to fool indexers.
 pub const CONTRACT_ADDRESS_KEY: &str = "_contract_address";
 
+/// Attribute key representing the entry point that emitted an event, added to
+/// events emitted by contracts with `Response::add_event`.
+pub const ENTRY_POINT_KEY: &str = "_entry_point";
+
+/// Types of the events emitted by the state machine: one for each contract call,
+/// named after the entry point, and those of the IBC handshakes and packets.
+///
+/// Contracts are not allowed to emit structured events of these types. Indexers
+/// and relayers trust them to come from the state machine; a relayer would
+/// otherwise relay a packet from a `send_packet` event forged by a contract.
+pub const HOST_EVENT_TYPES: &[&str] = &[
+    // contract calls
+    "instantiate",
+    "execute",
+    "migrate",
+    "receive",
+    "reply",
+    "bank_execute",
+    "before_block",
+    "after_block",
+    "before_tx",
+    "after_tx",
+    "ibc_client_create",
+    "ibc_client_update",
+    "ibc_client_misbehavior",
+    "ibc_channel_open",
+    "ibc_channel_connect",
+    "ibc_packet_receive",
+    "ibc_packet_ack",
+    "ibc_packet_timeout",
+    // chain
+    "set_config",
+    "upload",
+    // IBC clients
+    "create_client",
+    "update_client",
+    "client_misbehaviour",
+    // IBC connections
+    "connection_open_init",
+    "connection_open_try",
+    "connection_open_ack",
+    "connection_open_confirm",
+    // IBC channels
+    "channel_open_init",
+    "channel_open_try",
+    "channel_open_ack",
+    "channel_open_confirm",
+    "channel_closed",
+    // IBC packets
+    "send_packet",
+    "recv_packet",
+    "recv_packet_error",
+    "write_acknowledgement",
+    "acknowledge_packet",
+    "timeout_packet",
+];
+
 // Below: IBC event attribute keys.
 // For IBC events, we keep them consistent with ibc-go, which may make relayer
 // itegration easier.
@@ -25,3 +82,95 @@ pub const CLIENT_ID_KEY: &str = "client_id";
 /// In ibc-go, this is a string such as `07-tendermint`. In out case, this is
 /// the client contract's Wasm code hash.
 pub const CLIENT_TYPE_KEY: &str = "client_type";
+
+/// Attribute key representing the height of the consensus state that an IBC
+/// client was created or updated with, or at which it was frozen, in the
+/// format of `{revision_number}-{revision_height}`.
+pub const CONSENSUS_HEIGHT_KEY: &str = "consensus_height";
+
+/// Attribute key representing the header that an IBC client was updated with,
+/// hex-encoded.
+///
+/// In ibc-go, this is the Protobuf encoding of the header. In our case, this is
+/// its JSON encoding, since the format of the header is up to the client.
+pub const HEADER_KEY: &str = "header";
+
+/// Attribute key representing the identifier of an IBC client on the
+/// counterparty chain.
+pub const COUNTERPARTY_CLIENT_ID_KEY: &str = "counterparty_client_id";
+
+/// Attribute key representing the identifier of an IBC connection, such as
+/// `connection-0`.
+pub const CONNECTION_ID_KEY: &str = "connection_id";
+
+/// Attribute key representing the identifier of an IBC connection on the
+/// counterparty chain.
+pub const COUNTERPARTY_CONNECTION_ID_KEY: &str = "counterparty_connection_id";
+
+/// Attribute key representing the identifier of an IBC port.
+///
+/// In ibc-go, this is a string such as `transfer`. In our case, this is the
+/// address of the contract that owns the port.
+pub const PORT_ID_KEY: &str = "port_id";
+
+/// Attribute key representing the identifier of an IBC channel, such as
+/// `channel-0`.
+pub const CHANNEL_ID_KEY: &str = "channel_id";
+
+/// Attribute key representing the identifier of an IBC port on the
+/// counterparty chain.
+pub const COUNTERPARTY_PORT_ID_KEY: &str = "counterparty_port_id";
+
+/// Attribute key representing the identifier of an IBC channel on the
+/// counterparty chain.
+pub const COUNTERPARTY_CHANNEL_ID_KEY: &str = "counterparty_channel_id";
+
+/// Attribute key representing the application version of an IBC channel.
+pub const VERSION_KEY: &str = "version";
+
+// Below: IBC packet event attribute keys, same as ibc-go's. Relayers rely on
+// these to find packets to relay.
+
+/// Attribute key representing a packet's data, hex-encoded.
+pub const PACKET_DATA_HEX_KEY: &str = "packet_data_hex";
+
+/// Attribute key representing a packet's timeout height, in the format of
+/// `{revision_number}-{revision_height}`. Zero means no timeout height.
+pub const PACKET_TIMEOUT_HEIGHT_KEY: &str = "packet_timeout_height";
+
+/// Attribute key representing a packet's timeout timestamp, in nanoseconds.
+/// Zero means no timeout timestamp.
+pub const PACKET_TIMEOUT_TIMESTAMP_KEY: &str = "packet_timeout_timestamp";
+
+/// Attribute key representing a packet's sequence.
+pub const PACKET_SEQUENCE_KEY: &str = "packet_sequence";
+
+/// Attribute key representing the port a packet was sent from.
+pub const PACKET_SRC_PORT_KEY: &str = "packet_src_port";
+
+/// Attribute key representing the channel a packet was sent from.
+pub const PACKET_SRC_CHANNEL_KEY: &str = "packet_src_channel";
+
+/// Attribute key representing the port a packet was sent to.
+pub const PACKET_DST_PORT_KEY: &str = "packet_dst_port";
+
+/// Attribute key representing the channel a packet was sent to.
+pub const PACKET_DST_CHANNEL_KEY: &str = "packet_dst_channel";
+
+/// Attribute key representing the ordering of the channel a packet was sent
+/// over, e.g. `ORDER_UNORDERED`.
+pub const PACKET_CHANNEL_ORDERING_KEY: &str = "packet_channel_ordering";
+
+/// Attribute key representing the connection a packet was sent over.
+pub const PACKET_CONNECTION_KEY: &str = "packet_connection";
+
+/// Attribute key representing the acknowledgement of a packet, hex-encoded.
+pub const PACKET_ACK_HEX_KEY: &str = "packet_ack_hex";
+
+/// Attribute key representing the category of the error a contract returned
+/// when receiving a packet, e.g. `contract`.
+pub const PACKET_ERROR_CATEGORY_KEY: &str = "packet_error_category";
+
+/// Attribute key representing the error a contract returned when receiving a
+/// packet. The error acknowledgement only includes the error's category.
+pub const PACKET_ERROR_KEY: &str = "packet_error";
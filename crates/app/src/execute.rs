@@ -2,14 +2,28 @@
 use tracing::{debug, info, warn};
 use {
     crate::{
+        assert_channel_state, assert_client_active, assert_connection_state,
+        assert_ordering_supported, assert_packet_channel, assert_version_proposed,
         call_in_0_out_1_handle_response, call_in_1_out_1_handle_response,
-        call_in_2_out_1_handle_response, has_permission, AppError, AppResult, Vm, ACCOUNTS,
-        CHAIN_ID, CODES, CONFIG,
+        call_in_2_out_1_handle_response, channel_event, channel_path, connection_event,
+        connection_path, expected_counterparty_channel, expected_counterparty_connection,
+        format_height, has_permission, init_sequences, load_channel, load_connection,
+        load_sequence, next_sequence_ack_path, next_sequence_recv_path, next_sequence_send_path,
+        packet_ack_path, packet_commitment, packet_commitment_path, packet_event,
+        packet_receipt_path, pick_version, query_timestamp_at_height, read_ibc, remove_ibc,
+        save_channel, save_connection, save_sequence, verify_membership, verify_non_membership,
+        write_ibc, AppError, AppResult, TxStore, Vm, ACCOUNTS, CHAIN_ID, CLIENT_ID_KEY,
+        CLIENT_TYPE_KEY, CODES, CONFIG, CONSENSUS_HEIGHT_KEY, HEADER_KEY, NEXT_CHANNEL_SEQUENCE,
+        NEXT_CONNECTION_SEQUENCE, PACKET_ACK_HEX_KEY, PACKET_ERROR_CATEGORY_KEY, PACKET_ERROR_KEY,
     },
     grug_types::{
-        hash, Account, Addr, BankMsg, Binary, BlockInfo, Coins, Config, Context, Event, Hash, Json,
-        Storage, SubMsgResult, Tx,
+        from_json_slice, hash, to_json_vec, Account, Addr, BankMsg, Binary, BlockInfo, Coins,
+        Config, Context, Event, Hash, IbcAcknowledgement, IbcChannelConnectMsg,
+        IbcChannelCounterparty, IbcChannelEnd, IbcChannelOpenMsg, IbcChannelOrder, IbcChannelState,
+        IbcClientResponseData, IbcConnectionCounterparty, IbcConnectionEnd, IbcConnectionState,
+        IbcConnectionVersion, IbcPacket, IbcTimeout, Json, MsgResponse, Storage, SubMsgResult, Tx,
     },
+    serde::Serialize,
 };
 
 // ---------------------------------- config -----------------------------------
@@ -54,12 +68,17 @@ fn _do_set_config(storage: &mut dyn Storage, sender: &Addr, new_cfg: &Config) ->
 
 // ---------------------------------- upload -----------------------------------
 
-pub fn do_upload(
+pub fn do_upload<VM>(
+    vm: &VM,
     storage: &mut dyn Storage,
     uploader: &Addr,
     code: Vec<u8>,
-) -> AppResult<Vec<Event>> {
-    match _do_upload(storage, uploader, code) {
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm,
+    AppError: From<VM::Error>,
+{
+    match _do_upload(vm, storage, uploader, code) {
         Ok((event, _code_hash)) => {
             #[cfg(feature = "tracing")]
             info!(code_hash = _code_hash.to_string(), "Uploaded code");
@@ -74,17 +93,25 @@ pub fn do_upload(
 }
 
 // Return the hash of the code that is stored, for logging purpose.
-fn _do_upload(
+fn _do_upload<VM>(
+    vm: &VM,
     storage: &mut dyn Storage,
     uploader: &Addr,
     code: Vec<u8>,
-) -> AppResult<(Event, Hash)> {
+) -> AppResult<(Event, Hash)>
+where
+    VM: Vm,
+    AppError: From<VM::Error>,
+{
     // Make sure the user has the permission to upload contracts
     let cfg = CONFIG.load(storage)?;
     if !has_permission(&cfg.permissions.upload, cfg.owner.as_ref(), uploader) {
         return Err(AppError::Unauthorized);
     }
 
+    // Make sure the code can be run by the VM
+    vm.validate_code(&code)?;
+
     // Make sure that the same code isn't already uploaded
     let code_hash = hash(&code);
     if CODES.has(storage, &code_hash) {
@@ -103,7 +130,7 @@ fn _do_upload(
 
 pub fn do_transfer<VM>(
     vm: VM,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     from: Addr,
     to: Addr,
@@ -143,7 +170,7 @@ where
 
 fn _do_transfer<VM>(
     vm: VM,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     from: Addr,
     to: Addr,
@@ -179,7 +206,8 @@ where
         &account.code_hash,
         &ctx,
         &msg,
-    )?;
+    )?
+    .events;
 
     if do_receive {
         events.extend(_do_receive(vm, storage, ctx.block, msg)?);
@@ -190,7 +218,7 @@ where
 
 fn _do_receive<VM>(
     vm: VM,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     msg: BankMsg,
 ) -> AppResult<Vec<Event>>
@@ -210,13 +238,14 @@ where
     };
 
     call_in_0_out_1_handle_response(vm, "receive", storage, &account.code_hash, &ctx)
+        .map(|res| res.events)
 }
 
 // -------------------------------- instantiate --------------------------------
 
 pub fn do_instantiate<VM>(
     vm: VM,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     sender: Addr,
     code_hash: Hash,
@@ -224,7 +253,7 @@ pub fn do_instantiate<VM>(
     salt: Binary,
     funds: Coins,
     admin: Option<Addr>,
-) -> AppResult<Vec<Event>>
+) -> AppResult<MsgResponse>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -232,10 +261,10 @@ where
     match _do_instantiate(
         vm, storage, block, sender, code_hash, msg, salt, funds, admin,
     ) {
-        Ok((events, _address)) => {
+        Ok((res, _address)) => {
             #[cfg(feature = "tracing")]
             info!(address = _address.to_string(), "Instantiated contract");
-            Ok(events)
+            Ok(res)
         },
         Err(err) => {
             #[cfg(feature = "tracing")]
@@ -247,7 +276,7 @@ where
 
 pub fn _do_instantiate<VM>(
     vm: VM,
-    mut storage: Box<dyn Storage>,
+    mut storage: TxStore,
     block: BlockInfo,
     sender: Addr,
     code_hash: Hash,
@@ -255,7 +284,7 @@ pub fn _do_instantiate<VM>(
     salt: Binary,
     funds: Coins,
     admin: Option<Addr>,
-) -> AppResult<(Vec<Event>, Addr)>
+) -> AppResult<(MsgResponse, Addr)>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -302,38 +331,39 @@ where
         funds: Some(funds),
         simulate: None,
     };
-    events.extend(call_in_1_out_1_handle_response(
-        vm,
-        "instantiate",
-        storage,
-        &account.code_hash,
-        &ctx,
-        msg,
-    )?);
+    let res =
+        call_in_1_out_1_handle_response(vm, "instantiate", storage, &account.code_hash, &ctx, msg)?;
+    events.extend(res.events);
 
-    Ok((events, ctx.contract))
+    Ok((
+        MsgResponse {
+            events,
+            data: res.data,
+        },
+        ctx.contract,
+    ))
 }
 
 // ---------------------------------- execute ----------------------------------
 
 pub fn do_execute<VM>(
     vm: VM,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     contract: Addr,
     sender: Addr,
     msg: &Json,
     funds: Coins,
-) -> AppResult<Vec<Event>>
+) -> AppResult<MsgResponse>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
     match _do_execute(vm, storage, block, contract.clone(), sender, msg, funds) {
-        Ok(events) => {
+        Ok(res) => {
             #[cfg(feature = "tracing")]
             info!(contract = contract.to_string(), "Executed contract");
-            Ok(events)
+            Ok(res)
         },
         Err(err) => {
             #[cfg(feature = "tracing")]
@@ -345,13 +375,13 @@ where
 
 fn _do_execute<VM>(
     vm: VM,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     contract: Addr,
     sender: Addr,
     msg: &Json,
     funds: Coins,
-) -> AppResult<Vec<Event>>
+) -> AppResult<MsgResponse>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -382,29 +412,114 @@ where
         funds: Some(funds),
         simulate: None,
     };
-    events.extend(call_in_1_out_1_handle_response(
+    let res =
+        call_in_1_out_1_handle_response(vm, "execute", storage, &account.code_hash, &ctx, msg)?;
+    events.extend(res.events);
+
+    Ok(MsgResponse {
+        events,
+        data: res.data,
+    })
+}
+
+// ----------------------------------- call ------------------------------------
+
+/// Execute a contract synchronously, on behalf of another contract which is in
+/// the middle of its own execution.
+///
+/// The call is made under a savepoint, so if it fails, the changes it has made
+/// are discarded and the error is returned to the calling contract to handle.
+/// The calling contract is locked until the call returns, so that it can't be
+/// reentered while its own changes are only partially written.
+pub fn do_call<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    caller: Addr,
+    contract: Addr,
+    msg: &Json,
+    funds: Coins,
+) -> AppResult<MsgResponse>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_call(
         vm,
-        "execute",
         storage,
-        &account.code_hash,
-        &ctx,
+        block,
+        caller.clone(),
+        contract.clone(),
         msg,
-    )?);
+        funds,
+    ) {
+        Ok(res) => {
+            #[cfg(feature = "tracing")]
+            info!(
+                caller = caller.to_string(),
+                contract = contract.to_string(),
+                "Called contract"
+            );
+            Ok(res)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to call contract");
+            Err(err)
+        },
+    }
+}
 
-    Ok(events)
+fn _do_call<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    caller: Addr,
+    contract: Addr,
+    msg: &Json,
+    funds: Coins,
+) -> AppResult<MsgResponse>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let savepoint = storage.savepoint()?;
+
+    storage.lock(caller.clone());
+    let result = _do_execute(
+        vm,
+        storage.clone(),
+        block,
+        contract,
+        caller.clone(),
+        msg,
+        funds,
+    );
+    storage.unlock(&caller);
+
+    match result {
+        Ok(res) => {
+            storage.release(savepoint);
+            Ok(res)
+        },
+        Err(err) => {
+            storage.rollback_to(savepoint);
+            Err(err)
+        },
+    }
 }
 
 // ---------------------------------- migrate ----------------------------------
 
 pub fn do_migrate<VM>(
     vm: VM,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     contract: Addr,
     sender: Addr,
     new_code_hash: Hash,
     msg: &Json,
-) -> AppResult<Vec<Event>>
+) -> AppResult<MsgResponse>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -418,10 +533,10 @@ where
         new_code_hash,
         msg,
     ) {
-        Ok(events) => {
+        Ok(res) => {
             #[cfg(feature = "tracing")]
             info!(contract = contract.to_string(), "Migrated contract");
-            Ok(events)
+            Ok(res)
         },
         Err(err) => {
             #[cfg(feature = "tracing")]
@@ -433,13 +548,13 @@ where
 
 fn _do_migrate<VM>(
     vm: VM,
-    mut storage: Box<dyn Storage>,
+    mut storage: TxStore,
     block: BlockInfo,
     contract: Addr,
     sender: Addr,
     new_code_hash: Hash,
     msg: &Json,
-) -> AppResult<Vec<Event>>
+) -> AppResult<MsgResponse>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -478,7 +593,7 @@ where
 
 pub fn do_reply<VM>(
     vm: VM,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     contract: Addr,
     msg: &Json,
@@ -504,7 +619,7 @@ where
 
 fn _do_reply<VM>(
     vm: VM,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     contract: Addr,
     msg: &Json,
@@ -526,21 +641,23 @@ where
     };
 
     call_in_2_out_1_handle_response(vm, "reply", storage, &account.code_hash, &ctx, msg, result)
+        .map(|res| res.events)
 }
 
 // ------------------------- before/after transaction --------------------------
 
 pub fn do_before_tx<VM>(
     vm: VM,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     tx: &Tx,
+    simulate: bool,
 ) -> AppResult<Vec<Event>>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    match _do_before_or_after_tx(vm, "before_tx", storage, block, tx) {
+    match _do_before_or_after_tx(vm, "before_tx", storage, block, tx, simulate) {
         Ok(events) => {
             // TODO: add txhash here?
             #[cfg(feature = "tracing")]
@@ -563,15 +680,16 @@ where
 
 pub fn do_after_tx<VM>(
     vm: VM,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     tx: &Tx,
+    simulate: bool,
 ) -> AppResult<Vec<Event>>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    match _do_before_or_after_tx(vm, "after_tx", storage, block, tx) {
+    match _do_before_or_after_tx(vm, "after_tx", storage, block, tx, simulate) {
         Ok(events) => {
             // TODO: add txhash here?
             #[cfg(feature = "tracing")]
@@ -595,9 +713,10 @@ where
 fn _do_before_or_after_tx<VM>(
     vm: VM,
     name: &'static str,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     tx: &Tx,
+    simulate: bool,
 ) -> AppResult<Vec<Event>>
 where
     VM: Vm + Clone,
@@ -611,17 +730,18 @@ where
         contract: tx.sender.clone(),
         sender: None,
         funds: None,
-        simulate: Some(false),
+        simulate: Some(simulate),
     };
 
     call_in_1_out_1_handle_response(vm, name, storage, &account.code_hash, &ctx, tx)
+        .map(|res| res.events)
 }
 
 // ---------------------------- before/after block -----------------------------
 
 pub fn do_before_block<VM>(
     vm: VM,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     contract: Addr,
 ) -> AppResult<Vec<Event>>
@@ -645,7 +765,7 @@ where
 
 pub fn do_after_block<VM>(
     vm: VM,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     contract: Addr,
 ) -> AppResult<Vec<Event>>
@@ -670,7 +790,7 @@ where
 fn _do_before_or_after_block<VM>(
     vm: VM,
     name: &'static str,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     contract: Addr,
 ) -> AppResult<Vec<Event>>
@@ -690,4 +810,1792 @@ where
     };
 
     call_in_0_out_1_handle_response(vm, name, storage, &account.code_hash, &ctx)
+        .map(|res| res.events)
+}
+
+// ----------------------------- ibc client create -----------------------------
+
+pub fn do_create_client<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    sender: Addr,
+    code_hash: Hash,
+    client_state: &Json,
+    consensus_state: &Json,
+    salt: Binary,
+) -> AppResult<MsgResponse>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_create_client(
+        vm,
+        storage,
+        block,
+        sender,
+        code_hash,
+        client_state,
+        consensus_state,
+        salt,
+    ) {
+        Ok((res, _client_id)) => {
+            #[cfg(feature = "tracing")]
+            info!(client_id = _client_id.to_string(), "Created IBC client");
+            Ok(res)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to create IBC client");
+            Err(err)
+        },
+    }
+}
+
+fn _do_create_client<VM>(
+    vm: VM,
+    mut storage: TxStore,
+    block: BlockInfo,
+    sender: Addr,
+    code_hash: Hash,
+    client_state: &Json,
+    consensus_state: &Json,
+    salt: Binary,
+) -> AppResult<(MsgResponse, Addr)>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let chain_id = CHAIN_ID.load(&storage)?;
+
+    // Make sure the user has the permission to create clients, and the code is
+    // approved for use as an IBC client.
+    let cfg = CONFIG.load(&storage)?;
+    if !has_permission(&cfg.permissions.create_client, cfg.owner.as_ref(), &sender) {
+        return Err(AppError::Unauthorized);
+    }
+    if !cfg.allowed_clients.contains(&code_hash) {
+        return Err(AppError::NotAllowedClient { code_hash });
+    }
+
+    // Compute the client ID, and make sure there isn't already an account of
+    // the same address.
+    let address = Addr::compute(&sender, &code_hash, &salt);
+    if ACCOUNTS.has(&storage, &address) {
+        return Err(AppError::AccountExists { address });
+    }
+
+    // Clients don't have admins. They can't be migrated.
+    let account = Account {
+        code_hash,
+        admin: None,
+    };
+    ACCOUNTS.save(&mut storage, &address, &account)?;
+
+    // Call the client's `ibc_client_create` entry point
+    let ctx = Context {
+        chain_id,
+        block,
+        contract: address,
+        sender: None,
+        funds: None,
+        simulate: None,
+    };
+    let res = call_in_2_out_1_handle_response(
+        vm,
+        "ibc_client_create",
+        storage,
+        &account.code_hash,
+        &ctx,
+        client_state,
+        consensus_state,
+    )?;
+
+    let consensus_height = client_consensus_height(&res, &ctx.contract, "ibc_client_create")?;
+
+    let mut events = vec![Event::new("create_client")
+        .add_attribute(CLIENT_ID_KEY, &ctx.contract)
+        .add_attribute(CLIENT_TYPE_KEY, &account.code_hash)
+        .add_attribute(CONSENSUS_HEIGHT_KEY, format_height(consensus_height))];
+    events.extend(res.events);
+
+    Ok((
+        MsgResponse {
+            events,
+            data: res.data,
+        },
+        ctx.contract,
+    ))
+}
+
+// ----------------------------- ibc client update -----------------------------
+
+pub fn do_update_client<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    client_id: Addr,
+    header: &Json,
+) -> AppResult<MsgResponse>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_update_or_freeze_client(
+        vm,
+        "ibc_client_update",
+        "update_client",
+        true,
+        storage,
+        block,
+        client_id.clone(),
+        header,
+    ) {
+        Ok(res) => {
+            #[cfg(feature = "tracing")]
+            info!(client_id = client_id.to_string(), "Updated IBC client");
+            Ok(res)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to update IBC client");
+            Err(err)
+        },
+    }
+}
+
+// -------------------------- ibc client misbehavior ---------------------------
+
+pub fn do_submit_misbehavior<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    client_id: Addr,
+    misbehavior: &Json,
+) -> AppResult<MsgResponse>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_update_or_freeze_client(
+        vm,
+        "ibc_client_misbehavior",
+        "client_misbehaviour", // ibc-go uses British spelling
+        false,
+        storage,
+        block,
+        client_id.clone(),
+        misbehavior,
+    ) {
+        Ok(res) => {
+            #[cfg(feature = "tracing")]
+            info!(
+                client_id = client_id.to_string(),
+                "Submitted IBC client misbehavior"
+            );
+            Ok(res)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(
+                err = err.to_string(),
+                "Failed to submit IBC client misbehavior"
+            );
+            Err(err)
+        },
+    }
+}
+
+// Updating a client and submitting misbehavior evidence differ only in which
+// entry point is called, and whether the header is included in the event.
+// Neither is permissioned: the client is responsible for verifying the header
+// or evidence. The client's code must still be on the allow list though, in
+// case governance has since removed it.
+fn _do_update_or_freeze_client<VM>(
+    vm: VM,
+    name: &'static str,
+    event_type: &'static str,
+    include_header: bool,
+    storage: TxStore,
+    block: BlockInfo,
+    client_id: Addr,
+    msg: &Json,
+) -> AppResult<MsgResponse>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let chain_id = CHAIN_ID.load(&storage)?;
+    let cfg = CONFIG.load(&storage)?;
+    let account = ACCOUNTS.load(&storage, &client_id)?;
+    if !cfg.allowed_clients.contains(&account.code_hash) {
+        return Err(AppError::NotAllowedClient {
+            code_hash: account.code_hash,
+        });
+    }
+
+    let ctx = Context {
+        chain_id,
+        block,
+        contract: client_id,
+        sender: None,
+        funds: None,
+        simulate: None,
+    };
+    let res = call_in_1_out_1_handle_response(vm, name, storage, &account.code_hash, &ctx, msg)?;
+    let consensus_height = client_consensus_height(&res, &ctx.contract, name)?;
+
+    let mut event = Event::new(event_type)
+        .add_attribute(CLIENT_ID_KEY, &ctx.contract)
+        .add_attribute(CLIENT_TYPE_KEY, &account.code_hash)
+        .add_attribute(CONSENSUS_HEIGHT_KEY, format_height(consensus_height));
+    if include_header {
+        event = event.add_attribute(HEADER_KEY, hex::encode(to_json_vec(msg)?));
+    }
+
+    let mut events = vec![event];
+    events.extend(res.events);
+
+    Ok(MsgResponse {
+        events,
+        data: res.data,
+    })
+}
+
+/// Read the consensus height that a client sets as response data when it's
+/// created, updated, or frozen. Failing to set it is the client's fault.
+fn client_consensus_height(res: &MsgResponse, client_id: &Addr, name: &str) -> AppResult<u64> {
+    let Some(data) = &res.data else {
+        return Err(AppError::InvalidClientResponse {
+            reason: "response data is not set".to_string(),
+        }
+        .attribute(client_id, name));
+    };
+
+    from_json_slice::<IbcClientResponseData>(data)
+        .map(|data| data.consensus_height)
+        .map_err(|err| {
+            AppError::InvalidClientResponse {
+                reason: err.to_string(),
+            }
+            .attribute(client_id, name)
+        })
+}
+
+// ----------------------------- ibc connection init ---------------------------
+
+pub fn do_conn_open_init<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    sender: &Addr,
+    client_id: Addr,
+    counterparty: IbcConnectionCounterparty,
+    delay_period: u64,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_conn_open_init(
+        vm,
+        storage,
+        block,
+        sender,
+        client_id,
+        counterparty,
+        delay_period,
+    ) {
+        Ok((event, _connection_id)) => {
+            #[cfg(feature = "tracing")]
+            info!(connection_id = _connection_id, "Initialized IBC connection");
+            Ok(vec![event])
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to initialize IBC connection");
+            Err(err)
+        },
+    }
+}
+
+fn _do_conn_open_init<VM>(
+    vm: VM,
+    mut storage: TxStore,
+    block: BlockInfo,
+    sender: &Addr,
+    client_id: Addr,
+    mut counterparty: IbcConnectionCounterparty,
+    delay_period: u64,
+) -> AppResult<(Event, String)>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let cfg = CONFIG.load(&storage)?;
+    if !has_permission(
+        &cfg.permissions.create_connection,
+        cfg.owner.as_ref(),
+        sender,
+    ) {
+        return Err(AppError::Unauthorized);
+    }
+
+    assert_client_active(vm, Box::new(storage.clone()), block, &client_id)?;
+
+    // The counterparty doesn't have a connection end yet, so whatever ID the
+    // sender provided is ignored.
+    counterparty.connection_id = None;
+
+    let connection_id = next_connection_id(&mut storage)?;
+    let end = IbcConnectionEnd {
+        client_id,
+        versions: vec![IbcConnectionVersion::default()],
+        state: IbcConnectionState::Init,
+        counterparty,
+        delay_period,
+    };
+    save_connection(&mut storage, &connection_id, &end);
+
+    Ok((
+        connection_event("connection_open_init", &connection_id, &end),
+        connection_id,
+    ))
+}
+
+// ----------------------------- ibc connection try ----------------------------
+
+pub fn do_conn_open_try<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    client_id: Addr,
+    counterparty: IbcConnectionCounterparty,
+    counterparty_versions: Vec<IbcConnectionVersion>,
+    delay_period: u64,
+    proof_height: u64,
+    proof_init: Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_conn_open_try(
+        vm,
+        storage,
+        block,
+        client_id,
+        counterparty,
+        counterparty_versions,
+        delay_period,
+        proof_height,
+        proof_init,
+    ) {
+        Ok((event, _connection_id)) => {
+            #[cfg(feature = "tracing")]
+            info!(
+                connection_id = _connection_id,
+                "Tried opening IBC connection"
+            );
+            Ok(vec![event])
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(
+                err = err.to_string(),
+                "Failed to try opening IBC connection"
+            );
+            Err(err)
+        },
+    }
+}
+
+fn _do_conn_open_try<VM>(
+    vm: VM,
+    mut storage: TxStore,
+    block: BlockInfo,
+    client_id: Addr,
+    counterparty: IbcConnectionCounterparty,
+    counterparty_versions: Vec<IbcConnectionVersion>,
+    delay_period: u64,
+    proof_height: u64,
+    proof_init: Json,
+) -> AppResult<(Event, String)>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    // Unlike `ConnOpenInit`, this doesn't require the permission to create
+    // connections. The handshake has already been started on the counterparty
+    // chain, so as in ibc-go anyone may relay the remaining steps; the proof of
+    // the counterparty's connection end is what authorizes them.
+    assert_client_active(
+        vm.clone(),
+        Box::new(storage.clone()),
+        block.clone(),
+        &client_id,
+    )?;
+
+    let Some(counterparty_connection_id) = counterparty.connection_id.clone() else {
+        return Err(AppError::CounterpartyIdNotSet { kind: "connection" });
+    };
+    let version = pick_version(&counterparty_versions)?;
+    let end = IbcConnectionEnd {
+        client_id,
+        versions: vec![version],
+        state: IbcConnectionState::TryOpen,
+        counterparty,
+        delay_period,
+    };
+
+    // The counterparty's connection end must be in the `Init` state, and
+    // point to the client that tracks the counterparty on our chain.
+    verify_membership(
+        vm,
+        Box::new(storage.clone()),
+        block,
+        &end.client_id,
+        &end.counterparty.prefix,
+        proof_height,
+        0,
+        connection_path(&counterparty_connection_id),
+        expected_counterparty_connection(
+            &end,
+            None,
+            &counterparty_versions,
+            IbcConnectionState::Init,
+        ),
+        proof_init,
+    )?;
+
+    let connection_id = next_connection_id(&mut storage)?;
+    save_connection(&mut storage, &connection_id, &end);
+
+    Ok((
+        connection_event("connection_open_try", &connection_id, &end),
+        connection_id,
+    ))
+}
+
+// ----------------------------- ibc connection ack ----------------------------
+
+pub fn do_conn_open_ack<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    connection_id: String,
+    counterparty_connection_id: String,
+    version: IbcConnectionVersion,
+    proof_height: u64,
+    proof_try: Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_conn_open_ack(
+        vm,
+        storage,
+        block,
+        &connection_id,
+        counterparty_connection_id,
+        version,
+        proof_height,
+        proof_try,
+    ) {
+        Ok(event) => {
+            #[cfg(feature = "tracing")]
+            info!(connection_id, "Opened IBC connection");
+            Ok(vec![event])
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(
+                err = err.to_string(),
+                "Failed to acknowledge IBC connection"
+            );
+            Err(err)
+        },
+    }
+}
+
+fn _do_conn_open_ack<VM>(
+    vm: VM,
+    mut storage: TxStore,
+    block: BlockInfo,
+    connection_id: &str,
+    counterparty_connection_id: String,
+    version: IbcConnectionVersion,
+    proof_height: u64,
+    proof_try: Json,
+) -> AppResult<Event>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let mut end = load_connection(&storage, connection_id)?;
+    assert_connection_state(connection_id, &end, IbcConnectionState::Init)?;
+    assert_version_proposed(&version, &end.versions)?;
+    assert_client_active(
+        vm.clone(),
+        Box::new(storage.clone()),
+        block.clone(),
+        &end.client_id,
+    )?;
+
+    // The counterparty's connection end must be in the `TryOpen` state, and
+    // point to our connection end.
+    verify_membership(
+        vm,
+        Box::new(storage.clone()),
+        block,
+        &end.client_id,
+        &end.counterparty.prefix,
+        proof_height,
+        0,
+        connection_path(&counterparty_connection_id),
+        expected_counterparty_connection(
+            &end,
+            Some(connection_id),
+            std::slice::from_ref(&version),
+            IbcConnectionState::TryOpen,
+        ),
+        proof_try,
+    )?;
+
+    end.state = IbcConnectionState::Open;
+    end.versions = vec![version];
+    end.counterparty.connection_id = Some(counterparty_connection_id);
+    save_connection(&mut storage, connection_id, &end);
+
+    Ok(connection_event("connection_open_ack", connection_id, &end))
+}
+
+// --------------------------- ibc connection confirm --------------------------
+
+pub fn do_conn_open_confirm<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    connection_id: String,
+    proof_height: u64,
+    proof_ack: Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_conn_open_confirm(vm, storage, block, &connection_id, proof_height, proof_ack) {
+        Ok(event) => {
+            #[cfg(feature = "tracing")]
+            info!(connection_id, "Opened IBC connection");
+            Ok(vec![event])
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to confirm IBC connection");
+            Err(err)
+        },
+    }
+}
+
+fn _do_conn_open_confirm<VM>(
+    vm: VM,
+    mut storage: TxStore,
+    block: BlockInfo,
+    connection_id: &str,
+    proof_height: u64,
+    proof_ack: Json,
+) -> AppResult<Event>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let mut end = load_connection(&storage, connection_id)?;
+    assert_connection_state(connection_id, &end, IbcConnectionState::TryOpen)?;
+    assert_client_active(
+        vm.clone(),
+        Box::new(storage.clone()),
+        block.clone(),
+        &end.client_id,
+    )?;
+
+    let Some(counterparty_connection_id) = end.counterparty.connection_id.clone() else {
+        return Err(AppError::CounterpartyIdNotSet { kind: "connection" });
+    };
+
+    // The counterparty's connection end must be open.
+    verify_membership(
+        vm,
+        Box::new(storage.clone()),
+        block,
+        &end.client_id,
+        &end.counterparty.prefix,
+        proof_height,
+        0,
+        connection_path(&counterparty_connection_id),
+        expected_counterparty_connection(
+            &end,
+            Some(connection_id),
+            &end.versions,
+            IbcConnectionState::Open,
+        ),
+        proof_ack,
+    )?;
+
+    end.state = IbcConnectionState::Open;
+    save_connection(&mut storage, connection_id, &end);
+
+    Ok(connection_event(
+        "connection_open_confirm",
+        connection_id,
+        &end,
+    ))
+}
+
+fn next_connection_id(storage: &mut dyn Storage) -> AppResult<String> {
+    let sequence = NEXT_CONNECTION_SEQUENCE.may_load(storage)?.unwrap_or(0);
+    NEXT_CONNECTION_SEQUENCE.save(storage, &(sequence + 1))?;
+    Ok(format!("connection-{sequence}"))
+}
+
+// ------------------------------ ibc channel init -----------------------------
+
+pub fn do_chan_open_init<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    sender: &Addr,
+    port_id: Addr,
+    connection_id: String,
+    counterparty_port_id: String,
+    ordering: IbcChannelOrder,
+    version: String,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_chan_open_init(
+        vm,
+        storage,
+        block,
+        sender,
+        &port_id,
+        connection_id,
+        counterparty_port_id,
+        ordering,
+        version,
+    ) {
+        Ok((events, _channel_id)) => {
+            #[cfg(feature = "tracing")]
+            info!(
+                port_id = port_id.to_string(),
+                channel_id = _channel_id,
+                "Initialized IBC channel"
+            );
+            Ok(events)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to initialize IBC channel");
+            Err(err)
+        },
+    }
+}
+
+fn _do_chan_open_init<VM>(
+    vm: VM,
+    mut storage: TxStore,
+    block: BlockInfo,
+    sender: &Addr,
+    port_id: &Addr,
+    connection_id: String,
+    counterparty_port_id: String,
+    ordering: IbcChannelOrder,
+    version: String,
+) -> AppResult<(Vec<Event>, String)>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let connection = load_channel_connection(&storage, sender, port_id, &connection_id, ordering)?;
+    assert_client_active(
+        vm.clone(),
+        Box::new(storage.clone()),
+        block.clone(),
+        &connection.client_id,
+    )?;
+
+    let channel_id = next_channel_id(&mut storage)?;
+    let end = IbcChannelEnd {
+        state: IbcChannelState::Init,
+        ordering,
+        counterparty: IbcChannelCounterparty {
+            port_id: counterparty_port_id,
+            channel_id: None,
+        },
+        connection_hops: vec![connection_id],
+        version,
+    };
+    save_channel(&mut storage, port_id, &channel_id, &end);
+    init_sequences(&mut storage, port_id, &channel_id);
+
+    let mut events = vec![channel_event(
+        "channel_open_init",
+        port_id,
+        &channel_id,
+        &end,
+    )];
+    events.extend(call_port_channel_callback(
+        vm,
+        storage,
+        block,
+        port_id,
+        "ibc_channel_open",
+        &IbcChannelOpenMsg::Init {
+            channel_id: channel_id.clone(),
+            channel: end,
+        },
+    )?);
+
+    Ok((events, channel_id))
+}
+
+// ------------------------------ ibc channel try ------------------------------
+
+pub fn do_chan_open_try<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    sender: &Addr,
+    port_id: Addr,
+    connection_id: String,
+    counterparty: IbcChannelCounterparty,
+    ordering: IbcChannelOrder,
+    version: String,
+    counterparty_version: String,
+    proof_height: u64,
+    proof_init: Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_chan_open_try(
+        vm,
+        storage,
+        block,
+        sender,
+        &port_id,
+        connection_id,
+        counterparty,
+        ordering,
+        version,
+        counterparty_version,
+        proof_height,
+        proof_init,
+    ) {
+        Ok((events, _channel_id)) => {
+            #[cfg(feature = "tracing")]
+            info!(
+                port_id = port_id.to_string(),
+                channel_id = _channel_id,
+                "Tried opening IBC channel"
+            );
+            Ok(events)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to try opening IBC channel");
+            Err(err)
+        },
+    }
+}
+
+fn _do_chan_open_try<VM>(
+    vm: VM,
+    mut storage: TxStore,
+    block: BlockInfo,
+    sender: &Addr,
+    port_id: &Addr,
+    connection_id: String,
+    counterparty: IbcChannelCounterparty,
+    ordering: IbcChannelOrder,
+    version: String,
+    counterparty_version: String,
+    proof_height: u64,
+    proof_init: Json,
+) -> AppResult<(Vec<Event>, String)>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let connection = load_channel_connection(&storage, sender, port_id, &connection_id, ordering)?;
+    assert_client_active(
+        vm.clone(),
+        Box::new(storage.clone()),
+        block.clone(),
+        &connection.client_id,
+    )?;
+
+    let Some(counterparty_channel_id) = counterparty.channel_id.clone() else {
+        return Err(AppError::CounterpartyIdNotSet { kind: "channel" });
+    };
+    let Some(counterparty_connection_id) = connection.counterparty.connection_id else {
+        return Err(AppError::CounterpartyIdNotSet { kind: "connection" });
+    };
+    let end = IbcChannelEnd {
+        state: IbcChannelState::TryOpen,
+        ordering,
+        counterparty,
+        connection_hops: vec![connection_id],
+        version,
+    };
+
+    // The counterparty's channel end must be in the `Init` state, and point
+    // to our port.
+    verify_membership(
+        vm.clone(),
+        Box::new(storage.clone()),
+        block.clone(),
+        &connection.client_id,
+        &connection.counterparty.prefix,
+        proof_height,
+        0,
+        channel_path(&end.counterparty.port_id, &counterparty_channel_id),
+        expected_counterparty_channel(
+            &end,
+            port_id,
+            None,
+            counterparty_connection_id,
+            IbcChannelState::Init,
+            counterparty_version.clone(),
+        ),
+        proof_init,
+    )?;
+
+    let channel_id = next_channel_id(&mut storage)?;
+    save_channel(&mut storage, port_id, &channel_id, &end);
+    init_sequences(&mut storage, port_id, &channel_id);
+
+    let mut events = vec![channel_event(
+        "channel_open_try",
+        port_id,
+        &channel_id,
+        &end,
+    )];
+    events.extend(call_port_channel_callback(
+        vm,
+        storage,
+        block,
+        port_id,
+        "ibc_channel_open",
+        &IbcChannelOpenMsg::Try {
+            channel_id: channel_id.clone(),
+            channel: end,
+            counterparty_version,
+        },
+    )?);
+
+    Ok((events, channel_id))
+}
+
+// ------------------------------ ibc channel ack ------------------------------
+
+pub fn do_chan_open_ack<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    port_id: Addr,
+    channel_id: String,
+    counterparty_channel_id: String,
+    counterparty_version: String,
+    proof_height: u64,
+    proof_try: Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_chan_open_ack(
+        vm,
+        storage,
+        block,
+        &port_id,
+        &channel_id,
+        counterparty_channel_id,
+        counterparty_version,
+        proof_height,
+        proof_try,
+    ) {
+        Ok(events) => {
+            #[cfg(feature = "tracing")]
+            info!(
+                port_id = port_id.to_string(),
+                channel_id, "Opened IBC channel"
+            );
+            Ok(events)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to acknowledge IBC channel");
+            Err(err)
+        },
+    }
+}
+
+fn _do_chan_open_ack<VM>(
+    vm: VM,
+    mut storage: TxStore,
+    block: BlockInfo,
+    port_id: &Addr,
+    channel_id: &str,
+    counterparty_channel_id: String,
+    counterparty_version: String,
+    proof_height: u64,
+    proof_try: Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let mut end = load_channel(&storage, port_id, channel_id)?;
+    assert_channel_state(channel_id, &end, IbcChannelState::Init)?;
+
+    let connection = load_open_connection(&storage, &end.connection_hops[0])?;
+    assert_client_active(
+        vm.clone(),
+        Box::new(storage.clone()),
+        block.clone(),
+        &connection.client_id,
+    )?;
+
+    let Some(counterparty_connection_id) = connection.counterparty.connection_id else {
+        return Err(AppError::CounterpartyIdNotSet { kind: "connection" });
+    };
+
+    // The counterparty's channel end must be in the `TryOpen` state, and point
+    // to our channel end.
+    verify_membership(
+        vm.clone(),
+        Box::new(storage.clone()),
+        block.clone(),
+        &connection.client_id,
+        &connection.counterparty.prefix,
+        proof_height,
+        0,
+        channel_path(&end.counterparty.port_id, &counterparty_channel_id),
+        expected_counterparty_channel(
+            &end,
+            port_id,
+            Some(channel_id),
+            counterparty_connection_id,
+            IbcChannelState::TryOpen,
+            counterparty_version.clone(),
+        ),
+        proof_try,
+    )?;
+
+    // Same as in ibc-go, the version the counterparty agreed to becomes the
+    // channel's version.
+    end.state = IbcChannelState::Open;
+    end.version = counterparty_version;
+    end.counterparty.channel_id = Some(counterparty_channel_id);
+    save_channel(&mut storage, port_id, channel_id, &end);
+
+    let mut events = vec![channel_event("channel_open_ack", port_id, channel_id, &end)];
+    events.extend(call_port_channel_callback(
+        vm,
+        storage,
+        block,
+        port_id,
+        "ibc_channel_connect",
+        &IbcChannelConnectMsg::Ack {
+            channel_id: channel_id.to_string(),
+            channel: end,
+        },
+    )?);
+
+    Ok(events)
+}
+
+// ---------------------------- ibc channel confirm ----------------------------
+
+pub fn do_chan_open_confirm<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    port_id: Addr,
+    channel_id: String,
+    proof_height: u64,
+    proof_ack: Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_chan_open_confirm(
+        vm,
+        storage,
+        block,
+        &port_id,
+        &channel_id,
+        proof_height,
+        proof_ack,
+    ) {
+        Ok(events) => {
+            #[cfg(feature = "tracing")]
+            info!(
+                port_id = port_id.to_string(),
+                channel_id, "Opened IBC channel"
+            );
+            Ok(events)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to confirm IBC channel");
+            Err(err)
+        },
+    }
+}
+
+fn _do_chan_open_confirm<VM>(
+    vm: VM,
+    mut storage: TxStore,
+    block: BlockInfo,
+    port_id: &Addr,
+    channel_id: &str,
+    proof_height: u64,
+    proof_ack: Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let mut end = load_channel(&storage, port_id, channel_id)?;
+    assert_channel_state(channel_id, &end, IbcChannelState::TryOpen)?;
+
+    let connection = load_open_connection(&storage, &end.connection_hops[0])?;
+    assert_client_active(
+        vm.clone(),
+        Box::new(storage.clone()),
+        block.clone(),
+        &connection.client_id,
+    )?;
+
+    let Some(counterparty_connection_id) = connection.counterparty.connection_id else {
+        return Err(AppError::CounterpartyIdNotSet { kind: "connection" });
+    };
+    let Some(counterparty_channel_id) = end.counterparty.channel_id.clone() else {
+        return Err(AppError::CounterpartyIdNotSet { kind: "channel" });
+    };
+
+    // The counterparty's channel end must be open.
+    verify_membership(
+        vm.clone(),
+        Box::new(storage.clone()),
+        block.clone(),
+        &connection.client_id,
+        &connection.counterparty.prefix,
+        proof_height,
+        0,
+        channel_path(&end.counterparty.port_id, &counterparty_channel_id),
+        expected_counterparty_channel(
+            &end,
+            port_id,
+            Some(channel_id),
+            counterparty_connection_id,
+            IbcChannelState::Open,
+            end.version.clone(),
+        ),
+        proof_ack,
+    )?;
+
+    end.state = IbcChannelState::Open;
+    save_channel(&mut storage, port_id, channel_id, &end);
+
+    let mut events = vec![channel_event(
+        "channel_open_confirm",
+        port_id,
+        channel_id,
+        &end,
+    )];
+    events.extend(call_port_channel_callback(
+        vm,
+        storage,
+        block,
+        port_id,
+        "ibc_channel_connect",
+        &IbcChannelConnectMsg::Confirm {
+            channel_id: channel_id.to_string(),
+            channel: end,
+        },
+    )?);
+
+    Ok(events)
+}
+
+// Opening a channel requires either the permission to create channels, or
+// being the contract that owns the port. Channels can only be opened on top of
+// connections that are already open, and whose version supports the ordering.
+fn load_channel_connection(
+    storage: &dyn Storage,
+    sender: &Addr,
+    port_id: &Addr,
+    connection_id: &str,
+    ordering: IbcChannelOrder,
+) -> AppResult<IbcConnectionEnd> {
+    let cfg = CONFIG.load(storage)?;
+    if sender != port_id
+        && !has_permission(&cfg.permissions.create_channel, cfg.owner.as_ref(), sender)
+    {
+        return Err(AppError::Unauthorized);
+    }
+
+    // Ports are contracts, so the port must be an existing account.
+    ACCOUNTS.load(storage, port_id)?;
+
+    let connection = load_open_connection(storage, connection_id)?;
+    assert_ordering_supported(connection_id, &connection, ordering)?;
+
+    Ok(connection)
+}
+
+// Same as ibc-go's `OnChanOpen*` callbacks, the port contract is told of each
+// step of the handshake, and can abort it by returning an error, e.g. if it
+// doesn't support the channel's ordering or version.
+fn call_port_channel_callback<VM, M>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    port_id: &Addr,
+    name: &'static str,
+    msg: &M,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+    M: Serialize,
+{
+    let chain_id = CHAIN_ID.load(&storage)?;
+    let account = ACCOUNTS.load(&storage, port_id)?;
+    let ctx = Context {
+        chain_id,
+        block,
+        contract: port_id.clone(),
+        sender: None,
+        funds: None,
+        simulate: None,
+    };
+
+    call_in_1_out_1_handle_response(vm, name, storage, &account.code_hash, &ctx, msg)
+        .map(|res| res.events)
+}
+
+fn load_open_connection(storage: &dyn Storage, connection_id: &str) -> AppResult<IbcConnectionEnd> {
+    let connection = load_connection(storage, connection_id)?;
+    assert_connection_state(connection_id, &connection, IbcConnectionState::Open)?;
+    Ok(connection)
+}
+
+fn next_channel_id(storage: &mut dyn Storage) -> AppResult<String> {
+    let sequence = NEXT_CHANNEL_SEQUENCE.may_load(storage)?.unwrap_or(0);
+    NEXT_CHANNEL_SEQUENCE.save(storage, &(sequence + 1))?;
+    Ok(format!("channel-{sequence}"))
+}
+
+// ------------------------------ ibc send packet ------------------------------
+
+pub fn do_send_packet<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    sender: Addr,
+    channel_id: String,
+    data: Binary,
+    timeout: IbcTimeout,
+) -> AppResult<MsgResponse>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_send_packet(vm, storage, block, &sender, &channel_id, data, timeout) {
+        Ok((event, sequence)) => {
+            #[cfg(feature = "tracing")]
+            info!(
+                port_id = sender.to_string(),
+                channel_id, sequence, "Sent IBC packet"
+            );
+            Ok(MsgResponse {
+                events: vec![event],
+                data: Some(to_json_vec(&sequence)?.into()),
+            })
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to send IBC packet");
+            Err(err)
+        },
+    }
+}
+
+// The sender contract is the port. It's up to the contract to make sure the
+// packet data is meaningful to its counterparty.
+fn _do_send_packet<VM>(
+    vm: VM,
+    mut storage: TxStore,
+    block: BlockInfo,
+    sender: &Addr,
+    channel_id: &str,
+    data: Binary,
+    timeout: IbcTimeout,
+) -> AppResult<(Event, u64)>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    if timeout.height.is_none() && timeout.timestamp.is_none() {
+        return Err(AppError::EmptyPacketTimeout);
+    }
+
+    let channel = load_channel(&storage, sender, channel_id)?;
+    assert_channel_state(channel_id, &channel, IbcChannelState::Open)?;
+
+    let connection = load_open_connection(&storage, &channel.connection_hops[0])?;
+    assert_client_active(vm, Box::new(storage.clone()), block, &connection.client_id)?;
+
+    let Some(destination_channel) = channel.counterparty.channel_id.clone() else {
+        return Err(AppError::CounterpartyIdNotSet { kind: "channel" });
+    };
+
+    let port_id = sender.to_string();
+    let sequence_path = next_sequence_send_path(&port_id, channel_id);
+    let sequence = load_sequence(&storage, &sequence_path)?;
+    save_sequence(&mut storage, &sequence_path, sequence + 1);
+
+    let packet = IbcPacket {
+        sequence,
+        source_port: port_id,
+        source_channel: channel_id.to_string(),
+        destination_port: channel.counterparty.port_id.clone(),
+        destination_channel,
+        data,
+        timeout,
+    };
+    write_ibc(
+        &mut storage,
+        &packet_commitment_path(&packet.source_port, channel_id, sequence),
+        packet_commitment(&packet).as_ref(),
+    );
+
+    Ok((packet_event("send_packet", &packet, &channel), sequence))
+}
+
+// ---------------------------- ibc receive packet -----------------------------
+
+pub fn do_recv_packet<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    packet: IbcPacket,
+    proof_height: u64,
+    proof_commitment: Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    #[cfg(feature = "tracing")]
+    let (port_id, channel_id, sequence) = (
+        packet.destination_port.clone(),
+        packet.destination_channel.clone(),
+        packet.sequence,
+    );
+
+    match _do_recv_packet(vm, storage, block, packet, proof_height, proof_commitment) {
+        Ok(events) => {
+            #[cfg(feature = "tracing")]
+            info!(port_id, channel_id, sequence, "Received IBC packet");
+            Ok(events)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to receive IBC packet");
+            Err(err)
+        },
+    }
+}
+
+fn _do_recv_packet<VM>(
+    vm: VM,
+    mut storage: TxStore,
+    block: BlockInfo,
+    packet: IbcPacket,
+    proof_height: u64,
+    proof_commitment: Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let port_id = packet.destination_port.parse::<Addr>()?;
+    let channel_id = &packet.destination_channel;
+    let channel = load_channel(&storage, &port_id, channel_id)?;
+    assert_channel_state(channel_id, &channel, IbcChannelState::Open)?;
+    assert_packet_channel(&channel, &packet.source_port, &packet.source_channel)?;
+
+    let connection = load_open_connection(&storage, &channel.connection_hops[0])?;
+    assert_client_active(
+        vm.clone(),
+        Box::new(storage.clone()),
+        block.clone(),
+        &connection.client_id,
+    )?;
+
+    // The packet can't be received once the timeout has been reached on our
+    // chain; the sender will be able to time it out instead.
+    if packet
+        .timeout
+        .height
+        .is_some_and(|height| block.height.number() >= height)
+        || packet
+            .timeout
+            .timestamp
+            .is_some_and(|timestamp| block.timestamp >= timestamp)
+    {
+        return Err(AppError::PacketTimedOut {
+            sequence: packet.sequence,
+        });
+    }
+
+    verify_membership(
+        vm.clone(),
+        Box::new(storage.clone()),
+        block.clone(),
+        &connection.client_id,
+        &connection.counterparty.prefix,
+        proof_height,
+        connection.delay_period,
+        packet_commitment_path(&packet.source_port, &packet.source_channel, packet.sequence),
+        packet_commitment(&packet).to_vec(),
+        proof_commitment,
+    )?;
+
+    // Ordered channels receive packets strictly in sequence. Unordered ones
+    // keep a receipt of each packet, so it can't be received twice, and the
+    // sender can prove it hasn't been received when timing it out.
+    match channel.ordering {
+        IbcChannelOrder::Ordered => {
+            let path = next_sequence_recv_path(&packet.destination_port, channel_id);
+            let expect = load_sequence(&storage, &path)?;
+            if packet.sequence != expect {
+                return Err(AppError::IncorrectPacketSequence {
+                    expect,
+                    actual: packet.sequence,
+                });
+            }
+            save_sequence(&mut storage, &path, expect + 1);
+        },
+        IbcChannelOrder::Unordered => {
+            let path = packet_receipt_path(&packet.destination_port, channel_id, packet.sequence);
+            if read_ibc(&storage, &path).is_some() {
+                return Err(AppError::PacketAlreadyReceived {
+                    sequence: packet.sequence,
+                });
+            }
+            write_ibc(&mut storage, &path, &[1]);
+        },
+    }
+
+    let mut events = vec![packet_event("recv_packet", &packet, &channel)];
+
+    // Call the receiving contract under a savepoint. If it fails, its state
+    // changes are discarded, and an error acknowledgement is written, so that
+    // the sender can revert the state changes it made when sending.
+    let chain_id = CHAIN_ID.load(&storage)?;
+    let account = ACCOUNTS.load(&storage, &port_id)?;
+    let ctx = Context {
+        chain_id,
+        block,
+        contract: port_id,
+        sender: None,
+        funds: None,
+        simulate: None,
+    };
+    let savepoint = storage.savepoint()?;
+    let ack = match call_in_1_out_1_handle_response(
+        vm,
+        "ibc_packet_receive",
+        storage.clone(),
+        &account.code_hash,
+        &ctx,
+        &packet,
+    ) {
+        Ok(res) => {
+            storage.release(savepoint);
+            events.extend(res.events);
+            IbcAcknowledgement::Result(res.data.unwrap_or_default())
+        },
+        Err(err) => {
+            storage.rollback_to(savepoint);
+            // The acknowledgement is committed to, so it must not depend on
+            // anything that may differ between nodes, such as the wording of
+            // VM errors. Same as ibc-go, it only tells the kind of error; the
+            // error itself goes into an event, which isn't committed to.
+            let category = err.category();
+            events.push(
+                packet_event("recv_packet_error", &packet, &channel)
+                    .add_attribute(PACKET_ERROR_CATEGORY_KEY, category.as_str())
+                    .add_attribute(PACKET_ERROR_KEY, err.to_string()),
+            );
+            IbcAcknowledgement::Error(format!(
+                "ABCI code: {}: error handling packet: see events for details",
+                category.code()
+            ))
+        },
+    };
+
+    let ack = to_json_vec(&ack)?;
+    write_ibc(
+        &mut storage,
+        &packet_ack_path(&packet.destination_port, channel_id, packet.sequence),
+        hash(&ack).as_ref(),
+    );
+
+    events.push(
+        packet_event("write_acknowledgement", &packet, &channel)
+            .add_attribute(PACKET_ACK_HEX_KEY, hex::encode(&ack)),
+    );
+
+    Ok(events)
+}
+
+// ---------------------------- ibc acknowledgement ----------------------------
+
+pub fn do_acknowledge_packet<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    packet: IbcPacket,
+    acknowledgement: Binary,
+    proof_height: u64,
+    proof_acked: Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    #[cfg(feature = "tracing")]
+    let (port_id, channel_id, sequence) = (
+        packet.source_port.clone(),
+        packet.source_channel.clone(),
+        packet.sequence,
+    );
+
+    match _do_acknowledge_packet(
+        vm,
+        storage,
+        block,
+        packet,
+        acknowledgement,
+        proof_height,
+        proof_acked,
+    ) {
+        Ok(events) => {
+            #[cfg(feature = "tracing")]
+            info!(port_id, channel_id, sequence, "Acknowledged IBC packet");
+            Ok(events)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to acknowledge IBC packet");
+            Err(err)
+        },
+    }
+}
+
+fn _do_acknowledge_packet<VM>(
+    vm: VM,
+    mut storage: TxStore,
+    block: BlockInfo,
+    packet: IbcPacket,
+    acknowledgement: Binary,
+    proof_height: u64,
+    proof_acked: Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let (port_id, channel, connection) =
+        load_sent_packet(vm.clone(), &storage, block.clone(), &packet)?;
+
+    verify_membership(
+        vm.clone(),
+        Box::new(storage.clone()),
+        block.clone(),
+        &connection.client_id,
+        &connection.counterparty.prefix,
+        proof_height,
+        connection.delay_period,
+        packet_ack_path(
+            &packet.destination_port,
+            &packet.destination_channel,
+            packet.sequence,
+        ),
+        hash(&acknowledgement).to_vec(),
+        proof_acked,
+    )?;
+
+    if channel.ordering == IbcChannelOrder::Ordered {
+        let path = next_sequence_ack_path(&packet.source_port, &packet.source_channel);
+        let expect = load_sequence(&storage, &path)?;
+        if packet.sequence != expect {
+            return Err(AppError::IncorrectPacketSequence {
+                expect,
+                actual: packet.sequence,
+            });
+        }
+        save_sequence(&mut storage, &path, expect + 1);
+    }
+
+    // Deleting the commitment ensures the packet can't be acknowledged or
+    // timed out again.
+    remove_ibc(
+        &mut storage,
+        &packet_commitment_path(&packet.source_port, &packet.source_channel, packet.sequence),
+    );
+
+    let chain_id = CHAIN_ID.load(&storage)?;
+    let account = ACCOUNTS.load(&storage, &port_id)?;
+    let ctx = Context {
+        chain_id,
+        block,
+        contract: port_id,
+        sender: None,
+        funds: None,
+        simulate: None,
+    };
+    let res = call_in_2_out_1_handle_response(
+        vm,
+        "ibc_packet_ack",
+        storage,
+        &account.code_hash,
+        &ctx,
+        &packet,
+        &acknowledgement,
+    )?;
+
+    let mut events = vec![packet_event("acknowledge_packet", &packet, &channel)];
+    events.extend(res.events);
+
+    Ok(events)
+}
+
+// ----------------------------- ibc packet timeout ----------------------------
+
+pub fn do_timeout_packet<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    packet: IbcPacket,
+    next_sequence_recv: u64,
+    proof_height: u64,
+    proof_unreceived: Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    #[cfg(feature = "tracing")]
+    let (port_id, channel_id, sequence) = (
+        packet.source_port.clone(),
+        packet.source_channel.clone(),
+        packet.sequence,
+    );
+
+    match _do_timeout_packet(
+        vm,
+        storage,
+        block,
+        packet,
+        next_sequence_recv,
+        proof_height,
+        proof_unreceived,
+    ) {
+        Ok(events) => {
+            #[cfg(feature = "tracing")]
+            info!(port_id, channel_id, sequence, "Timed out IBC packet");
+            Ok(events)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to time out IBC packet");
+            Err(err)
+        },
+    }
+}
+
+fn _do_timeout_packet<VM>(
+    vm: VM,
+    mut storage: TxStore,
+    block: BlockInfo,
+    packet: IbcPacket,
+    next_sequence_recv: u64,
+    proof_height: u64,
+    proof_unreceived: Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let (port_id, mut channel, connection) =
+        load_sent_packet(vm.clone(), &storage, block.clone(), &packet)?;
+
+    // The timeout must have been reached on the counterparty chain, as of the
+    // height the proof is for.
+    let height_reached = packet
+        .timeout
+        .height
+        .is_some_and(|height| proof_height >= height);
+    let timestamp_reached = match packet.timeout.timestamp {
+        Some(timestamp) => {
+            query_timestamp_at_height(
+                vm.clone(),
+                Box::new(storage.clone()),
+                block.clone(),
+                &connection.client_id,
+                proof_height,
+            )? >= timestamp
+        },
+        None => false,
+    };
+    if !height_reached && !timestamp_reached {
+        return Err(AppError::PacketNotTimedOut {
+            sequence: packet.sequence,
+        });
+    }
+
+    let mut events = vec![packet_event("timeout_packet", &packet, &channel)];
+
+    // For ordered channels, the counterparty's next receive sequence proves
+    // the packet hasn't been received, and since the packets after it can no
+    // longer be received either, the channel is closed. For unordered ones,
+    // the absence of a receipt proves it.
+    match channel.ordering {
+        IbcChannelOrder::Ordered => {
+            if next_sequence_recv > packet.sequence {
+                return Err(AppError::PacketAlreadyReceived {
+                    sequence: packet.sequence,
+                });
+            }
+
+            verify_membership(
+                vm.clone(),
+                Box::new(storage.clone()),
+                block.clone(),
+                &connection.client_id,
+                &connection.counterparty.prefix,
+                proof_height,
+                connection.delay_period,
+                next_sequence_recv_path(&packet.destination_port, &packet.destination_channel),
+                next_sequence_recv.to_be_bytes().to_vec(),
+                proof_unreceived,
+            )?;
+
+            channel.state = IbcChannelState::Closed;
+            save_channel(&mut storage, &port_id, &packet.source_channel, &channel);
+
+            events.push(channel_event(
+                "channel_closed",
+                &port_id,
+                &packet.source_channel,
+                &channel,
+            ));
+        },
+        IbcChannelOrder::Unordered => {
+            verify_non_membership(
+                vm.clone(),
+                Box::new(storage.clone()),
+                block.clone(),
+                &connection.client_id,
+                &connection.counterparty.prefix,
+                proof_height,
+                connection.delay_period,
+                packet_receipt_path(
+                    &packet.destination_port,
+                    &packet.destination_channel,
+                    packet.sequence,
+                ),
+                proof_unreceived,
+            )?;
+        },
+    }
+
+    remove_ibc(
+        &mut storage,
+        &packet_commitment_path(&packet.source_port, &packet.source_channel, packet.sequence),
+    );
+
+    let chain_id = CHAIN_ID.load(&storage)?;
+    let account = ACCOUNTS.load(&storage, &port_id)?;
+    let ctx = Context {
+        chain_id,
+        block,
+        contract: port_id,
+        sender: None,
+        funds: None,
+        simulate: None,
+    };
+    let res = call_in_1_out_1_handle_response(
+        vm,
+        "ibc_packet_timeout",
+        storage,
+        &account.code_hash,
+        &ctx,
+        &packet,
+    )?;
+    events.extend(res.events);
+
+    Ok(events)
+}
+
+// Acknowledgements and timeouts are both for packets sent by us. The channel
+// must be open, and the packet must match the commitment stored when sending
+// it, which also ensures it hasn't been acknowledged or timed out already.
+fn load_sent_packet<VM>(
+    vm: VM,
+    storage: &TxStore,
+    block: BlockInfo,
+    packet: &IbcPacket,
+) -> AppResult<(Addr, IbcChannelEnd, IbcConnectionEnd)>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let port_id = packet.source_port.parse::<Addr>()?;
+    let channel_id = &packet.source_channel;
+    let channel = load_channel(storage, &port_id, channel_id)?;
+    assert_channel_state(channel_id, &channel, IbcChannelState::Open)?;
+    assert_packet_channel(
+        &channel,
+        &packet.destination_port,
+        &packet.destination_channel,
+    )?;
+
+    let connection = load_open_connection(storage, &channel.connection_hops[0])?;
+    assert_client_active(vm, Box::new(storage.clone()), block, &connection.client_id)?;
+
+    let commitment = read_ibc(
+        storage,
+        &packet_commitment_path(&packet.source_port, channel_id, packet.sequence),
+    );
+    if commitment.as_deref() != Some(packet_commitment(packet).as_ref()) {
+        return Err(AppError::PacketCommitmentNotFound {
+            sequence: packet.sequence,
+        });
+    }
+
+    Ok((port_id, channel, connection))
 }
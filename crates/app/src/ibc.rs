@@ -0,0 +1,869 @@
+//! Storage of IBC connection and channel ends, and verification of the
+//! counterparty chain's ends through the IBC client contracts.
+//!
+//! For counterparty chains to be able to prove our connection and channel ends,
+//! they are stored encoded as the Protobuf types defined by ibc-go, under the
+//! same paths ibc-go uses (ICS-24), prefixed by the `ibc` namespace. In other
+//! words, our commitment prefix is `ibc`, same as Cosmos SDK chains'.
+//!
+//! See:
+//! - <https://github.com/cosmos/ibc-go/blob/v8.0.0/proto/ibc/core/connection/v1/connection.proto>
+//! - <https://github.com/cosmos/ibc-go/blob/v8.0.0/proto/ibc/core/channel/v1/channel.proto>
+
+use {
+    crate::{
+        call_in_1_out_1, AppError, AppResult, Vm, ACCOUNTS, CHAIN_ID, CHANNEL_ID_KEY,
+        CLIENT_ID_KEY, CONNECTION_ID_KEY, COUNTERPARTY_CHANNEL_ID_KEY, COUNTERPARTY_CLIENT_ID_KEY,
+        COUNTERPARTY_CONNECTION_ID_KEY, COUNTERPARTY_PORT_ID_KEY, IBC_NAMESPACE,
+        PACKET_CHANNEL_ORDERING_KEY, PACKET_CONNECTION_KEY, PACKET_DATA_HEX_KEY,
+        PACKET_DST_CHANNEL_KEY, PACKET_DST_PORT_KEY, PACKET_SEQUENCE_KEY, PACKET_SRC_CHANNEL_KEY,
+        PACKET_SRC_PORT_KEY, PACKET_TIMEOUT_HEIGHT_KEY, PACKET_TIMEOUT_TIMESTAMP_KEY, PORT_ID_KEY,
+        VERSION_KEY,
+    },
+    grug_types::{
+        concat, hash, Addr, Binary, BlockInfo, Context, Event, GenericResult, Hash,
+        IbcChannelCounterparty, IbcChannelEnd, IbcChannelOrder, IbcChannelState, IbcClientQuery,
+        IbcClientQueryResponse, IbcClientStateResponse, IbcClientStatus, IbcConnectionCounterparty,
+        IbcConnectionEnd, IbcConnectionState, IbcConnectionVersion, IbcPacket, Json, StdError,
+        StdResult, Storage, Timestamp,
+    },
+    prost::{Enumeration, Message},
+};
+
+// ---------------------------------- protobuf ---------------------------------
+
+#[derive(Enumeration, Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub(crate) enum ConnectionState {
+    UninitializedUnspecified = 0,
+    Init = 1,
+    Tryopen = 2,
+    Open = 3,
+}
+
+#[derive(Message, Clone, PartialEq)]
+pub(crate) struct ConnectionEnd {
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+    #[prost(message, repeated, tag = "2")]
+    pub versions: Vec<Version>,
+    #[prost(enumeration = "ConnectionState", tag = "3")]
+    pub state: i32,
+    #[prost(message, optional, tag = "4")]
+    pub counterparty: Option<ConnectionCounterparty>,
+    #[prost(uint64, tag = "5")]
+    pub delay_period: u64,
+}
+
+#[derive(Message, Clone, PartialEq)]
+pub(crate) struct Version {
+    #[prost(string, tag = "1")]
+    pub identifier: String,
+    #[prost(string, repeated, tag = "2")]
+    pub features: Vec<String>,
+}
+
+#[derive(Message, Clone, PartialEq)]
+pub(crate) struct ConnectionCounterparty {
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+    #[prost(string, tag = "2")]
+    pub connection_id: String,
+    #[prost(message, optional, tag = "3")]
+    pub prefix: Option<MerklePrefix>,
+}
+
+#[derive(Message, Clone, PartialEq)]
+pub(crate) struct MerklePrefix {
+    #[prost(bytes = "vec", tag = "1")]
+    pub key_prefix: Vec<u8>,
+}
+
+#[derive(Enumeration, Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub(crate) enum ChannelState {
+    UninitializedUnspecified = 0,
+    Init = 1,
+    Tryopen = 2,
+    Open = 3,
+    Closed = 4,
+}
+
+#[derive(Enumeration, Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub(crate) enum Order {
+    NoneUnspecified = 0,
+    Unordered = 1,
+    Ordered = 2,
+}
+
+#[derive(Message, Clone, PartialEq)]
+pub(crate) struct Channel {
+    #[prost(enumeration = "ChannelState", tag = "1")]
+    pub state: i32,
+    #[prost(enumeration = "Order", tag = "2")]
+    pub ordering: i32,
+    #[prost(message, optional, tag = "3")]
+    pub counterparty: Option<ChannelCounterparty>,
+    #[prost(string, repeated, tag = "4")]
+    pub connection_hops: Vec<String>,
+    #[prost(string, tag = "5")]
+    pub version: String,
+}
+
+#[derive(Message, Clone, PartialEq)]
+pub(crate) struct ChannelCounterparty {
+    #[prost(string, tag = "1")]
+    pub port_id: String,
+    #[prost(string, tag = "2")]
+    pub channel_id: String,
+}
+
+// -------------------------------- conversions --------------------------------
+
+impl From<&IbcConnectionVersion> for Version {
+    fn from(version: &IbcConnectionVersion) -> Self {
+        Self {
+            identifier: version.identifier.clone(),
+            features: version.features.clone(),
+        }
+    }
+}
+
+impl From<Version> for IbcConnectionVersion {
+    fn from(version: Version) -> Self {
+        Self {
+            identifier: version.identifier,
+            features: version.features,
+        }
+    }
+}
+
+impl From<IbcConnectionState> for ConnectionState {
+    fn from(state: IbcConnectionState) -> Self {
+        match state {
+            IbcConnectionState::Init => ConnectionState::Init,
+            IbcConnectionState::TryOpen => ConnectionState::Tryopen,
+            IbcConnectionState::Open => ConnectionState::Open,
+        }
+    }
+}
+
+impl From<&IbcConnectionCounterparty> for ConnectionCounterparty {
+    fn from(counterparty: &IbcConnectionCounterparty) -> Self {
+        Self {
+            client_id: counterparty.client_id.clone(),
+            connection_id: counterparty.connection_id.clone().unwrap_or_default(),
+            prefix: Some(MerklePrefix {
+                key_prefix: counterparty.prefix.to_vec(),
+            }),
+        }
+    }
+}
+
+impl From<&IbcConnectionEnd> for ConnectionEnd {
+    fn from(end: &IbcConnectionEnd) -> Self {
+        Self {
+            client_id: end.client_id.to_string(),
+            versions: end.versions.iter().map(Into::into).collect(),
+            state: ConnectionState::from(end.state).into(),
+            counterparty: Some((&end.counterparty).into()),
+            delay_period: end.delay_period,
+        }
+    }
+}
+
+impl TryFrom<ConnectionEnd> for IbcConnectionEnd {
+    type Error = StdError;
+
+    fn try_from(end: ConnectionEnd) -> StdResult<Self> {
+        let state = match ConnectionState::try_from(end.state) {
+            Ok(ConnectionState::Init) => IbcConnectionState::Init,
+            Ok(ConnectionState::Tryopen) => IbcConnectionState::TryOpen,
+            Ok(ConnectionState::Open) => IbcConnectionState::Open,
+            _ => return Err(StdError::deserialize::<Self>("invalid connection state")),
+        };
+        let counterparty = end.counterparty.unwrap_or_default();
+        Ok(Self {
+            client_id: end.client_id.parse()?,
+            versions: end.versions.into_iter().map(Into::into).collect(),
+            state,
+            counterparty: IbcConnectionCounterparty {
+                client_id: counterparty.client_id,
+                connection_id: non_empty(counterparty.connection_id),
+                prefix: counterparty.prefix.unwrap_or_default().key_prefix.into(),
+            },
+            delay_period: end.delay_period,
+        })
+    }
+}
+
+impl From<IbcChannelState> for ChannelState {
+    fn from(state: IbcChannelState) -> Self {
+        match state {
+            IbcChannelState::Init => ChannelState::Init,
+            IbcChannelState::TryOpen => ChannelState::Tryopen,
+            IbcChannelState::Open => ChannelState::Open,
+            IbcChannelState::Closed => ChannelState::Closed,
+        }
+    }
+}
+
+impl From<IbcChannelOrder> for Order {
+    fn from(order: IbcChannelOrder) -> Self {
+        match order {
+            IbcChannelOrder::Unordered => Order::Unordered,
+            IbcChannelOrder::Ordered => Order::Ordered,
+        }
+    }
+}
+
+impl From<&IbcChannelCounterparty> for ChannelCounterparty {
+    fn from(counterparty: &IbcChannelCounterparty) -> Self {
+        Self {
+            port_id: counterparty.port_id.clone(),
+            channel_id: counterparty.channel_id.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&IbcChannelEnd> for Channel {
+    fn from(end: &IbcChannelEnd) -> Self {
+        Self {
+            state: ChannelState::from(end.state).into(),
+            ordering: Order::from(end.ordering).into(),
+            counterparty: Some((&end.counterparty).into()),
+            connection_hops: end.connection_hops.clone(),
+            version: end.version.clone(),
+        }
+    }
+}
+
+impl TryFrom<Channel> for IbcChannelEnd {
+    type Error = StdError;
+
+    fn try_from(end: Channel) -> StdResult<Self> {
+        let state = match ChannelState::try_from(end.state) {
+            Ok(ChannelState::Init) => IbcChannelState::Init,
+            Ok(ChannelState::Tryopen) => IbcChannelState::TryOpen,
+            Ok(ChannelState::Open) => IbcChannelState::Open,
+            Ok(ChannelState::Closed) => IbcChannelState::Closed,
+            _ => return Err(StdError::deserialize::<Self>("invalid channel state")),
+        };
+        let ordering = match Order::try_from(end.ordering) {
+            Ok(Order::Unordered) => IbcChannelOrder::Unordered,
+            Ok(Order::Ordered) => IbcChannelOrder::Ordered,
+            _ => return Err(StdError::deserialize::<Self>("invalid channel ordering")),
+        };
+        let counterparty = end.counterparty.unwrap_or_default();
+        Ok(Self {
+            state,
+            ordering,
+            counterparty: IbcChannelCounterparty {
+                port_id: counterparty.port_id,
+                channel_id: non_empty(counterparty.channel_id),
+            },
+            connection_hops: end.connection_hops,
+            version: end.version,
+        })
+    }
+}
+
+// Protobuf doesn't distinguish between an empty and a missing string.
+fn non_empty(s: String) -> Option<String> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+// --------------------------------- handshakes --------------------------------
+
+/// The connection end we expect the counterparty chain to have committed, as
+/// seen from our end of the connection.
+pub(crate) fn expected_counterparty_connection(
+    end: &IbcConnectionEnd,
+    connection_id: Option<&str>,
+    versions: &[IbcConnectionVersion],
+    state: IbcConnectionState,
+) -> Vec<u8> {
+    ConnectionEnd {
+        client_id: end.counterparty.client_id.clone(),
+        versions: versions.iter().map(Into::into).collect(),
+        state: ConnectionState::from(state).into(),
+        counterparty: Some(ConnectionCounterparty {
+            client_id: end.client_id.to_string(),
+            connection_id: connection_id.unwrap_or_default().to_string(),
+            prefix: Some(MerklePrefix {
+                key_prefix: IBC_NAMESPACE.to_vec(),
+            }),
+        }),
+        delay_period: end.delay_period,
+    }
+    .encode_to_vec()
+}
+
+/// The channel end we expect the counterparty chain to have committed, as seen
+/// from our end of the channel.
+pub(crate) fn expected_counterparty_channel(
+    end: &IbcChannelEnd,
+    port_id: &Addr,
+    channel_id: Option<&str>,
+    counterparty_connection_id: String,
+    state: IbcChannelState,
+    version: String,
+) -> Vec<u8> {
+    Channel {
+        state: ChannelState::from(state).into(),
+        ordering: Order::from(end.ordering).into(),
+        counterparty: Some(ChannelCounterparty {
+            port_id: port_id.to_string(),
+            channel_id: channel_id.unwrap_or_default().to_string(),
+        }),
+        connection_hops: vec![counterparty_connection_id],
+        version,
+    }
+    .encode_to_vec()
+}
+
+/// Return whether we support the given connection version, i.e. it's version
+/// `1`, and only has features that we support.
+fn is_supported_version(version: &IbcConnectionVersion, supported: &IbcConnectionVersion) -> bool {
+    version.identifier == supported.identifier
+        && !version.features.is_empty()
+        && version
+            .features
+            .iter()
+            .all(|feature| supported.features.contains(feature))
+}
+
+/// Among the connection versions proposed by the counterparty chain, pick the
+/// first one we support, keeping only the features we also support.
+pub(crate) fn pick_version(
+    counterparty_versions: &[IbcConnectionVersion],
+) -> AppResult<IbcConnectionVersion> {
+    let supported = IbcConnectionVersion::default();
+    counterparty_versions
+        .iter()
+        .find_map(|version| {
+            let picked = IbcConnectionVersion {
+                identifier: version.identifier.clone(),
+                features: version
+                    .features
+                    .iter()
+                    .filter(|feature| supported.features.contains(feature))
+                    .cloned()
+                    .collect(),
+            };
+            is_supported_version(&picked, &supported).then_some(picked)
+        })
+        .ok_or_else(|| AppError::UnsupportedConnectionVersion {
+            version: format!("{counterparty_versions:?}"),
+        })
+}
+
+/// Ensure the version picked by the counterparty chain is one we proposed.
+pub(crate) fn assert_version_proposed(
+    version: &IbcConnectionVersion,
+    proposed: &[IbcConnectionVersion],
+) -> AppResult<()> {
+    if !proposed.iter().any(|p| is_supported_version(version, p)) {
+        return Err(AppError::UnsupportedConnectionVersion {
+            version: format!("{version:?}"),
+        });
+    }
+
+    Ok(())
+}
+
+pub(crate) fn assert_connection_state(
+    connection_id: &str,
+    end: &IbcConnectionEnd,
+    expect: IbcConnectionState,
+) -> AppResult<()> {
+    if end.state != expect {
+        return Err(AppError::IncorrectConnectionState {
+            connection_id: connection_id.to_string(),
+            expect: format!("{expect:?}"),
+            actual: format!("{:?}", end.state),
+        });
+    }
+
+    Ok(())
+}
+
+pub(crate) fn assert_channel_state(
+    channel_id: &str,
+    end: &IbcChannelEnd,
+    expect: IbcChannelState,
+) -> AppResult<()> {
+    if end.state != expect {
+        return Err(AppError::IncorrectChannelState {
+            channel_id: channel_id.to_string(),
+            expect: format!("{expect:?}"),
+            actual: format!("{:?}", end.state),
+        });
+    }
+
+    Ok(())
+}
+
+/// Ensure the channel ordering is among the features of the connection's
+/// negotiated version.
+pub(crate) fn assert_ordering_supported(
+    connection_id: &str,
+    connection: &IbcConnectionEnd,
+    ordering: IbcChannelOrder,
+) -> AppResult<()> {
+    let feature = match ordering {
+        IbcChannelOrder::Unordered => "ORDER_UNORDERED",
+        IbcChannelOrder::Ordered => "ORDER_ORDERED",
+    };
+    if !connection
+        .versions
+        .iter()
+        .all(|version| version.features.iter().any(|f| f == feature))
+    {
+        return Err(AppError::UnsupportedChannelOrdering {
+            connection_id: connection_id.to_string(),
+            ordering: format!("{ordering:?}"),
+        });
+    }
+
+    Ok(())
+}
+
+pub(crate) fn connection_event(ty: &str, connection_id: &str, end: &IbcConnectionEnd) -> Event {
+    Event::new(ty)
+        .add_attribute(CONNECTION_ID_KEY, connection_id)
+        .add_attribute(CLIENT_ID_KEY, &end.client_id)
+        .add_attribute(COUNTERPARTY_CLIENT_ID_KEY, &end.counterparty.client_id)
+        .add_attribute(
+            COUNTERPARTY_CONNECTION_ID_KEY,
+            end.counterparty
+                .connection_id
+                .as_deref()
+                .unwrap_or_default(),
+        )
+}
+
+pub(crate) fn channel_event(
+    ty: &str,
+    port_id: &Addr,
+    channel_id: &str,
+    end: &IbcChannelEnd,
+) -> Event {
+    Event::new(ty)
+        .add_attribute(PORT_ID_KEY, port_id)
+        .add_attribute(CHANNEL_ID_KEY, channel_id)
+        .add_attribute(COUNTERPARTY_PORT_ID_KEY, &end.counterparty.port_id)
+        .add_attribute(
+            COUNTERPARTY_CHANNEL_ID_KEY,
+            end.counterparty.channel_id.as_deref().unwrap_or_default(),
+        )
+        .add_attribute(CONNECTION_ID_KEY, &end.connection_hops[0])
+        .add_attribute(VERSION_KEY, &end.version)
+}
+
+// ----------------------------------- paths -----------------------------------
+
+// ICS-24 paths of the IBC state, under `IBC_NAMESPACE`. Relayers need these to
+// query the state and its proofs.
+
+pub fn connection_path(connection_id: &str) -> String {
+    format!("connections/{connection_id}")
+}
+
+pub fn channel_path(port_id: &str, channel_id: &str) -> String {
+    format!("channelEnds/ports/{port_id}/channels/{channel_id}")
+}
+
+pub fn next_sequence_send_path(port_id: &str, channel_id: &str) -> String {
+    format!("nextSequenceSend/ports/{port_id}/channels/{channel_id}")
+}
+
+pub fn next_sequence_recv_path(port_id: &str, channel_id: &str) -> String {
+    format!("nextSequenceRecv/ports/{port_id}/channels/{channel_id}")
+}
+
+pub fn next_sequence_ack_path(port_id: &str, channel_id: &str) -> String {
+    format!("nextSequenceAck/ports/{port_id}/channels/{channel_id}")
+}
+
+pub fn packet_commitment_path(port_id: &str, channel_id: &str, sequence: u64) -> String {
+    format!("commitments/ports/{port_id}/channels/{channel_id}/sequences/{sequence}")
+}
+
+pub fn packet_receipt_path(port_id: &str, channel_id: &str, sequence: u64) -> String {
+    format!("receipts/ports/{port_id}/channels/{channel_id}/sequences/{sequence}")
+}
+
+pub fn packet_ack_path(port_id: &str, channel_id: &str, sequence: u64) -> String {
+    format!("acks/ports/{port_id}/channels/{channel_id}/sequences/{sequence}")
+}
+
+// ---------------------------------- storage ----------------------------------
+
+pub(crate) fn read_ibc(storage: &dyn Storage, path: &str) -> Option<Vec<u8>> {
+    storage.read(&concat(IBC_NAMESPACE, path.as_bytes()))
+}
+
+pub(crate) fn write_ibc(storage: &mut dyn Storage, path: &str, value: &[u8]) {
+    storage.write(&concat(IBC_NAMESPACE, path.as_bytes()), value);
+}
+
+pub(crate) fn remove_ibc(storage: &mut dyn Storage, path: &str) {
+    storage.remove(&concat(IBC_NAMESPACE, path.as_bytes()));
+}
+
+pub fn load_connection(storage: &dyn Storage, connection_id: &str) -> AppResult<IbcConnectionEnd> {
+    let Some(bytes) = read_ibc(storage, &connection_path(connection_id)) else {
+        return Err(AppError::ConnectionNotFound {
+            connection_id: connection_id.to_string(),
+        });
+    };
+
+    ConnectionEnd::decode(bytes.as_slice())
+        .map_err(StdError::deserialize::<ConnectionEnd>)?
+        .try_into()
+        .map_err(Into::into)
+}
+
+pub fn save_connection(storage: &mut dyn Storage, connection_id: &str, end: &IbcConnectionEnd) {
+    write_ibc(
+        storage,
+        &connection_path(connection_id),
+        &ConnectionEnd::from(end).encode_to_vec(),
+    );
+}
+
+pub fn load_channel(
+    storage: &dyn Storage,
+    port_id: &Addr,
+    channel_id: &str,
+) -> AppResult<IbcChannelEnd> {
+    let Some(bytes) = read_ibc(storage, &channel_path(&port_id.to_string(), channel_id)) else {
+        return Err(AppError::ChannelNotFound {
+            port_id: port_id.clone(),
+            channel_id: channel_id.to_string(),
+        });
+    };
+
+    Channel::decode(bytes.as_slice())
+        .map_err(StdError::deserialize::<Channel>)?
+        .try_into()
+        .map_err(Into::into)
+}
+
+pub fn save_channel(
+    storage: &mut dyn Storage,
+    port_id: &Addr,
+    channel_id: &str,
+    end: &IbcChannelEnd,
+) {
+    write_ibc(
+        storage,
+        &channel_path(&port_id.to_string(), channel_id),
+        &Channel::from(end).encode_to_vec(),
+    );
+}
+
+/// Load a packet sequence, stored as a big-endian number as in ibc-go.
+/// Sequences start from 1.
+pub(crate) fn load_sequence(storage: &dyn Storage, path: &str) -> AppResult<u64> {
+    let Some(bytes) = read_ibc(storage, path) else {
+        return Ok(1);
+    };
+
+    let bytes = bytes
+        .try_into()
+        .map_err(|_| StdError::deserialize::<u64>("sequence must be 8 bytes"))?;
+
+    Ok(u64::from_be_bytes(bytes))
+}
+
+pub(crate) fn save_sequence(storage: &mut dyn Storage, path: &str, sequence: u64) {
+    write_ibc(storage, path, &sequence.to_be_bytes());
+}
+
+/// Start a new channel's packet sequences at 1. They would default to 1 anyway,
+/// but the counterparty needs to be able to prove the next receive sequence of
+/// an ordered channel to time out a packet, even before any is received.
+pub(crate) fn init_sequences(storage: &mut dyn Storage, port_id: &Addr, channel_id: &str) {
+    let port_id = port_id.to_string();
+    save_sequence(storage, &next_sequence_send_path(&port_id, channel_id), 1);
+    save_sequence(storage, &next_sequence_recv_path(&port_id, channel_id), 1);
+    save_sequence(storage, &next_sequence_ack_path(&port_id, channel_id), 1);
+}
+
+// ---------------------------------- heights ----------------------------------
+
+/// Format a height the way ibc-go does in events, i.e.
+/// `{revision_number}-{revision_height}`.
+///
+/// Heights don't have revision numbers on our side, and clients only support
+/// counterparties whose revision number is zero (the Tendermint client rejects
+/// chain IDs such as `cosmoshub-4`), so the revision number is always zero.
+pub(crate) fn format_height(height: u64) -> String {
+    format!("0-{height}")
+}
+
+// ---------------------------------- packets ----------------------------------
+
+/// The commitment to a packet that the sending chain stores, same as ibc-go's:
+/// the SHA-256 hash of the timeout timestamp, the timeout height, and the hash
+/// of the data.
+///
+/// The revision number of the timeout height is always zero; see
+/// [`format_height`].
+pub(crate) fn packet_commitment(packet: &IbcPacket) -> Hash {
+    let timestamp = packet.timeout.timestamp.map(|t| t.nanos()).unwrap_or(0);
+    let height = packet.timeout.height.unwrap_or(0);
+
+    let mut preimage = Vec::with_capacity(56);
+    preimage.extend(timestamp.to_be_bytes());
+    preimage.extend(0_u64.to_be_bytes());
+    preimage.extend(height.to_be_bytes());
+    preimage.extend(hash(&packet.data).as_ref());
+
+    hash(preimage)
+}
+
+/// Ensure the packet was sent from our end of the channel to the counterparty's
+/// end, or vice versa.
+pub(crate) fn assert_packet_channel(
+    end: &IbcChannelEnd,
+    counterparty_port_id: &str,
+    counterparty_channel_id: &str,
+) -> AppResult<()> {
+    if end.counterparty.port_id != counterparty_port_id
+        || end.counterparty.channel_id.as_deref() != Some(counterparty_channel_id)
+    {
+        return Err(AppError::IncorrectPacketCounterparty {
+            port_id: counterparty_port_id.to_string(),
+            channel_id: counterparty_channel_id.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+pub(crate) fn packet_event(ty: &str, packet: &IbcPacket, end: &IbcChannelEnd) -> Event {
+    let ordering = match end.ordering {
+        IbcChannelOrder::Unordered => "ORDER_UNORDERED",
+        IbcChannelOrder::Ordered => "ORDER_ORDERED",
+    };
+
+    Event::new(ty)
+        .add_attribute(PACKET_DATA_HEX_KEY, hex::encode(&packet.data))
+        .add_attribute(
+            PACKET_TIMEOUT_HEIGHT_KEY,
+            format_height(packet.timeout.height.unwrap_or(0)),
+        )
+        .add_attribute(
+            PACKET_TIMEOUT_TIMESTAMP_KEY,
+            packet.timeout.timestamp.map(|t| t.nanos()).unwrap_or(0),
+        )
+        .add_attribute(PACKET_SEQUENCE_KEY, packet.sequence)
+        .add_attribute(PACKET_SRC_PORT_KEY, &packet.source_port)
+        .add_attribute(PACKET_SRC_CHANNEL_KEY, &packet.source_channel)
+        .add_attribute(PACKET_DST_PORT_KEY, &packet.destination_port)
+        .add_attribute(PACKET_DST_CHANNEL_KEY, &packet.destination_channel)
+        .add_attribute(PACKET_CHANNEL_ORDERING_KEY, ordering)
+        .add_attribute(PACKET_CONNECTION_KEY, &end.connection_hops[0])
+}
+
+// ---------------------------------- clients ----------------------------------
+
+/// Query the client. The client must respond with the variant matching the
+/// query; see [`unexpected_client_response`].
+fn query_client<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    block: BlockInfo,
+    client_id: &Addr,
+    msg: &IbcClientQuery,
+) -> AppResult<IbcClientQueryResponse>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let chain_id = CHAIN_ID.load(&storage)?;
+    let account = ACCOUNTS.load(&storage, client_id)?;
+    let ctx = Context {
+        chain_id,
+        block,
+        contract: client_id.clone(),
+        sender: None,
+        funds: None,
+        simulate: None,
+    };
+
+    call_in_1_out_1::<_, _, GenericResult<IbcClientQueryResponse>>(
+        vm,
+        "ibc_client_query",
+        storage,
+        &account.code_hash,
+        &ctx,
+        msg,
+    )?
+    .into_contract_result(&ctx.contract, "ibc_client_query")
+    .map_err(Into::into)
+}
+
+/// The error for a client responding to a query with a variant that doesn't
+/// match it. The client is at fault, not the host.
+fn unexpected_client_response(client_id: &Addr, expect: &str) -> AppError {
+    AppError::InvalidClientResponse {
+        reason: format!("expecting a response of type `{expect}`"),
+    }
+    .attribute(client_id, "ibc_client_query")
+}
+
+/// Query the client's current client state and latest consensus state.
+pub fn query_client_state<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    block: BlockInfo,
+    client_id: &Addr,
+) -> AppResult<IbcClientStateResponse>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match query_client(vm, storage, block, client_id, &IbcClientQuery::State {})? {
+        IbcClientQueryResponse::State(state) => Ok(state),
+        _ => Err(unexpected_client_response(client_id, "state")),
+    }
+}
+
+/// Ensure the client is active, i.e. neither frozen nor expired. Connections
+/// can't be opened through an inactive client.
+pub fn assert_client_active<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    block: BlockInfo,
+    client_id: &Addr,
+) -> AppResult<()>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let status = match query_client(vm, storage, block, client_id, &IbcClientQuery::Status {})? {
+        IbcClientQueryResponse::Status(status) => status,
+        _ => return Err(unexpected_client_response(client_id, "status")),
+    };
+    if status != IbcClientStatus::Active {
+        return Err(AppError::ClientNotActive {
+            client_id: client_id.clone(),
+            status,
+        });
+    }
+
+    Ok(())
+}
+
+/// Verify, through the client, that the counterparty chain committed the
+/// given value under the given path at the given height.
+///
+/// The path doesn't include the counterparty's commitment prefix, which is
+/// given separately, as recorded in the connection end; it's up to the client
+/// to prepend it. The delay period is the connection's, in nanoseconds; it's
+/// zero for handshakes.
+pub fn verify_membership<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    block: BlockInfo,
+    client_id: &Addr,
+    prefix: &Binary,
+    height: u64,
+    delay_period: u64,
+    path: String,
+    value: Vec<u8>,
+    proof: Json,
+) -> AppResult<()>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let msg = IbcClientQuery::VerifyMembership {
+        height,
+        delay_time_period: delay_period,
+        delay_block_period: 0,
+        prefix: prefix.clone(),
+        key: Binary::from(path.clone().into_bytes()),
+        value: value.into(),
+        proof,
+    };
+    match query_client(vm, storage, block, client_id, &msg)? {
+        IbcClientQueryResponse::VerifyMembership(true) => {},
+        IbcClientQueryResponse::VerifyMembership(false) => {
+            return Err(AppError::ProofVerificationFailed { path });
+        },
+        _ => return Err(unexpected_client_response(client_id, "verify_membership")),
+    }
+
+    Ok(())
+}
+
+/// Verify, through the client, that the counterparty chain had nothing stored
+/// under the given path at the given height.
+pub fn verify_non_membership<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    block: BlockInfo,
+    client_id: &Addr,
+    prefix: &Binary,
+    height: u64,
+    delay_period: u64,
+    path: String,
+    proof: Json,
+) -> AppResult<()>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let msg = IbcClientQuery::VerifyNonMembership {
+        height,
+        delay_time_period: delay_period,
+        delay_block_period: 0,
+        prefix: prefix.clone(),
+        key: Binary::from(path.clone().into_bytes()),
+        proof,
+    };
+    match query_client(vm, storage, block, client_id, &msg)? {
+        IbcClientQueryResponse::VerifyNonMembership(true) => {},
+        IbcClientQueryResponse::VerifyNonMembership(false) => {
+            return Err(AppError::ProofVerificationFailed { path });
+        },
+        _ => {
+            return Err(unexpected_client_response(
+                client_id,
+                "verify_non_membership",
+            ))
+        },
+    }
+
+    Ok(())
+}
+
+/// Query the counterparty chain's block time at the given height, as known to
+/// the client.
+pub fn query_timestamp_at_height<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    block: BlockInfo,
+    client_id: &Addr,
+    height: u64,
+) -> AppResult<Timestamp>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let msg = IbcClientQuery::TimestampAtHeight { height };
+    match query_client(vm, storage, block, client_id, &msg)? {
+        IbcClientQueryResponse::TimestampAtHeight(timestamp) => Ok(timestamp),
+        _ => Err(unexpected_client_response(client_id, "timestamp_at_height")),
+    }
+}
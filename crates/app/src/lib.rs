@@ -2,18 +2,22 @@
 mod abci;
 mod app;
 mod buffer;
+mod diff;
 mod error;
 mod events;
 mod execute;
+mod ibc;
 mod providers;
 mod query;
 mod shared;
 mod state;
 mod submessage;
+mod tracer;
 mod traits;
+mod tx_store;
 mod vm;
 
 pub use crate::{
-    app::*, buffer::*, error::*, events::*, execute::*, providers::*, query::*, shared::*,
-    state::*, submessage::*, traits::*, vm::*,
+    app::*, buffer::*, diff::*, error::*, events::*, execute::*, ibc::*, providers::*, query::*,
+    shared::*, state::*, submessage::*, tracer::*, traits::*, tx_store::*, vm::*,
 };
@@ -1,8 +1,11 @@
 use {
-    crate::{process_query, AppError, Vm},
+    crate::{
+        do_call, new_call, process_query, record_storage, trace_call, AppError, Shared, TxStore, Vm,
+    },
     grug_types::{
-        concat, increment_last_byte, BlockInfo, Order, Querier, QueryRequest, QueryResponse,
-        Record, StdError, StdResult, Storage,
+        concat, increment_last_byte, to_json_value, trim, Addr, Batch, BlockInfo, CallKind,
+        CallTrace, Caller, Coins, Event, Json, MsgResponse, Op, Order, Querier, QueryRequest,
+        QueryResponse, Record, StdError, StdResult, Storage,
     },
 };
 
@@ -37,7 +40,12 @@ impl StorageProvider {
 impl Storage for StorageProvider {
     fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
         let prefixed_key = concat(&self.namespace, key);
-        self.storage.read(&prefixed_key)
+        let value = self.storage.read(&prefixed_key);
+        record_storage(|stats| {
+            stats.reads += 1;
+            stats.read_bytes += value.as_ref().map_or(0, |value| value.len() as u64);
+        });
+        value
     }
 
     fn scan<'a>(
@@ -46,8 +54,13 @@ impl Storage for StorageProvider {
         max: Option<&[u8]>,
         order: Order,
     ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        record_storage(|stats| stats.scans += 1);
         let (min, max) = prefixed_range_bounds(&self.namespace, min, max);
-        self.storage.scan(Some(&min), Some(&max), order)
+        let iter = self
+            .storage
+            .scan(Some(&min), Some(&max), order)
+            .map(|(k, v)| (trim(&self.namespace, &k), v));
+        Box::new(iter)
     }
 
     fn scan_keys<'a>(
@@ -56,8 +69,13 @@ impl Storage for StorageProvider {
         max: Option<&[u8]>,
         order: Order,
     ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        record_storage(|stats| stats.scans += 1);
         let (min, max) = prefixed_range_bounds(&self.namespace, min, max);
-        self.storage.scan_keys(Some(&min), Some(&max), order)
+        let iter = self
+            .storage
+            .scan_keys(Some(&min), Some(&max), order)
+            .map(|k| trim(&self.namespace, &k));
+        Box::new(iter)
     }
 
     fn scan_values<'a>(
@@ -66,21 +84,85 @@ impl Storage for StorageProvider {
         max: Option<&[u8]>,
         order: Order,
     ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        record_storage(|stats| stats.scans += 1);
         let (min, max) = prefixed_range_bounds(&self.namespace, min, max);
         self.storage.scan_values(Some(&min), Some(&max), order)
     }
 
     fn write(&mut self, key: &[u8], value: &[u8]) {
+        record_storage(|stats| {
+            stats.writes += 1;
+            stats.write_bytes += (key.len() + value.len()) as u64;
+        });
         let prefixed_key = concat(&self.namespace, key);
         self.storage.write(&prefixed_key, value);
     }
 
+    fn has(&self, key: &[u8]) -> bool {
+        record_storage(|stats| stats.reads += 1);
+        let prefixed_key = concat(&self.namespace, key);
+        self.storage.has(&prefixed_key)
+    }
+
+    fn read_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        let prefixed_keys = keys
+            .iter()
+            .map(|key| concat(&self.namespace, key))
+            .collect::<Vec<_>>();
+        let prefixed_keys = prefixed_keys.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        let values = self.storage.read_many(&prefixed_keys);
+        record_storage(|stats| {
+            stats.reads += values.len() as u64;
+            stats.read_bytes += values
+                .iter()
+                .flatten()
+                .map(|value| value.len() as u64)
+                .sum::<u64>();
+        });
+        values
+    }
+
+    fn scan_prefix<'a>(
+        &'a self,
+        prefix: &[u8],
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        record_storage(|stats| stats.scans += 1);
+        let prefixed_prefix = concat(&self.namespace, prefix);
+        let iter = self
+            .storage
+            .scan_prefix(&prefixed_prefix, order)
+            .map(|(k, v)| (trim(&self.namespace, &k), v));
+        Box::new(iter)
+    }
+
+    fn write_batch(&mut self, batch: Batch) {
+        record_storage(|stats| {
+            for (key, op) in &batch {
+                match op {
+                    Op::Insert(value) => {
+                        stats.writes += 1;
+                        stats.write_bytes += (key.len() + value.len()) as u64;
+                    },
+                    Op::Delete => stats.removes += 1,
+                }
+            }
+        });
+        let prefixed_batch = batch
+            .into_iter()
+            .map(|(key, op)| (concat(&self.namespace, &key), op))
+            .collect();
+        self.storage.write_batch(prefixed_batch);
+    }
+
     fn remove(&mut self, key: &[u8]) {
+        record_storage(|stats| stats.removes += 1);
         let prefixed_key = concat(&self.namespace, key);
         self.storage.remove(&prefixed_key);
     }
 
     fn remove_range(&mut self, min: Option<&[u8]>, max: Option<&[u8]>) {
+        record_storage(|stats| stats.removes += 1);
         let (min, max) = prefixed_range_bounds(&self.namespace, min, max);
         self.storage.remove_range(Some(&min), Some(&max))
     }
@@ -105,16 +187,39 @@ fn prefixed_range_bounds(
 
 // ---------------------------------- querier ----------------------------------
 
-/// Provides querier functionalities to the VM.
+/// Provides querier functionalities to the VM, as well as the ability for
+/// contracts being executed to call other contracts.
+#[derive(Clone)]
 pub struct QuerierProvider<VM> {
     vm: VM,
     storage: Box<dyn Storage>,
     block: BlockInfo,
+    caller: Option<ContractCaller>,
 }
 
 impl<VM> QuerierProvider<VM> {
     pub fn new(vm: VM, storage: Box<dyn Storage>, block: BlockInfo) -> Self {
-        Self { vm, storage, block }
+        Self {
+            vm,
+            storage,
+            block,
+            caller: None,
+        }
+    }
+
+    /// Allow the contract to call other contracts synchronously. Only to be
+    /// used for instances that are executed, not queried.
+    pub(crate) fn with_caller(mut self, caller: Option<ContractCaller>) -> Self {
+        self.caller = caller;
+        self
+    }
+
+    /// Return a mutable reference to the VM that queries are dispatched to.
+    ///
+    /// VMs use this to pass state down to the instances built for nested
+    /// queries, such as how deep in the call stack those instances are.
+    pub fn vm_mut(&mut self) -> &mut VM {
+        &mut self.vm
     }
 }
 
@@ -124,12 +229,76 @@ where
     AppError: From<VM::Error>,
 {
     fn query_chain(&self, req: QueryRequest) -> StdResult<QueryResponse> {
-        process_query(
+        let call = new_call(|| CallTrace {
+            msg: to_json_value(&req).ok(),
+            ..CallTrace::new(CallKind::Query)
+        });
+        trace_call(call, || {
+            process_query(
+                self.vm.clone(),
+                self.storage.clone(),
+                self.block.clone(),
+                req,
+            )
+        })
+        .map_err(|err| err.into_execution_error().into())
+    }
+}
+
+impl<VM> Caller for QuerierProvider<VM>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    fn call_contract(&self, contract: Addr, msg: Json, funds: Coins) -> StdResult<MsgResponse> {
+        let Some(caller) = &self.caller else {
+            return Err(StdError::generic_err(
+                "contracts can only be called during execution, not during queries",
+            ));
+        };
+
+        let res = do_call(
             self.vm.clone(),
-            self.storage.clone(),
+            caller.storage.clone(),
             self.block.clone(),
-            req,
+            caller.contract.clone(),
+            contract,
+            &msg,
+            funds,
         )
-        .map_err(|err| StdError::Generic(err.to_string()))
+        .map_err(AppError::into_execution_error)?;
+
+        caller.events.write_access().extend(res.events.clone());
+
+        Ok(res)
+    }
+}
+
+/// The contract being executed, on behalf of which other contracts are called
+/// synchronously.
+#[derive(Clone)]
+pub(crate) struct ContractCaller {
+    storage: TxStore,
+    contract: Addr,
+    /// Events emitted by the calls made so far.
+    events: Shared<Vec<Event>>,
+}
+
+impl ContractCaller {
+    pub fn new(storage: TxStore, contract: Addr) -> Self {
+        Self {
+            storage,
+            contract,
+            events: Shared::new(vec![]),
+        }
+    }
+
+    pub fn is_locked(&self, contract: &Addr) -> bool {
+        self.storage.is_locked(contract)
+    }
+
+    /// Take the events emitted by the calls made so far.
+    pub fn take_events(&self) -> Vec<Event> {
+        std::mem::take(&mut *self.events.write_access())
     }
 }
@@ -1,18 +1,25 @@
 use {
     crate::{
-        call_in_1_out_1, AppError, AppResult, StorageProvider, Vm, ACCOUNTS, CHAIN_ID, CODES,
-        CONFIG, CONTRACT_NAMESPACE, LAST_FINALIZED_BLOCK,
+        call_in_1_out_1, load_channel, load_connection, query_client_state, AppError, AppResult,
+        StorageProvider, Vm, ACCOUNTS, CHAIN_ID, CODES, CONFIG, CONTRACT_NAMESPACE,
+        LAST_FINALIZED_BLOCK,
     },
     grug_storage::Bound,
     grug_types::{
-        AccountResponse, Addr, BankQuery, BankQueryResponse, Binary, BlockInfo, Coin, Coins,
-        Context, GenericResult, Hash, InfoResponse, Json, Order, StdResult, Storage,
-        WasmRawResponse, WasmSmartResponse,
+        AccountResponse, Addr, BankQuery, BankQueryResponse, Binary, BlockInfo, ClientResponse,
+        Coin, Coins, Context, DenomMetadata, GenericResult, Hash, IbcChannelEnd, IbcConnectionEnd,
+        InfoResponse, Json, Order, StdResult, Storage, WasmRawResponse, WasmSmartResponse,
     },
+    std::collections::BTreeMap,
 };
 
 const DEFAULT_PAGE_LIMIT: u32 = 30;
 
+/// Maximum number of records a raw scan of a contract's storage may return.
+/// Unlike the other paginated queries, the values here are unbounded byte
+/// strings, so the limit given by the caller must be capped.
+const MAX_SCAN_LIMIT: u32 = 100;
+
 pub fn query_info(storage: &dyn Storage) -> AppResult<InfoResponse> {
     Ok(InfoResponse {
         chain_id: CHAIN_ID.load(storage)?,
@@ -87,6 +94,37 @@ where
     .map(|res| res.as_supplies())
 }
 
+pub fn query_metadata<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    block: BlockInfo,
+    denom: String,
+) -> AppResult<Option<DenomMetadata>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    _query_bank(vm, storage, block, &BankQuery::Metadata { denom }).map(|res| res.as_metadata())
+}
+
+pub fn query_metadatas<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    block: BlockInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> AppResult<BTreeMap<String, DenomMetadata>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    _query_bank(vm, storage, block, &BankQuery::Metadatas {
+        start_after,
+        limit,
+    })
+    .map(|res| res.as_metadatas())
+}
+
 fn _query_bank<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
@@ -117,8 +155,8 @@ where
         &ctx,
         msg,
     )?
-    .into_std_result()
-    .map_err(AppError::Std)
+    .into_contract_result(&ctx.contract, "bank_query")
+    .map_err(Into::into)
 }
 
 pub fn query_code(storage: &dyn Storage, hash: Hash) -> AppResult<Binary> {
@@ -185,6 +223,23 @@ pub fn query_wasm_raw(
     })
 }
 
+pub fn query_wasm_scan(
+    storage: Box<dyn Storage>,
+    contract: Addr,
+    min: Option<Binary>,
+    max: Option<Binary>,
+    limit: Option<u32>,
+) -> AppResult<BTreeMap<Binary, Binary>> {
+    let substore = StorageProvider::new(storage, &[CONTRACT_NAMESPACE, &contract]);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_SCAN_LIMIT);
+
+    Ok(substore
+        .scan(min.as_deref(), max.as_deref(), Order::Ascending)
+        .take(limit as usize)
+        .map(|(k, v)| (Binary::from(k), Binary::from(v)))
+        .collect())
+}
+
 pub fn query_wasm_smart<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
@@ -214,10 +269,46 @@ where
         &ctx,
         &msg,
     )?
-    .into_std_result()?;
+    .into_contract_result(&ctx.contract, "query")?;
 
     Ok(WasmSmartResponse {
         contract: ctx.contract,
         data,
     })
 }
+
+pub fn query_client<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    block: BlockInfo,
+    client_id: Addr,
+) -> AppResult<ClientResponse>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let account = ACCOUNTS.load(&storage, &client_id)?;
+    let state = query_client_state(vm, storage, block, &client_id)?;
+
+    Ok(ClientResponse {
+        client_id: client_id.to_string(),
+        code_hash: account.code_hash,
+        client_state: state.client_state,
+        consensus_state: state.consensus_state,
+    })
+}
+
+pub fn query_connection(
+    storage: &dyn Storage,
+    connection_id: String,
+) -> AppResult<IbcConnectionEnd> {
+    load_connection(storage, &connection_id)
+}
+
+pub fn query_channel(
+    storage: &dyn Storage,
+    port_id: Addr,
+    channel_id: String,
+) -> AppResult<IbcChannelEnd> {
+    load_channel(storage, &port_id, &channel_id)
+}
@@ -1,5 +1,5 @@
 use {
-    grug_types::{increment_last_byte, Batch, Order, Record, Storage},
+    grug_types::{extend_one_byte, Batch, Order, Record, Storage},
     std::{
         sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
         vec,
@@ -125,8 +125,17 @@ impl<S: Storage> Storage for Shared<S> {
         self.write_access().remove_range(min, max)
     }
 
-    fn flush(&mut self, batch: Batch) {
-        self.write_access().flush(batch)
+    fn has(&self, key: &[u8]) -> bool {
+        self.read_access().has(key)
+    }
+
+    // Acquire the read lock only once for all keys.
+    fn read_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        self.read_access().read_many(keys)
+    }
+
+    fn write_batch(&mut self, batch: Batch) {
+        self.write_access().write_batch(batch)
     }
 }
 
@@ -168,7 +177,7 @@ impl<'a, S: Storage> SharedIter<'a, S> {
         // now we need to update the bounds
         if let Some((key, _)) = batch.iter().last() {
             match self.order {
-                Order::Ascending => self.min = Some(increment_last_byte(key.clone())),
+                Order::Ascending => self.min = Some(extend_one_byte(key.clone())),
                 Order::Descending => self.max = Some(key.clone()),
             }
         }
@@ -238,4 +247,24 @@ mod tests {
             .collect::<Vec<_>>();
         assert_eq!(records, mock_records(1, 100, Order::Descending));
     }
+
+    #[test]
+    fn iterator_works_across_batches_with_prefixed_keys() {
+        // the first batch ends at key [29], and the next key [29, 1] extends it,
+        // so it must not be skipped when the bound is moved for the next batch.
+        let mut storage = Shared::new(MockStorage::new());
+        let mut records = vec![];
+        for i in 0..30 {
+            records.push((vec![i], vec![i]));
+        }
+        records.push((vec![29, 1], vec![255]));
+        for (k, v) in &records {
+            storage.write(k, v);
+        }
+
+        let scanned = storage
+            .scan(None, None, Order::Ascending)
+            .collect::<Vec<_>>();
+        assert_eq!(scanned, records);
+    }
 }
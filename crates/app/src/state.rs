@@ -18,6 +18,19 @@ pub const CODES: Map<&Hash, Vec<u8>> = Map::new("code");
 /// Account metadata: address => account
 pub const ACCOUNTS: Map<&Addr, Account> = Map::new("account");
 
+/// The number to be used in the ID of the next IBC connection, e.g. `0` for
+/// `connection-0`
+pub const NEXT_CONNECTION_SEQUENCE: Item<u64> = Item::new("next_connection_sequence");
+
+/// The number to be used in the ID of the next IBC channel, e.g. `0` for
+/// `channel-0`
+pub const NEXT_CHANNEL_SEQUENCE: Item<u64> = Item::new("next_channel_sequence");
+
 /// Each contract has its own storage space, which we term the "substore".
 /// A key in a contract's substore is prefixed by the word "wasm" + contract address.
 pub const CONTRACT_NAMESPACE: &[u8] = b"wasm";
+
+/// IBC connection and channel ends are stored under the word "ibc" + their
+/// ICS-24 paths, e.g. `ibc/connections/connection-0` (without the slash). This
+/// is also the commitment prefix counterparty chains use to verify them.
+pub const IBC_NAMESPACE: &[u8] = b"ibc";
@@ -1,6 +1,9 @@
 use {
-    crate::{do_reply, process_msg, AppError, AppResult, Buffer, Shared, Vm},
-    grug_types::{Addr, BlockInfo, Event, GenericResult, ReplyOn, Storage, SubMessage},
+    crate::{do_reply, new_call, process_msg, trace_call, AppError, AppResult, TxStore, Vm},
+    grug_types::{
+        to_json_value, Addr, BlockInfo, CallKind, CallTrace, Event, GenericResult, ReplyOn,
+        SubMessage,
+    },
 };
 
 /// Recursively execute submessages emitted in a contract response using a
@@ -10,30 +13,7 @@ use {
 /// account that emitted the submessages, not the transaction's sender.
 pub fn handle_submessages<VM>(
     vm: VM,
-    // This function takes a boxed store instead of using a generic like others.
-    //
-    // This is because this function is recursive: every layer of recursion, it
-    // wraps the store with `Shared<Buffer<S>>`.
-    //
-    // Although the recursion is guaranteed to be bounded at run time (thanks to
-    // gas limit), the compiler can't understand this. The compiler thinks the
-    // wrapping can possibly go on infinitely. It would throw this error:
-    //
-    // > error: reached the recursion limit while instantiating
-    // > `process_msg::<Shared<Buffer<Shared<Buffer<Shared<...>>>>>>`
-    //
-    // To prevent this, we use `Box<dyn Storage>` instead, which is an opaque
-    // type, so that the compiler does not think about how many layers of
-    // wrapping there are.
-    //
-    // Another complexity involved here is that we need the store to be clonable.
-    // However we can't write `Box<dyn Storage + Clone>` because `Clone` is not
-    // an object-safe trait:
-    // https://doc.rust-lang.org/reference/items/traits.html#object-safety
-    //
-    // Instead, we use the `dyn_clone::DynClone` trait:
-    // https://docs.rs/dyn-clone/1.0.16/dyn_clone/
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     block: BlockInfo,
     sender: Addr,
     submsgs: Vec<SubMessage>,
@@ -44,53 +24,88 @@ where
 {
     let mut events = vec![];
     for submsg in submsgs {
-        let buffer = Shared::new(Buffer::new(storage.clone(), None));
-        let result = process_msg(
-            vm.clone(),
-            Box::new(buffer.share()),
-            block.clone(),
-            sender.clone(),
-            submsg.msg,
-        );
-        match (submsg.reply_on, result) {
-            // success - callback requested
-            // flush state changes, log events, give callback
-            (ReplyOn::Success(payload) | ReplyOn::Always(payload), Result::Ok(submsg_events)) => {
-                buffer.disassemble().consume();
-                events.extend(submsg_events.clone());
-                events.extend(do_reply(
-                    vm.clone(),
-                    storage.clone(),
-                    block.clone(),
-                    sender.clone(),
-                    &payload,
-                    &GenericResult::Ok(submsg_events),
-                )?);
-            },
-            // error - callback requested
-            // discard uncommitted state changes, give callback
-            (ReplyOn::Error(payload) | ReplyOn::Always(payload), Result::Err(err)) => {
-                events.extend(do_reply(
-                    vm.clone(),
-                    storage.clone(),
-                    block.clone(),
-                    sender.clone(),
-                    &payload,
-                    &GenericResult::Err(err.to_string()),
-                )?);
-            },
-            // success - callback not requested
-            // flush state changes, log events, move on to the next submsg
-            (ReplyOn::Error(_) | ReplyOn::Never, Result::Ok(submsg_events)) => {
-                buffer.disassemble().consume();
-                events.extend(submsg_events);
-            },
-            // error - callback not requested
-            // abort by throwing error
-            (ReplyOn::Success(_) | ReplyOn::Never, Result::Err(err)) => {
-                return Err(err);
-            },
-        };
+        let call = new_call(|| CallTrace {
+            contract: Some(sender.clone()),
+            msg: to_json_value(&submsg.msg).ok(),
+            ..CallTrace::new(CallKind::Submessage)
+        });
+        events.extend(trace_call(call, || {
+            handle_submessage(
+                vm.clone(),
+                storage.clone(),
+                block.clone(),
+                sender.clone(),
+                submsg,
+            )
+        })?);
     }
     Ok(events)
 }
+
+fn handle_submessage<VM>(
+    vm: VM,
+    storage: TxStore,
+    block: BlockInfo,
+    sender: Addr,
+    submsg: SubMessage,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let mut events = vec![];
+    // Each submessage is executed under its own savepoint, so that its state
+    // changes can be discarded if it fails, without affecting the changes made
+    // by the caller or the submessages before it.
+    let savepoint = storage.savepoint()?;
+    let result = process_msg(
+        vm.clone(),
+        storage.clone(),
+        block.clone(),
+        sender.clone(),
+        submsg.msg,
+    );
+    match (submsg.reply_on, result) {
+        // success - callback requested
+        // keep state changes, log events, give callback
+        (ReplyOn::Success(payload) | ReplyOn::Always(payload), Result::Ok(submsg_res)) => {
+            storage.release(savepoint);
+            events.extend(submsg_res.events.clone());
+            events.extend(do_reply(
+                vm.clone(),
+                storage.clone(),
+                block.clone(),
+                sender.clone(),
+                &payload,
+                &GenericResult::Ok(submsg_res),
+            )?);
+        },
+        // error - callback requested
+        // discard uncommitted state changes, give callback
+        (ReplyOn::Error(payload) | ReplyOn::Always(payload), Result::Err(err)) => {
+            storage.rollback_to(savepoint);
+            events.extend(do_reply(
+                vm.clone(),
+                storage.clone(),
+                block.clone(),
+                sender.clone(),
+                &payload,
+                &GenericResult::Err(err.into_execution_error()),
+            )?);
+        },
+        // success - callback not requested
+        // keep state changes, log events, move on to the next submsg
+        (ReplyOn::Error(_) | ReplyOn::Never, Result::Ok(submsg_res)) => {
+            storage.release(savepoint);
+            events.extend(submsg_res.events);
+        },
+        // error - callback not requested
+        // abort by throwing error
+        (ReplyOn::Success(_) | ReplyOn::Never, Result::Err(err)) => {
+            storage.rollback_to(savepoint);
+            return Err(err);
+        },
+    };
+
+    Ok(events)
+}
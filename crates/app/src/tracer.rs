@@ -0,0 +1,111 @@
+use {
+    crate::{AppError, AppResult},
+    grug_types::{CallTrace, ExecutionError, StorageStats, Trace},
+    std::{cell::RefCell, time::Instant},
+};
+
+thread_local! {
+    /// The tracer recording the calls made on this thread, if any.
+    ///
+    /// The app executes a transaction on a single thread, including the calls
+    /// contracts make to each other through the VM. Keeping the tracer here
+    /// means the functions making these calls don't need to pass it along, and
+    /// cost nothing but a lookup when tracing is off, which is almost always.
+    static TRACER: RefCell<Option<Tracer>> = const { RefCell::new(None) };
+}
+
+#[derive(Default)]
+struct Tracer {
+    /// Calls that have started but not yet finished, outermost first.
+    stack: Vec<(CallTrace, Instant)>,
+    /// Calls that have finished and aren't nested in any other call.
+    finished: Vec<CallTrace>,
+}
+
+/// Run the closure, and return its output along with a trace of the calls
+/// made during it.
+pub fn record_trace<T>(f: impl FnOnce() -> T) -> (T, Trace) {
+    // Install a fresh tracer, restoring whichever was there before once done,
+    // even if the closure panics.
+    struct Guard(Option<Tracer>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            TRACER.with_borrow_mut(|tracer| *tracer = self.0.take());
+        }
+    }
+
+    let guard = Guard(TRACER.with_borrow_mut(|tracer| tracer.replace(Tracer::default())));
+    let output = f();
+    let tracer = TRACER.with_borrow_mut(|tracer| tracer.take().unwrap_or_default());
+    drop(guard);
+
+    (output, Trace {
+        calls: tracer.finished,
+    })
+}
+
+/// Return a new node for the call tree, if tracing is on.
+///
+/// The node is built lazily, so that its fields, such as the serialized
+/// message, don't need to be computed when tracing is off.
+pub(crate) fn new_call(build: impl FnOnce() -> CallTrace) -> Option<CallTrace> {
+    TRACER.with_borrow(Option::is_some).then(build)
+}
+
+/// Run the closure as the given call, recording its duration and result, and
+/// any calls made during it as nested calls.
+///
+/// If `call` is `None`, i.e. tracing is off, simply run the closure.
+pub(crate) fn trace_call<T>(
+    call: Option<CallTrace>,
+    f: impl FnOnce() -> AppResult<T>,
+) -> AppResult<T> {
+    let Some(call) = call else {
+        return f();
+    };
+
+    TRACER.with_borrow_mut(|tracer| {
+        if let Some(tracer) = tracer {
+            tracer.stack.push((call, Instant::now()));
+        }
+    });
+
+    let result = f();
+
+    TRACER.with_borrow_mut(|tracer| {
+        let Some(tracer) = tracer else {
+            return;
+        };
+
+        let Some((mut call, start)) = tracer.stack.pop() else {
+            return;
+        };
+
+        call.duration = start.elapsed().as_nanos().try_into().unwrap_or(u64::MAX);
+        call.error = result.as_ref().err().map(to_execution_error);
+
+        match tracer.stack.last_mut() {
+            Some((parent, _)) => parent.calls.push(call),
+            None => tracer.finished.push(call),
+        }
+    });
+
+    result
+}
+
+/// Record storage operations made by the call currently being executed.
+pub(crate) fn record_storage(f: impl FnOnce(&mut StorageStats)) {
+    TRACER.with_borrow_mut(|tracer| {
+        if let Some((call, _)) = tracer.as_mut().and_then(|tracer| tracer.stack.last_mut()) {
+            f(&mut call.storage);
+        }
+    });
+}
+
+fn to_execution_error(err: &AppError) -> ExecutionError {
+    match err {
+        AppError::Execution(err) => err.clone(),
+        err => ExecutionError::new(err.category(), err),
+    }
+}
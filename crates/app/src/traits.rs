@@ -1,5 +1,6 @@
 use {
     crate::{QuerierProvider, StorageProvider},
+    grug_ics23::CommitmentProof,
     grug_types::{Batch, Context, Hash, Storage},
     serde::{de::DeserializeOwned, ser::Serialize},
 };
@@ -37,6 +38,10 @@ pub trait Db {
     /// Type of the Merkle proof. The DB can choose any Merkle tree scheme.
     type Proof: Serialize + DeserializeOwned;
 
+    /// Type of the ICS-23 proofs generated by `ics23_prove`, as in the `type`
+    /// of a Tendermint `ProofOp`, e.g. `ics23:iavl` for Cosmos SDK stores.
+    const ICS23_PROOF_TYPE: &'static str;
+
     /// Return the state commitment as an owned, read-only, `Storage` object.
     /// This should be a _Merklized_ KV store that stores _hashed_ keys and _hashed_ values.
     fn state_commitment(&self) -> impl Storage + Clone + 'static;
@@ -61,6 +66,15 @@ pub trait Db {
     /// _membership_ proof; otherwise, it should be a _non-membership_ proof.
     fn prove(&self, key: &[u8], version: Option<u64>) -> Result<Self::Proof, Self::Error>;
 
+    /// Generate an ICS-23 commitment proof of the given key at the given
+    /// version, for IBC relayers and counterparty chains, which don't know of
+    /// the DB's own proof type.
+    /// If version is unspecified, use the latest version.
+    /// If the key exists at that version, the returned value should be an
+    /// _existence_ proof; otherwise, it should be a _non-existence_ proof.
+    fn ics23_prove(&self, key: &[u8], version: Option<u64>)
+        -> Result<CommitmentProof, Self::Error>;
+
     /// Accept a batch ops (an op is either a DB insertion or a deletion), keep
     /// them in the memory, but do not persist to disk yet; also, increment the
     /// version.
@@ -101,6 +115,14 @@ pub trait Vm: Sized {
         querier: QuerierProvider<Self>,
         code: &[u8],
     ) -> Result<Self::Instance, Self::Error>;
+
+    /// Check whether a guest program can be run by this VM, before it's
+    /// uploaded. Programs that fail the check are rejected.
+    ///
+    /// By default, all programs are accepted.
+    fn validate_code(&self, _code: &[u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
 }
 
 pub trait Instance {
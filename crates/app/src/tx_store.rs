@@ -0,0 +1,240 @@
+use {
+    crate::{AppError, AppResult, Buffer, ChangeLog, Savepoint, Shared},
+    grug_types::{Addr, Batch, Order, Record, Storage},
+    std::collections::BTreeSet,
+};
+
+/// The maximum number of savepoints that can be open at the same time.
+///
+/// A savepoint is created for each transaction, each group of messages in a
+/// transaction, and each submessage, so this effectively limits how deeply
+/// submessages can be nested.
+pub const MAX_SAVEPOINT_DEPTH: usize = 32;
+
+/// The storage that state transitions are executed against.
+///
+/// All the messages and submessages of a block share a single buffer, in which
+/// nested executions are delimited by savepoints instead of wrapping the store
+/// in one more buffer per layer. As such:
+///
+/// - reverting a failed submessage only undoes the keys it has written;
+/// - committing a successful submessage doesn't copy its writes anywhere;
+/// - the type is the same regardless of the nesting depth, so recursive
+///   functions such as `handle_submessages` don't need to box the storage to
+///   avoid hitting the compiler's recursion limit.
+///
+/// Cloning a `TxStore` gives another handle to the same buffer.
+#[derive(Clone)]
+pub struct TxStore {
+    inner: Shared<Buffer<Box<dyn Storage>>>,
+    /// Contracts in the middle of a synchronous call to another contract,
+    /// which can't be executed again until the call returns.
+    locked: Shared<BTreeSet<Addr>>,
+}
+
+impl TxStore {
+    pub fn new(base: Box<dyn Storage>) -> Self {
+        Self {
+            inner: Shared::new(Buffer::new(base, None)),
+            locked: Shared::new(BTreeSet::new()),
+        }
+    }
+
+    /// Mark the current state, so that changes made from now on can be
+    /// discarded with [`TxStore::rollback_to`] or kept with [`TxStore::release`].
+    ///
+    /// Errors if [`MAX_SAVEPOINT_DEPTH`] savepoints are already open.
+    pub fn savepoint(&self) -> AppResult<Savepoint> {
+        let mut buffer = self.inner.write_access();
+        if buffer.depth() >= MAX_SAVEPOINT_DEPTH {
+            return Err(AppError::SavepointDepthExceeded {
+                max: MAX_SAVEPOINT_DEPTH,
+            });
+        }
+
+        Ok(buffer.savepoint())
+    }
+
+    /// Discard all changes made since the savepoint was created, and close it
+    /// along with all savepoints created after it.
+    pub fn rollback_to(&self, savepoint: Savepoint) {
+        self.inner.write_access().rollback_to(savepoint)
+    }
+
+    /// Keep all changes made since the savepoint was created, and close it
+    /// along with all savepoints created after it.
+    pub fn release(&self, savepoint: Savepoint) {
+        self.inner.write_access().release(savepoint)
+    }
+
+    /// Record the changes made from now on in a change log. See
+    /// [`Buffer::track_changes`].
+    pub fn track_changes(&self) {
+        self.inner.write_access().track_changes()
+    }
+
+    /// Start a new section of the change log, if changes are tracked.
+    pub fn start_section(&self) {
+        self.inner.write_access().start_section()
+    }
+
+    /// Return the changes recorded so far and clear the log, or `None` if
+    /// changes aren't tracked.
+    pub fn take_change_log(&self) -> Option<ChangeLog> {
+        self.inner.write_access().take_change_log()
+    }
+
+    /// Mark the contract as being in the middle of a synchronous call, so that
+    /// it can't be reentered until [`TxStore::unlock`] is called.
+    pub fn lock(&self, contract: Addr) {
+        self.locked.write_access().insert(contract);
+    }
+
+    pub fn unlock(&self, contract: &Addr) {
+        self.locked.write_access().remove(contract);
+    }
+
+    pub fn is_locked(&self, contract: &Addr) -> bool {
+        self.locked.read_access().contains(contract)
+    }
+
+    /// Consume self, return the underlying store and the pending ops.
+    ///
+    /// Panics if there are other handles to the same buffer.
+    pub fn disassemble(self) -> (Box<dyn Storage>, Batch) {
+        self.inner.disassemble().disassemble()
+    }
+}
+
+impl Storage for TxStore {
+    fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.inner.read(key)
+    }
+
+    fn scan<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        self.inner.scan(min, max, order)
+    }
+
+    fn scan_keys<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        self.inner.scan_keys(min, max, order)
+    }
+
+    fn scan_values<'a>(
+        &'a self,
+        min: Option<&[u8]>,
+        max: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Vec<u8>> + 'a> {
+        self.inner.scan_values(min, max, order)
+    }
+
+    fn write(&mut self, key: &[u8], value: &[u8]) {
+        self.inner.write(key, value)
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.inner.remove(key)
+    }
+
+    fn remove_range(&mut self, min: Option<&[u8]>, max: Option<&[u8]>) {
+        self.inner.remove_range(min, max)
+    }
+
+    fn has(&self, key: &[u8]) -> bool {
+        self.inner.has(key)
+    }
+
+    fn read_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        self.inner.read_many(keys)
+    }
+
+    fn scan_prefix<'a>(
+        &'a self,
+        prefix: &[u8],
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        self.inner.scan_prefix(prefix, order)
+    }
+
+    fn write_batch(&mut self, batch: Batch) {
+        self.inner.write_batch(batch)
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, grug_types::MockStorage};
+
+    #[test]
+    fn savepoints_are_shared_between_handles() {
+        let mut store = TxStore::new(Box::new(MockStorage::new()));
+        store.write(b"a", b"1");
+
+        let savepoint = store.savepoint().unwrap();
+        let mut handle = store.clone();
+        handle.write(b"a", b"2");
+        handle.write(b"b", b"3");
+        assert_eq!(store.read(b"a"), Some(b"2".to_vec()));
+
+        store.rollback_to(savepoint);
+        assert_eq!(handle.read(b"a"), Some(b"1".to_vec()));
+        assert!(!handle.has(b"b"));
+
+        drop(handle);
+        let (_, pending) = store.disassemble();
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[test]
+    fn scan_prefix_sees_pending_writes() {
+        let mut base = MockStorage::new();
+        base.write(b"ab", b"1");
+        base.write(b"ac", b"2");
+        base.write(b"b", b"3");
+
+        let mut store = TxStore::new(Box::new(base));
+        store.savepoint().unwrap();
+        store.write(b"aa", b"4");
+        store.remove(b"ac");
+
+        assert_eq!(
+            store
+                .scan_prefix(b"a", Order::Descending)
+                .collect::<Vec<_>>(),
+            [
+                (b"ab".to_vec(), b"1".to_vec()),
+                (b"aa".to_vec(), b"4".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn savepoint_depth_is_limited() {
+        let store = TxStore::new(Box::new(MockStorage::new()));
+        let first = store.savepoint().unwrap();
+        for _ in 1..MAX_SAVEPOINT_DEPTH {
+            store.savepoint().unwrap();
+        }
+
+        assert!(matches!(
+            store.savepoint(),
+            Err(AppError::SavepointDepthExceeded { .. })
+        ));
+
+        // closing savepoints frees up room for new ones
+        store.release(first);
+        assert!(store.savepoint().is_ok());
+    }
+}
@@ -1,17 +1,20 @@
 use {
     crate::{
-        handle_submessages, AppError, AppResult, Instance, QuerierProvider, StorageProvider, Vm,
-        CODES, CONTRACT_ADDRESS_KEY, CONTRACT_NAMESPACE,
+        handle_submessages, new_call, trace_call, AppError, AppResult, ContractCaller, Instance,
+        QuerierProvider, StorageProvider, TxStore, Vm, CODES, CONTRACT_ADDRESS_KEY,
+        CONTRACT_NAMESPACE, ENTRY_POINT_KEY, HOST_EVENT_TYPES,
     },
     grug_types::{
-        from_json_slice, to_json_vec, Addr, BlockInfo, Context, Event, GenericResult, Hash,
-        Response, Storage,
+        from_json_slice, to_json_value, to_json_vec, Addr, BlockInfo, CallKind, CallTrace, Context,
+        ContractEvent, Event, GenericResult, Hash, Json, MsgResponse, Response, Storage,
     },
     serde::{de::DeserializeOwned, ser::Serialize},
 };
 
 /// Create a VM instance, and call a function that takes no input parameter and
 /// returns one output.
+///
+/// Errors are attributed to the contract and entry point being called.
 pub fn call_in_0_out_1<VM, R>(
     vm: VM,
     name: &'static str,
@@ -19,13 +22,39 @@ pub fn call_in_0_out_1<VM, R>(
     code_hash: &Hash,
     ctx: &Context,
 ) -> AppResult<R>
+where
+    R: DeserializeOwned,
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    trace_call(new_call(|| contract_call(name, ctx, None::<&()>)), || {
+        _call_in_0_out_1(vm, name, storage, None, code_hash, ctx)
+            .map_err(|err| err.attribute(&ctx.contract, name))
+    })
+}
+
+fn _call_in_0_out_1<VM, R>(
+    vm: VM,
+    name: &'static str,
+    storage: Box<dyn Storage>,
+    caller: Option<ContractCaller>,
+    code_hash: &Hash,
+    ctx: &Context,
+) -> AppResult<R>
 where
     R: DeserializeOwned,
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
     // Create the VM instance
-    let instance = create_vm_instance(vm, storage, ctx.block.clone(), &ctx.contract, code_hash)?;
+    let instance = create_vm_instance(
+        vm,
+        storage,
+        caller,
+        ctx.block.clone(),
+        &ctx.contract,
+        code_hash,
+    )?;
 
     // Call the function; deserialize the output as JSON
     let out_raw = instance.call_in_0_out_1(name, ctx)?;
@@ -36,6 +65,8 @@ where
 
 /// Create a VM instance, and call a function that takes exactly one parameter
 /// and returns one output.
+///
+/// Errors are attributed to the contract and entry point being called.
 pub fn call_in_1_out_1<VM, P, R>(
     vm: VM,
     name: &'static str,
@@ -44,6 +75,27 @@ pub fn call_in_1_out_1<VM, P, R>(
     ctx: &Context,
     param: &P,
 ) -> AppResult<R>
+where
+    P: Serialize,
+    R: DeserializeOwned,
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    trace_call(new_call(|| contract_call(name, ctx, Some(param))), || {
+        _call_in_1_out_1(vm, name, storage, None, code_hash, ctx, param)
+            .map_err(|err| err.attribute(&ctx.contract, name))
+    })
+}
+
+fn _call_in_1_out_1<VM, P, R>(
+    vm: VM,
+    name: &'static str,
+    storage: Box<dyn Storage>,
+    caller: Option<ContractCaller>,
+    code_hash: &Hash,
+    ctx: &Context,
+    param: &P,
+) -> AppResult<R>
 where
     P: Serialize,
     R: DeserializeOwned,
@@ -51,7 +103,14 @@ where
     AppError: From<VM::Error>,
 {
     // Create the VM instance
-    let instance = create_vm_instance(vm, storage, ctx.block.clone(), &ctx.contract, code_hash)?;
+    let instance = create_vm_instance(
+        vm,
+        storage,
+        caller,
+        ctx.block.clone(),
+        &ctx.contract,
+        code_hash,
+    )?;
 
     // Serialize the param as JSON
     let param_raw = to_json_vec(param)?;
@@ -65,6 +124,8 @@ where
 
 /// Create a VM instance, and call a function that takes exactly two parameters
 /// and returns one output.
+///
+/// Errors are attributed to the contract and entry point being called.
 pub fn call_in_2_out_1<VM, P1, P2, R>(
     vm: VM,
     name: &'static str,
@@ -74,6 +135,29 @@ pub fn call_in_2_out_1<VM, P1, P2, R>(
     param1: &P1,
     param2: &P2,
 ) -> AppResult<R>
+where
+    P1: Serialize,
+    P2: Serialize,
+    R: DeserializeOwned,
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    trace_call(new_call(|| contract_call(name, ctx, Some(param1))), || {
+        _call_in_2_out_1(vm, name, storage, None, code_hash, ctx, param1, param2)
+            .map_err(|err| err.attribute(&ctx.contract, name))
+    })
+}
+
+fn _call_in_2_out_1<VM, P1, P2, R>(
+    vm: VM,
+    name: &'static str,
+    storage: Box<dyn Storage>,
+    caller: Option<ContractCaller>,
+    code_hash: &Hash,
+    ctx: &Context,
+    param1: &P1,
+    param2: &P2,
+) -> AppResult<R>
 where
     P1: Serialize,
     P2: Serialize,
@@ -82,7 +166,14 @@ where
     AppError: From<VM::Error>,
 {
     // Create the VM instance
-    let instance = create_vm_instance(vm, storage, ctx.block.clone(), &ctx.contract, code_hash)?;
+    let instance = create_vm_instance(
+        vm,
+        storage,
+        caller,
+        ctx.block.clone(),
+        &ctx.contract,
+        code_hash,
+    )?;
 
     // Serialize the params as JSON
     let param1_raw = to_json_vec(param1)?;
@@ -96,96 +187,132 @@ where
 }
 
 /// Create a VM instance, call a function that takes exactly no input parameter
-/// and returns [`Response`], and handle the submessages. Return a vector of
-/// events emitted.
+/// and returns [`Response`], and handle the submessages. Return the events
+/// emitted and the data returned.
 #[rustfmt::skip]
 pub fn call_in_0_out_1_handle_response<VM>(
     vm: VM,
     name: &'static str,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     code_hash: &Hash,
     ctx: &Context,
-) -> AppResult<Vec<Event>>
+) -> AppResult<MsgResponse>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    let response = call_in_0_out_1::<_, GenericResult<Response>>(
-        vm.clone(),
-        name,
-        storage.clone(),
-        code_hash,
-        ctx,
-    )?
-    .into_std_result()?;
+    // Trace the call and the submessages it emits as a single call, so that the
+    // submessages are nested in it.
+    trace_call(new_call(|| contract_call(name, ctx, None::<&()>)), || {
+        let caller = ContractCaller::new(storage.clone(), ctx.contract.clone());
+        let response = _call_in_0_out_1::<_, GenericResult<Response>>(
+            vm.clone(),
+            name,
+            Box::new(storage.clone()),
+            Some(caller.clone()),
+            code_hash,
+            ctx,
+        )
+        .map_err(|err| err.attribute(&ctx.contract, name))?
+        .into_contract_result(&ctx.contract, name)?;
 
-    handle_response(vm, name, storage, ctx, response)
+        handle_response(vm, name, storage, ctx, response, caller.take_events())
+    })
 }
 
 /// Create a VM instance, call a function that takes exactly one parameter and
-/// returns [`Response`], and handle the submessages. Return a vector of events
-/// emitted.
+/// returns [`Response`], and handle the submessages. Return the events emitted
+/// and the data returned.
 pub fn call_in_1_out_1_handle_response<VM, P>(
     vm: VM,
     name: &'static str,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     code_hash: &Hash,
     ctx: &Context,
     param: &P,
-) -> AppResult<Vec<Event>>
+) -> AppResult<MsgResponse>
 where
     P: Serialize,
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    let response = call_in_1_out_1::<_, _, GenericResult<Response>>(
-        vm.clone(),
-        name,
-        storage.clone(),
-        code_hash,
-        ctx,
-        param,
-    )?
-    .into_std_result()?;
+    trace_call(new_call(|| contract_call(name, ctx, Some(param))), || {
+        let caller = ContractCaller::new(storage.clone(), ctx.contract.clone());
+        let response = _call_in_1_out_1::<_, _, GenericResult<Response>>(
+            vm.clone(),
+            name,
+            Box::new(storage.clone()),
+            Some(caller.clone()),
+            code_hash,
+            ctx,
+            param,
+        )
+        .map_err(|err| err.attribute(&ctx.contract, name))?
+        .into_contract_result(&ctx.contract, name)?;
 
-    handle_response(vm, name, storage, ctx, response)
+        handle_response(vm, name, storage, ctx, response, caller.take_events())
+    })
 }
 
 /// Create a VM instance, call a function that takes exactly two parameter and
-/// returns [`Response`], and handle the submessages. Return a vector of events
-/// emitted.
+/// returns [`Response`], and handle the submessages. Return the events emitted
+/// and the data returned.
 pub fn call_in_2_out_1_handle_response<VM, P1, P2>(
     vm: VM,
     name: &'static str,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     code_hash: &Hash,
     ctx: &Context,
     param1: &P1,
     param2: &P2,
-) -> AppResult<Vec<Event>>
+) -> AppResult<MsgResponse>
 where
     P1: Serialize,
     P2: Serialize,
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    let response = call_in_2_out_1::<_, _, _, GenericResult<Response>>(
-        vm.clone(),
-        name,
-        storage.clone(),
-        code_hash,
-        ctx,
-        param1,
-        param2,
-    )?
-    .into_std_result()?;
+    trace_call(new_call(|| contract_call(name, ctx, Some(param1))), || {
+        let caller = ContractCaller::new(storage.clone(), ctx.contract.clone());
+        let response = _call_in_2_out_1::<_, _, _, GenericResult<Response>>(
+            vm.clone(),
+            name,
+            Box::new(storage.clone()),
+            Some(caller.clone()),
+            code_hash,
+            ctx,
+            param1,
+            param2,
+        )
+        .map_err(|err| err.attribute(&ctx.contract, name))?
+        .into_contract_result(&ctx.contract, name)?;
 
-    handle_response(vm, name, storage, ctx, response)
+        handle_response(vm, name, storage, ctx, response, caller.take_events())
+    })
+}
+
+/// Create a call tree node for a call to a contract. For calls that take more
+/// than one parameter, the first one is recorded as the message.
+fn contract_call<P>(name: &str, ctx: &Context, msg: Option<&P>) -> CallTrace
+where
+    P: Serialize,
+{
+    CallTrace {
+        contract: Some(ctx.contract.clone()),
+        entry_point: Some(name.to_string()),
+        msg: msg.and_then(|msg| to_json_value(msg).ok()),
+        funds: ctx.funds.clone(),
+        ..CallTrace::new(CallKind::Contract)
+    }
 }
 
+/// Create a VM instance. If a caller is given, i.e. the instance is to be
+/// executed rather than queried, the contract may call other contracts
+/// synchronously, and may not already be in the middle of such a call.
 fn create_vm_instance<VM>(
     mut vm: VM,
     storage: Box<dyn Storage>,
+    caller: Option<ContractCaller>,
     block: BlockInfo,
     address: &Addr,
     code_hash: &Hash,
@@ -194,11 +321,22 @@ where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
+    // A contract that's waiting for a synchronous call to return can't be
+    // executed again until then
+    if caller
+        .as_ref()
+        .is_some_and(|caller| caller.is_locked(address))
+    {
+        return Err(AppError::Reentrancy {
+            contract: address.clone(),
+        });
+    }
+
     // Load the program code from storage and deserialize
     let code = CODES.load(&storage, code_hash)?;
 
     // Create the providers
-    let querier = QuerierProvider::new(vm.clone(), storage.clone(), block);
+    let querier = QuerierProvider::new(vm.clone(), storage.clone(), block).with_caller(caller);
     let storage = StorageProvider::new(storage, &[CONTRACT_NAMESPACE, address]);
 
     Ok(vm.build_instance(storage, querier, &code)?)
@@ -207,10 +345,11 @@ where
 pub(crate) fn handle_response<VM>(
     vm: VM,
     name: &'static str,
-    storage: Box<dyn Storage>,
+    storage: TxStore,
     ctx: &Context,
     response: Response,
-) -> AppResult<Vec<Event>>
+    call_events: Vec<Event>,
+) -> AppResult<MsgResponse>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -220,8 +359,19 @@ where
         .add_attribute(CONTRACT_ADDRESS_KEY, &ctx.contract)
         .add_attributes(response.attributes);
 
-    // Handle submessages; append events emitted during submessage handling
+    // Append structured events emitted by the contract
     let mut events = vec![event];
+    for event in response.events {
+        events.push(
+            namespace_event(name, &ctx.contract, event)
+                .map_err(|err| err.attribute(&ctx.contract, name))?,
+        );
+    }
+
+    // Append events emitted by synchronous calls made during this call
+    events.extend(call_events);
+
+    // Handle submessages; append events emitted during submessage handling
     events.extend(handle_submessages(
         vm,
         storage,
@@ -230,5 +380,46 @@ where
         response.submsgs,
     )?);
 
-    Ok(events)
+    Ok(MsgResponse {
+        events,
+        data: response.data,
+    })
+}
+
+/// Convert a structured event emitted by a contract into one that can be
+/// indexed, with each field of its payload as an attribute whose value is the
+/// field encoded as JSON, after the address of the contract and the entry point
+/// that emitted it. Events of the types emitted by the host are rejected.
+fn namespace_event(name: &str, contract: &Addr, event: ContractEvent) -> AppResult<Event> {
+    if HOST_EVENT_TYPES.contains(&event.r#type.as_str()) {
+        return Err(AppError::InvalidEvent {
+            ty: event.r#type,
+            reason: "event type is reserved for the host".to_string(),
+        });
+    }
+
+    let Json::Object(fields) = event.data else {
+        return Err(AppError::InvalidEvent {
+            ty: event.r#type,
+            reason: "payload is not a JSON object".to_string(),
+        });
+    };
+
+    let mut namespaced = Event::new(&event.r#type)
+        .add_attribute(CONTRACT_ADDRESS_KEY, contract)
+        .add_attribute(ENTRY_POINT_KEY, name);
+
+    for (key, value) in fields {
+        // keys prefixed with an underscore are reserved for the host
+        if key.starts_with('_') {
+            return Err(AppError::InvalidEvent {
+                ty: event.r#type,
+                reason: format!("attribute key `{key}` is reserved"),
+            });
+        }
+
+        namespaced = namespaced.add_attribute(key, value);
+    }
+
+    Ok(namespaced)
 }
@@ -1,6 +1,7 @@
 use {
     crate::{DbError, DbResult, U64Comparator, U64Timestamp},
     grug_app::{Buffer, Db},
+    grug_ics23::CommitmentProof,
     grug_jmt::{MerkleTree, Proof},
     grug_types::{hash, Batch, Hash, Op, Order, Record, Storage},
     rocksdb::{
@@ -118,6 +119,8 @@ impl Db for DiskDb {
     type Error = DbError;
     type Proof = Proof;
 
+    const ICS23_PROOF_TYPE: &'static str = grug_jmt::ICS23_PROOF_TYPE;
+
     fn state_commitment(&self) -> impl Storage + Clone + 'static {
         StateCommitment {
             inner: Arc::clone(&self.inner),
@@ -159,6 +162,16 @@ impl Db for DiskDb {
         Ok(MERKLE_TREE.prove(&self.state_commitment(), &hash(key), version)?)
     }
 
+    fn ics23_prove(&self, key: &[u8], version: Option<u64>) -> DbResult<CommitmentProof> {
+        let version = version.unwrap_or_else(|| self.latest_version().unwrap_or(0));
+        Ok(MERKLE_TREE.ics23_prove(
+            &self.state_commitment(),
+            &self.state_storage(Some(version)),
+            key,
+            version,
+        )?)
+    }
+
     fn flush_but_not_commit(&self, batch: Batch) -> DbResult<(u64, Option<Hash>)> {
         // a write batch must not already exist. if it does, it means a batch
         // has been flushed, but not committed, then a next batch is flusehd,
@@ -314,6 +327,33 @@ impl Storage for StateStorage {
             })
     }
 
+    fn has(&self, key: &[u8]) -> bool {
+        // use a pinned read so that the value doesn't need to be copied
+        let opts = new_read_options(Some(self.version), None, None);
+        self.inner
+            .db
+            .get_pinned_cf_opt(&cf_state_storage(&self.inner.db), key, &opts)
+            .unwrap_or_else(|err| {
+                panic!("failed to read from state storage: {err}");
+            })
+            .is_some()
+    }
+
+    fn read_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        let opts = new_read_options(Some(self.version), None, None);
+        self.inner
+            .db
+            .batched_multi_get_cf_opt(&cf_state_storage(&self.inner.db), keys, false, &opts)
+            .into_iter()
+            .map(|res| {
+                res.unwrap_or_else(|err| {
+                    panic!("failed to read from state storage: {err}");
+                })
+                .map(|value| value.to_vec())
+            })
+            .collect()
+    }
+
     fn scan<'a>(
         &'a self,
         min: Option<&[u8]>,
@@ -1,6 +1,7 @@
 use {
     crate::{DbError, DbResult, VersionedMap},
     grug_app::{Buffer, Db},
+    grug_ics23::CommitmentProof,
     grug_jmt::{MerkleTree, Proof},
     grug_types::{hash, Batch, Hash, Op, Order, Record, Storage},
     std::{
@@ -89,6 +90,8 @@ impl Db for MemDb {
     type Error = DbError;
     type Proof = Proof;
 
+    const ICS23_PROOF_TYPE: &'static str = grug_jmt::ICS23_PROOF_TYPE;
+
     fn state_commitment(&self) -> impl Storage + Clone + 'static {
         StateCommitment { db: self.clone() }
     }
@@ -114,6 +117,16 @@ impl Db for MemDb {
         Ok(MERKLE_TREE.prove(&self.state_commitment(), &hash(key), version)?)
     }
 
+    fn ics23_prove(&self, key: &[u8], version: Option<u64>) -> DbResult<CommitmentProof> {
+        let version = version.unwrap_or_else(|| self.latest_version().unwrap_or(0));
+        Ok(MERKLE_TREE.ics23_prove(
+            &self.state_commitment(),
+            &self.state_storage(Some(version)),
+            key,
+            version,
+        )?)
+    }
+
     // Note on implementing this function: We must make sure that we don't
     // attempt to lock the DB (either read or write) inside the `with_write`
     // callback. Doing so will result in error:
@@ -245,6 +258,20 @@ impl Storage for StateStorage {
             .with_read(|inner| inner.state_storage.get(key, self.version).cloned())
     }
 
+    fn has(&self, key: &[u8]) -> bool {
+        self.db
+            .with_read(|inner| inner.state_storage.get(key, self.version).is_some())
+    }
+
+    fn read_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        // Acquire the read lock only once for all keys.
+        self.db.with_read(|inner| {
+            keys.iter()
+                .map(|key| inner.state_storage.get(*key, self.version).cloned())
+                .collect()
+        })
+    }
+
     fn scan<'a>(
         &'a self,
         min: Option<&[u8]>,
@@ -0,0 +1,39 @@
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Ics23Error {
+    #[error("failed to decode proof: {reason}")]
+    Decode { reason: String },
+
+    #[error("unsupported hash operation: {op:?}")]
+    UnsupportedHashOp { op: i32 },
+
+    #[error("unsupported length operation: {op:?}")]
+    UnsupportedLengthOp { op: i32 },
+
+    #[error("data has incorrect length: expecting {expect}, found {actual}")]
+    IncorrectLength { expect: usize, actual: usize },
+
+    #[error("proof doesn't match spec: {reason}")]
+    SpecMismatch { reason: &'static str },
+
+    #[error("proof is malformed: {reason}")]
+    MalformedProof { reason: &'static str },
+
+    #[error("key mismatch: expecting {expect:?}, found {actual:?}")]
+    KeyMismatch { expect: Vec<u8>, actual: Vec<u8> },
+
+    #[error("value mismatch: expecting {expect:?}, found {actual:?}")]
+    ValueMismatch { expect: Vec<u8>, actual: Vec<u8> },
+
+    #[error("root mismatch: expecting {expect:?}, computed {actual:?}")]
+    RootMismatch { expect: Vec<u8>, actual: Vec<u8> },
+
+    #[error("neighbors in non-existence proof are not adjacent")]
+    NotAdjacent,
+
+    #[error("expecting {expect} proofs for a merkle path of {expect} keys, found {actual}")]
+    ProofCountMismatch { expect: usize, actual: usize },
+}
+
+pub type Ics23Result<T> = core::result::Result<T, Ics23Error>;
@@ -0,0 +1,8 @@
+mod error;
+mod merkle;
+mod ops;
+mod proto;
+mod specs;
+mod verify;
+
+pub use crate::{error::*, merkle::*, ops::*, proto::*, specs::*, verify::*};
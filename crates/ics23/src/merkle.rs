@@ -0,0 +1,263 @@
+use crate::{
+    calculate_existence_root, calculate_non_existence_root, verify_membership,
+    verify_non_membership, CommitmentProof, ExistenceProof, Ics23Error, Ics23Result, MerkleProof,
+    Proof, ProofSpec,
+};
+
+/// Verify that a key-value pair exists under the given root, where the key is
+/// a path through multiple nested trees, such as `["ibc", "connections/0"]`.
+///
+/// `keys` goes from the outermost tree to the innermost, while the proofs and
+/// specs go the other way around, as they do in ibc-go.
+pub fn verify_merkle_membership(
+    specs: &[ProofSpec],
+    root: &[u8],
+    proof: &MerkleProof,
+    keys: &[&[u8]],
+    value: &[u8],
+) -> Ics23Result<()> {
+    ensure_proof_count(specs, proof, keys)?;
+
+    verify_chained_membership(specs, root, &proof.proofs, keys, value)
+}
+
+/// Verify that a key doesn't exist under the given root, where the key is a
+/// path through multiple nested trees.
+///
+/// Only the innermost proof is a non-existence proof. The others prove the
+/// existence of the root of the tree inside which the key doesn't exist.
+pub fn verify_merkle_non_membership(
+    specs: &[ProofSpec],
+    root: &[u8],
+    proof: &MerkleProof,
+    keys: &[&[u8]],
+) -> Ics23Result<()> {
+    ensure_proof_count(specs, proof, keys)?;
+
+    let Some(Proof::Nonexist(non_exist)) = &proof.proofs[0].proof else {
+        return Err(Ics23Error::MalformedProof {
+            reason: "innermost proof must be a non-existence proof",
+        });
+    };
+    let (key, outer_keys) = keys.split_last().unwrap();
+    let subroot = calculate_non_existence_root(non_exist)?;
+
+    verify_non_membership(&specs[0], &subroot, non_exist, key)?;
+    verify_chained_membership(&specs[1..], root, &proof.proofs[1..], outer_keys, &subroot)
+}
+
+fn ensure_proof_count(specs: &[ProofSpec], proof: &MerkleProof, keys: &[&[u8]]) -> Ics23Result<()> {
+    if keys.is_empty() {
+        return Err(Ics23Error::MalformedProof {
+            reason: "merkle path is empty",
+        });
+    }
+
+    if proof.proofs.len() != keys.len() {
+        return Err(Ics23Error::ProofCountMismatch {
+            expect: keys.len(),
+            actual: proof.proofs.len(),
+        });
+    }
+
+    if specs.len() != keys.len() {
+        return Err(Ics23Error::SpecMismatch {
+            reason: "number of specs doesn't match merkle path length",
+        });
+    }
+
+    Ok(())
+}
+
+fn verify_chained_membership(
+    specs: &[ProofSpec],
+    root: &[u8],
+    proofs: &[CommitmentProof],
+    keys: &[&[u8]],
+    value: &[u8],
+) -> Ics23Result<()> {
+    let mut value = value.to_vec();
+
+    for ((spec, proof), key) in specs.iter().zip(proofs).zip(keys.iter().rev()) {
+        let exist = existence_proof(proof)?;
+        let subroot = calculate_existence_root(exist)?;
+        verify_membership(spec, &subroot, exist, key, &value)?;
+        value = subroot;
+    }
+
+    if value != root {
+        return Err(Ics23Error::RootMismatch {
+            expect: root.to_vec(),
+            actual: value,
+        });
+    }
+
+    Ok(())
+}
+
+fn existence_proof(proof: &CommitmentProof) -> Ics23Result<&ExistenceProof> {
+    match &proof.proof {
+        Some(Proof::Exist(exist)) => Ok(exist),
+        _ => Err(Ics23Error::MalformedProof {
+            reason: "expecting an existence proof",
+        }),
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{apply_inner, apply_leaf, tendermint_spec, HashOp, InnerOp, NonExistenceProof},
+    };
+
+    /// Build a simple Merkle tree of four leaves, following the Tendermint
+    /// spec. Return the root, and existence proofs of each leaf.
+    fn build_tree(records: [(&[u8], &[u8]); 4]) -> (Vec<u8>, Vec<ExistenceProof>) {
+        let spec = tendermint_spec();
+        let leaf = spec.leaf_spec.unwrap();
+        let inner = |prefix: Vec<u8>, suffix: Vec<u8>| InnerOp {
+            hash: HashOp::Sha256.into(),
+            prefix,
+            suffix,
+        };
+        let left_of = |sibling: &[u8]| inner(vec![1], sibling.to_vec());
+        let right_of = |sibling: &[u8]| inner([&[1], sibling].concat(), vec![]);
+
+        let leaves = records.map(|(key, value)| apply_leaf(&leaf, key, value).unwrap());
+        let left = apply_inner(&left_of(&leaves[1]), &leaves[0]).unwrap();
+        let right = apply_inner(&left_of(&leaves[3]), &leaves[2]).unwrap();
+        let root = apply_inner(&left_of(&right), &left).unwrap();
+
+        let paths = [
+            vec![left_of(&leaves[1]), left_of(&right)],
+            vec![right_of(&leaves[0]), left_of(&right)],
+            vec![left_of(&leaves[3]), right_of(&left)],
+            vec![right_of(&leaves[2]), right_of(&left)],
+        ];
+        let proofs = records
+            .into_iter()
+            .zip(paths)
+            .map(|((key, value), path)| ExistenceProof {
+                key: key.to_vec(),
+                value: value.to_vec(),
+                leaf: Some(leaf.clone()),
+                path,
+            })
+            .collect();
+
+        (root, proofs)
+    }
+
+    fn non_existence(
+        key: &[u8],
+        left: Option<&ExistenceProof>,
+        right: Option<&ExistenceProof>,
+    ) -> NonExistenceProof {
+        NonExistenceProof {
+            key: key.to_vec(),
+            left: left.cloned(),
+            right: right.cloned(),
+        }
+    }
+
+    #[test]
+    fn verifying_membership_works() {
+        let spec = tendermint_spec();
+        let (root, proofs) = build_tree([(b"a", b"1"), (b"c", b"2"), (b"e", b"3"), (b"g", b"4")]);
+
+        for proof in &proofs {
+            assert_eq!(calculate_existence_root(proof).unwrap(), root);
+            assert!(verify_membership(&spec, &root, proof, &proof.key, &proof.value).is_ok());
+        }
+
+        assert!(matches!(
+            verify_membership(&spec, &root, &proofs[0], b"a", b"2"),
+            Err(Ics23Error::ValueMismatch { .. })
+        ));
+
+        // a proof with a tampered path doesn't lead to the root
+        let mut tampered = proofs[1].clone();
+        tampered.path[0].prefix.push(0);
+        assert!(verify_membership(&spec, &root, &tampered, b"c", b"2").is_err());
+    }
+
+    #[test]
+    fn verifying_non_membership_works() {
+        let spec = tendermint_spec();
+        let (root, proofs) = build_tree([(b"a", b"1"), (b"c", b"2"), (b"e", b"3"), (b"g", b"4")]);
+
+        // between two adjacent leaves, in the same or in different subtrees
+        for (key, left, right) in [(b"b", 0, 1), (b"d", 1, 2), (b"f", 2, 3)] {
+            let proof = non_existence(key, Some(&proofs[left]), Some(&proofs[right]));
+            assert!(verify_non_membership(&spec, &root, &proof, key).is_ok());
+        }
+
+        // before the left-most leaf, or after the right-most one
+        let proof = non_existence(b"0", None, Some(&proofs[0]));
+        assert!(verify_non_membership(&spec, &root, &proof, b"0").is_ok());
+        let proof = non_existence(b"z", Some(&proofs[3]), None);
+        assert!(verify_non_membership(&spec, &root, &proof, b"z").is_ok());
+
+        // the neighbors must be adjacent
+        let proof = non_existence(b"d", Some(&proofs[0]), Some(&proofs[2]));
+        assert_eq!(
+            verify_non_membership(&spec, &root, &proof, b"d"),
+            Err(Ics23Error::NotAdjacent)
+        );
+        let proof = non_existence(b"b", None, Some(&proofs[1]));
+        assert_eq!(
+            verify_non_membership(&spec, &root, &proof, b"b"),
+            Err(Ics23Error::NotAdjacent)
+        );
+
+        // the key must be in between the neighbors
+        let proof = non_existence(b"h", Some(&proofs[2]), Some(&proofs[3]));
+        assert!(verify_non_membership(&spec, &root, &proof, b"h").is_err());
+    }
+
+    #[test]
+    fn verifying_chained_proofs_works() {
+        let specs = [tendermint_spec(), tendermint_spec()];
+        let (inner_root, inner_proofs) =
+            build_tree([(b"a", b"1"), (b"c", b"2"), (b"e", b"3"), (b"g", b"4")]);
+        let (root, outer_proofs) = build_tree([
+            (b"acc", b"x"),
+            (b"bank", b"y"),
+            (b"ibc", &inner_root),
+            (b"wasm", b"z"),
+        ]);
+
+        let proof = MerkleProof {
+            proofs: vec![
+                CommitmentProof {
+                    proof: Some(Proof::Exist(inner_proofs[1].clone())),
+                },
+                CommitmentProof {
+                    proof: Some(Proof::Exist(outer_proofs[2].clone())),
+                },
+            ],
+        };
+        assert!(verify_merkle_membership(&specs, &root, &proof, &[b"ibc", b"c"], b"2").is_ok());
+        assert!(verify_merkle_membership(&specs, &root, &proof, &[b"bank", b"c"], b"2").is_err());
+
+        let proof = MerkleProof {
+            proofs: vec![
+                CommitmentProof {
+                    proof: Some(Proof::Nonexist(non_existence(
+                        b"d",
+                        Some(&inner_proofs[1]),
+                        Some(&inner_proofs[2]),
+                    ))),
+                },
+                CommitmentProof {
+                    proof: Some(Proof::Exist(outer_proofs[2].clone())),
+                },
+            ],
+        };
+        assert!(verify_merkle_non_membership(&specs, &root, &proof, &[b"ibc", b"d"]).is_ok());
+        assert!(verify_merkle_non_membership(&specs, &root, &proof, &[b"ibc", b"c"]).is_err());
+    }
+}
@@ -0,0 +1,255 @@
+use {
+    crate::{ExistenceProof, HashOp, Ics23Error, Ics23Result, InnerOp, LeafOp, LengthOp},
+    prost::encoding::encode_varint,
+    sha2::{Digest, Sha256, Sha512},
+};
+
+/// Hash the data with the given hash operation.
+///
+/// Only the SHA-2 family is supported, which covers IAVL, Tendermint's simple
+/// Merkle tree, and the Jellyfish Merkle tree.
+pub fn do_hash(op: HashOp, data: &[u8]) -> Ics23Result<Vec<u8>> {
+    match op {
+        HashOp::NoHash => Ok(data.to_vec()),
+        HashOp::Sha256 => Ok(Sha256::digest(data).to_vec()),
+        HashOp::Sha512 => Ok(Sha512::digest(data).to_vec()),
+        op => Err(Ics23Error::UnsupportedHashOp { op: op as i32 }),
+    }
+}
+
+/// Prefix the data with its length, in the encoding specified by the given
+/// length operation.
+pub fn do_length(op: LengthOp, data: &[u8]) -> Ics23Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(data.len() + 8);
+    match op {
+        LengthOp::NoPrefix => {},
+        LengthOp::VarProto => encode_varint(data.len() as u64, &mut out),
+        LengthOp::Fixed32Big => out.extend((data.len() as u32).to_be_bytes()),
+        LengthOp::Fixed32Little => out.extend((data.len() as u32).to_le_bytes()),
+        LengthOp::Fixed64Big => out.extend((data.len() as u64).to_be_bytes()),
+        LengthOp::Fixed64Little => out.extend((data.len() as u64).to_le_bytes()),
+        LengthOp::Require32Bytes => ensure_length(data, 32)?,
+        LengthOp::Require64Bytes => ensure_length(data, 64)?,
+        op => return Err(Ics23Error::UnsupportedLengthOp { op: op as i32 }),
+    }
+    out.extend(data);
+    Ok(out)
+}
+
+/// Compute the hash of a leaf node from the key and value.
+pub fn apply_leaf(leaf: &LeafOp, key: &[u8], value: &[u8]) -> Ics23Result<Vec<u8>> {
+    if key.is_empty() {
+        return Err(Ics23Error::MalformedProof {
+            reason: "leaf key is empty",
+        });
+    }
+    if value.is_empty() {
+        return Err(Ics23Error::MalformedProof {
+            reason: "leaf value is empty",
+        });
+    }
+
+    let mut data = leaf.prefix.clone();
+    data.extend(prepare_leaf_data(leaf.prehash_key(), leaf.length(), key)?);
+    data.extend(prepare_leaf_data(
+        leaf.prehash_value(),
+        leaf.length(),
+        value,
+    )?);
+
+    do_hash(leaf.hash(), &data)
+}
+
+/// Compute the hash of a parent node from the hash of a child.
+pub fn apply_inner(inner: &InnerOp, child: &[u8]) -> Ics23Result<Vec<u8>> {
+    if child.is_empty() {
+        return Err(Ics23Error::MalformedProof {
+            reason: "inner op child is empty",
+        });
+    }
+
+    let mut data = inner.prefix.clone();
+    data.extend(child);
+    data.extend(&inner.suffix);
+
+    do_hash(inner.hash(), &data)
+}
+
+/// Compute the root hash implied by an existence proof.
+pub fn calculate_existence_root(proof: &ExistenceProof) -> Ics23Result<Vec<u8>> {
+    let Some(leaf) = &proof.leaf else {
+        return Err(Ics23Error::MalformedProof {
+            reason: "existence proof must have a leaf op",
+        });
+    };
+
+    let mut hash = apply_leaf(leaf, &proof.key, &proof.value)?;
+    for inner in &proof.path {
+        hash = apply_inner(inner, &hash)?;
+    }
+
+    Ok(hash)
+}
+
+fn prepare_leaf_data(prehash: HashOp, length: LengthOp, data: &[u8]) -> Ics23Result<Vec<u8>> {
+    let hashed = do_hash(prehash, data)?;
+    do_length(length, &hashed)
+}
+
+fn ensure_length(data: &[u8], expect: usize) -> Ics23Result<()> {
+    if data.len() != expect {
+        return Err(Ics23Error::IncorrectLength {
+            expect,
+            actual: data.len(),
+        });
+    }
+    Ok(())
+}
+
+// ----------------------------------- tests -----------------------------------
+
+// Test vectors from cosmos/ics23.
+#[cfg(test)]
+mod tests {
+    use {super::*, hex_literal::hex, test_case::test_case};
+
+    #[test_case(HashOp::NoHash, &hex!("666f6f64"); "no hash")]
+    #[test_case(HashOp::Sha256, &hex!("c1f026582fe6e8cb620d0c85a72fe421ddded756662a8ec00ed4c297ad10676b"); "sha256")]
+    #[test_case(HashOp::Sha512, &hex!("c235548cfe84fc87678ff04c9134e060cdcd7512d09ed726192151a995541ed8db9fda5204e72e7ac268214c322c17787c70530513c59faede52b7dd9ce64331"); "sha512")]
+    fn hashing_food(op: HashOp, expect: &[u8]) {
+        assert_eq!(do_hash(op, b"food").unwrap(), expect);
+    }
+
+    #[test]
+    fn unsupported_hash_ops_are_rejected() {
+        assert_eq!(
+            do_hash(HashOp::Keccak256, b"food"),
+            Err(Ics23Error::UnsupportedHashOp {
+                op: HashOp::Keccak256 as i32
+            })
+        );
+    }
+
+    #[test_case(LengthOp::NoPrefix, &hex!("666f6f64"); "no prefix")]
+    #[test_case(LengthOp::VarProto, &hex!("04666f6f64"); "var proto")]
+    #[test_case(LengthOp::Fixed32Big, &hex!("00000004666f6f64"); "fixed32 big")]
+    #[test_case(LengthOp::Fixed32Little, &hex!("04000000666f6f64"); "fixed32 little")]
+    #[test_case(LengthOp::Fixed64Big, &hex!("0000000000000004666f6f64"); "fixed64 big")]
+    #[test_case(LengthOp::Fixed64Little, &hex!("0400000000000000666f6f64"); "fixed64 little")]
+    fn length_prefix(op: LengthOp, expect: &[u8]) {
+        assert_eq!(do_length(op, b"food").unwrap(), expect);
+    }
+
+    #[test]
+    fn required_lengths_are_enforced() {
+        assert_eq!(
+            do_length(LengthOp::Require32Bytes, &[1; 32]).unwrap(),
+            [1; 32]
+        );
+        assert_eq!(
+            do_length(LengthOp::Require32Bytes, b"food"),
+            Err(Ics23Error::IncorrectLength {
+                expect: 32,
+                actual: 4
+            })
+        );
+    }
+
+    #[test_case(
+        HashOp::Sha256, HashOp::NoHash, LengthOp::NoPrefix, b"foo", b"bar",
+        &hex!("c3ab8ff13720e8ad9047dd39466b3c8974e592c2fa383d4a3960714caef0c4f2");
+        "hash"
+    )]
+    #[test_case(
+        HashOp::Sha512, HashOp::NoHash, LengthOp::NoPrefix, b"f", b"oobaz",
+        &hex!("4f79f191298ec7461d60136c60f77c2ae8ddd85dbf6168bb925092d51bfb39b559219b39ae5385ba04946c87f64741385bef90578ea6fe6dac85dbf7ad3f79e1");
+        "hash 512"
+    )]
+    #[test_case(
+        HashOp::Sha256, HashOp::NoHash, LengthOp::VarProto, b"food", b"some longer text",
+        &hex!("b68f5d298e915ae1753dd333da1f9cf605411a5f2e12516be6758f365e6db265");
+        "hash length"
+    )]
+    #[test_case(
+        HashOp::Sha256, HashOp::Sha256, LengthOp::VarProto, b"food", b"yet another long string",
+        &hex!("87e0483e8fb624aef2e2f7b13f4166cda485baa8e39f437c83d74c94bedb148f");
+        "prehash length"
+    )]
+    fn leaf_hash(
+        hash: HashOp,
+        prehash_value: HashOp,
+        length: LengthOp,
+        key: &[u8],
+        value: &[u8],
+        expect: &[u8],
+    ) {
+        let leaf = LeafOp {
+            hash: hash.into(),
+            prehash_key: HashOp::NoHash.into(),
+            prehash_value: prehash_value.into(),
+            length: length.into(),
+            prefix: vec![],
+        };
+        assert_eq!(apply_leaf(&leaf, key, value).unwrap(), expect);
+    }
+
+    // echo -n 012345678900cafe00deadbeef | xxd -r -p | sha256sum
+    #[test_case(
+        &hex!("0123456789"), &hex!("deadbeef"), &hex!("00cafe00"),
+        &hex!("0339f76086684506a6d42a60da4b5a719febd4d96d8b8d85ae92849e3a849a5e");
+        "prefix and suffix"
+    )]
+    // echo -n 00204080a0c0e0ffccbb997755331100 | xxd -r -p | sha256sum
+    #[test_case(
+        &hex!("00204080a0c0e0"), b"", &hex!("ffccbb997755331100"),
+        &hex!("45bece1678cf2e9f4f2ae033e546fc35a2081b2415edcb13121a0e908dca1927");
+        "prefix only"
+    )]
+    // echo -n 'this is a sha256 hash, really.... just kidding!' | sha256sum
+    #[test_case(
+        b"", b" just kidding!", b"this is a sha256 hash, really....",
+        &hex!("79ef671d27e42a53fba2201c1bbc529a099af578ee8a38df140795db0ae2184b");
+        "suffix only"
+    )]
+    fn inner_hash(prefix: &[u8], suffix: &[u8], child: &[u8], expect: &[u8]) {
+        let inner = InnerOp {
+            hash: HashOp::Sha256.into(),
+            prefix: prefix.to_vec(),
+            suffix: suffix.to_vec(),
+        };
+        assert_eq!(apply_inner(&inner, child).unwrap(), expect);
+    }
+
+    #[test_case(
+        &[],
+        &hex!("b68f5d298e915ae1753dd333da1f9cf605411a5f2e12516be6758f365e6db265");
+        "leaf only"
+    )]
+    #[test_case(
+        &[&hex!("deadbeef00cafe00")],
+        &hex!("836ea236a6902a665c2a004c920364f24cad52ded20b1e4f22c3179bfe25b2a9");
+        "leaf and inner"
+    )]
+    fn existence_root(prefixes: &[&[u8]], expect: &[u8]) {
+        let proof = ExistenceProof {
+            key: b"food".to_vec(),
+            value: b"some longer text".to_vec(),
+            leaf: Some(LeafOp {
+                hash: HashOp::Sha256.into(),
+                prehash_key: HashOp::NoHash.into(),
+                prehash_value: HashOp::NoHash.into(),
+                length: LengthOp::VarProto.into(),
+                prefix: vec![],
+            }),
+            path: prefixes
+                .iter()
+                .map(|prefix| InnerOp {
+                    hash: HashOp::Sha256.into(),
+                    prefix: prefix.to_vec(),
+                    suffix: vec![],
+                })
+                .collect(),
+        };
+        assert_eq!(calculate_existence_root(&proof).unwrap(), expect);
+    }
+}
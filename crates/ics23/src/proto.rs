@@ -0,0 +1,181 @@
+//! Protobuf types defined by the ICS-23 spec, as well as the `MerkleProof` type
+//! defined by ibc-go, which chains multiple ICS-23 proofs together.
+//!
+//! These are hand-written rather than generated, and only include what we need.
+//! Notably, batch and compressed proofs are not supported.
+//!
+//! See: <https://github.com/cosmos/ics23/blob/master/proto/cosmos/ics23/v1/proofs.proto>
+
+use {
+    crate::{Ics23Error, Ics23Result},
+    prost::{Enumeration, Message},
+};
+
+#[derive(Enumeration, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(i32)]
+pub enum HashOp {
+    NoHash = 0,
+    Sha256 = 1,
+    Sha512 = 2,
+    Keccak256 = 3,
+    Ripemd160 = 4,
+    Bitcoin = 5,
+    Sha512256 = 6,
+    Blake2b512 = 7,
+    Blake2s256 = 8,
+    Blake3 = 9,
+}
+
+#[derive(Enumeration, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(i32)]
+pub enum LengthOp {
+    NoPrefix = 0,
+    VarProto = 1,
+    VarRlp = 2,
+    Fixed32Big = 3,
+    Fixed32Little = 4,
+    Fixed64Big = 5,
+    Fixed64Little = 6,
+    Require32Bytes = 7,
+    Require64Bytes = 8,
+}
+
+/// Proves that a key-value pair exists in a Merkle tree, by providing the
+/// operations to hash the leaf, and the path from the leaf to the root.
+#[derive(Message, Clone, PartialEq)]
+pub struct ExistenceProof {
+    #[prost(bytes = "vec", tag = "1")]
+    pub key: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+    #[prost(message, optional, tag = "3")]
+    pub leaf: Option<LeafOp>,
+    #[prost(message, repeated, tag = "4")]
+    pub path: Vec<InnerOp>,
+}
+
+/// Proves that a key doesn't exist in a Merkle tree, by proving the existence
+/// of its two neighbors, i.e. the keys immediately to its left and right.
+/// One of the neighbors may be missing, if the key is smaller or greater than
+/// all keys in the tree.
+#[derive(Message, Clone, PartialEq)]
+pub struct NonExistenceProof {
+    #[prost(bytes = "vec", tag = "1")]
+    pub key: Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub left: Option<ExistenceProof>,
+    #[prost(message, optional, tag = "3")]
+    pub right: Option<ExistenceProof>,
+}
+
+#[derive(Message, Clone, PartialEq)]
+pub struct CommitmentProof {
+    #[prost(oneof = "commitment_proof::Proof", tags = "1, 2")]
+    pub proof: Option<commitment_proof::Proof>,
+}
+
+pub mod commitment_proof {
+    use {
+        super::{ExistenceProof, NonExistenceProof},
+        prost::Oneof,
+    };
+
+    #[derive(Oneof, Clone, PartialEq)]
+    pub enum Proof {
+        #[prost(message, tag = "1")]
+        Exist(ExistenceProof),
+        #[prost(message, tag = "2")]
+        Nonexist(NonExistenceProof),
+    }
+}
+
+pub use commitment_proof::Proof;
+
+/// The operation that hashes a key-value pair into a leaf node:
+///
+/// ```plain
+/// leaf := hash(prefix | length(prehash_key(key)) | length(prehash_value(value)))
+/// ```
+#[derive(Message, Clone, PartialEq, Eq)]
+pub struct LeafOp {
+    #[prost(enumeration = "HashOp", tag = "1")]
+    pub hash: i32,
+    #[prost(enumeration = "HashOp", tag = "2")]
+    pub prehash_key: i32,
+    #[prost(enumeration = "HashOp", tag = "3")]
+    pub prehash_value: i32,
+    #[prost(enumeration = "LengthOp", tag = "4")]
+    pub length: i32,
+    #[prost(bytes = "vec", tag = "5")]
+    pub prefix: Vec<u8>,
+}
+
+/// The operation that hashes a child node into its parent:
+///
+/// ```plain
+/// parent := hash(prefix | child | suffix)
+/// ```
+///
+/// The prefix and suffix contain the child's siblings.
+#[derive(Message, Clone, PartialEq, Eq)]
+pub struct InnerOp {
+    #[prost(enumeration = "HashOp", tag = "1")]
+    pub hash: i32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub prefix: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub suffix: Vec<u8>,
+}
+
+/// Describes the structure of a Merkle tree. A proof is only accepted if it
+/// matches the spec of the tree it's supposed to be generated from.
+#[derive(Message, Clone, PartialEq, Eq)]
+pub struct ProofSpec {
+    #[prost(message, optional, tag = "1")]
+    pub leaf_spec: Option<LeafOp>,
+    #[prost(message, optional, tag = "2")]
+    pub inner_spec: Option<InnerSpec>,
+    #[prost(int32, tag = "3")]
+    pub max_depth: i32,
+    #[prost(int32, tag = "4")]
+    pub min_depth: i32,
+    #[prost(bool, tag = "5")]
+    pub prehash_key_before_comparison: bool,
+}
+
+#[derive(Message, Clone, PartialEq, Eq)]
+pub struct InnerSpec {
+    /// The order in which children are hashed, e.g. `[0, 1]` for a binary tree
+    /// that hashes the left child before the right one.
+    #[prost(int32, repeated, tag = "1")]
+    pub child_order: Vec<i32>,
+    #[prost(int32, tag = "2")]
+    pub child_size: i32,
+    #[prost(int32, tag = "3")]
+    pub min_prefix_length: i32,
+    #[prost(int32, tag = "4")]
+    pub max_prefix_length: i32,
+    /// The value of an empty child, if the tree has them.
+    #[prost(bytes = "vec", tag = "5")]
+    pub empty_child: Vec<u8>,
+    #[prost(enumeration = "HashOp", tag = "6")]
+    pub hash: i32,
+}
+
+/// A chain of ICS-23 proofs, from the innermost tree to the outermost, as
+/// defined by ibc-go. E.g. in a Cosmos SDK chain, the first proof is against
+/// the IAVL tree of a store, and the second proves that store's root in the
+/// multistore.
+#[derive(Message, Clone, PartialEq)]
+pub struct MerkleProof {
+    #[prost(message, repeated, tag = "1")]
+    pub proofs: Vec<CommitmentProof>,
+}
+
+impl MerkleProof {
+    pub fn decode_bytes(bytes: &[u8]) -> Ics23Result<Self> {
+        Self::decode(bytes).map_err(|err| Ics23Error::Decode {
+            reason: err.to_string(),
+        })
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{HashOp, InnerSpec, LeafOp, LengthOp, ProofSpec};
+
+/// Spec of the IAVL trees used by Cosmos SDK stores.
+pub fn iavl_spec() -> ProofSpec {
+    ProofSpec {
+        leaf_spec: Some(LeafOp {
+            hash: HashOp::Sha256.into(),
+            prehash_key: HashOp::NoHash.into(),
+            prehash_value: HashOp::Sha256.into(),
+            length: LengthOp::VarProto.into(),
+            prefix: vec![0],
+        }),
+        inner_spec: Some(InnerSpec {
+            child_order: vec![0, 1],
+            child_size: 33,
+            min_prefix_length: 4,
+            max_prefix_length: 12,
+            empty_child: vec![],
+            hash: HashOp::Sha256.into(),
+        }),
+        max_depth: 0,
+        min_depth: 0,
+        prehash_key_before_comparison: false,
+    }
+}
+
+/// Spec of the simple Merkle tree that Tendermint uses, among other things, to
+/// commit to the roots of the stores in a Cosmos SDK multistore.
+pub fn tendermint_spec() -> ProofSpec {
+    ProofSpec {
+        leaf_spec: Some(LeafOp {
+            hash: HashOp::Sha256.into(),
+            prehash_key: HashOp::NoHash.into(),
+            prehash_value: HashOp::Sha256.into(),
+            length: LengthOp::VarProto.into(),
+            prefix: vec![0],
+        }),
+        inner_spec: Some(InnerSpec {
+            child_order: vec![0, 1],
+            child_size: 32,
+            min_prefix_length: 1,
+            max_prefix_length: 1,
+            empty_child: vec![],
+            hash: HashOp::Sha256.into(),
+        }),
+        max_depth: 0,
+        min_depth: 0,
+        prehash_key_before_comparison: false,
+    }
+}
+
+/// Proof specs of a Cosmos SDK chain, in the order of the proofs in an ibc-go
+/// `MerkleProof`: the IAVL store first, followed by the multistore.
+pub fn sdk_specs() -> Vec<ProofSpec> {
+    vec![iavl_spec(), tendermint_spec()]
+}
@@ -0,0 +1,818 @@
+use {
+    crate::{
+        calculate_existence_root, do_hash, ExistenceProof, Ics23Error, Ics23Result, InnerOp,
+        InnerSpec, LeafOp, NonExistenceProof, ProofSpec,
+    },
+    std::cmp::Ordering,
+};
+
+/// Verify that the key-value pair exists in the tree of the given root.
+pub fn verify_membership(
+    spec: &ProofSpec,
+    root: &[u8],
+    proof: &ExistenceProof,
+    key: &[u8],
+    value: &[u8],
+) -> Ics23Result<()> {
+    if proof.key != key {
+        return Err(Ics23Error::KeyMismatch {
+            expect: key.to_vec(),
+            actual: proof.key.clone(),
+        });
+    }
+
+    if proof.value != value {
+        return Err(Ics23Error::ValueMismatch {
+            expect: value.to_vec(),
+            actual: proof.value.clone(),
+        });
+    }
+
+    verify_existence(spec, root, proof)
+}
+
+/// Verify that the key doesn't exist in the tree of the given root.
+pub fn verify_non_membership(
+    spec: &ProofSpec,
+    root: &[u8],
+    proof: &NonExistenceProof,
+    key: &[u8],
+) -> Ics23Result<()> {
+    if proof.key != key {
+        return Err(Ics23Error::KeyMismatch {
+            expect: key.to_vec(),
+            actual: proof.key.clone(),
+        });
+    }
+
+    let inner_spec = inner_spec(spec)?;
+
+    match (&proof.left, &proof.right) {
+        (None, None) => {
+            return Err(Ics23Error::MalformedProof {
+                reason: "non-existence proof must have at least one neighbor",
+            });
+        },
+        (Some(left), None) => {
+            verify_existence(spec, root, left)?;
+            ensure_key_order(spec, &left.key, key)?;
+            ensure_right_most(inner_spec, &left.path)?;
+        },
+        (None, Some(right)) => {
+            verify_existence(spec, root, right)?;
+            ensure_key_order(spec, key, &right.key)?;
+            ensure_left_most(inner_spec, &right.path)?;
+        },
+        (Some(left), Some(right)) => {
+            verify_existence(spec, root, left)?;
+            verify_existence(spec, root, right)?;
+            ensure_key_order(spec, &left.key, key)?;
+            ensure_key_order(spec, key, &right.key)?;
+            ensure_left_neighbor(inner_spec, &left.path, &right.path)?;
+        },
+    }
+
+    Ok(())
+}
+
+/// Compute the root hash implied by a non-existence proof, from whichever of
+/// the two neighbors is present.
+pub fn calculate_non_existence_root(proof: &NonExistenceProof) -> Ics23Result<Vec<u8>> {
+    match (&proof.left, &proof.right) {
+        (Some(left), _) => calculate_existence_root(left),
+        (None, Some(right)) => calculate_existence_root(right),
+        (None, None) => Err(Ics23Error::MalformedProof {
+            reason: "non-existence proof must have at least one neighbor",
+        }),
+    }
+}
+
+fn verify_existence(spec: &ProofSpec, root: &[u8], proof: &ExistenceProof) -> Ics23Result<()> {
+    check_existence_spec(spec, proof)?;
+
+    let computed = calculate_existence_root(proof)?;
+    if computed != root {
+        return Err(Ics23Error::RootMismatch {
+            expect: root.to_vec(),
+            actual: computed,
+        });
+    }
+
+    Ok(())
+}
+
+// ---------------------------------- checks -----------------------------------
+
+fn leaf_spec(spec: &ProofSpec) -> Ics23Result<&LeafOp> {
+    spec.leaf_spec.as_ref().ok_or(Ics23Error::SpecMismatch {
+        reason: "spec has no leaf spec",
+    })
+}
+
+fn inner_spec(spec: &ProofSpec) -> Ics23Result<&InnerSpec> {
+    spec.inner_spec.as_ref().ok_or(Ics23Error::SpecMismatch {
+        reason: "spec has no inner spec",
+    })
+}
+
+fn check_existence_spec(spec: &ProofSpec, proof: &ExistenceProof) -> Ics23Result<()> {
+    let Some(leaf) = &proof.leaf else {
+        return Err(Ics23Error::MalformedProof {
+            reason: "existence proof must have a leaf op",
+        });
+    };
+
+    check_leaf_op(leaf_spec(spec)?, leaf)?;
+
+    let depth = proof.path.len() as i32;
+    if spec.min_depth > 0 && depth < spec.min_depth {
+        return Err(Ics23Error::SpecMismatch {
+            reason: "path is shorter than the spec's min depth",
+        });
+    }
+    if spec.max_depth > 0 && depth > spec.max_depth {
+        return Err(Ics23Error::SpecMismatch {
+            reason: "path is longer than the spec's max depth",
+        });
+    }
+
+    for inner in &proof.path {
+        check_inner_op(spec, inner)?;
+    }
+
+    Ok(())
+}
+
+fn check_leaf_op(expect: &LeafOp, leaf: &LeafOp) -> Ics23Result<()> {
+    if leaf.hash != expect.hash
+        || leaf.prehash_key != expect.prehash_key
+        || leaf.prehash_value != expect.prehash_value
+        || leaf.length != expect.length
+    {
+        return Err(Ics23Error::SpecMismatch {
+            reason: "leaf op doesn't match the leaf spec",
+        });
+    }
+
+    if !leaf.prefix.starts_with(&expect.prefix) {
+        return Err(Ics23Error::SpecMismatch {
+            reason: "leaf prefix doesn't start with the leaf spec prefix",
+        });
+    }
+
+    Ok(())
+}
+
+fn check_inner_op(spec: &ProofSpec, inner: &InnerOp) -> Ics23Result<()> {
+    let leaf_spec = leaf_spec(spec)?;
+    let inner_spec = inner_spec(spec)?;
+
+    if inner.hash != inner_spec.hash {
+        return Err(Ics23Error::SpecMismatch {
+            reason: "inner op hash doesn't match the inner spec",
+        });
+    }
+
+    // Otherwise, a leaf could be disguised as an inner node, or vice versa.
+    if !leaf_spec.prefix.is_empty() && inner.prefix.starts_with(&leaf_spec.prefix) {
+        return Err(Ics23Error::SpecMismatch {
+            reason: "inner op prefix starts with the leaf prefix",
+        });
+    }
+
+    let child_size = inner_spec.child_size as usize;
+    let max_left_child_bytes = (inner_spec.child_order.len() - 1) * child_size;
+    if inner.prefix.len() < inner_spec.min_prefix_length as usize
+        || inner.prefix.len() > inner_spec.max_prefix_length as usize + max_left_child_bytes
+    {
+        return Err(Ics23Error::SpecMismatch {
+            reason: "inner op prefix length is out of bounds",
+        });
+    }
+
+    if child_size == 0 || inner.suffix.len() % child_size != 0 {
+        return Err(Ics23Error::SpecMismatch {
+            reason: "inner op suffix length isn't a multiple of the child size",
+        });
+    }
+
+    Ok(())
+}
+
+fn ensure_key_order(spec: &ProofSpec, left: &[u8], right: &[u8]) -> Ics23Result<()> {
+    let ordering = if spec.prehash_key_before_comparison {
+        let prehash = leaf_spec(spec)?.prehash_key();
+        do_hash(prehash, left)?.cmp(&do_hash(prehash, right)?)
+    } else {
+        left.cmp(right)
+    };
+
+    if ordering != Ordering::Less {
+        return Err(Ics23Error::MalformedProof {
+            reason: "keys in non-existence proof are not in order",
+        });
+    }
+
+    Ok(())
+}
+
+// -------------------------------- neighbors ----------------------------------
+
+/// The byte lengths of the prefix and suffix of an inner op, if the child
+/// being hashed is of a certain branch.
+struct Padding {
+    min_prefix: usize,
+    max_prefix: usize,
+    suffix: usize,
+}
+
+fn get_position(spec: &InnerSpec, branch: usize) -> Ics23Result<usize> {
+    spec.child_order
+        .iter()
+        .position(|&child| child as usize == branch)
+        .ok_or(Ics23Error::SpecMismatch {
+            reason: "branch not found in child order",
+        })
+}
+
+fn get_padding(spec: &InnerSpec, branch: usize) -> Ics23Result<Padding> {
+    let idx = get_position(spec, branch)?;
+    let child_size = spec.child_size as usize;
+    let prefix = idx * child_size;
+    let suffix = (spec.child_order.len() - 1 - idx) * child_size;
+    Ok(Padding {
+        min_prefix: prefix + spec.min_prefix_length as usize,
+        max_prefix: prefix + spec.max_prefix_length as usize,
+        suffix,
+    })
+}
+
+fn has_padding(op: &InnerOp, padding: &Padding) -> bool {
+    op.prefix.len() >= padding.min_prefix
+        && op.prefix.len() <= padding.max_prefix
+        && op.suffix.len() == padding.suffix
+}
+
+/// Find out which branch the child hashed by the inner op is.
+fn order_from_padding(spec: &InnerSpec, op: &InnerOp) -> Ics23Result<usize> {
+    for branch in 0..spec.child_order.len() {
+        if has_padding(op, &get_padding(spec, branch)?) {
+            return Ok(branch);
+        }
+    }
+
+    Err(Ics23Error::MalformedProof {
+        reason: "inner op padding doesn't match any branch",
+    })
+}
+
+/// Whether all the children to the left of the child hashed by the inner op
+/// are empty.
+fn left_branches_are_empty(spec: &InnerSpec, op: &InnerOp) -> Ics23Result<bool> {
+    let Ok(branch) = order_from_padding(spec, op) else {
+        return Ok(false);
+    };
+    let left_branches = get_position(spec, branch)?;
+    if left_branches == 0 || spec.empty_child.is_empty() {
+        return Ok(false);
+    }
+
+    // The left children are the last bytes of the prefix.
+    let child_size = spec.child_size as usize;
+    let Some(start) = op.prefix.len().checked_sub(left_branches * child_size) else {
+        return Ok(false);
+    };
+
+    Ok(op.prefix[start..]
+        .chunks(child_size)
+        .all(|child| child == spec.empty_child))
+}
+
+/// Whether all the children to the right of the child hashed by the inner op
+/// are empty.
+fn right_branches_are_empty(spec: &InnerSpec, op: &InnerOp) -> Ics23Result<bool> {
+    let Ok(branch) = order_from_padding(spec, op) else {
+        return Ok(false);
+    };
+    let right_branches = spec.child_order.len() - 1 - get_position(spec, branch)?;
+    if right_branches == 0 || spec.empty_child.is_empty() {
+        return Ok(false);
+    }
+
+    // The right children make up the entire suffix.
+    let child_size = spec.child_size as usize;
+    if op.suffix.len() != right_branches * child_size {
+        return Ok(false);
+    }
+
+    Ok(op
+        .suffix
+        .chunks(child_size)
+        .all(|child| child == spec.empty_child))
+}
+
+/// Ensure the path leads to the left-most leaf of the tree, i.e. the key is the
+/// smallest one.
+fn ensure_left_most(spec: &InnerSpec, path: &[InnerOp]) -> Ics23Result<()> {
+    let padding = get_padding(spec, 0)?;
+    for step in path {
+        if !has_padding(step, &padding) && !left_branches_are_empty(spec, step)? {
+            return Err(Ics23Error::NotAdjacent);
+        }
+    }
+    Ok(())
+}
+
+/// Ensure the path leads to the right-most leaf of the tree, i.e. the key is
+/// the greatest one.
+fn ensure_right_most(spec: &InnerSpec, path: &[InnerOp]) -> Ics23Result<()> {
+    let padding = get_padding(spec, spec.child_order.len() - 1)?;
+    for step in path {
+        if !has_padding(step, &padding) && !right_branches_are_empty(spec, step)? {
+            return Err(Ics23Error::NotAdjacent);
+        }
+    }
+    Ok(())
+}
+
+/// Ensure the two paths lead to adjacent leaves, i.e. there is no other leaf
+/// in between.
+///
+/// Starting from the root, the paths must share some common ancestors, then
+/// split at a node where the left path takes a branch immediately to the left
+/// of the right path's. Below the split, the left path must then be right-most
+/// and the right path left-most.
+fn ensure_left_neighbor(spec: &InnerSpec, left: &[InnerOp], right: &[InnerOp]) -> Ics23Result<()> {
+    let mut left = left.to_vec();
+    let mut right = right.to_vec();
+
+    let (mut top_left, mut top_right) = match (left.pop(), right.pop()) {
+        (Some(top_left), Some(top_right)) => (top_left, top_right),
+        _ => return Err(Ics23Error::NotAdjacent),
+    };
+
+    while top_left.prefix == top_right.prefix && top_left.suffix == top_right.suffix {
+        (top_left, top_right) = match (left.pop(), right.pop()) {
+            (Some(top_left), Some(top_right)) => (top_left, top_right),
+            _ => return Err(Ics23Error::NotAdjacent),
+        };
+    }
+
+    if order_from_padding(spec, &top_left)? + 1 != order_from_padding(spec, &top_right)? {
+        return Err(Ics23Error::NotAdjacent);
+    }
+
+    ensure_right_most(spec, &left)?;
+    ensure_left_most(spec, &right)
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{apply_leaf, iavl_spec, tendermint_spec, HashOp, LengthOp},
+        sha2::{Digest, Sha256},
+    };
+
+    // Keys of the tree that existence and non-existence proofs are tested
+    // against. Seven of them, so that the tree isn't balanced.
+    const KEYS: [&[u8]; 7] = [b"b", b"d", b"f", b"h", b"j", b"l", b"n"];
+
+    fn value_of(key: &[u8]) -> Vec<u8> {
+        [b"value-".as_slice(), key].concat()
+    }
+
+    /// Build a Tendermint simple Merkle tree of the leaves, which must be
+    /// sorted by key, and return its root hash and the path of each leaf.
+    fn build_tree(leaves: &[(&[u8], Vec<u8>)]) -> (Vec<u8>, Vec<Vec<InnerOp>>) {
+        if let [(key, value)] = leaves {
+            let leaf = tendermint_spec().leaf_spec.unwrap();
+            return (apply_leaf(&leaf, key, value).unwrap(), vec![vec![]]);
+        }
+
+        // The left subtree has the largest power of two leaves less than the
+        // total number of leaves.
+        let split = leaves.len().next_power_of_two() / 2;
+        let (left, mut left_paths) = build_tree(&leaves[..split]);
+        let (right, mut right_paths) = build_tree(&leaves[split..]);
+
+        for path in &mut left_paths {
+            path.push(InnerOp {
+                hash: HashOp::Sha256.into(),
+                prefix: vec![1],
+                suffix: right.clone(),
+            });
+        }
+
+        for path in &mut right_paths {
+            path.push(InnerOp {
+                hash: HashOp::Sha256.into(),
+                prefix: [[1].as_slice(), &left].concat(),
+                suffix: vec![],
+            });
+        }
+
+        let root = Sha256::digest([[1].as_slice(), &left, &right].concat()).to_vec();
+        left_paths.extend(right_paths);
+
+        (root, left_paths)
+    }
+
+    /// Return the root of the tree of [`KEYS`], and an existence proof for
+    /// each of the keys.
+    fn setup() -> (Vec<u8>, Vec<ExistenceProof>) {
+        let leaves = KEYS
+            .iter()
+            .map(|key| (*key, value_of(key)))
+            .collect::<Vec<_>>();
+        let (root, paths) = build_tree(&leaves);
+        let proofs = leaves
+            .into_iter()
+            .zip(paths)
+            .map(|((key, value), path)| ExistenceProof {
+                key: key.to_vec(),
+                value,
+                leaf: tendermint_spec().leaf_spec,
+                path,
+            })
+            .collect();
+
+        (root, proofs)
+    }
+
+    fn non_existence_proof(
+        key: &[u8],
+        left: Option<&ExistenceProof>,
+        right: Option<&ExistenceProof>,
+    ) -> NonExistenceProof {
+        NonExistenceProof {
+            key: key.to_vec(),
+            left: left.cloned(),
+            right: right.cloned(),
+        }
+    }
+
+    #[test]
+    fn existence_proofs_work() {
+        let spec = tendermint_spec();
+        let (root, proofs) = setup();
+
+        // every key, including the left-most, right-most, and those in the
+        // middle of the tree
+        for (key, proof) in KEYS.iter().zip(&proofs) {
+            verify_membership(&spec, &root, proof, key, &value_of(key)).unwrap();
+        }
+
+        // incorrect value
+        assert!(matches!(
+            verify_membership(&spec, &root, &proofs[3], b"h", b"value-j"),
+            Err(Ics23Error::ValueMismatch { .. })
+        ));
+
+        // incorrect key
+        assert!(matches!(
+            verify_membership(&spec, &root, &proofs[3], b"j", b"value-h"),
+            Err(Ics23Error::KeyMismatch { .. })
+        ));
+
+        // incorrect root
+        assert!(matches!(
+            verify_membership(&spec, &[0; 32], &proofs[3], b"h", b"value-h"),
+            Err(Ics23Error::RootMismatch { .. })
+        ));
+
+        // the value in the proof is tampered with
+        let mut proof = proofs[3].clone();
+        proof.value = b"evil".to_vec();
+        assert!(matches!(
+            verify_membership(&spec, &root, &proof, b"h", b"evil"),
+            Err(Ics23Error::RootMismatch { .. })
+        ));
+
+        // the proof is of a different spec
+        assert!(matches!(
+            verify_membership(&iavl_spec(), &root, &proofs[3], b"h", b"value-h"),
+            Err(Ics23Error::SpecMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn non_existence_proofs_work() {
+        let spec = tendermint_spec();
+        let (root, proofs) = setup();
+
+        // left of the left-most key
+        let proof = non_existence_proof(b"a", None, Some(&proofs[0]));
+        verify_non_membership(&spec, &root, &proof, b"a").unwrap();
+
+        // right of the right-most key
+        let proof = non_existence_proof(b"o", Some(&proofs[6]), None);
+        verify_non_membership(&spec, &root, &proof, b"o").unwrap();
+
+        // in the middle, between two leaves of the same parent
+        let proof = non_existence_proof(b"e", Some(&proofs[1]), Some(&proofs[2]));
+        verify_non_membership(&spec, &root, &proof, b"e").unwrap();
+
+        // in the middle, between the two subtrees of the root
+        let proof = non_existence_proof(b"i", Some(&proofs[3]), Some(&proofs[4]));
+        verify_non_membership(&spec, &root, &proof, b"i").unwrap();
+    }
+
+    #[test]
+    fn invalid_non_existence_proofs_are_rejected() {
+        let spec = tendermint_spec();
+        let (root, proofs) = setup();
+
+        for (key, left, right, expect) in [
+            // the key exists
+            (
+                b"d".as_slice(),
+                Some(&proofs[0]),
+                Some(&proofs[2]),
+                Ics23Error::NotAdjacent,
+            ),
+            // the neighbors aren't adjacent
+            (
+                b"e".as_slice(),
+                Some(&proofs[0]),
+                Some(&proofs[2]),
+                Ics23Error::NotAdjacent,
+            ),
+            (
+                b"i".as_slice(),
+                Some(&proofs[2]),
+                Some(&proofs[5]),
+                Ics23Error::NotAdjacent,
+            ),
+            // the left neighbor alone isn't the right-most key
+            (
+                b"o".as_slice(),
+                Some(&proofs[5]),
+                None,
+                Ics23Error::NotAdjacent,
+            ),
+            // the right neighbor alone isn't the left-most key
+            (
+                b"a".as_slice(),
+                None,
+                Some(&proofs[1]),
+                Ics23Error::NotAdjacent,
+            ),
+            // the key isn't between the neighbors
+            (
+                b"c".as_slice(),
+                Some(&proofs[1]),
+                Some(&proofs[2]),
+                Ics23Error::MalformedProof {
+                    reason: "keys in non-existence proof are not in order",
+                },
+            ),
+            // the neighbors are swapped
+            (
+                b"e".as_slice(),
+                Some(&proofs[2]),
+                Some(&proofs[1]),
+                Ics23Error::MalformedProof {
+                    reason: "keys in non-existence proof are not in order",
+                },
+            ),
+            // no neighbors
+            (b"e".as_slice(), None, None, Ics23Error::MalformedProof {
+                reason: "non-existence proof must have at least one neighbor",
+            }),
+        ] {
+            let proof = non_existence_proof(key, left, right);
+            assert_eq!(
+                verify_non_membership(&spec, &root, &proof, key),
+                Err(expect),
+                "key: {key:?}"
+            );
+        }
+
+        // a neighbor that isn't in the tree
+        let mut left = proofs[1].clone();
+        left.value = b"evil".to_vec();
+        let proof = non_existence_proof(b"e", Some(&left), Some(&proofs[2]));
+        assert!(matches!(
+            verify_non_membership(&spec, &root, &proof, b"e"),
+            Err(Ics23Error::RootMismatch { .. })
+        ));
+
+        // proving a different key
+        let proof = non_existence_proof(b"e", Some(&proofs[1]), Some(&proofs[2]));
+        assert!(matches!(
+            verify_non_membership(&spec, &root, &proof, b"g"),
+            Err(Ics23Error::KeyMismatch { .. })
+        ));
+    }
+
+    // Ported from cosmos/ics23's `TestCheckAgainstSpec`.
+    #[test]
+    fn existence_spec_is_enforced() {
+        let leaf = LeafOp {
+            hash: HashOp::Sha256.into(),
+            prehash_key: HashOp::NoHash.into(),
+            prehash_value: HashOp::Sha256.into(),
+            length: LengthOp::VarProto.into(),
+            prefix: vec![0, 2, 2],
+        };
+        let invalid_leaf = LeafOp {
+            hash: HashOp::Sha512.into(),
+            prehash_key: HashOp::NoHash.into(),
+            prehash_value: HashOp::NoHash.into(),
+            length: LengthOp::VarProto.into(),
+            prefix: vec![0],
+        };
+        let valid_inner = |height: u8| InnerOp {
+            hash: HashOp::Sha256.into(),
+            prefix: vec![height, 2, 2, 0],
+            suffix: vec![],
+        };
+        let invalid_inner = InnerOp {
+            hash: HashOp::Sha256.into(),
+            prefix: vec![0xaa],
+            suffix: vec![],
+        };
+        let invalid_inner_hash = InnerOp {
+            hash: HashOp::Sha512.into(),
+            prefix: vec![0xde, 0xad, 0xbe, 0xef, 0x00, 0xca, 0xfe, 0x00],
+            suffix: vec![],
+        };
+
+        let mut depth_limited_spec = iavl_spec();
+        depth_limited_spec.min_depth = 2;
+        depth_limited_spec.max_depth = 4;
+
+        for (name, leaf, path, spec, valid) in [
+            ("empty proof fails", None, vec![], iavl_spec(), false),
+            (
+                "accepts one valid leaf",
+                Some(leaf.clone()),
+                vec![],
+                iavl_spec(),
+                true,
+            ),
+            (
+                "rejects invalid leaf",
+                Some(invalid_leaf),
+                vec![],
+                iavl_spec(),
+                false,
+            ),
+            (
+                "rejects only inner (no leaf)",
+                None,
+                vec![valid_inner(2)],
+                iavl_spec(),
+                false,
+            ),
+            (
+                "accepts leaf and valid inner",
+                Some(leaf.clone()),
+                vec![valid_inner(2)],
+                iavl_spec(),
+                true,
+            ),
+            (
+                "rejects invalid inner (prefix)",
+                Some(leaf.clone()),
+                vec![invalid_inner],
+                iavl_spec(),
+                false,
+            ),
+            (
+                "rejects invalid inner (hash)",
+                Some(leaf.clone()),
+                vec![invalid_inner_hash],
+                iavl_spec(),
+                false,
+            ),
+            (
+                "accepts depth limited with proper number of inner nodes",
+                Some(leaf.clone()),
+                vec![valid_inner(2), valid_inner(4), valid_inner(6)],
+                depth_limited_spec.clone(),
+                true,
+            ),
+            (
+                "rejects depth limited with too few inner nodes",
+                Some(leaf.clone()),
+                vec![valid_inner(2)],
+                depth_limited_spec.clone(),
+                false,
+            ),
+            (
+                "rejects depth limited with too many inner nodes",
+                Some(leaf),
+                vec![valid_inner(2); 5],
+                depth_limited_spec,
+                false,
+            ),
+        ] {
+            let proof = ExistenceProof {
+                key: b"foo".to_vec(),
+                value: b"bar".to_vec(),
+                leaf,
+                path,
+            };
+            assert_eq!(check_existence_spec(&spec, &proof).is_ok(), valid, "{name}");
+        }
+    }
+
+    // Ported from cosmos/ics23's `TestEmptyBranch`.
+    #[test]
+    fn empty_branches_are_detected() {
+        let empty_child = b"32_empty_child_placeholder_bytes".to_vec();
+        let spec = ProofSpec {
+            leaf_spec: Some(LeafOp {
+                hash: HashOp::Sha256.into(),
+                prehash_key: HashOp::NoHash.into(),
+                prehash_value: HashOp::Sha256.into(),
+                length: LengthOp::NoPrefix.into(),
+                prefix: vec![0],
+            }),
+            inner_spec: Some(InnerSpec {
+                child_order: vec![0, 1],
+                child_size: 32,
+                min_prefix_length: 1,
+                max_prefix_length: 1,
+                empty_child: empty_child.clone(),
+                hash: HashOp::Sha256.into(),
+            }),
+            max_depth: 0,
+            min_depth: 0,
+            prehash_key_before_comparison: false,
+        };
+        let non_empty_spec = tendermint_spec();
+
+        let inner_op = |prefix: Vec<u8>, suffix: Vec<u8>| InnerOp {
+            hash: HashOp::Sha256.into(),
+            prefix,
+            suffix,
+        };
+
+        for (idx, (op, spec, is_left, is_right)) in [
+            (
+                inner_op([[1].as_slice(), &empty_child].concat(), vec![]),
+                &spec,
+                true,
+                false,
+            ),
+            (inner_op(vec![1], empty_child.clone()), &spec, false, true),
+            // non-empty cases
+            (
+                inner_op([[1].as_slice(), &[0; 32]].concat(), vec![]),
+                &spec,
+                false,
+                false,
+            ),
+            (inner_op(vec![1], vec![0; 32]), &spec, false, false),
+            (
+                inner_op(
+                    [[1].as_slice(), &empty_child[..28], b"xxxx"].concat(),
+                    vec![],
+                ),
+                &spec,
+                false,
+                false,
+            ),
+            (
+                inner_op(vec![1], [&empty_child[..28], b"xxxx"].concat()),
+                &spec,
+                false,
+                false,
+            ),
+            // a spec without an empty child
+            (
+                inner_op([[1].as_slice(), &[0; 32]].concat(), vec![]),
+                &non_empty_spec,
+                false,
+                false,
+            ),
+            (
+                inner_op(vec![1], vec![0; 32]),
+                &non_empty_spec,
+                false,
+                false,
+            ),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            check_inner_op(spec, &op).unwrap();
+            let inner = inner_spec(spec).unwrap();
+            assert_eq!(
+                left_branches_are_empty(inner, &op).unwrap(),
+                is_left,
+                "case {idx}"
+            );
+            assert_eq!(
+                right_branches_are_empty(inner, &op).unwrap(),
+                is_right,
+                "case {idx}"
+            );
+        }
+    }
+}
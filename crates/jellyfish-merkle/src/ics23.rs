@@ -0,0 +1,172 @@
+use {
+    crate::{
+        node::{INTERNAL_NODE_HASH_PREFIX, LEAF_NODE_HASH_PERFIX},
+        BitArray, MembershipProof,
+    },
+    grug_ics23::{ExistenceProof, HashOp, InnerOp, InnerSpec, LeafOp, LengthOp, ProofSpec},
+    grug_types::{hash, Hash, Order},
+};
+
+/// Type of the `ProofOp`s containing ICS-23 proofs of our Merkle tree, in the
+/// responses of `/store` ABCI queries.
+pub const ICS23_PROOF_TYPE: &str = "ics23:jmt";
+
+/// Spec of our binary Jellyfish Merkle tree, for verifying ICS-23 proofs
+/// generated by `MerkleTree::ics23_prove`.
+///
+/// Leaves are hashed as `sha256(1 | sha256(key) | sha256(value))` and internal
+/// nodes as `sha256(0 | left | right)`, where a missing child is represented by
+/// a zero hash. Since keys are sorted by their hashes, so must they be when
+/// checking the neighbors in non-existence proofs.
+pub fn ics23_spec() -> ProofSpec {
+    ProofSpec {
+        leaf_spec: Some(LeafOp {
+            hash: HashOp::Sha256.into(),
+            prehash_key: HashOp::Sha256.into(),
+            prehash_value: HashOp::Sha256.into(),
+            length: LengthOp::NoPrefix.into(),
+            prefix: LEAF_NODE_HASH_PERFIX.to_vec(),
+        }),
+        inner_spec: Some(InnerSpec {
+            child_order: vec![0, 1],
+            child_size: Hash::LENGTH as i32,
+            min_prefix_length: INTERNAL_NODE_HASH_PREFIX.len() as i32,
+            max_prefix_length: INTERNAL_NODE_HASH_PREFIX.len() as i32,
+            empty_child: Hash::ZERO.into_vec(),
+            hash: HashOp::Sha256.into(),
+        }),
+        max_depth: BitArray::MAX_BIT_LENGTH as i32,
+        min_depth: 0,
+        prehash_key_before_comparison: true,
+    }
+}
+
+/// Convert a membership proof of the key-value pair to an ICS-23 existence
+/// proof.
+pub fn ics23_existence_proof(key: &[u8], value: &[u8], proof: &MembershipProof) -> ExistenceProof {
+    let bitarray = BitArray::from_bytes(&hash(key));
+    let path = bitarray
+        .range(None, Some(proof.sibling_hashes.len()), Order::Descending)
+        .zip(&proof.sibling_hashes)
+        .map(|(bit, sibling_hash)| {
+            let sibling_hash = sibling_hash.as_ref().unwrap_or(&Hash::ZERO);
+            if bit == 0 {
+                InnerOp {
+                    hash: HashOp::Sha256.into(),
+                    prefix: INTERNAL_NODE_HASH_PREFIX.to_vec(),
+                    suffix: sibling_hash.to_vec(),
+                }
+            } else {
+                InnerOp {
+                    hash: HashOp::Sha256.into(),
+                    prefix: [INTERNAL_NODE_HASH_PREFIX, sibling_hash].concat(),
+                    suffix: vec![],
+                }
+            }
+        })
+        .collect();
+
+    ExistenceProof {
+        key: key.to_vec(),
+        value: value.to_vec(),
+        leaf: ics23_spec().leaf_spec,
+        path,
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::MerkleTree,
+        grug_ics23::{verify_membership, verify_non_membership, Proof},
+        grug_types::{Batch, MockStorage, Op, Storage},
+    };
+
+    const TREE: MerkleTree = MerkleTree::new_default();
+
+    /// Apply the batch to both the tree and a raw KV store.
+    fn apply(
+        storage: &mut MockStorage,
+        state_storage: &mut MockStorage,
+        old_version: u64,
+        new_version: u64,
+        batch: Batch,
+    ) -> Hash {
+        for (key, op) in &batch {
+            match op {
+                Op::Insert(value) => state_storage.write(key, value),
+                Op::Delete => state_storage.remove(key),
+            }
+        }
+
+        TREE.apply_raw(storage, old_version, new_version, &batch)
+            .unwrap()
+            .unwrap()
+    }
+
+    fn prove(storage: &MockStorage, state_storage: &MockStorage, key: &str, version: u64) -> Proof {
+        TREE.ics23_prove(storage, state_storage, key.as_bytes(), version)
+            .unwrap()
+            .proof
+            .unwrap()
+    }
+
+    #[test]
+    fn ics23_proofs_work() {
+        let mut storage = MockStorage::new();
+        let mut state_storage = MockStorage::new();
+        let spec = ics23_spec();
+
+        let keys = (0..20).map(|i| format!("key{i}")).collect::<Vec<_>>();
+        let batch = keys
+            .iter()
+            .map(|key| {
+                (
+                    key.as_bytes().to_vec(),
+                    Op::Insert(key.repeat(2).into_bytes()),
+                )
+            })
+            .collect();
+        let root = apply(&mut storage, &mut state_storage, 0, 1, batch);
+
+        for key in &keys {
+            let Proof::Exist(proof) = prove(&storage, &state_storage, key, 1) else {
+                panic!("expecting an existence proof for {key}");
+            };
+            let value = key.repeat(2);
+            verify_membership(&spec, &root, &proof, key.as_bytes(), value.as_bytes()).unwrap();
+            assert!(verify_membership(&spec, &root, &proof, key.as_bytes(), b"wrong").is_err());
+        }
+
+        // keys that don't exist have neighbors on either or both sides,
+        // depending on where their hashes fall
+        let mut sides = [false; 3];
+        for i in 20..300 {
+            let key = format!("key{i}");
+            let Proof::Nonexist(proof) = prove(&storage, &state_storage, &key, 1) else {
+                panic!("expecting a non-existence proof for {key}");
+            };
+            match (&proof.left, &proof.right) {
+                (Some(_), Some(_)) => sides[0] = true,
+                (Some(_), None) => sides[1] = true,
+                (None, Some(_)) => sides[2] = true,
+                (None, None) => panic!("non-existence proof without neighbors"),
+            }
+            verify_non_membership(&spec, &root, &proof, key.as_bytes()).unwrap();
+            assert!(verify_non_membership(&spec, &root, &proof, b"key0").is_err());
+        }
+        assert_eq!(sides, [true; 3]);
+
+        // once a key is deleted, its neighbors become adjacent
+        let batch = Batch::from([(b"key7".to_vec(), Op::Delete)]);
+        let new_root = apply(&mut storage, &mut state_storage, 1, 2, batch);
+        let Proof::Nonexist(proof) = prove(&storage, &state_storage, "key7", 2) else {
+            panic!("expecting a non-existence proof for key7");
+        };
+        verify_non_membership(&spec, &new_root, &proof, b"key7").unwrap();
+        assert!(verify_non_membership(&spec, &root, &proof, b"key7").is_err());
+    }
+}
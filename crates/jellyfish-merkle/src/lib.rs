@@ -1,14 +1,18 @@
 mod bitarray;
+mod ics23;
 mod node;
 mod proof;
 mod tree;
 
 pub use crate::{
     bitarray::{BitArray, BitIterator},
+    ics23::{ics23_existence_proof, ics23_spec, ICS23_PROOF_TYPE},
     node::{hash_internal_node, hash_leaf_node, Child, InternalNode, LeafNode, Node},
     proof::{
         verify_membership_proof, verify_non_membership_proof, verify_proof, MembershipProof,
         NonMembershipProof, Proof, ProofError, ProofNode,
     },
-    tree::{MerkleTree, DEFAULT_NODE_NAMESPACE, DEFAULT_ORPHAN_NAMESPACE},
+    tree::{
+        MerkleTree, DEFAULT_NODE_NAMESPACE, DEFAULT_ORPHAN_NAMESPACE, DEFAULT_PREIMAGE_NAMESPACE,
+    },
 };
@@ -4,8 +4,8 @@ use {
     sha2::{Digest, Sha256},
 };
 
-const INTERNAL_NODE_HASH_PREFIX: &[u8] = &[0];
-const LEAF_NODE_HASH_PERFIX: &[u8] = &[1];
+pub(crate) const INTERNAL_NODE_HASH_PREFIX: &[u8] = &[0];
+pub(crate) const LEAF_NODE_HASH_PERFIX: &[u8] = &[1];
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Child {
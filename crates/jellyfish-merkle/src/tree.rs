@@ -1,15 +1,18 @@
 use {
     crate::{
-        BitArray, Child, InternalNode, LeafNode, MembershipProof, Node, NonMembershipProof, Proof,
-        ProofNode,
+        ics23_existence_proof, BitArray, Child, InternalNode, LeafNode, MembershipProof, Node,
+        NonMembershipProof, Proof, ProofNode,
     },
+    grug_ics23::{commitment_proof, CommitmentProof, NonExistenceProof},
     grug_storage::{Map, Set},
-    grug_types::{hash, Batch, Hash, Op, Order, StdResult, Storage},
+    grug_types::{hash, Batch, Hash, Op, Order, StdError, StdResult, Storage},
 };
 
 // default storage namespaces
 pub const DEFAULT_NODE_NAMESPACE: &str = "n";
 pub const DEFAULT_ORPHAN_NAMESPACE: &str = "o";
+pub const DEFAULT_PREIMAGE_NAMESPACE: &str = "p";
+pub const DEFAULT_STALE_PREIMAGE_NAMESPACE: &str = "s";
 
 /// The bit path of the root node, which is just empty
 pub const ROOT_BITS: &BitArray = &BitArray::new_empty();
@@ -45,6 +48,10 @@ pub struct MerkleTree<'a> {
     nodes: Map<'a, (u64, &'a BitArray), Node>,
     // (orphaned_since_version, version, bitarray) => Empty
     orphans: Set<'a, (u64, u64, &'a BitArray)>,
+    // key_hash => key
+    preimages: Map<'a, &'a Hash, Vec<u8>>,
+    // key_hash => deleted_since_version
+    stale_preimages: Map<'a, &'a Hash, u64>,
 }
 
 impl<'a> Default for MerkleTree<'a> {
@@ -54,11 +61,31 @@ impl<'a> Default for MerkleTree<'a> {
 }
 
 impl<'a> MerkleTree<'a> {
-    /// Create a new Merkle tree with the given namespaces.
+    /// Create a new Merkle tree with the given namespaces, and the default
+    /// namespaces for key preimages.
     pub const fn new(node_namespace: &'a str, orphan_namespace: &'a str) -> Self {
+        Self::new_with_preimages(
+            node_namespace,
+            orphan_namespace,
+            DEFAULT_PREIMAGE_NAMESPACE,
+            DEFAULT_STALE_PREIMAGE_NAMESPACE,
+        )
+    }
+
+    /// Create a new Merkle tree with the given namespaces, including those for
+    /// key preimages (see `ics23_prove`) and preimages of deleted keys pending
+    /// pruning.
+    pub const fn new_with_preimages(
+        node_namespace: &'a str,
+        orphan_namespace: &'a str,
+        preimage_namespace: &'a str,
+        stale_preimage_namespace: &'a str,
+    ) -> Self {
         Self {
             nodes: Map::new(node_namespace),
             orphans: Set::new(orphan_namespace),
+            preimages: Map::new(preimage_namespace),
+            stale_preimages: Map::new(stale_preimage_namespace),
         }
     }
 
@@ -96,11 +123,31 @@ impl<'a> MerkleTree<'a> {
         new_version: u64,
         batch: &Batch,
     ) -> StdResult<Option<Hash>> {
-        // hash the keys and values
-        let mut batch: Vec<_> = batch
+        // hash the keys and values. for inserted keys, remember the preimages,
+        // which ICS-23 proofs need (see `ics23_prove`). those of deleted keys
+        // are still needed for proofs at older versions, so they're marked as
+        // stale, to be deleted when the versions are pruned.
+        let mut batch = batch
             .iter()
-            .map(|(k, op)| (hash(k), op.as_ref().map(hash)))
-            .collect();
+            .map(|(k, op)| {
+                let key_hash = hash(k);
+                match op {
+                    Op::Insert(_) => {
+                        self.preimages.save(storage, &key_hash, k)?;
+                        self.stale_preimages.remove(storage, &key_hash);
+                    },
+                    Op::Delete => {
+                        if self.preimages.has(storage, &key_hash)
+                            && !self.stale_preimages.has(storage, &key_hash)
+                        {
+                            self.stale_preimages
+                                .save(storage, &key_hash, &new_version)?;
+                        }
+                    },
+                }
+                Ok((key_hash, op.as_ref().map(hash)))
+            })
+            .collect::<StdResult<Vec<_>>>()?;
 
         // sort by key hashes ascendingly
         batch.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
@@ -482,11 +529,204 @@ impl<'a> MerkleTree<'a> {
         }
     }
 
-    /// Delete nodes that are no longer part of the tree as of `up_to_version`.
-    /// If no `up_to_version` is provided then delete all orphans.
-    pub fn prune(&self, _store: &mut dyn Storage, _up_to_version: Option<u64>) -> StdResult<()> {
-        // we should first implement a `range_remove` method on Storage trait
-        todo!()
+    /// Generate an ICS-23 commitment proof of the given key at the given
+    /// version, for IBC relayers and counterparty chains.
+    ///
+    /// Unlike our own proofs, ICS-23 proofs contain the raw keys and values,
+    /// and non-existence proofs consist of the key's two neighbors. The tree
+    /// only has the hashes, so the caller needs to provide the raw KV store at
+    /// the same version, from which the values are read. The neighbors' keys
+    /// are found from the preimages the tree saves in `apply_raw`.
+    ///
+    /// Preimages are only saved for keys inserted through `apply_raw`. For a
+    /// tree built before preimages were introduced, run `backfill_preimages`
+    /// once, otherwise non-existence proofs whose neighbors were inserted
+    /// earlier fail with a "data not found" error.
+    pub fn ics23_prove(
+        &self,
+        storage: &dyn Storage,
+        state_storage: &dyn Storage,
+        key: &[u8],
+        version: u64,
+    ) -> StdResult<CommitmentProof> {
+        let key_hash = hash(key);
+
+        let proof = match self.prove(storage, &key_hash, version)? {
+            Proof::Membership(proof) => {
+                let value = state_storage
+                    .read(key)
+                    .ok_or_else(|| StdError::data_not_found::<Vec<u8>>(key))?;
+                commitment_proof::Proof::Exist(ics23_existence_proof(key, &value, &proof))
+            },
+            Proof::NonMembership(_) => {
+                let root = self.nodes.load(storage, (version, ROOT_BITS))?;
+                let neighbor = |order| -> StdResult<_> {
+                    let Some(leaf) =
+                        self.find_neighbor(storage, ROOT_BITS, root.clone(), &key_hash, order)?
+                    else {
+                        return Ok(None);
+                    };
+
+                    let key = self.preimages.load(storage, &leaf.key_hash)?;
+                    let value = state_storage
+                        .read(&key)
+                        .ok_or_else(|| StdError::data_not_found::<Vec<u8>>(&key))?;
+                    let Proof::Membership(proof) = self.prove(storage, &leaf.key_hash, version)?
+                    else {
+                        return Err(StdError::generic_err(format!(
+                            "neighbor leaf {} found but its membership can't be proven",
+                            leaf.key_hash
+                        )));
+                    };
+
+                    Ok(Some(ics23_existence_proof(&key, &value, &proof)))
+                };
+
+                commitment_proof::Proof::Nonexist(NonExistenceProof {
+                    key: key.to_vec(),
+                    left: neighbor(Order::Descending)?,
+                    right: neighbor(Order::Ascending)?,
+                })
+            },
+        };
+
+        Ok(CommitmentProof { proof: Some(proof) })
+    }
+
+    /// Find the leaf in the subtree whose key hash is closest to the given one
+    /// on one side of it: the greatest smaller one if the order is descending,
+    /// the smallest greater one if ascending.
+    fn find_neighbor(
+        &self,
+        storage: &dyn Storage,
+        bits: &BitArray,
+        node: Node,
+        key_hash: &Hash,
+        order: Order,
+    ) -> StdResult<Option<LeafNode>> {
+        let (left_child, right_child) = match node {
+            Node::Leaf(leaf) => {
+                let is_neighbor = match order {
+                    Order::Ascending => leaf.key_hash > *key_hash,
+                    Order::Descending => leaf.key_hash < *key_hash,
+                };
+                return Ok(is_neighbor.then_some(leaf));
+            },
+            Node::Internal(InternalNode {
+                left_child,
+                right_child,
+            }) => (left_child, right_child),
+        };
+
+        // look for the neighbor in the same branch as the key first. if not
+        // found, it's the nearest leaf in the sibling branch, if the sibling is
+        // on the neighbor's side of the key.
+        let bit = bit_at_index(key_hash, bits.num_bits);
+        let (child, sibling) = match (bit, order) {
+            (0, Order::Ascending) => (left_child, right_child.map(|child| (1, child))),
+            (0, Order::Descending) => (left_child, None),
+            (1, Order::Ascending) => (right_child, None),
+            (1, Order::Descending) => (right_child, left_child.map(|child| (0, child))),
+            (bit, _) => unreachable!("unexpected bit: {bit}"),
+        };
+
+        if let Some(child) = child {
+            let child_bits = bits.extend_one_bit(bit == 0);
+            let node = self.nodes.load(storage, (child.version, &child_bits))?;
+            if let Some(leaf) = self.find_neighbor(storage, &child_bits, node, key_hash, order)? {
+                return Ok(Some(leaf));
+            }
+        }
+
+        let Some((sibling_bit, sibling)) = sibling else {
+            return Ok(None);
+        };
+
+        // the nearest leaf in the sibling branch: the left-most one if the
+        // sibling is to the right of the key, the right-most one otherwise.
+        let mut bits = bits.extend_one_bit(sibling_bit == 0);
+        let mut node = self.nodes.load(storage, (sibling.version, &bits))?;
+        loop {
+            let (left_child, right_child) = match node {
+                Node::Leaf(leaf) => return Ok(Some(leaf)),
+                Node::Internal(InternalNode {
+                    left_child,
+                    right_child,
+                }) => (left_child, right_child),
+            };
+
+            let (bit, child) = match (order, left_child, right_child) {
+                (Order::Ascending, Some(child), _) | (Order::Descending, Some(child), None) => {
+                    (0, child)
+                },
+                (Order::Descending, _, Some(child)) | (Order::Ascending, None, Some(child)) => {
+                    (1, child)
+                },
+                (_, None, None) => unreachable!("internal node without children"),
+            };
+
+            bits.push(bit);
+            node = self.nodes.load(storage, (child.version, &bits))?;
+        }
+    }
+
+    /// Save the preimages of all keys in the raw KV store, i.e. the state the
+    /// tree commits to at its latest version.
+    ///
+    /// `apply_raw` only saves the preimages of keys it inserts, so this needs
+    /// to be run once for a tree that was built before it started doing so.
+    pub fn backfill_preimages(
+        &self,
+        storage: &mut dyn Storage,
+        state_storage: &dyn Storage,
+    ) -> StdResult<()> {
+        for key in state_storage.scan_keys(None, None, Order::Ascending) {
+            self.preimages.save(storage, &hash(&key), &key)?;
+        }
+
+        Ok(())
+    }
+
+    /// Delete nodes that are no longer part of the tree as of `up_to_version`,
+    /// as well as the preimages of keys deleted as of it. If no `up_to_version`
+    /// is provided then delete all orphans and stale preimages.
+    pub fn prune(&self, storage: &mut dyn Storage, up_to_version: Option<u64>) -> StdResult<()> {
+        let is_prunable = |version: u64| up_to_version.map_or(true, |up_to| version <= up_to);
+
+        // orphans are sorted by the versions since which they're orphaned, so
+        // stop at the first one that's orphaned after `up_to_version`.
+        let orphans = self
+            .orphans
+            .range(storage, None, None, Order::Ascending)
+            .take_while(|res| {
+                res.as_ref().map_or(true, |(orphaned_since_version, ..)| {
+                    is_prunable(*orphaned_since_version)
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for (orphaned_since_version, version, bits) in orphans {
+            self.nodes.remove(storage, (version, &bits));
+            self.orphans
+                .remove(storage, (orphaned_since_version, version, &bits));
+        }
+
+        let stale_preimages = self
+            .stale_preimages
+            .range(storage, None, None, Order::Ascending)
+            .filter(|res| {
+                res.as_ref().map_or(true, |(_, deleted_since_version)| {
+                    is_prunable(*deleted_since_version)
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for (key_hash, _) in stale_preimages {
+            self.preimages.remove(storage, &key_hash);
+            self.stale_preimages.remove(storage, &key_hash);
+        }
+
+        Ok(())
     }
 
     #[inline]
@@ -841,6 +1081,72 @@ mod tests {
         }
     }
 
+    #[test]
+    fn pruning() {
+        let (mut storage, _) = build_test_case().unwrap();
+
+        // delete a key, and delete then re-insert another
+        let batch = Batch::from([(b"m".to_vec(), Op::Delete), (b"a".to_vec(), Op::Delete)]);
+        TREE.apply_raw(&mut storage, 1, 2, &batch).unwrap();
+        let batch = Batch::from([(b"a".to_vec(), Op::Insert(b"buzz".to_vec()))]);
+        let root_hash = TREE.apply_raw(&mut storage, 2, 3, &batch).unwrap();
+
+        // the deleted key's preimage is kept for proofs at version 1
+        assert!(TREE.preimages.has(&storage, &hash("m")));
+        assert_eq!(TREE.stale_preimages.load(&storage, &hash("m")).unwrap(), 2);
+        assert!(!TREE.stale_preimages.has(&storage, &hash("a")));
+
+        // pruning up to version 1 removes nothing, as version 1 is still whole
+        TREE.prune(&mut storage, Some(1)).unwrap();
+        assert!(TREE.root_hash(&storage, 1).unwrap().is_some());
+        assert!(TREE.preimages.has(&storage, &hash("m")));
+
+        // pruning up to version 2 removes version 1, and with it the deleted
+        // key's preimage, but not the re-inserted one's
+        TREE.prune(&mut storage, Some(2)).unwrap();
+        assert!(TREE.root_hash(&storage, 1).unwrap().is_none());
+        assert!(!TREE.preimages.has(&storage, &hash("m")));
+        assert!(!TREE.stale_preimages.has(&storage, &hash("m")));
+        assert!(TREE.preimages.has(&storage, &hash("a")));
+        for item in TREE.orphans.range(&storage, None, None, Order::Ascending) {
+            let (orphaned_since_version, ..) = item.unwrap();
+            assert_eq!(orphaned_since_version, 3);
+        }
+
+        // the latest version is intact
+        assert_eq!(TREE.root_hash(&storage, 3).unwrap(), root_hash);
+        for key in ["r", "L", "a"] {
+            assert!(matches!(
+                TREE.prove(&storage, &hash(key), 3).unwrap(),
+                Proof::Membership(_)
+            ));
+        }
+
+        // pruning everything leaves only the latest version
+        TREE.prune(&mut storage, None).unwrap();
+        assert!(TREE.orphans.is_empty(&storage));
+        assert!(TREE.root_hash(&storage, 2).unwrap().is_none());
+        assert_eq!(TREE.root_hash(&storage, 3).unwrap(), root_hash);
+    }
+
+    #[test]
+    fn backfilling_preimages() {
+        let (mut storage, _) = build_test_case().unwrap();
+        let mut state_storage = MockStorage::new();
+        for (key, value) in [("r", "foo"), ("m", "bar"), ("L", "fuzz"), ("a", "buzz")] {
+            state_storage.write(key.as_bytes(), value.as_bytes());
+        }
+
+        // a tree built before preimages were saved can't prove non-membership,
+        // as the neighbors' keys are unknown
+        TREE.preimages.clear(&mut storage, None, None);
+        assert!(TREE.ics23_prove(&storage, &state_storage, b"b", 1).is_err());
+
+        TREE.backfill_preimages(&mut storage, &state_storage)
+            .unwrap();
+        assert!(TREE.ics23_prove(&storage, &state_storage, b"b", 1).is_ok());
+    }
+
     #[test_case(
         "r",
         Proof::Membership(MembershipProof {
@@ -86,6 +86,38 @@ pub fn grug_derive(attr: TokenStream, input: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Implement `TypedEvent` for a struct, so that it can be emitted by contracts
+/// with `Response::add_event`, and decoded by clients with `Event::decode`.
+///
+/// The event type is the struct's name in snake case, e.g. `PoolCreated`
+/// becomes `pool_created`. Serialization needs to be derived separately, e.g.
+/// with `#[grug_derive(serde)]`.
+#[proc_macro_derive(TypedEvent)]
+pub fn derive_typed_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let ident = input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut event_type = String::new();
+    for (i, c) in ident.to_string().chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                event_type.push('_');
+            }
+            event_type.extend(c.to_lowercase());
+        } else {
+            event_type.push(c);
+        }
+    }
+
+    quote! {
+        impl #impl_generics ::grug::TypedEvent for #ident #ty_generics #where_clause {
+            const EVENT_TYPE: &'static str = #event_type;
+        }
+    }
+    .into()
+}
+
 #[proc_macro_attribute]
 pub fn grug_export(_attr: TokenStream, mut item: TokenStream) -> TokenStream {
     let cloned = item.clone();
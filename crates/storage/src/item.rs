@@ -1,6 +1,6 @@
 use {
     crate::{Borsh, Codec, Path},
-    grug_types::{StdError, StdResult, Storage},
+    grug_types::{Addr, QuerierWrapper, StdError, StdResult, Storage},
     std::marker::PhantomData,
 };
 
@@ -51,6 +51,17 @@ where
         self.path().load(storage)
     }
 
+    /// Load the item from another contract's storage, via the querier.
+    pub fn may_query(&self, querier: &QuerierWrapper, contract: Addr) -> StdResult<Option<T>> {
+        self.path().may_query(querier, contract)
+    }
+
+    /// Load the item from another contract's storage, via the querier.
+    /// Error if the item doesn't exist.
+    pub fn query(&self, querier: &QuerierWrapper, contract: Addr) -> StdResult<T> {
+        self.path().query(querier, contract)
+    }
+
     pub fn update<A, Err>(&self, storage: &mut dyn Storage, action: A) -> Result<Option<T>, Err>
     where
         A: FnOnce(Option<T>) -> Result<Option<T>, Err>,
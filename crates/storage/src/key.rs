@@ -171,6 +171,20 @@ impl<'a> Key for &'a Addr {
     }
 }
 
+impl Key for Addr {
+    type Output = Addr;
+    type Prefix = ();
+    type Suffix = ();
+
+    fn raw_keys(&self) -> Vec<Cow<[u8]>> {
+        vec![Cow::Borrowed(self.as_ref())]
+    }
+
+    fn deserialize(bytes: &[u8]) -> StdResult<Self::Output> {
+        bytes.try_into()
+    }
+}
+
 impl<'a> Key for &'a Hash {
     type Output = Hash;
     type Prefix = ();
@@ -1,6 +1,6 @@
 use {
     crate::{Borsh, Bound, Codec, Key, PathBuf, Prefix},
-    grug_types::{Order, Record, StdError, StdResult, Storage},
+    grug_types::{Addr, Order, QuerierWrapper, Record, StdError, StdResult, Storage},
     std::{borrow::Cow, marker::PhantomData},
 };
 
@@ -80,6 +80,25 @@ where
         self.path(key).as_path().load(storage)
     }
 
+    /// Load a value from another contract's storage, via the querier.
+    ///
+    /// The other contract must store its data in a map of the same namespace,
+    /// key type, and codec.
+    pub fn may_query(
+        &self,
+        querier: &QuerierWrapper,
+        contract: Addr,
+        key: K,
+    ) -> StdResult<Option<T>> {
+        self.path(key).as_path().may_query(querier, contract)
+    }
+
+    /// Load a value from another contract's storage, via the querier.
+    /// Error if the value doesn't exist.
+    pub fn query(&self, querier: &QuerierWrapper, contract: Addr, key: K) -> StdResult<T> {
+        self.path(key).as_path().query(querier, contract)
+    }
+
     pub fn save_raw(&self, storage: &mut dyn Storage, key_raw: &[u8], data_raw: &[u8]) {
         self.path_raw(key_raw).as_path().save_raw(storage, data_raw)
     }
@@ -129,6 +148,20 @@ where
         self.no_prefix().range(storage, min, max, order)
     }
 
+    /// Enumerate key-value pairs in another contract's storage in ascending
+    /// order, via the querier. Returns at most `limit` items.
+    pub fn query_range(
+        &self,
+        querier: &QuerierWrapper,
+        contract: Addr,
+        min: Option<Bound<K>>,
+        max: Option<Bound<K>>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<(K::Output, T)>> {
+        self.no_prefix()
+            .query_range(querier, contract, min, max, limit)
+    }
+
     pub fn keys_raw<'b>(
         &self,
         storage: &'b dyn Storage,
@@ -179,9 +212,12 @@ where
 #[cfg(test)]
 mod test {
     use {
-        crate::Map,
+        crate::{Bound, Map},
         borsh::{BorshDeserialize, BorshSerialize},
-        grug_types::MockStorage,
+        grug_types::{
+            Addr, Binary, MockStorage, Order, Querier, QuerierWrapper, QueryRequest, QueryResponse,
+            StdResult, Storage, WasmRawResponse,
+        },
     };
 
     const FOOS: Map<u64, Foo> = Map::new("foo");
@@ -201,6 +237,39 @@ mod test {
         }
     }
 
+    /// A querier that serves raw queries from a single storage, regardless of
+    /// which contract is being queried.
+    struct StorageQuerier<'a> {
+        storage: &'a MockStorage,
+    }
+
+    impl<'a> Querier for StorageQuerier<'a> {
+        fn query_chain(&self, req: QueryRequest) -> StdResult<QueryResponse> {
+            match req {
+                QueryRequest::WasmRaw { contract, key } => {
+                    let value = self.storage.read(&key).map(Binary::from);
+                    Ok(QueryResponse::WasmRaw(WasmRawResponse {
+                        contract,
+                        key,
+                        value,
+                    }))
+                },
+                QueryRequest::WasmScan {
+                    min, max, limit, ..
+                } => {
+                    let records = self
+                        .storage
+                        .scan(min.as_deref(), max.as_deref(), Order::Ascending)
+                        .take(limit.unwrap_or(u32::MAX) as usize)
+                        .map(|(k, v)| (Binary::from(k), Binary::from(v)))
+                        .collect();
+                    Ok(QueryResponse::WasmScan(records))
+                },
+                _ => unreachable!(),
+            }
+        }
+    }
+
     fn setup_test() -> MockStorage {
         let mut storage = MockStorage::new();
 
@@ -224,4 +293,26 @@ mod test {
         let first = FOOS.load(&storage, 1).unwrap();
         assert_eq!(first, Foo::new("name_1", "surname_1"));
     }
+
+    #[test]
+    fn map_query_works() {
+        let storage = setup_test();
+        let querier = StorageQuerier { storage: &storage };
+        let querier = QuerierWrapper::new(&querier);
+        let contract = Addr::mock(1);
+
+        let second = FOOS.query(&querier, contract.clone(), 2).unwrap();
+        assert_eq!(second, Foo::new("name_2", "surname_2"));
+
+        let fifth = FOOS.may_query(&querier, contract.clone(), 5).unwrap();
+        assert_eq!(fifth, None);
+
+        let items = FOOS
+            .query_range(&querier, contract, Some(Bound::Exclusive(1)), None, Some(2))
+            .unwrap();
+        assert_eq!(items, vec![
+            (2, Foo::new("name_2", "surname_2")),
+            (3, Foo::new("name_3", "surname_3")),
+        ]);
+    }
 }
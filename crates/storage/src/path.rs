@@ -1,6 +1,8 @@
 use {
     crate::{Borsh, Codec},
-    grug_types::{nested_namespaces_with_key, StdError, StdResult, Storage},
+    grug_types::{
+        nested_namespaces_with_key, Addr, Binary, QuerierWrapper, StdError, StdResult, Storage,
+    },
     std::{borrow::Cow, marker::PhantomData},
 };
 
@@ -77,6 +79,18 @@ where
             .and_then(|val| C::decode(&val))
     }
 
+    pub fn may_query(&self, querier: &QuerierWrapper, contract: Addr) -> StdResult<Option<T>> {
+        querier
+            .query_wasm_raw(contract, Binary::from(self.storage_key.to_vec()))?
+            .map(|val| C::decode(&val))
+            .transpose()
+    }
+
+    pub fn query(&self, querier: &QuerierWrapper, contract: Addr) -> StdResult<T> {
+        self.may_query(querier, contract)?
+            .ok_or_else(|| StdError::data_not_found::<T>(self.storage_key))
+    }
+
     pub fn save_raw(&self, storage: &mut dyn Storage, data_raw: &[u8]) {
         storage.write(self.storage_key, data_raw)
     }
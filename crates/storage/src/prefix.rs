@@ -2,7 +2,7 @@ use {
     crate::{Borsh, Bound, Codec, Key, RawBound},
     grug_types::{
         concat, encode_length, extend_one_byte, increment_last_byte, nested_namespaces_with_key,
-        trim, Order, Record, StdResult, Storage,
+        trim, Addr, Binary, Order, QuerierWrapper, Record, StdResult, Storage,
     },
     std::{borrow::Cow, marker::PhantomData},
 };
@@ -69,7 +69,7 @@ where
         let iter = storage
             .scan(Some(&min), Some(&max), order)
             .map(move |(k, v)| {
-                debug_assert_eq!(&k[0..prefix.len()], prefix, "prefix mispatch");
+                debug_assert_eq!(&k[0..prefix.len()], prefix, "prefix mismatch");
                 (trim(&prefix, &k), v)
             });
 
@@ -94,6 +94,37 @@ where
         Box::new(iter)
     }
 
+    pub fn query_range(
+        &self,
+        querier: &QuerierWrapper,
+        contract: Addr,
+        min: Option<Bound<K>>,
+        max: Option<Bound<K>>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<(K::Output, T)>> {
+        let (min, max) = range_bounds(&self.prefix, min, max);
+
+        querier
+            .query_wasm_scan(
+                contract,
+                Some(Binary::from(min)),
+                Some(Binary::from(max)),
+                limit,
+            )?
+            .into_iter()
+            .map(|(key_raw, value_raw)| {
+                debug_assert_eq!(
+                    &key_raw[0..self.prefix.len()],
+                    self.prefix,
+                    "prefix mismatch"
+                );
+                let key = K::deserialize(&trim(&self.prefix, &key_raw))?;
+                let value = C::decode(&value_raw)?;
+                Ok((key, value))
+            })
+            .collect()
+    }
+
     pub fn keys_raw<'a>(
         &self,
         storage: &'a dyn Storage,
@@ -106,7 +137,7 @@ where
         let iter = storage
             .scan_keys(Some(&min), Some(&max), order)
             .map(move |k| {
-                debug_assert_eq!(&k[0..prefix.len()], prefix, "prefix mispatch");
+                debug_assert_eq!(&k[0..prefix.len()], prefix, "prefix mismatch");
                 trim(&prefix, &k)
             });
 
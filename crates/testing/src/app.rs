@@ -0,0 +1,168 @@
+use {
+    grug_app::{App, AppResult, Db, TxOutcome},
+    grug_db_memory::MemDb,
+    grug_jmt::Proof,
+    grug_types::{
+        hash, to_json_vec, Addr, BlockInfo, GenesisState, Hash, Message, QueryRequest,
+        QueryResponse, Timestamp, Trace, Tx, TxResponse, Uint64,
+    },
+    grug_vm_rust::RustVm,
+    std::time::{SystemTime, UNIX_EPOCH},
+};
+
+fn current_time() -> Timestamp {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("failed to get current system time")
+        .as_nanos()
+        .try_into()
+        .expect("current system time overflows u64");
+    Timestamp::from_nanos(nanos)
+}
+
+pub struct MockApp {
+    inner: App<MemDb, RustVm>,
+    db: MemDb,
+    vm: RustVm,
+    genesis_time: Option<Timestamp>,
+}
+
+// need to implement this to make clippy not complain
+// TODO: create a clippy.toml to disable this
+impl Default for MockApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockApp {
+    pub fn new() -> Self {
+        let db = MemDb::new();
+        let vm = RustVm::new();
+        Self {
+            inner: App::new(db.clone(), vm.clone()),
+            db,
+            vm,
+            genesis_time: None,
+        }
+    }
+
+    /// Record the state changes made by each transaction, returned in the
+    /// outcomes of [`MockApp::make_block`].
+    pub fn with_state_diffs(self, enabled: bool) -> Self {
+        Self {
+            inner: self.inner.with_state_diffs(enabled),
+            ..self
+        }
+    }
+
+    /// Start the chain at the given time instead of the current time, so that
+    /// the app hashes of the blocks only depend on their transactions.
+    pub fn with_genesis_time(self, genesis_time: Timestamp) -> Self {
+        Self {
+            genesis_time: Some(genesis_time),
+            ..self
+        }
+    }
+
+    /// Return the VM used by the app, with which contracts need to be
+    /// registered before they can be uploaded.
+    pub fn vm(&self) -> &RustVm {
+        &self.vm
+    }
+
+    pub fn init_chain(&mut self, chain_id: impl ToString, genesis_state: GenesisState) {
+        let block = BlockInfo {
+            height: Uint64::new(0), // genesis height is always zero
+            timestamp: self.genesis_time.unwrap_or_else(current_time),
+            hash: Hash::ZERO,
+        };
+        self.inner
+            .do_init_chain(chain_id.to_string(), block, genesis_state)
+            .unwrap();
+    }
+
+    pub fn query(&self, req: QueryRequest) -> QueryResponse {
+        self.inner.do_query_app(req, 0, false).unwrap()
+    }
+
+    /// Return the app hash, i.e. the root hash of the state tree, as of the
+    /// latest block.
+    pub fn app_hash(&self) -> Hash {
+        self.inner.do_info().unwrap().1
+    }
+
+    /// Perform a raw query of the app's key-value store at the given height
+    /// (zero for the latest), optionally with an ICS-23 proof.
+    pub fn query_store(
+        &self,
+        key: &[u8],
+        height: u64,
+        prove: bool,
+    ) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+        self.inner.do_query_store(key, height, prove).unwrap()
+    }
+
+    /// Return a `grug_jmt` proof of the key's presence or absence in the app's
+    /// state at the given height (zero for the latest), as verified by the grug
+    /// light client.
+    pub fn prove(&self, key: &[u8], height: u64) -> Proof {
+        let version = if height == 0 {
+            None
+        } else {
+            Some(height)
+        };
+        self.db.prove(key, version).unwrap()
+    }
+
+    /// Finalize and commit a block containing the given transactions, one
+    /// second after the last block. Return the block, the app hash, and the
+    /// outcomes of the transactions.
+    pub fn make_block(&mut self, txs: Vec<Tx>) -> (BlockInfo, Hash, Vec<TxOutcome>) {
+        let last_block = self
+            .query(QueryRequest::Info {})
+            .as_info()
+            .last_finalized_block;
+        let block = BlockInfo {
+            height: Uint64::new(last_block.height.number() + 1),
+            timestamp: last_block.timestamp.plus_seconds(1),
+            hash: Hash::ZERO,
+        };
+        let txs = txs
+            .into_iter()
+            .map(|tx| (hash(to_json_vec(&tx).unwrap()), tx))
+            .collect();
+
+        let (app_hash, _, outcomes) = self.inner.do_finalize_block(block.clone(), txs).unwrap();
+        self.inner.do_commit().unwrap();
+
+        (block, app_hash, outcomes)
+    }
+
+    /// Execute a transaction consisting of the given messages in a new block,
+    /// and return its result.
+    ///
+    /// The transaction has no credential, so the sender account must accept it
+    /// without one.
+    pub fn execute(&mut self, sender: &Addr, msgs: Vec<Message>) -> AppResult<TxResponse> {
+        let tx = Tx {
+            sender: sender.clone(),
+            msgs,
+            credential: Vec::new().into(),
+        };
+        let (_, _, outcomes) = self.make_block(vec![tx]);
+        outcomes.into_iter().next().unwrap().result
+    }
+
+    /// Simulate a transaction consisting of the given messages against the
+    /// latest state, without committing any change. Return its result and the
+    /// trace of the contract calls it made.
+    pub fn simulate(&self, sender: &Addr, msgs: Vec<Message>) -> (AppResult<TxResponse>, Trace) {
+        let tx = Tx {
+            sender: sender.clone(),
+            msgs,
+            credential: Vec::new().into(),
+        };
+        self.inner.do_simulate(tx).unwrap()
+    }
+}
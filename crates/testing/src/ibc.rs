@@ -0,0 +1,646 @@
+use {
+    crate::{MockApp, MockHeader, MockLightClient},
+    grug_app::{
+        channel_path, connection_path, next_sequence_recv_path, packet_ack_path,
+        packet_commitment_path, packet_receipt_path, AppResult, CHANNEL_ID_KEY, CLIENT_ID_KEY,
+        CONNECTION_ID_KEY, IBC_NAMESPACE, PACKET_ACK_HEX_KEY, PACKET_CHANNEL_ORDERING_KEY,
+        PACKET_DATA_HEX_KEY, PACKET_DST_CHANNEL_KEY, PACKET_DST_PORT_KEY, PACKET_SEQUENCE_KEY,
+        PACKET_SRC_CHANNEL_KEY, PACKET_SRC_PORT_KEY, PACKET_TIMEOUT_HEIGHT_KEY,
+        PACKET_TIMEOUT_TIMESTAMP_KEY,
+    },
+    grug_types::{
+        hash, Addr, Binary, Event, GenesisState, IbcChannelCounterparty, IbcChannelOrder,
+        IbcConnectionCounterparty, IbcConnectionVersion, IbcPacket, IbcTimeout, Json, Message,
+        QueryRequest, StdResult, Timestamp, TxResponse,
+    },
+    grug_vm_rust::ContractWrapper,
+    std::mem,
+};
+
+/// Version under which light clients are registered with the chains' VMs.
+const LIGHT_CLIENT_VERSION: &str = "0.0.0";
+
+/// A light client with which the chains of a [`MockIbc`] track each other.
+///
+/// The harness describes the blocks of the chains as [`MockHeader`]s, which the
+/// light client turns into the messages that its contract expects.
+pub trait LightClient {
+    /// The name under which the client's contract is registered with the
+    /// chains' VMs.
+    fn name(&self) -> &'static str;
+
+    fn contract(&self) -> ContractWrapper;
+
+    /// Return the client state and consensus state to create a client with,
+    /// from the header of the tracked chain's latest block.
+    fn initial_states(&self, header: &MockHeader) -> StdResult<(Json, Json)>;
+
+    /// Return the header to update a client with, given the height of the
+    /// client's latest consensus state, which it is to be verified against.
+    fn update_header(&self, header: &MockHeader, trusted_height: u64) -> StdResult<Json>;
+
+    /// Return a proof of the key's presence or absence in the tracked chain's
+    /// state at the given height.
+    fn prove(&self, chain: &MockApp, key: &[u8], height: u64) -> StdResult<Json>;
+}
+
+/// One of the two chains of a [`MockIbc`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    A,
+    B,
+}
+
+impl Side {
+    pub fn counterparty(self) -> Self {
+        match self {
+            Side::A => Side::B,
+            Side::B => Side::A,
+        }
+    }
+}
+
+/// A packet sent by a chain that hasn't been relayed to the counterparty yet.
+struct PendingPacket {
+    packet: IbcPacket,
+    ordering: IbcChannelOrder,
+}
+
+/// An acknowledgement written by a chain that hasn't been relayed back to the
+/// sender of the packet yet.
+struct PendingAck {
+    packet: IbcPacket,
+    acknowledgement: Binary,
+}
+
+struct MockChain {
+    app: MockApp,
+    chain_id: String,
+    relayer: Option<Addr>,
+    client_id: Option<Addr>,
+    /// The latest height of the counterparty that the client knows of.
+    client_height: u64,
+    packets: Vec<PendingPacket>,
+    acks: Vec<PendingAck>,
+}
+
+/// Two mock chains connected by IBC, with an in-process relayer.
+///
+/// Each chain tracks the other with a [`LightClient`], by default the
+/// [mock light client](crate::mock_client), which is uploaded at genesis. The
+/// relayer updates the clients with headers of the latest blocks, and delivers
+/// the packets and acknowledgements emitted by transactions executed through
+/// [`MockIbc::execute`], along with proofs queried from the chains' stores.
+///
+/// Every transaction is executed in a block of its own.
+pub struct MockIbc {
+    chains: [MockChain; 2],
+    light_client: Box<dyn LightClient>,
+}
+
+// need to implement this to make clippy not complain
+impl Default for MockIbc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockIbc {
+    /// Create two chains, which are yet to be started. Contracts need to be
+    /// registered with their VMs before they are started.
+    pub fn new() -> Self {
+        let chain = || MockChain {
+            app: MockApp::new(),
+            chain_id: String::new(),
+            relayer: None,
+            client_id: None,
+            client_height: 0,
+            packets: vec![],
+            acks: vec![],
+        };
+
+        Self {
+            chains: [chain(), chain()],
+            light_client: Box::new(MockLightClient),
+        }
+    }
+
+    /// Have the chains track each other with the given light client instead of
+    /// the mock one. Must be called before the chains are started.
+    pub fn with_light_client(self, light_client: impl LightClient + 'static) -> Self {
+        Self {
+            light_client: Box::new(light_client),
+            ..self
+        }
+    }
+
+    fn inner(&self, side: Side) -> &MockChain {
+        &self.chains[side as usize]
+    }
+
+    fn inner_mut(&mut self, side: Side) -> &mut MockChain {
+        &mut self.chains[side as usize]
+    }
+
+    pub fn chain(&self, side: Side) -> &MockApp {
+        &self.inner(side).app
+    }
+
+    pub fn chain_mut(&mut self, side: Side) -> &mut MockApp {
+        &mut self.inner_mut(side).app
+    }
+
+    /// Return the address of the client on the given chain that tracks the
+    /// counterparty chain.
+    ///
+    /// Panics if the clients haven't been created.
+    pub fn client_id(&self, side: Side) -> &Addr {
+        self.inner(side)
+            .client_id
+            .as_ref()
+            .expect("clients haven't been created")
+    }
+
+    fn relayer(&self, side: Side) -> Addr {
+        self.inner(side)
+            .relayer
+            .clone()
+            .expect("chain hasn't been started")
+    }
+
+    /// Start one of the chains. The light client is registered with the chain's
+    /// VM and uploaded at genesis, and allowed to be used for creating clients.
+    ///
+    /// The relayer account sends all the relayer's transactions, without any
+    /// credential. It must be instantiated at genesis, and must be permitted to
+    /// create clients, connections, and channels.
+    pub fn init_chain(
+        &mut self,
+        side: Side,
+        chain_id: impl ToString,
+        mut genesis_state: GenesisState,
+        relayer: Addr,
+    ) {
+        let code = self
+            .chain(side)
+            .vm()
+            .register(
+                self.light_client.name(),
+                LIGHT_CLIENT_VERSION,
+                self.light_client.contract(),
+            )
+            .unwrap();
+        genesis_state.config.allowed_clients.insert(hash(&code));
+        genesis_state
+            .msgs
+            .insert(0, Message::Upload { code: code.into() });
+
+        let chain = self.inner_mut(side);
+        chain.chain_id = chain_id.to_string();
+        chain.relayer = Some(relayer);
+        chain.app.init_chain(chain_id, genesis_state);
+    }
+
+    /// Create a client on each chain that tracks the other chain, from the
+    /// header of its latest block.
+    ///
+    /// Like CometBFT chains, whose first block is at height 1, the chains make
+    /// a block after genesis if they haven't yet, since light clients such as
+    /// Tendermint's can't be created at height zero.
+    pub fn create_clients(&mut self) -> AppResult<()> {
+        for side in [Side::A, Side::B] {
+            if self.header(side).height == 0 {
+                self.chain_mut(side).make_block(vec![]);
+            }
+        }
+
+        let name = self.light_client.name();
+        let code_hash = hash(format!("{name}@{LIGHT_CLIENT_VERSION}"));
+
+        for side in [Side::A, Side::B] {
+            let header = self.header(side.counterparty());
+            let (client_state, consensus_state) = self.light_client.initial_states(&header)?;
+
+            let res = self.execute(side, &self.relayer(side), vec![Message::CreateClient {
+                code_hash: code_hash.clone(),
+                client_state,
+                consensus_state,
+                salt: name.as_bytes().to_vec().into(),
+            }])?;
+            let client_id = find_attribute(&res.events, "create_client", CLIENT_ID_KEY);
+
+            let chain = self.inner_mut(side);
+            chain.client_id = Some(client_id.parse()?);
+            chain.client_height = header.height;
+        }
+
+        Ok(())
+    }
+
+    /// Return the header of the given chain's latest block.
+    pub fn header(&self, side: Side) -> MockHeader {
+        let chain = self.inner(side);
+        let block = chain
+            .app
+            .query(QueryRequest::Info {})
+            .as_info()
+            .last_finalized_block;
+
+        MockHeader {
+            chain_id: chain.chain_id.clone(),
+            height: block.height.number(),
+            timestamp: block.timestamp,
+            app_hash: chain.app.app_hash(),
+        }
+    }
+
+    /// Update the client on the given chain with the header of the counterparty
+    /// chain's latest block. Return the height of the header, at which proofs
+    /// of the counterparty's state can then be verified.
+    ///
+    /// Nothing is done if the client already knows of the block, e.g. if the
+    /// counterparty hasn't made any since the last update, since light clients
+    /// such as Tendermint's only accept headers newer than the trusted one.
+    pub fn update_client(&mut self, side: Side) -> AppResult<u64> {
+        let header = self.header(side.counterparty());
+        let height = header.height;
+
+        let trusted_height = self.inner(side).client_height;
+        if height == trusted_height {
+            return Ok(height);
+        }
+
+        let header = self.light_client.update_header(&header, trusted_height)?;
+        self.execute(side, &self.relayer(side), vec![Message::UpdateClient {
+            client_id: self.client_id(side).clone(),
+            header,
+        }])?;
+        self.inner_mut(side).client_height = height;
+
+        Ok(height)
+    }
+
+    /// Execute a transaction on the given chain. The packets it sends and the
+    /// acknowledgements it writes are queued to be relayed.
+    pub fn execute(
+        &mut self,
+        side: Side,
+        sender: &Addr,
+        msgs: Vec<Message>,
+    ) -> AppResult<TxResponse> {
+        let chain = self.inner_mut(side);
+        let res = chain.app.execute(sender, msgs)?;
+
+        for event in &res.events {
+            match event.r#type.as_str() {
+                "send_packet" => chain.packets.push(PendingPacket {
+                    packet: packet_from_event(event),
+                    ordering: match attribute(event, PACKET_CHANNEL_ORDERING_KEY) {
+                        "ORDER_ORDERED" => IbcChannelOrder::Ordered,
+                        _ => IbcChannelOrder::Unordered,
+                    },
+                }),
+                "write_acknowledgement" => chain.acks.push(PendingAck {
+                    packet: packet_from_event(event),
+                    acknowledgement: hex_decode(attribute(event, PACKET_ACK_HEX_KEY)).into(),
+                }),
+                _ => {},
+            }
+        }
+
+        Ok(res)
+    }
+
+    /// Return the proof of the given chain's IBC state at the given path and
+    /// height, in the format the light client expects.
+    fn prove(&self, side: Side, path: &str, height: u64) -> AppResult<Json> {
+        let key = [IBC_NAMESPACE, path.as_bytes()].concat();
+
+        Ok(self.light_client.prove(self.chain(side), &key, height)?)
+    }
+
+    /// Open a connection between the two chains, initiated by chain A. Return
+    /// the IDs of the connection on chain A and B respectively.
+    pub fn open_connection(&mut self) -> AppResult<(String, String)> {
+        let counterparty =
+            |client_id: &Addr, connection_id: Option<&str>| IbcConnectionCounterparty {
+                client_id: client_id.to_string(),
+                connection_id: connection_id.map(Into::into),
+                prefix: IBC_NAMESPACE.to_vec().into(),
+            };
+
+        let res = self.execute(Side::A, &self.relayer(Side::A), vec![
+            Message::ConnOpenInit {
+                client_id: self.client_id(Side::A).clone(),
+                counterparty: counterparty(self.client_id(Side::B), None),
+                delay_period: 0,
+            },
+        ])?;
+        let connection_a = find_attribute(&res.events, "connection_open_init", CONNECTION_ID_KEY);
+
+        let proof_height = self.update_client(Side::B)?;
+        let res = self.execute(Side::B, &self.relayer(Side::B), vec![
+            Message::ConnOpenTry {
+                client_id: self.client_id(Side::B).clone(),
+                counterparty: counterparty(self.client_id(Side::A), Some(&connection_a)),
+                counterparty_versions: vec![IbcConnectionVersion::default()],
+                delay_period: 0,
+                proof_height,
+                proof_init: self.prove(Side::A, &connection_path(&connection_a), proof_height)?,
+            },
+        ])?;
+        let connection_b = find_attribute(&res.events, "connection_open_try", CONNECTION_ID_KEY);
+
+        let proof_height = self.update_client(Side::A)?;
+        self.execute(Side::A, &self.relayer(Side::A), vec![
+            Message::ConnOpenAck {
+                connection_id: connection_a.clone(),
+                counterparty_connection_id: connection_b.clone(),
+                version: IbcConnectionVersion::default(),
+                proof_height,
+                proof_try: self.prove(Side::B, &connection_path(&connection_b), proof_height)?,
+            },
+        ])?;
+
+        let proof_height = self.update_client(Side::B)?;
+        self.execute(Side::B, &self.relayer(Side::B), vec![
+            Message::ConnOpenConfirm {
+                connection_id: connection_b.clone(),
+                proof_height,
+                proof_ack: self.prove(Side::A, &connection_path(&connection_a), proof_height)?,
+            },
+        ])?;
+
+        Ok((connection_a, connection_b))
+    }
+
+    /// Open a channel between the given ports on the two chains, over the given
+    /// connection, initiated by chain A. Return the IDs of the channel on chain
+    /// A and B respectively.
+    pub fn open_channel(
+        &mut self,
+        (connection_a, connection_b): (&str, &str),
+        (port_a, port_b): (&Addr, &Addr),
+        ordering: IbcChannelOrder,
+        version: &str,
+    ) -> AppResult<(String, String)> {
+        let res = self.execute(Side::A, &self.relayer(Side::A), vec![
+            Message::ChanOpenInit {
+                port_id: port_a.clone(),
+                connection_id: connection_a.into(),
+                counterparty_port_id: port_b.to_string(),
+                ordering,
+                version: version.into(),
+            },
+        ])?;
+        let channel_a = find_attribute(&res.events, "channel_open_init", CHANNEL_ID_KEY);
+
+        let proof_height = self.update_client(Side::B)?;
+        let res = self.execute(Side::B, &self.relayer(Side::B), vec![
+            Message::ChanOpenTry {
+                port_id: port_b.clone(),
+                connection_id: connection_b.into(),
+                counterparty: IbcChannelCounterparty {
+                    port_id: port_a.to_string(),
+                    channel_id: Some(channel_a.clone()),
+                },
+                ordering,
+                version: version.into(),
+                counterparty_version: version.into(),
+                proof_height,
+                proof_init: self.prove(
+                    Side::A,
+                    &channel_path(&port_a.to_string(), &channel_a),
+                    proof_height,
+                )?,
+            },
+        ])?;
+        let channel_b = find_attribute(&res.events, "channel_open_try", CHANNEL_ID_KEY);
+
+        let proof_height = self.update_client(Side::A)?;
+        self.execute(Side::A, &self.relayer(Side::A), vec![
+            Message::ChanOpenAck {
+                port_id: port_a.clone(),
+                channel_id: channel_a.clone(),
+                counterparty_channel_id: channel_b.clone(),
+                counterparty_version: version.into(),
+                proof_height,
+                proof_try: self.prove(
+                    Side::B,
+                    &channel_path(&port_b.to_string(), &channel_b),
+                    proof_height,
+                )?,
+            },
+        ])?;
+
+        let proof_height = self.update_client(Side::B)?;
+        self.execute(Side::B, &self.relayer(Side::B), vec![
+            Message::ChanOpenConfirm {
+                port_id: port_b.clone(),
+                channel_id: channel_b.clone(),
+                proof_height,
+                proof_ack: self.prove(
+                    Side::A,
+                    &channel_path(&port_a.to_string(), &channel_a),
+                    proof_height,
+                )?,
+            },
+        ])?;
+
+        Ok((channel_a, channel_b))
+    }
+
+    /// Relay all pending packets and acknowledgements in both directions, until
+    /// there are none left.
+    ///
+    /// Packets that can no longer be received, because the destination chain
+    /// has reached their timeout, are timed out on the sending chain instead.
+    pub fn relay(&mut self) -> AppResult<()> {
+        while self.relay_from(Side::A)? | self.relay_from(Side::B)? {}
+
+        Ok(())
+    }
+
+    /// Relay the packets sent and the acknowledgements written by the given
+    /// chain. Return whether there was anything to relay.
+    fn relay_from(&mut self, side: Side) -> AppResult<bool> {
+        let packets = mem::take(&mut self.inner_mut(side).packets);
+        let acks = mem::take(&mut self.inner_mut(side).acks);
+
+        if packets.is_empty() && acks.is_empty() {
+            return Ok(false);
+        }
+
+        for PendingPacket { packet, ordering } in packets {
+            if self.has_timed_out(side.counterparty(), &packet.timeout) {
+                self.timeout_packet(side, packet, ordering)?;
+            } else {
+                self.receive_packet(side, packet)?;
+            }
+        }
+
+        for ack in acks {
+            self.acknowledge_packet(side, ack)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Return whether a packet with the given timeout would be rejected if the
+    /// given chain were to receive it in its next block.
+    fn has_timed_out(&self, side: Side, timeout: &IbcTimeout) -> bool {
+        let header = self.header(side);
+        let next_height = header.height + 1;
+        let next_timestamp = header.timestamp.plus_seconds(1);
+
+        timeout.height.is_some_and(|height| next_height >= height)
+            || timeout
+                .timestamp
+                .is_some_and(|timestamp| next_timestamp >= timestamp)
+    }
+
+    /// Deliver a packet sent by the given chain to the counterparty.
+    fn receive_packet(&mut self, side: Side, packet: IbcPacket) -> AppResult<()> {
+        let destination = side.counterparty();
+        let proof_height = self.update_client(destination)?;
+        let proof_commitment = self.prove(
+            side,
+            &packet_commitment_path(&packet.source_port, &packet.source_channel, packet.sequence),
+            proof_height,
+        )?;
+
+        self.execute(destination, &self.relayer(destination), vec![
+            Message::RecvPacket {
+                packet,
+                proof_height,
+                proof_commitment,
+            },
+        ])?;
+
+        Ok(())
+    }
+
+    /// Deliver an acknowledgement written by the given chain back to the chain
+    /// that sent the packet.
+    fn acknowledge_packet(&mut self, side: Side, ack: PendingAck) -> AppResult<()> {
+        let source = side.counterparty();
+        let proof_height = self.update_client(source)?;
+        let proof_acked = self.prove(
+            side,
+            &packet_ack_path(
+                &ack.packet.destination_port,
+                &ack.packet.destination_channel,
+                ack.packet.sequence,
+            ),
+            proof_height,
+        )?;
+
+        self.execute(source, &self.relayer(source), vec![
+            Message::Acknowledgement {
+                packet: ack.packet,
+                acknowledgement: ack.acknowledgement,
+                proof_height,
+                proof_acked,
+            },
+        ])?;
+
+        Ok(())
+    }
+
+    /// Time out a packet sent by the given chain, proving that the counterparty
+    /// hasn't received it as of a height at which it has timed out.
+    fn timeout_packet(
+        &mut self,
+        side: Side,
+        packet: IbcPacket,
+        ordering: IbcChannelOrder,
+    ) -> AppResult<()> {
+        // The timeout has only been reached as of the counterparty's next
+        // block, so make the block first.
+        let destination = side.counterparty();
+        self.chain_mut(destination).make_block(vec![]);
+        let proof_height = self.update_client(side)?;
+
+        let (next_sequence_recv, proof_unreceived) = match ordering {
+            IbcChannelOrder::Ordered => {
+                let path =
+                    next_sequence_recv_path(&packet.destination_port, &packet.destination_channel);
+                let (value, _) = self.chain(destination).query_store(
+                    &[IBC_NAMESPACE, path.as_bytes()].concat(),
+                    proof_height,
+                    false,
+                );
+                let next_sequence_recv =
+                    u64::from_be_bytes(value.unwrap().try_into().unwrap_or_default());
+
+                (
+                    next_sequence_recv,
+                    self.prove(destination, &path, proof_height)?,
+                )
+            },
+            IbcChannelOrder::Unordered => {
+                let path = packet_receipt_path(
+                    &packet.destination_port,
+                    &packet.destination_channel,
+                    packet.sequence,
+                );
+
+                (0, self.prove(destination, &path, proof_height)?)
+            },
+        };
+
+        self.execute(side, &self.relayer(side), vec![Message::Timeout {
+            packet,
+            next_sequence_recv,
+            proof_height,
+            proof_unreceived,
+        }])?;
+
+        Ok(())
+    }
+}
+
+fn attribute<'a>(event: &'a Event, key: &str) -> &'a str {
+    event
+        .attributes
+        .iter()
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value.as_str())
+        .unwrap_or_else(|| panic!("event `{}` has no attribute `{key}`", event.r#type))
+}
+
+fn find_attribute(events: &[Event], ty: &str, key: &str) -> String {
+    events
+        .iter()
+        .find(|event| event.r#type == ty)
+        .map(|event| attribute(event, key).to_string())
+        .unwrap_or_else(|| panic!("no `{ty}` event emitted"))
+}
+
+fn packet_from_event(event: &Event) -> IbcPacket {
+    // e.g. `0-123`, where the revision number is always zero on our side, and
+    // a zero height means no timeout height
+    let height = attribute(event, PACKET_TIMEOUT_HEIGHT_KEY)
+        .split_once('-')
+        .and_then(|(_, height)| height.parse().ok())
+        .filter(|height| *height > 0);
+    let timestamp = attribute(event, PACKET_TIMEOUT_TIMESTAMP_KEY)
+        .parse()
+        .ok()
+        .filter(|nanos| *nanos > 0)
+        .map(Timestamp::from_nanos);
+
+    IbcPacket {
+        sequence: attribute(event, PACKET_SEQUENCE_KEY).parse().unwrap(),
+        source_port: attribute(event, PACKET_SRC_PORT_KEY).into(),
+        source_channel: attribute(event, PACKET_SRC_CHANNEL_KEY).into(),
+        destination_port: attribute(event, PACKET_DST_PORT_KEY).into(),
+        destination_channel: attribute(event, PACKET_DST_CHANNEL_KEY).into(),
+        data: hex_decode(attribute(event, PACKET_DATA_HEX_KEY)).into(),
+        timeout: IbcTimeout { height, timestamp },
+    }
+}
+
+fn hex_decode(hex: &str) -> Vec<u8> {
+    hex::decode(hex).unwrap_or_else(|err| panic!("invalid hex `{hex}`: {err}"))
+}
@@ -0,0 +1,284 @@
+use {
+    crate::{LightClient, MockApp},
+    borsh::{BorshDeserialize, BorshSerialize},
+    grug_ics23::{commitment_proof, verify_membership, verify_non_membership, CommitmentProof},
+    grug_jmt::ics23_spec,
+    grug_storage::{Item, Map},
+    grug_types::{
+        from_json_value, to_json_value, to_json_vec, Binary, Empty, Hash, IbcClientQuery,
+        IbcClientQueryResponse, IbcClientResponseData, IbcClientStateResponse, IbcClientStatus,
+        ImmutableCtx, Json, MutableCtx, Response, StdError, StdResult, SudoCtx, Timestamp,
+    },
+    grug_vm_rust::{ContractBuilder, ContractWrapper},
+    prost::Message,
+    serde::{Deserialize, Serialize},
+};
+
+/// Client state of the mock light client.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct MockClientState {
+    /// ID of the chain being tracked. Headers of other chains are rejected.
+    pub chain_id: String,
+    pub latest_height: u64,
+    pub frozen: bool,
+}
+
+/// Consensus state of the mock light client, i.e. what it knows of a block of
+/// the chain being tracked.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct MockConsensusState {
+    pub timestamp: Timestamp,
+    /// The app hash, i.e. the root of the chain's Merkle tree.
+    pub root: Hash,
+}
+
+/// Header of the mock light client.
+///
+/// Mock chains don't have validators, so instead of a commit signed by them,
+/// a header is a synthetic commit of the block, which is trusted as long as
+/// it's for the right chain.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct MockHeader {
+    pub chain_id: String,
+    pub height: u64,
+    pub timestamp: Timestamp,
+    pub app_hash: Hash,
+}
+
+/// Evidence of misbehavior for the mock light client: two different headers at
+/// the same height.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct MockMisbehavior {
+    pub header_1: MockHeader,
+    pub header_2: MockHeader,
+}
+
+/// The [`LightClient`] for the mock light client, which
+/// [`MockIbc`](crate::MockIbc) uses by default.
+pub struct MockLightClient;
+
+impl LightClient for MockLightClient {
+    fn name(&self) -> &'static str {
+        "mock_client"
+    }
+
+    fn contract(&self) -> ContractWrapper {
+        mock_client()
+    }
+
+    fn initial_states(&self, header: &MockHeader) -> StdResult<(Json, Json)> {
+        let client_state = MockClientState {
+            chain_id: header.chain_id.clone(),
+            latest_height: header.height,
+            frozen: false,
+        };
+        let consensus_state = MockConsensusState {
+            timestamp: header.timestamp,
+            root: header.app_hash.clone(),
+        };
+
+        Ok((
+            to_json_value(&client_state)?,
+            to_json_value(&consensus_state)?,
+        ))
+    }
+
+    fn update_header(&self, header: &MockHeader, _trusted_height: u64) -> StdResult<Json> {
+        to_json_value(header)
+    }
+
+    fn prove(&self, chain: &MockApp, key: &[u8], height: u64) -> StdResult<Json> {
+        let (_, proof) = chain.query_store(key, height, true);
+        to_json_value(&Binary::from(proof.unwrap()))
+    }
+}
+
+const CLIENT_STATE: Item<MockClientState> = Item::new("client_state");
+
+const CONSENSUS_STATES: Map<u64, MockConsensusState> = Map::new("consensus_state");
+
+/// Build the mock light client contract, for tracking mock chains.
+///
+/// Proofs are Protobuf-encoded ICS-23 commitment proofs of the chain's Merkle
+/// tree, as returned by [`MockApp::query_store`](crate::MockApp::query_store).
+/// Delay periods are ignored.
+pub fn mock_client() -> ContractWrapper {
+    ContractBuilder::new(Box::new(instantiate))
+        .with_ibc_client_create(Box::new(create))
+        .with_ibc_client_update(Box::new(update))
+        .with_ibc_client_misbehavior(Box::new(submit_misbehavior))
+        .with_ibc_client_query(Box::new(query))
+        .build()
+}
+
+fn instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+    Err(StdError::generic_err(
+        "the mock client can only be created as an IBC client",
+    ))
+}
+
+fn create(
+    ctx: SudoCtx,
+    client_state: MockClientState,
+    consensus_state: MockConsensusState,
+) -> StdResult<Response> {
+    if client_state.frozen {
+        return Err(StdError::generic_err("can't create a frozen client"));
+    }
+
+    CLIENT_STATE.save(ctx.storage, &client_state)?;
+    CONSENSUS_STATES.save(ctx.storage, client_state.latest_height, &consensus_state)?;
+
+    response(client_state.latest_height)
+}
+
+fn update(ctx: SudoCtx, header: MockHeader) -> StdResult<Response> {
+    let mut client_state = CLIENT_STATE.load(ctx.storage)?;
+
+    if client_state.frozen {
+        return Err(StdError::generic_err("client is frozen"));
+    }
+
+    if header.chain_id != client_state.chain_id {
+        return Err(StdError::generic_err(format!(
+            "header is for chain `{}`, expecting `{}`",
+            header.chain_id, client_state.chain_id
+        )));
+    }
+
+    let consensus_state = MockConsensusState {
+        timestamp: header.timestamp,
+        root: header.app_hash,
+    };
+
+    // Headers may arrive out of order, but a different header at a height that
+    // is already known is misbehavior, which must be submitted as such.
+    match CONSENSUS_STATES.may_load(ctx.storage, header.height)? {
+        Some(existing) if existing == consensus_state => {},
+        Some(_) => {
+            return Err(StdError::generic_err(format!(
+                "conflicting header at height {}",
+                header.height
+            )));
+        },
+        None => {
+            CONSENSUS_STATES.save(ctx.storage, header.height, &consensus_state)?;
+        },
+    }
+
+    if header.height > client_state.latest_height {
+        client_state.latest_height = header.height;
+        CLIENT_STATE.save(ctx.storage, &client_state)?;
+    }
+
+    response(header.height)
+}
+
+fn submit_misbehavior(ctx: SudoCtx, misbehavior: MockMisbehavior) -> StdResult<Response> {
+    let MockMisbehavior { header_1, header_2 } = misbehavior;
+    let mut client_state = CLIENT_STATE.load(ctx.storage)?;
+
+    if header_1.chain_id != client_state.chain_id
+        || header_2.chain_id != client_state.chain_id
+        || header_1.height != header_2.height
+        || header_1 == header_2
+    {
+        return Err(StdError::generic_err("invalid misbehavior"));
+    }
+
+    client_state.frozen = true;
+    CLIENT_STATE.save(ctx.storage, &client_state)?;
+
+    response(header_1.height)
+}
+
+/// The response of the client's entry points, with the consensus height that
+/// the host includes in its events as data.
+fn response(consensus_height: u64) -> StdResult<Response> {
+    let data = IbcClientResponseData { consensus_height };
+    Ok(Response::new().set_data(to_json_vec(&data)?))
+}
+
+fn query(ctx: ImmutableCtx, msg: IbcClientQuery) -> StdResult<IbcClientQueryResponse> {
+    match msg {
+        IbcClientQuery::Status {} => {
+            let status = if CLIENT_STATE.load(ctx.storage)?.frozen {
+                IbcClientStatus::Frozen
+            } else {
+                IbcClientStatus::Active
+            };
+            Ok(IbcClientQueryResponse::Status(status))
+        },
+        IbcClientQuery::State {} => {
+            let client_state = CLIENT_STATE.load(ctx.storage)?;
+            let consensus_state = CONSENSUS_STATES.load(ctx.storage, client_state.latest_height)?;
+            Ok(IbcClientQueryResponse::State(IbcClientStateResponse {
+                client_state: to_json_vec(&client_state)?.into(),
+                consensus_state: to_json_vec(&consensus_state)?.into(),
+            }))
+        },
+        IbcClientQuery::VerifyMembership {
+            height,
+            prefix,
+            key,
+            value,
+            proof,
+            ..
+        } => {
+            let (root, key) = load_root_and_key(&ctx, height, &prefix, &key)?;
+            let verified = match decode_proof(proof)? {
+                commitment_proof::Proof::Exist(proof) => {
+                    verify_membership(&ics23_spec(), root.as_ref(), &proof, &key, &value).is_ok()
+                },
+                _ => false,
+            };
+            Ok(IbcClientQueryResponse::VerifyMembership(verified))
+        },
+        IbcClientQuery::VerifyNonMembership {
+            height,
+            prefix,
+            key,
+            proof,
+            ..
+        } => {
+            let (root, key) = load_root_and_key(&ctx, height, &prefix, &key)?;
+            let verified = match decode_proof(proof)? {
+                commitment_proof::Proof::Nonexist(proof) => {
+                    verify_non_membership(&ics23_spec(), root.as_ref(), &proof, &key).is_ok()
+                },
+                _ => false,
+            };
+            Ok(IbcClientQueryResponse::VerifyNonMembership(verified))
+        },
+        IbcClientQuery::TimestampAtHeight { height } => {
+            let consensus_state = CONSENSUS_STATES.load(ctx.storage, height)?;
+            Ok(IbcClientQueryResponse::TimestampAtHeight(
+                consensus_state.timestamp,
+            ))
+        },
+    }
+}
+
+fn load_root_and_key(
+    ctx: &ImmutableCtx,
+    height: u64,
+    prefix: &[u8],
+    key: &[u8],
+) -> StdResult<(Hash, Vec<u8>)> {
+    let consensus_state = CONSENSUS_STATES.load(ctx.storage, height)?;
+    let key = [prefix, key].concat();
+
+    Ok((consensus_state.root, key))
+}
+
+fn decode_proof(proof: Json) -> StdResult<commitment_proof::Proof> {
+    let proof: Binary = from_json_value(proof)?;
+    CommitmentProof::decode(proof.as_ref())
+        .ok()
+        .and_then(|proof| proof.proof)
+        .ok_or_else(|| StdError::generic_err("invalid commitment proof"))
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use {
+    common::{account, genesis_address, instantiate, mock_config, query_empty, register, upload},
+    grug_storage::Map,
+    grug_testing::MockApp,
+    grug_types::{
+        to_json_value, Addr, BankMsg, BankQuery, BankQueryResponse, Coin, Coins, GenesisState,
+        ImmutableCtx, Message, MutableCtx, Number, NumberConst, QueryRequest, Response, StdError,
+        StdResult, SudoCtx, Tx, Uint128,
+    },
+    grug_vm_rust::ContractBuilder,
+    std::collections::BTreeMap,
+};
+
+const BALANCES: Map<(&Addr, &str), Uint128> = Map::new("balance");
+
+fn bank_instantiate(
+    ctx: MutableCtx,
+    initial_balances: BTreeMap<Addr, Coins>,
+) -> StdResult<Response> {
+    for (address, coins) in initial_balances {
+        for coin in coins {
+            BALANCES.save(ctx.storage, (&address, &coin.denom), &coin.amount)?;
+        }
+    }
+    Ok(Response::new())
+}
+
+fn bank_execute(ctx: SudoCtx, msg: BankMsg) -> StdResult<Response> {
+    for coin in msg.coins {
+        let from_balance = BALANCES.load(ctx.storage, (&msg.from, &coin.denom))?;
+        let to_balance = BALANCES
+            .may_load(ctx.storage, (&msg.to, &coin.denom))?
+            .unwrap_or(Uint128::ZERO);
+        BALANCES.save(
+            ctx.storage,
+            (&msg.from, &coin.denom),
+            &from_balance.checked_sub(coin.amount)?,
+        )?;
+        BALANCES.save(
+            ctx.storage,
+            (&msg.to, &coin.denom),
+            &to_balance.checked_add(coin.amount)?,
+        )?;
+    }
+    Ok(Response::new())
+}
+
+fn bank_query(ctx: ImmutableCtx, msg: BankQuery) -> StdResult<BankQueryResponse> {
+    let BankQuery::Balance { address, denom } = msg else {
+        return Err(StdError::generic_err("only balance queries are supported"));
+    };
+    let amount = BALANCES
+        .may_load(ctx.storage, (&address, &denom))?
+        .unwrap_or(Uint128::ZERO);
+    Ok(BankQueryResponse::Balance(Coin { denom, amount }))
+}
+
+#[test]
+fn native_bank_and_account_work() {
+    let mut app = MockApp::new();
+
+    let bank_contract = ContractBuilder::new(Box::new(bank_instantiate))
+        .with_bank_execute(Box::new(bank_execute))
+        .with_bank_query(Box::new(bank_query))
+        .build();
+    let (bank_code, bank_code_hash) = register(&app, "bank", bank_contract);
+    let bank = genesis_address(&bank_code_hash, b"bank");
+
+    let (account_code, account_code_hash) = register(&app, "account", account());
+    let alice = genesis_address(&account_code_hash, b"alice");
+    let bob = genesis_address(&account_code_hash, b"bob");
+
+    app.init_chain("dev-1", GenesisState {
+        config: mock_config(bank.clone()),
+        msgs: vec![
+            upload(bank_code),
+            upload(account_code),
+            instantiate(&account_code_hash, b"alice"),
+            instantiate(&account_code_hash, b"bob"),
+            Message::Instantiate {
+                code_hash: bank_code_hash,
+                msg: to_json_value(&BTreeMap::from([(
+                    alice.clone(),
+                    Coins::new_one("ugrug", 100_u128),
+                )]))
+                .unwrap(),
+                salt: b"bank".to_vec().into(),
+                funds: Coins::new_empty(),
+                admin: None,
+            },
+        ],
+    });
+
+    // the first transfer succeeds, the second one fails for insufficient
+    // balance. both txs pass `before_tx`, which increments the sequence.
+    let transfer = |amount: u128| Tx {
+        sender: alice.clone(),
+        msgs: vec![Message::Transfer {
+            to: bob.clone(),
+            coins: Coins::new_one("ugrug", amount),
+        }],
+        credential: Vec::new().into(),
+    };
+    let (_, _, outcomes) = app.make_block(vec![transfer(30), transfer(80)]);
+    assert!(outcomes[0].result.is_ok());
+    assert!(outcomes[1].result.is_err());
+
+    let balance_of = |address: &Addr| {
+        app.query(QueryRequest::Balance {
+            address: address.clone(),
+            denom: "ugrug".into(),
+        })
+        .as_balance()
+        .amount
+    };
+    assert_eq!(balance_of(&alice), Uint128::new(70));
+    assert_eq!(balance_of(&bob), Uint128::new(30));
+
+    let sequence: u32 = query_empty(&app, &alice);
+    assert_eq!(sequence, 2);
+}
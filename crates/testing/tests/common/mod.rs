@@ -0,0 +1,620 @@
+// Each integration test is compiled as its own crate, and uses only some of the
+// contracts and helpers here.
+#![allow(dead_code)]
+
+use {
+    ed25519_dalek::{Signer, SigningKey},
+    grug_ibc_client_tendermint::{
+        hash_header, hash_validator_set, validator_address, vote_sign_bytes, BlockId, BlockIdFlag,
+        ClientState, Commit, CommitSig, ConsensusState, Fraction, Header, LightHeader,
+        PartSetHeader, SignedHeader, Validator, Version,
+    },
+    grug_storage::{Item, Map},
+    grug_testing::{LightClient, MockApp, MockHeader},
+    grug_types::{
+        from_json_slice, from_json_value, hash, to_json_value, to_json_vec, Addr, AuthCtx, Binary,
+        Coins, Config, Empty, Hash, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcClientQuery,
+        IbcClientQueryResponse, IbcClientResponseData, IbcClientStateResponse, IbcClientStatus,
+        IbcPacket, IbcTimeout, ImmutableCtx, Json, Message, MockApi, MutableCtx, Permission,
+        Permissions, QueryRequest, ReplyOn, Response, StdError, StdResult, SubMessage,
+        SubMsgResult, SudoCtx, Tx, GENESIS_SENDER,
+    },
+    grug_vm_rust::{ContractBuilder, ContractWrapper},
+    serde::de::DeserializeOwned,
+    std::collections::BTreeSet,
+};
+
+// --------------------------------- helpers ----------------------------------
+
+pub fn mock_config(bank: Addr) -> Config {
+    Config {
+        owner: None,
+        bank,
+        begin_blockers: vec![],
+        end_blockers: vec![],
+        permissions: Permissions {
+            upload: Permission::Everybody,
+            instantiate: Permission::Everybody,
+            create_client: Permission::Everybody,
+            create_connection: Permission::Everybody,
+            create_channel: Permission::Everybody,
+        },
+        allowed_clients: BTreeSet::new(),
+    }
+}
+
+/// Register a contract with the app's VM, and return its code and code hash.
+pub fn register(app: &MockApp, name: &str, contract: ContractWrapper) -> (Vec<u8>, Hash) {
+    let code = app.vm().register(name, "0.0.0", contract).unwrap();
+    let code_hash = hash(&code);
+    (code, code_hash)
+}
+
+/// Return the address of a contract instantiated during genesis.
+pub fn genesis_address(code_hash: &Hash, salt: &[u8]) -> Addr {
+    Addr::compute(&GENESIS_SENDER, code_hash, salt)
+}
+
+pub fn upload(code: Vec<u8>) -> Message {
+    Message::Upload { code: code.into() }
+}
+
+/// Instantiate a contract with an empty message and no funds.
+pub fn instantiate(code_hash: &Hash, salt: &[u8]) -> Message {
+    Message::Instantiate {
+        code_hash: code_hash.clone(),
+        msg: to_json_value(&Empty {}).unwrap(),
+        salt: salt.to_vec().into(),
+        funds: Coins::new_empty(),
+        admin: None,
+    }
+}
+
+/// Query a contract with an empty message, and decode the response.
+pub fn query_empty<T: DeserializeOwned>(app: &MockApp, contract: &Addr) -> T {
+    let data = app
+        .query(QueryRequest::WasmSmart {
+            contract: contract.clone(),
+            msg: to_json_value(&Empty {}).unwrap(),
+        })
+        .as_wasm_smart()
+        .data;
+    from_json_value(data).unwrap()
+}
+
+// ---------------------------------- bank -----------------------------------
+
+/// A bank contract that does nothing. For tests that don't transfer coins.
+pub fn bank() -> ContractWrapper {
+    ContractBuilder::new(Box::new(bank_instantiate)).build()
+}
+
+fn bank_instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::new().add_attribute("action", "bank_instantiate"))
+}
+
+// --------------------------------- account ---------------------------------
+
+const SEQUENCE: Item<u32> = Item::new("sequence");
+
+/// An account that accepts any transaction, and counts how many it has sent.
+/// Its sequence can be queried with an empty message.
+pub fn account() -> ContractWrapper {
+    ContractBuilder::new(Box::new(empty_instantiate))
+        .with_before_tx(Box::new(account_before_tx))
+        .with_after_tx(Box::new(account_after_tx))
+        .with_receive(Box::new(account_receive))
+        .with_query(Box::new(account_query))
+        .build()
+}
+
+/// Instantiate function for contracts that don't need any initial state.
+pub fn empty_instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn account_before_tx(ctx: AuthCtx, _tx: Tx) -> StdResult<Response> {
+    let sequence = SEQUENCE.may_load(ctx.storage)?.unwrap_or(0);
+    SEQUENCE.save(ctx.storage, &(sequence + 1))?;
+    Ok(Response::new())
+}
+
+fn account_after_tx(_ctx: AuthCtx, _tx: Tx) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn account_receive(_ctx: MutableCtx) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn account_query(ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    to_json_value(&SEQUENCE.may_load(ctx.storage)?.unwrap_or(0))
+}
+
+// ---------------------------------- caller ----------------------------------
+
+const LAST_ERROR: Item<Vec<u8>> = Item::new("last_error");
+
+/// A contract whose execution always fails.
+pub fn failer() -> ContractWrapper {
+    ContractBuilder::new(Box::new(empty_instantiate))
+        .with_execute(Box::new(failer_execute))
+        .build()
+}
+
+/// A contract that executes the given contract as a submessage, and records
+/// the error in `reply` if it fails. The last error can be queried with an
+/// empty message.
+pub fn caller() -> ContractWrapper {
+    ContractBuilder::new(Box::new(empty_instantiate))
+        .with_execute(Box::new(caller_execute))
+        .with_reply(Box::new(caller_reply))
+        .with_query(Box::new(caller_query))
+        .build()
+}
+
+fn failer_execute(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+    Err(StdError::generic_err("something went wrong"))
+}
+
+fn caller_execute(_ctx: MutableCtx, failer: Addr) -> StdResult<Response> {
+    Ok(Response::new().add_submessage(SubMessage {
+        msg: Message::Execute {
+            contract: failer,
+            msg: to_json_value(&Empty {})?,
+            funds: Coins::new_empty(),
+        },
+        reply_on: ReplyOn::Error(to_json_value(&Empty {})?),
+    }))
+}
+
+fn caller_reply(ctx: SudoCtx, _msg: Empty, res: SubMsgResult) -> StdResult<Response> {
+    LAST_ERROR.save(ctx.storage, &to_json_vec(&res.as_err())?)?;
+    Ok(Response::new())
+}
+
+fn caller_query(ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    from_json_slice(LAST_ERROR.load(ctx.storage)?)
+}
+
+// --------------------------------- counter ---------------------------------
+
+const COUNT: Item<u32> = Item::new("count");
+
+/// A contract that increments a counter when executed, and returns the new
+/// count as response data. If executed with `true`, it increments the counter
+/// and then fails. The count can be queried with an empty message.
+pub fn counter() -> ContractWrapper {
+    ContractBuilder::new(Box::new(empty_instantiate))
+        .with_execute(Box::new(counter_execute))
+        .with_query(Box::new(counter_query))
+        .build()
+}
+
+fn counter_execute(ctx: MutableCtx, fail: bool) -> StdResult<Response> {
+    let count = COUNT.may_load(ctx.storage)?.unwrap_or(0) + 1;
+    COUNT.save(ctx.storage, &count)?;
+    if fail {
+        return Err(StdError::generic_err("something went wrong"));
+    }
+    Ok(Response::new()
+        .add_attribute("count", count)
+        .set_data(to_json_vec(&count)?))
+}
+
+fn counter_query(ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    to_json_value(&COUNT.may_load(ctx.storage)?.unwrap_or(0))
+}
+
+// --------------------------------- client ----------------------------------
+
+const CLIENT_STATE: Item<u64> = Item::new("client_state");
+
+const CONSENSUS_STATES: Map<u64, String> = Map::new("consensus_state");
+
+const FROZEN: Item<bool> = Item::new("frozen");
+
+/// A mock IBC client, whose client state is its latest height, and whose
+/// consensus states are arbitrary strings. A header is a height and a
+/// consensus state, which is accepted as long as the height is increasing.
+/// A membership proof is simply the value itself, as raw bytes, while a
+/// non-membership proof is `null`.
+pub fn client() -> ContractWrapper {
+    ContractBuilder::new(Box::new(empty_instantiate))
+        .with_ibc_client_create(Box::new(client_create))
+        .with_ibc_client_update(Box::new(client_update))
+        .with_ibc_client_misbehavior(Box::new(client_misbehavior))
+        .with_ibc_client_query(Box::new(client_query))
+        .build()
+}
+
+/// Create a mock client at height 1.
+pub fn create_client(code_hash: &Hash, salt: &[u8]) -> Message {
+    Message::CreateClient {
+        code_hash: code_hash.clone(),
+        client_state: to_json_value(&1).unwrap(),
+        consensus_state: to_json_value(&"root-1").unwrap(),
+        salt: salt.to_vec().into(),
+    }
+}
+
+/// Return a proof that the mock client accepts for the value committed under
+/// the given IBC path as of the latest block, that is, the value itself.
+pub fn mock_proof(app: &MockApp, path: &str) -> Json {
+    let (value, _) = app.query_store(&[b"ibc", path.as_bytes()].concat(), 0, false);
+    to_json_value(&Binary::from(value.unwrap())).unwrap()
+}
+
+/// The response of the client's entry points, with the consensus height as
+/// data.
+pub fn client_response(consensus_height: u64) -> StdResult<Response> {
+    let data = IbcClientResponseData { consensus_height };
+    Ok(Response::new().set_data(to_json_vec(&data)?))
+}
+
+fn client_create(ctx: SudoCtx, latest_height: u64, root: String) -> StdResult<Response> {
+    CLIENT_STATE.save(ctx.storage, &latest_height)?;
+    CONSENSUS_STATES.save(ctx.storage, latest_height, &root)?;
+    FROZEN.save(ctx.storage, &false)?;
+    client_response(latest_height)
+}
+
+fn client_update(ctx: SudoCtx, (height, root): (u64, String)) -> StdResult<Response> {
+    if FROZEN.load(ctx.storage)? {
+        return Err(StdError::generic_err("client is frozen"));
+    }
+    if height <= CLIENT_STATE.load(ctx.storage)? {
+        return Err(StdError::generic_err("height must increase"));
+    }
+    CLIENT_STATE.save(ctx.storage, &height)?;
+    CONSENSUS_STATES.save(ctx.storage, height, &root)?;
+    client_response(height)
+}
+
+fn client_misbehavior(ctx: SudoCtx, _misbehavior: Empty) -> StdResult<Response> {
+    FROZEN.save(ctx.storage, &true)?;
+    client_response(CLIENT_STATE.load(ctx.storage)?)
+}
+
+fn client_query(ctx: ImmutableCtx, msg: IbcClientQuery) -> StdResult<IbcClientQueryResponse> {
+    match msg {
+        IbcClientQuery::Status {} => {
+            let status = if FROZEN.load(ctx.storage)? {
+                IbcClientStatus::Frozen
+            } else {
+                IbcClientStatus::Active
+            };
+            Ok(IbcClientQueryResponse::Status(status))
+        },
+        IbcClientQuery::State {} => {
+            let latest_height = CLIENT_STATE.load(ctx.storage)?;
+            let root = CONSENSUS_STATES.load(ctx.storage, latest_height)?;
+            Ok(IbcClientQueryResponse::State(IbcClientStateResponse {
+                client_state: to_json_vec(&latest_height)?.into(),
+                consensus_state: to_json_vec(&root)?.into(),
+            }))
+        },
+        IbcClientQuery::VerifyMembership { value, proof, .. } => {
+            let proof: Binary = from_json_value(proof)?;
+            Ok(IbcClientQueryResponse::VerifyMembership(proof == value))
+        },
+        IbcClientQuery::VerifyNonMembership { proof, .. } => {
+            Ok(IbcClientQueryResponse::VerifyNonMembership(proof.is_null()))
+        },
+        IbcClientQuery::TimestampAtHeight { .. } => {
+            Err(StdError::generic_err("timestamps are not supported"))
+        },
+    }
+}
+
+// -------------------------------- grug client --------------------------------
+
+/// Number of validators of each mock chain, as seen by the grug light client.
+const NUM_VALIDATORS: u8 = 4;
+
+/// The [`LightClient`] for the grug light client, so that the chains of a
+/// `MockIbc` track each other as real grug chains would.
+///
+/// Mock chains don't run CometBFT, so the headers are synthetic CometBFT
+/// commits of their blocks, signed by validators whose keys are derived from
+/// the chain ID. They are verified the same way as by the Tendermint client.
+/// Proofs are `grug_jmt` proofs of the chains' Merkle trees.
+pub struct GrugLightClient;
+
+impl LightClient for GrugLightClient {
+    fn name(&self) -> &'static str {
+        "grug_client"
+    }
+
+    fn contract(&self) -> ContractWrapper {
+        ContractBuilder::new(Box::new(empty_instantiate))
+            .with_ibc_client_create(Box::new(grug_ibc_client_tendermint::create_client))
+            .with_ibc_client_update(Box::new(grug_ibc_client_tendermint::update_client))
+            .with_ibc_client_misbehavior(Box::new(grug_ibc_client_tendermint::submit_misbehavior))
+            .with_ibc_client_query(Box::new(grug_client_query))
+            .build()
+    }
+
+    fn initial_states(&self, header: &MockHeader) -> StdResult<(Json, Json)> {
+        let client_state = ClientState {
+            chain_id: header.chain_id.clone(),
+            trust_level: Fraction {
+                numerator: 1,
+                denominator: 3,
+            },
+            trusting_period: 14 * 24 * 60 * 60,
+            unbonding_period: 21 * 24 * 60 * 60,
+            // Each mock chain's clock advances by a second per block, so the
+            // two chains' clocks drift apart as they make different numbers of
+            // blocks.
+            max_clock_drift: 60 * 60,
+            latest_height: header.height,
+            frozen_height: None,
+        };
+        let consensus_state = ConsensusState {
+            timestamp: header.timestamp,
+            root: header.app_hash.to_vec().into(),
+            next_validators_hash: validators_hash(&cometbft_validators(&header.chain_id)),
+        };
+
+        Ok((
+            to_json_value(&client_state)?,
+            to_json_value(&consensus_state)?,
+        ))
+    }
+
+    fn update_header(&self, header: &MockHeader, trusted_height: u64) -> StdResult<Json> {
+        to_json_value(&cometbft_header(header, trusted_height))
+    }
+
+    fn prove(&self, chain: &MockApp, key: &[u8], height: u64) -> StdResult<Json> {
+        to_json_value(&chain.prove(key, height))
+    }
+}
+
+fn grug_client_query(
+    ctx: ImmutableCtx,
+    msg: IbcClientQuery,
+) -> anyhow::Result<IbcClientQueryResponse> {
+    match msg {
+        IbcClientQuery::Status {} => grug_ibc_client_tendermint::query_status(ctx)
+            .map(IbcClientQueryResponse::Status)
+            .map_err(Into::into),
+        IbcClientQuery::State {} => grug_ibc_client_tendermint::query_state(ctx)
+            .map(IbcClientQueryResponse::State)
+            .map_err(Into::into),
+        IbcClientQuery::VerifyMembership {
+            height,
+            delay_time_period,
+            delay_block_period,
+            prefix,
+            key,
+            value,
+            proof,
+        } => grug_ibc_client_grug::verify_membership(
+            ctx,
+            height,
+            delay_time_period,
+            delay_block_period,
+            &prefix,
+            &key,
+            &value,
+            proof,
+        )
+        .map(IbcClientQueryResponse::VerifyMembership),
+        IbcClientQuery::VerifyNonMembership {
+            height,
+            delay_time_period,
+            delay_block_period,
+            prefix,
+            key,
+            proof,
+        } => grug_ibc_client_grug::verify_non_membership(
+            ctx,
+            height,
+            delay_time_period,
+            delay_block_period,
+            &prefix,
+            &key,
+            proof,
+        )
+        .map(IbcClientQueryResponse::VerifyNonMembership),
+        IbcClientQuery::TimestampAtHeight { height } => {
+            grug_ibc_client_tendermint::query_timestamp_at_height(ctx, height)
+                .map(IbcClientQueryResponse::TimestampAtHeight)
+                .map_err(Into::into)
+        },
+    }
+}
+
+/// The signing keys of a mock chain's validators.
+pub fn cometbft_keys(chain_id: &str) -> Vec<SigningKey> {
+    (0..NUM_VALIDATORS)
+        .map(|i| SigningKey::from_bytes(&hash(format!("{chain_id}/{i}")).into_slice()))
+        .collect()
+}
+
+/// The validators of a mock chain, all of the same voting power.
+pub fn cometbft_validators(chain_id: &str) -> Vec<Validator> {
+    cometbft_keys(chain_id)
+        .iter()
+        .map(|key| {
+            let pub_key = key.verifying_key().to_bytes();
+            Validator {
+                address: validator_address(&MockApi, &pub_key).to_vec().into(),
+                pub_key: pub_key.to_vec().into(),
+                voting_power: 10,
+            }
+        })
+        .collect()
+}
+
+fn validators_hash(validators: &[Validator]) -> Binary {
+    hash_validator_set(&MockApi, validators).to_vec().into()
+}
+
+/// Produce a synthetic CometBFT commit of a mock chain's block, signed by all
+/// of its validators, as a header to update the grug light client with.
+///
+/// The validator set never changes, so the header can be verified against any
+/// trusted height, adjacent or not.
+pub fn cometbft_header(header: &MockHeader, trusted_height: u64) -> Header {
+    let validators = cometbft_validators(&header.chain_id);
+    let light_header = LightHeader {
+        version: Version { block: 11, app: 0 },
+        chain_id: header.chain_id.clone(),
+        height: header.height,
+        time: header.timestamp,
+        last_block_id: BlockId {
+            hash: Binary::empty(),
+            part_set_header: PartSetHeader {
+                total: 0,
+                hash: Binary::empty(),
+            },
+        },
+        last_commit_hash: Binary::empty(),
+        data_hash: Binary::empty(),
+        validators_hash: validators_hash(&validators),
+        next_validators_hash: validators_hash(&validators),
+        consensus_hash: Binary::empty(),
+        app_hash: header.app_hash.to_vec().into(),
+        last_results_hash: Binary::empty(),
+        evidence_hash: Binary::empty(),
+        proposer_address: validators[0].address.clone(),
+    };
+    let block_id = BlockId {
+        hash: hash_header(&MockApi, &light_header).to_vec().into(),
+        part_set_header: PartSetHeader {
+            total: 1,
+            hash: vec![0; 32].into(),
+        },
+    };
+    let sign_bytes = vote_sign_bytes(
+        &header.chain_id,
+        header.height,
+        0,
+        &block_id,
+        header.timestamp,
+    );
+    let signatures = cometbft_keys(&header.chain_id)
+        .iter()
+        .zip(&validators)
+        .map(|(key, validator)| CommitSig {
+            block_id_flag: BlockIdFlag::Commit,
+            validator_address: validator.address.clone(),
+            timestamp: header.timestamp,
+            signature: key.sign(&sign_bytes).to_bytes().to_vec().into(),
+        })
+        .collect();
+
+    Header {
+        signed_header: SignedHeader {
+            header: light_header,
+            commit: Commit {
+                height: header.height,
+                round: 0,
+                block_id,
+                signatures,
+            },
+        },
+        validator_set: validators.clone(),
+        trusted_height,
+        trusted_validators: validators,
+    }
+}
+
+// ---------------------------------- port -----------------------------------
+
+const RECEIVED: Item<Vec<Binary>> = Item::new("received");
+
+const ACKNOWLEDGED: Item<Vec<Binary>> = Item::new("acknowledged");
+
+const TIMED_OUT: Item<Vec<u64>> = Item::new("timed_out");
+
+/// A mock IBC application, which sends packets of the given data on the given
+/// channel, and records the packets it receives, the acknowledgements it gets,
+/// and the packets that time out. Packets with data `fail` are rejected, and so
+/// are channels of version `fail`.
+///
+/// Like an account, it accepts any transaction, so it can open channels on its
+/// own port.
+pub fn port() -> ContractWrapper {
+    ContractBuilder::new(Box::new(port_instantiate))
+        .with_execute(Box::new(port_execute))
+        .with_query(Box::new(port_query))
+        .with_before_tx(Box::new(account_before_tx))
+        .with_after_tx(Box::new(account_after_tx))
+        .with_ibc_channel_open(Box::new(port_channel_open))
+        .with_ibc_channel_connect(Box::new(port_channel_connect))
+        .with_ibc_packet_receive(Box::new(port_receive))
+        .with_ibc_packet_ack(Box::new(port_ack))
+        .with_ibc_packet_timeout(Box::new(port_timeout))
+        .build()
+}
+
+fn port_instantiate(ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+    RECEIVED.save(ctx.storage, &vec![])?;
+    ACKNOWLEDGED.save(ctx.storage, &vec![])?;
+    TIMED_OUT.save(ctx.storage, &vec![])?;
+    Ok(Response::new())
+}
+
+fn port_execute(
+    _ctx: MutableCtx,
+    (channel_id, data, timeout_height): (String, Binary, u64),
+) -> StdResult<Response> {
+    Ok(Response::new().add_message(Message::SendPacket {
+        channel_id,
+        data,
+        timeout: IbcTimeout {
+            height: Some(timeout_height),
+            timestamp: None,
+        },
+    }))
+}
+
+fn port_channel_open(_ctx: SudoCtx, msg: IbcChannelOpenMsg) -> StdResult<Response> {
+    let (IbcChannelOpenMsg::Init { channel, .. } | IbcChannelOpenMsg::Try { channel, .. }) = msg;
+    if channel.version == "fail" {
+        return Err(StdError::generic_err("channel rejected"));
+    }
+    Ok(Response::new().add_attribute("action", "channel_open"))
+}
+
+fn port_channel_connect(_ctx: SudoCtx, _msg: IbcChannelConnectMsg) -> StdResult<Response> {
+    Ok(Response::new().add_attribute("action", "channel_connect"))
+}
+
+fn port_receive(ctx: SudoCtx, packet: IbcPacket) -> StdResult<Response> {
+    if packet.data.as_ref() == b"fail" {
+        return Err(StdError::generic_err("packet rejected"));
+    }
+    let mut received = RECEIVED.load(ctx.storage)?;
+    received.push(packet.data);
+    RECEIVED.save(ctx.storage, &received)?;
+    Ok(Response::new()
+        .add_attribute("action", "receive")
+        .set_data(b"ok".to_vec()))
+}
+
+fn port_ack(ctx: SudoCtx, _packet: IbcPacket, ack: Binary) -> StdResult<Response> {
+    let mut acks = ACKNOWLEDGED.load(ctx.storage)?;
+    acks.push(ack);
+    ACKNOWLEDGED.save(ctx.storage, &acks)?;
+    Ok(Response::new())
+}
+
+fn port_timeout(ctx: SudoCtx, packet: IbcPacket) -> StdResult<Response> {
+    let mut sequences = TIMED_OUT.load(ctx.storage)?;
+    sequences.push(packet.sequence);
+    TIMED_OUT.save(ctx.storage, &sequences)?;
+    Ok(Response::new())
+}
+
+fn port_query(ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    to_json_value(&(
+        RECEIVED.load(ctx.storage)?,
+        ACKNOWLEDGED.load(ctx.storage)?,
+        TIMED_OUT.load(ctx.storage)?,
+    ))
+}
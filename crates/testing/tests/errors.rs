@@ -0,0 +1,53 @@
+mod common;
+
+use {
+    common::{
+        bank, caller, failer, genesis_address, instantiate, mock_config, query_empty, register,
+        upload,
+    },
+    grug_testing::MockApp,
+    grug_types::{
+        to_json_value, Coins, ErrorCategory, ExecutionError, GenesisState, Message, StdError,
+    },
+};
+
+#[test]
+fn submessage_errors_are_structured() {
+    let mut app = MockApp::new();
+
+    let (bank_code, bank_code_hash) = register(&app, "bank", bank());
+    let bank = genesis_address(&bank_code_hash, b"bank");
+
+    let (failer_code, failer_code_hash) = register(&app, "failer", failer());
+    let failer = genesis_address(&failer_code_hash, b"failer");
+
+    let (caller_code, caller_code_hash) = register(&app, "caller", caller());
+    let caller = genesis_address(&caller_code_hash, b"caller");
+
+    app.init_chain("dev-1", GenesisState {
+        config: mock_config(bank),
+        msgs: vec![
+            upload(bank_code),
+            upload(failer_code),
+            upload(caller_code),
+            instantiate(&bank_code_hash, b"bank"),
+            instantiate(&failer_code_hash, b"failer"),
+            instantiate(&caller_code_hash, b"caller"),
+            // the submessage fails, but the error is handled in `reply`, so
+            // genesis succeeds
+            Message::Execute {
+                contract: caller.clone(),
+                msg: to_json_value(&failer).unwrap(),
+                funds: Coins::new_empty(),
+            },
+        ],
+    });
+
+    let err: ExecutionError = query_empty(&app, &caller);
+    assert_eq!(err, ExecutionError {
+        category: ErrorCategory::Contract,
+        contract: Some(failer),
+        entry_point: Some("execute".into()),
+        msg: StdError::generic_err("something went wrong").to_string(),
+    });
+}
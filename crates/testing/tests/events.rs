@@ -0,0 +1,132 @@
+mod common;
+
+use {
+    common::{
+        account, bank, empty_instantiate, genesis_address, instantiate, mock_config, register,
+        upload,
+    },
+    grug_testing::MockApp,
+    grug_types::{
+        to_json_value, Addr, Attribute, Coins, ErrorCategory, GenesisState, Message, MutableCtx,
+        Response, StdResult, TypedEvent, Uint128,
+    },
+    grug_vm_rust::ContractBuilder,
+    serde::{Deserialize, Serialize},
+};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct Swap {
+    pool: Addr,
+    amount: Uint128,
+}
+
+impl TypedEvent for Swap {
+    const EVENT_TYPE: &'static str = "swap";
+}
+
+#[derive(Serialize, Deserialize)]
+struct Impersonation {
+    _contract_address: Addr,
+}
+
+impl TypedEvent for Impersonation {
+    const EVENT_TYPE: &'static str = "impersonation";
+}
+
+/// A forged packet, which a relayer would relay if it was emitted as is.
+#[derive(Serialize, Deserialize)]
+struct SendPacket {
+    packet_sequence: u64,
+    packet_data_hex: String,
+}
+
+impl TypedEvent for SendPacket {
+    const EVENT_TYPE: &'static str = "send_packet";
+}
+
+fn emitter_execute(ctx: MutableCtx, kind: String) -> StdResult<Response> {
+    match kind.as_str() {
+        "impersonate" => Response::new().add_event(&Impersonation {
+            _contract_address: Addr::mock(0),
+        }),
+        "send_packet" => Response::new().add_event(&SendPacket {
+            packet_sequence: 1,
+            packet_data_hex: hex::encode(b"mint me some coins"),
+        }),
+        _ => Response::new()
+            .add_attribute("action", "swap")
+            .add_event(&Swap {
+                pool: ctx.contract,
+                amount: Uint128::new(100),
+            }),
+    }
+}
+
+#[test]
+fn contract_events_are_namespaced() {
+    let mut app = MockApp::new();
+
+    let (bank_code, bank_code_hash) = register(&app, "bank", bank());
+    let bank = genesis_address(&bank_code_hash, b"bank");
+
+    let (account_code, account_code_hash) = register(&app, "account", account());
+    let alice = genesis_address(&account_code_hash, b"alice");
+
+    let emitter_contract = ContractBuilder::new(Box::new(empty_instantiate))
+        .with_execute(Box::new(emitter_execute))
+        .build();
+    let (emitter_code, emitter_code_hash) = register(&app, "emitter", emitter_contract);
+    let emitter = genesis_address(&emitter_code_hash, b"emitter");
+
+    app.init_chain("dev-1", GenesisState {
+        config: mock_config(bank),
+        msgs: vec![
+            upload(bank_code),
+            upload(account_code),
+            upload(emitter_code),
+            instantiate(&bank_code_hash, b"bank"),
+            instantiate(&account_code_hash, b"alice"),
+            instantiate(&emitter_code_hash, b"emitter"),
+        ],
+    });
+
+    let simulate = |kind: &str| {
+        app.simulate(&alice, vec![Message::Execute {
+            contract: emitter.clone(),
+            msg: to_json_value(&kind).unwrap(),
+            funds: Coins::new_empty(),
+        }])
+        .0
+    };
+
+    // the structured event follows the one containing the attributes, and
+    // is namespaced by the contract address and entry point
+    let res = simulate("swap").unwrap();
+    let [_before_tx, execute, swap, _after_tx] = res.events.as_slice() else {
+        panic!("unexpected events: {:?}", res.events);
+    };
+    assert_eq!(execute.r#type, "execute");
+    assert_eq!(swap.r#type, "swap");
+    assert_eq!(swap.attributes[..2], [
+        Attribute::new("_contract_address", &emitter),
+        Attribute::new("_entry_point", "execute"),
+    ]);
+    assert_eq!(swap.decode::<Swap>().unwrap(), Swap {
+        pool: emitter.clone(),
+        amount: Uint128::new(100),
+    });
+
+    // contracts can't emit attributes reserved for the host
+    let err = simulate("impersonate").unwrap_err().into_execution_error();
+    assert_eq!(err.category, ErrorCategory::Contract);
+    assert!(err
+        .msg
+        .contains("attribute key `_contract_address` is reserved"));
+
+    // nor events of the types emitted by the host
+    let err = simulate("send_packet").unwrap_err().into_execution_error();
+    assert_eq!(err.category, ErrorCategory::Contract);
+    assert_eq!(err.contract, Some(emitter));
+    assert!(err.msg.contains("invalid event `send_packet`"));
+    assert!(err.msg.contains("reserved for the host"));
+}
@@ -0,0 +1,224 @@
+mod common;
+
+use {
+    common::{
+        account, bank, client, client_response, create_client, empty_instantiate, genesis_address,
+        instantiate, mock_config, register, upload,
+    },
+    grug_testing::MockApp,
+    grug_types::{
+        to_json_value, to_json_vec, Addr, Attribute, Binary, ClientResponse, Empty, ErrorCategory,
+        GenesisState, IbcClientQuery, IbcClientQueryResponse, IbcConnectionCounterparty,
+        ImmutableCtx, Json, Message, Permission, QueryRequest, Response, StdResult, SudoCtx,
+    },
+    grug_vm_rust::ContractBuilder,
+    std::collections::BTreeSet,
+};
+
+#[test]
+fn ibc_client_lifecycle_works() {
+    let mut app = MockApp::new();
+
+    let (bank_code, bank_code_hash) = register(&app, "bank", bank());
+    let bank = genesis_address(&bank_code_hash, b"bank");
+
+    let (account_code, account_code_hash) = register(&app, "account", account());
+    let alice = genesis_address(&account_code_hash, b"alice");
+    let bob = genesis_address(&account_code_hash, b"bob");
+
+    let (client_code, client_code_hash) = register(&app, "client", client());
+    let client = genesis_address(&client_code_hash, b"client");
+
+    let mut config = mock_config(bank);
+    config.permissions.create_client = Permission::Somebodies(BTreeSet::from([alice.clone()]));
+    config.allowed_clients = BTreeSet::from([client_code_hash.clone()]);
+    app.init_chain("dev-1", GenesisState {
+        config,
+        msgs: vec![
+            upload(bank_code),
+            upload(account_code),
+            upload(client_code),
+            instantiate(&bank_code_hash, b"bank"),
+            instantiate(&account_code_hash, b"alice"),
+            instantiate(&account_code_hash, b"bob"),
+            create_client(&client_code_hash, b"client"),
+        ],
+    });
+
+    // the client's states can be queried by its ID
+    let res = app
+        .query(QueryRequest::Client {
+            client_id: client.clone(),
+        })
+        .as_client();
+    assert_eq!(res, ClientResponse {
+        client_id: client.to_string(),
+        code_hash: client_code_hash.clone(),
+        client_state: to_json_vec(&1).unwrap().into(),
+        consensus_state: to_json_vec(&"root-1").unwrap().into(),
+    });
+
+    // only codes on the allow list can be used as clients
+    let err = app
+        .simulate(&alice, vec![create_client(&account_code_hash, b"")])
+        .0
+        .unwrap_err();
+    assert!(err.to_string().contains("not allowed as IBC client"));
+
+    // only accounts with the permission can create clients
+    let err = app
+        .simulate(&bob, vec![create_client(&client_code_hash, b"")])
+        .0
+        .unwrap_err();
+    assert!(err.to_string().contains("does not have permission"));
+
+    let res = app
+        .simulate(&alice, vec![create_client(&client_code_hash, b"")])
+        .0
+        .unwrap();
+    let create = res
+        .events
+        .iter()
+        .find(|event| event.r#type == "create_client")
+        .unwrap();
+    assert_eq!(create.attributes, [
+        Attribute::new("client_id", Addr::compute(&alice, &client_code_hash, b"")),
+        Attribute::new("client_type", &client_code_hash),
+        Attribute::new("consensus_height", "0-1"),
+    ]);
+
+    // anyone can update a client, as long as the client accepts the header
+    let header = |height: u64| to_json_value(&(height, format!("root-{height}"))).unwrap();
+    let update = |height: u64| Message::UpdateClient {
+        client_id: client.clone(),
+        header: header(height),
+    };
+    let res = app.simulate(&alice, vec![update(2)]).0.unwrap();
+    let [_before_tx, update_client, update_event, _after_tx] = res.events.as_slice() else {
+        panic!("unexpected events: {:?}", res.events);
+    };
+    assert_eq!(update_client.r#type, "update_client");
+    assert_eq!(update_client.attributes, [
+        Attribute::new("client_id", &client),
+        Attribute::new("client_type", &client_code_hash),
+        Attribute::new("consensus_height", "0-2"),
+        Attribute::new("header", hex::encode(to_json_vec(&header(2)).unwrap())),
+    ]);
+    assert_eq!(update_event.r#type, "ibc_client_update");
+
+    let err = app.simulate(&alice, vec![update(1)]).0.unwrap_err();
+    assert!(err.to_string().contains("height must increase"));
+
+    // a frozen client can no longer be updated
+    let misbehavior = Message::SubmitMisbehavior {
+        client_id: client.clone(),
+        misbehavior: to_json_value(&Empty {}).unwrap(),
+    };
+    let res = app.simulate(&alice, vec![misbehavior.clone()]).0.unwrap();
+    let misbehaviour = res
+        .events
+        .iter()
+        .find(|event| event.r#type == "client_misbehaviour")
+        .unwrap();
+    assert_eq!(misbehaviour.attributes, [
+        Attribute::new("client_id", &client),
+        Attribute::new("client_type", &client_code_hash),
+        Attribute::new("consensus_height", "0-1"),
+    ]);
+
+    let err = app
+        .simulate(&alice, vec![misbehavior, update(3)])
+        .0
+        .unwrap_err();
+    assert!(err.to_string().contains("client is frozen"));
+}
+
+// A client that doesn't report the consensus height it's created at.
+fn silent_client_create(
+    _ctx: SudoCtx,
+    _client_state: Json,
+    _consensus_state: Json,
+) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+// A client that answers every query with the wrong type of response.
+fn confused_client_create(_ctx: SudoCtx, latest_height: u64, _root: String) -> StdResult<Response> {
+    client_response(latest_height)
+}
+
+fn confused_client_query(
+    _ctx: ImmutableCtx,
+    _msg: IbcClientQuery,
+) -> StdResult<IbcClientQueryResponse> {
+    Ok(IbcClientQueryResponse::VerifyMembership(true))
+}
+
+#[test]
+fn invalid_client_responses_are_rejected() {
+    let mut app = MockApp::new();
+
+    let (bank_code, bank_code_hash) = register(&app, "bank", bank());
+    let bank = genesis_address(&bank_code_hash, b"bank");
+
+    let (account_code, account_code_hash) = register(&app, "account", account());
+    let alice = genesis_address(&account_code_hash, b"alice");
+
+    let silent_contract = ContractBuilder::new(Box::new(empty_instantiate))
+        .with_ibc_client_create(Box::new(silent_client_create))
+        .build();
+    let (silent_code, silent_code_hash) = register(&app, "silent", silent_contract);
+
+    let confused_contract = ContractBuilder::new(Box::new(empty_instantiate))
+        .with_ibc_client_create(Box::new(confused_client_create))
+        .with_ibc_client_query(Box::new(confused_client_query))
+        .build();
+    let (confused_code, confused_code_hash) = register(&app, "confused", confused_contract);
+    let confused = genesis_address(&confused_code_hash, b"confused");
+
+    let mut config = mock_config(bank);
+    config.allowed_clients = BTreeSet::from([silent_code_hash.clone(), confused_code_hash.clone()]);
+    app.init_chain("dev-1", GenesisState {
+        config,
+        msgs: vec![
+            upload(bank_code),
+            upload(account_code),
+            upload(silent_code),
+            upload(confused_code),
+            instantiate(&bank_code_hash, b"bank"),
+            instantiate(&account_code_hash, b"alice"),
+            create_client(&confused_code_hash, b"confused"),
+        ],
+    });
+
+    // the client must report the consensus height as response data
+    let err = app
+        .simulate(&alice, vec![create_client(&silent_code_hash, b"silent")])
+        .0
+        .unwrap_err()
+        .into_execution_error();
+    assert_eq!(err.category, ErrorCategory::Contract);
+    assert_eq!(
+        err.contract,
+        Some(Addr::compute(&alice, &silent_code_hash, b"silent"))
+    );
+    assert!(err.msg.contains("response data is not set"));
+
+    // a response of the wrong type is an error, not a panic
+    let err = app
+        .simulate(&alice, vec![Message::ConnOpenInit {
+            client_id: confused.clone(),
+            counterparty: IbcConnectionCounterparty {
+                client_id: "07-tendermint-0".to_string(),
+                connection_id: None,
+                prefix: Binary::from(b"ibc".to_vec()),
+            },
+            delay_period: 0,
+        }])
+        .0
+        .unwrap_err()
+        .into_execution_error();
+    assert_eq!(err.category, ErrorCategory::Contract);
+    assert_eq!(err.contract, Some(confused));
+    assert!(err.msg.contains("expecting a response of type `status`"));
+}
@@ -0,0 +1,275 @@
+mod common;
+
+use {
+    common::{
+        account, bank, client, create_client, genesis_address, instantiate, mock_config,
+        mock_proof, port, register, upload,
+    },
+    grug_testing::MockApp,
+    grug_types::{
+        to_json_value, Attribute, Binary, Event, GenesisState, IbcChannelCounterparty,
+        IbcChannelEnd, IbcChannelOrder, IbcChannelState, IbcConnectionCounterparty,
+        IbcConnectionEnd, IbcConnectionState, IbcConnectionVersion, Json, Message, Permission,
+        QueryRequest,
+    },
+    std::collections::BTreeSet,
+};
+
+fn find_event(events: &[Event], ty: &str) -> Vec<Attribute> {
+    events
+        .iter()
+        .find(|event| event.r#type == ty)
+        .unwrap()
+        .attributes
+        .clone()
+}
+
+// Open a connection and a channel between the chain and itself, with the
+// client tracking the chain itself. Every handshake step takes place in its
+// own block, and proves the state committed in the previous one.
+#[test]
+fn ibc_handshakes_work() {
+    let mut app = MockApp::new();
+
+    let (bank_code, bank_code_hash) = register(&app, "bank", bank());
+    let bank = genesis_address(&bank_code_hash, b"bank");
+
+    let (account_code, account_code_hash) = register(&app, "account", account());
+    let alice = genesis_address(&account_code_hash, b"alice");
+
+    let (port_code, port_code_hash) = register(&app, "port", port());
+    let port = genesis_address(&port_code_hash, b"port");
+
+    let (client_code, client_code_hash) = register(&app, "client", client());
+    let client = genesis_address(&client_code_hash, b"client");
+
+    let mut config = mock_config(bank);
+    config.permissions.create_connection = Permission::Somebodies(BTreeSet::from([port.clone()]));
+    config.permissions.create_channel = Permission::Nobody;
+    config.allowed_clients = BTreeSet::from([client_code_hash.clone()]);
+    app.init_chain("dev-1", GenesisState {
+        config,
+        msgs: vec![
+            upload(bank_code),
+            upload(account_code),
+            upload(client_code),
+            upload(port_code),
+            instantiate(&bank_code_hash, b"bank"),
+            instantiate(&account_code_hash, b"alice"),
+            instantiate(&port_code_hash, b"port"),
+            create_client(&client_code_hash, b"client"),
+        ],
+    });
+
+    let connection = |app: &MockApp, connection_id: &str| {
+        app.query(QueryRequest::Connection {
+            connection_id: connection_id.into(),
+        })
+        .as_connection()
+    };
+    let channel = |app: &MockApp, channel_id: &str| {
+        app.query(QueryRequest::Channel {
+            port_id: port.clone(),
+            channel_id: channel_id.into(),
+        })
+        .as_channel()
+    };
+
+    // ------------------------------ connection -------------------------------
+
+    let counterparty = |connection_id: Option<&str>| IbcConnectionCounterparty {
+        client_id: client.to_string(),
+        connection_id: connection_id.map(Into::into),
+        prefix: b"ibc".to_vec().into(),
+    };
+    // only starting the handshake requires the permission to create
+    // connections, the other steps can be relayed by anyone
+    let init_msg = Message::ConnOpenInit {
+        client_id: client.clone(),
+        counterparty: counterparty(None),
+        delay_period: 0,
+    };
+    let err = app.simulate(&alice, vec![init_msg.clone()]).0.unwrap_err();
+    assert!(err.to_string().contains("does not have permission"));
+
+    let res = app.execute(&port, vec![init_msg]).unwrap();
+    assert_eq!(find_event(&res.events, "connection_open_init"), [
+        Attribute::new("connection_id", "connection-0"),
+        Attribute::new("client_id", &client),
+        Attribute::new("counterparty_client_id", &client),
+        Attribute::new("counterparty_connection_id", ""),
+    ]);
+    assert_eq!(connection(&app, "connection-0"), IbcConnectionEnd {
+        client_id: client.clone(),
+        versions: vec![IbcConnectionVersion::default()],
+        state: IbcConnectionState::Init,
+        counterparty: counterparty(None),
+        delay_period: 0,
+    });
+
+    // a proof of a different state is rejected
+    let try_msg = |proof_init: Json| Message::ConnOpenTry {
+        client_id: client.clone(),
+        counterparty: counterparty(Some("connection-0")),
+        counterparty_versions: vec![IbcConnectionVersion::default()],
+        delay_period: 0,
+        proof_height: 1,
+        proof_init,
+    };
+    let err = app
+        .execute(&alice, vec![try_msg(
+            to_json_value(&Binary::from(b"wrong".to_vec())).unwrap(),
+        )])
+        .unwrap_err();
+    assert!(err.to_string().contains("failed to verify proof"));
+
+    let proof_init = mock_proof(&app, "connections/connection-0");
+    let res = app.execute(&alice, vec![try_msg(proof_init)]).unwrap();
+    assert_eq!(
+        find_event(&res.events, "connection_open_try")[0],
+        Attribute::new("connection_id", "connection-1")
+    );
+    assert_eq!(
+        connection(&app, "connection-1").state,
+        IbcConnectionState::TryOpen
+    );
+
+    let ack_msg = Message::ConnOpenAck {
+        connection_id: "connection-0".into(),
+        counterparty_connection_id: "connection-1".into(),
+        version: IbcConnectionVersion::default(),
+        proof_height: 3,
+        proof_try: mock_proof(&app, "connections/connection-1"),
+    };
+    app.execute(&alice, vec![ack_msg.clone()]).unwrap();
+    assert_eq!(
+        connection(&app, "connection-0").state,
+        IbcConnectionState::Open
+    );
+
+    // the same step can't be done twice
+    let err = app.execute(&alice, vec![ack_msg]).unwrap_err();
+    assert!(err.to_string().contains("incorrect state"));
+
+    let proof_ack = mock_proof(&app, "connections/connection-0");
+    app.execute(&alice, vec![Message::ConnOpenConfirm {
+        connection_id: "connection-1".into(),
+        proof_height: 4,
+        proof_ack,
+    }])
+    .unwrap();
+    assert_eq!(connection(&app, "connection-1"), IbcConnectionEnd {
+        client_id: client.clone(),
+        versions: vec![IbcConnectionVersion::default()],
+        state: IbcConnectionState::Open,
+        counterparty: counterparty(Some("connection-0")),
+        delay_period: 0,
+    });
+
+    // -------------------------------- channel --------------------------------
+
+    // without the permission to create channels, only the port contract
+    // itself can open channels on its port
+    let init_msg = Message::ChanOpenInit {
+        port_id: port.clone(),
+        connection_id: "connection-0".into(),
+        counterparty_port_id: port.to_string(),
+        ordering: IbcChannelOrder::Unordered,
+        version: "ics20-1".into(),
+    };
+    let err = app.execute(&alice, vec![init_msg.clone()]).unwrap_err();
+    assert!(err.to_string().contains("does not have permission"));
+
+    // the port contract can reject the channel, e.g. for its version
+    let err = app
+        .simulate(&port, vec![Message::ChanOpenInit {
+            port_id: port.clone(),
+            connection_id: "connection-0".into(),
+            counterparty_port_id: port.to_string(),
+            ordering: IbcChannelOrder::Unordered,
+            version: "fail".into(),
+        }])
+        .0
+        .unwrap_err();
+    assert!(err.to_string().contains("channel rejected"));
+
+    let res = app.execute(&port, vec![init_msg]).unwrap();
+    assert_eq!(find_event(&res.events, "channel_open_init"), [
+        Attribute::new("port_id", &port),
+        Attribute::new("channel_id", "channel-0"),
+        Attribute::new("counterparty_port_id", &port),
+        Attribute::new("counterparty_channel_id", ""),
+        Attribute::new("connection_id", "connection-0"),
+        Attribute::new("version", "ics20-1"),
+    ]);
+    assert_eq!(find_event(&res.events, "ibc_channel_open"), [
+        Attribute::new("_contract_address", &port),
+        Attribute::new("action", "channel_open"),
+    ]);
+
+    let proof_init = mock_proof(
+        &app,
+        &format!("channelEnds/ports/{port}/channels/channel-0"),
+    );
+    app.execute(&port, vec![Message::ChanOpenTry {
+        port_id: port.clone(),
+        connection_id: "connection-1".into(),
+        counterparty: IbcChannelCounterparty {
+            port_id: port.to_string(),
+            channel_id: Some("channel-0".into()),
+        },
+        ordering: IbcChannelOrder::Unordered,
+        version: "ics20-1".into(),
+        counterparty_version: "ics20-1".into(),
+        proof_height: 8,
+        proof_init,
+    }])
+    .unwrap();
+    assert_eq!(channel(&app, "channel-1").state, IbcChannelState::TryOpen);
+
+    let proof_try = mock_proof(
+        &app,
+        &format!("channelEnds/ports/{port}/channels/channel-1"),
+    );
+    app.execute(&alice, vec![Message::ChanOpenAck {
+        port_id: port.clone(),
+        channel_id: "channel-0".into(),
+        counterparty_channel_id: "channel-1".into(),
+        counterparty_version: "ics20-1".into(),
+        proof_height: 9,
+        proof_try,
+    }])
+    .unwrap();
+    assert_eq!(channel(&app, "channel-0").state, IbcChannelState::Open);
+
+    let proof_ack = mock_proof(
+        &app,
+        &format!("channelEnds/ports/{port}/channels/channel-0"),
+    );
+    let res = app
+        .execute(&alice, vec![Message::ChanOpenConfirm {
+            port_id: port.clone(),
+            channel_id: "channel-1".into(),
+            proof_height: 10,
+            proof_ack,
+        }])
+        .unwrap();
+    assert_eq!(
+        find_event(&res.events, "channel_open_confirm")[3],
+        Attribute::new("counterparty_channel_id", "channel-0")
+    );
+    assert_eq!(
+        find_event(&res.events, "ibc_channel_connect")[1],
+        Attribute::new("action", "channel_connect")
+    );
+    assert_eq!(channel(&app, "channel-1"), IbcChannelEnd {
+        state: IbcChannelState::Open,
+        ordering: IbcChannelOrder::Unordered,
+        counterparty: IbcChannelCounterparty {
+            port_id: port.to_string(),
+            channel_id: Some("channel-0".into()),
+        },
+        connection_hops: vec!["connection-1".into()],
+        version: "ics20-1".into(),
+    });
+}
@@ -0,0 +1,335 @@
+mod common;
+
+use {
+    common::{
+        account, bank, client, create_client, genesis_address, instantiate, mock_config,
+        mock_proof, port, query_empty, register, upload,
+    },
+    grug_testing::MockApp,
+    grug_types::{
+        from_json_slice, to_json_value, to_json_vec, Addr, Attribute, Binary, Coins, GenesisState,
+        IbcAcknowledgement, IbcChannelCounterparty, IbcChannelOrder, IbcConnectionCounterparty,
+        IbcConnectionVersion, IbcPacket, IbcTimeout, Json, Message,
+    },
+    std::collections::BTreeSet,
+};
+
+// Open a connection, and an unordered channel from the port to itself, in
+// blocks 1 to 8.
+fn open_channel(app: &mut MockApp, sender: &Addr, client: &Addr, port: &Addr) {
+    let counterparty = |connection_id: Option<&str>| IbcConnectionCounterparty {
+        client_id: client.to_string(),
+        connection_id: connection_id.map(Into::into),
+        prefix: b"ibc".to_vec().into(),
+    };
+    let execute = |app: &mut MockApp, msg: Message| {
+        app.execute(sender, vec![msg]).unwrap();
+    };
+
+    execute(app, Message::ConnOpenInit {
+        client_id: client.clone(),
+        counterparty: counterparty(None),
+        delay_period: 0,
+    });
+    let proof_init = mock_proof(app, "connections/connection-0");
+    execute(app, Message::ConnOpenTry {
+        client_id: client.clone(),
+        counterparty: counterparty(Some("connection-0")),
+        counterparty_versions: vec![IbcConnectionVersion::default()],
+        delay_period: 0,
+        proof_height: 1,
+        proof_init,
+    });
+    let proof_try = mock_proof(app, "connections/connection-1");
+    execute(app, Message::ConnOpenAck {
+        connection_id: "connection-0".into(),
+        counterparty_connection_id: "connection-1".into(),
+        version: IbcConnectionVersion::default(),
+        proof_height: 2,
+        proof_try,
+    });
+    let proof_ack = mock_proof(app, "connections/connection-0");
+    execute(app, Message::ConnOpenConfirm {
+        connection_id: "connection-1".into(),
+        proof_height: 3,
+        proof_ack,
+    });
+
+    execute(app, Message::ChanOpenInit {
+        port_id: port.clone(),
+        connection_id: "connection-0".into(),
+        counterparty_port_id: port.to_string(),
+        ordering: IbcChannelOrder::Unordered,
+        version: "ics20-1".into(),
+    });
+    let proof_init = mock_proof(app, &format!("channelEnds/ports/{port}/channels/channel-0"));
+    execute(app, Message::ChanOpenTry {
+        port_id: port.clone(),
+        connection_id: "connection-1".into(),
+        counterparty: IbcChannelCounterparty {
+            port_id: port.to_string(),
+            channel_id: Some("channel-0".into()),
+        },
+        ordering: IbcChannelOrder::Unordered,
+        version: "ics20-1".into(),
+        counterparty_version: "ics20-1".into(),
+        proof_height: 5,
+        proof_init,
+    });
+    let proof_try = mock_proof(app, &format!("channelEnds/ports/{port}/channels/channel-1"));
+    execute(app, Message::ChanOpenAck {
+        port_id: port.clone(),
+        channel_id: "channel-0".into(),
+        counterparty_channel_id: "channel-1".into(),
+        counterparty_version: "ics20-1".into(),
+        proof_height: 6,
+        proof_try,
+    });
+    let proof_ack = mock_proof(app, &format!("channelEnds/ports/{port}/channels/channel-0"));
+    execute(app, Message::ChanOpenConfirm {
+        port_id: port.clone(),
+        channel_id: "channel-1".into(),
+        proof_height: 7,
+        proof_ack,
+    });
+}
+
+#[test]
+fn ibc_packets_work() {
+    let mut app = MockApp::new();
+
+    let (bank_code, bank_code_hash) = register(&app, "bank", bank());
+    let bank = genesis_address(&bank_code_hash, b"bank");
+
+    let (account_code, account_code_hash) = register(&app, "account", account());
+    let alice = genesis_address(&account_code_hash, b"alice");
+
+    let (client_code, client_code_hash) = register(&app, "client", client());
+    let client = genesis_address(&client_code_hash, b"client");
+
+    let (port_code, port_code_hash) = register(&app, "port", port());
+    let port = genesis_address(&port_code_hash, b"port");
+
+    let mut config = mock_config(bank);
+    config.allowed_clients = BTreeSet::from([client_code_hash.clone()]);
+    app.init_chain("dev-1", GenesisState {
+        config,
+        msgs: vec![
+            upload(bank_code),
+            upload(account_code),
+            upload(client_code),
+            upload(port_code),
+            instantiate(&bank_code_hash, b"bank"),
+            instantiate(&account_code_hash, b"alice"),
+            instantiate(&port_code_hash, b"port"),
+            create_client(&client_code_hash, b"client"),
+        ],
+    });
+
+    open_channel(&mut app, &alice, &client, &port);
+
+    let port_state =
+        |app: &MockApp| query_empty::<(Vec<Binary>, Vec<Binary>, Vec<u64>)>(app, &port);
+
+    // --------------------------- send and receive ----------------------------
+
+    let send = |data: &[u8], timeout_height: u64| Message::Execute {
+        contract: port.clone(),
+        msg: to_json_value(&("channel-0", Binary::from(data.to_vec()), timeout_height)).unwrap(),
+        funds: Coins::new_empty(),
+    };
+    let packet = |sequence: u64, data: &[u8], timeout_height: u64| IbcPacket {
+        sequence,
+        source_port: port.to_string(),
+        source_channel: "channel-0".into(),
+        destination_port: port.to_string(),
+        destination_channel: "channel-1".into(),
+        data: data.to_vec().into(),
+        timeout: IbcTimeout {
+            height: Some(timeout_height),
+            timestamp: None,
+        },
+    };
+    let commitment_path =
+        |sequence: u64| format!("commitments/ports/{port}/channels/channel-0/sequences/{sequence}");
+    let ack_path =
+        |sequence: u64| format!("acks/ports/{port}/channels/channel-1/sequences/{sequence}");
+
+    // block 9
+    let res = app.execute(&alice, vec![send(b"hello", 100)]).unwrap();
+    let event = res
+        .events
+        .iter()
+        .find(|event| event.r#type == "send_packet")
+        .unwrap();
+    assert!(event
+        .attributes
+        .contains(&Attribute::new("packet_sequence", "1")));
+
+    // a packet that doesn't match the commitment is rejected
+    let proof_commitment = mock_proof(&app, &commitment_path(1));
+    let err = app
+        .execute(&alice, vec![Message::RecvPacket {
+            packet: packet(1, b"hullo", 100),
+            proof_height: 9,
+            proof_commitment: proof_commitment.clone(),
+        }])
+        .unwrap_err();
+    assert!(err.to_string().contains("failed to verify proof"));
+
+    // block 11
+    let res = app
+        .execute(&alice, vec![Message::RecvPacket {
+            packet: packet(1, b"hello", 100),
+            proof_height: 9,
+            proof_commitment: proof_commitment.clone(),
+        }])
+        .unwrap();
+    let ack = to_json_vec(&IbcAcknowledgement::Result(b"ok".to_vec().into())).unwrap();
+    assert!(res
+        .events
+        .iter()
+        .find(|event| event.r#type == "write_acknowledgement")
+        .unwrap()
+        .attributes
+        .contains(&Attribute::new("packet_ack_hex", hex::encode(&ack))));
+    assert_eq!(port_state(&app).0, [Binary::from(b"hello".to_vec())]);
+
+    // a packet can't be received twice
+    let err = app
+        .execute(&alice, vec![Message::RecvPacket {
+            packet: packet(1, b"hello", 100),
+            proof_height: 9,
+            proof_commitment,
+        }])
+        .unwrap_err();
+    assert!(err.to_string().contains("already been received"));
+
+    let ack_msg = Message::Acknowledgement {
+        packet: packet(1, b"hello", 100),
+        acknowledgement: ack.clone().into(),
+        proof_height: 11,
+        proof_acked: mock_proof(&app, &ack_path(1)),
+    };
+    app.execute(&alice, vec![ack_msg.clone()]).unwrap();
+    assert_eq!(port_state(&app).1, [Binary::from(ack)]);
+
+    // the commitment is deleted, so it can't be acknowledged again
+    let err = app.execute(&alice, vec![ack_msg]).unwrap_err();
+    assert!(err.to_string().contains("doesn't match any commitment"));
+
+    // ------------------------- error acknowledgement -------------------------
+
+    // the receiving contract failing results in an error acknowledgement, and
+    // its state changes are discarded
+    app.execute(&alice, vec![send(b"fail", 100)]).unwrap(); // block 15
+    let proof_commitment = mock_proof(&app, &commitment_path(2));
+    let res = app
+        .execute(&alice, vec![Message::RecvPacket {
+            packet: packet(2, b"fail", 100),
+            proof_height: 15,
+            proof_commitment,
+        }])
+        .unwrap();
+    let attribute = |ty: &str, key: &str| {
+        res.events
+            .iter()
+            .find(|event| event.r#type == ty)
+            .unwrap()
+            .attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .unwrap()
+            .value
+            .clone()
+    };
+    let ack = attribute("write_acknowledgement", "packet_ack_hex");
+    let ack: IbcAcknowledgement = from_json_slice(hex::decode(ack).unwrap()).unwrap();
+    assert_eq!(
+        ack,
+        IbcAcknowledgement::Error(
+            "ABCI code: 1: error handling packet: see events for details".into()
+        )
+    );
+    // the error itself is in an event, as it's not committed to
+    assert_eq!(
+        attribute("recv_packet_error", "packet_error_category"),
+        "contract"
+    );
+    assert!(attribute("recv_packet_error", "packet_error").contains("packet rejected"));
+    assert_eq!(port_state(&app).0.len(), 1);
+
+    // -------------------------------- timeout --------------------------------
+
+    app.execute(&alice, vec![send(b"late", 18)]).unwrap(); // block 17
+
+    // the packet can't be received on or after the timeout height
+    let proof_commitment = mock_proof(&app, &commitment_path(3));
+    let err = app
+        .execute(&alice, vec![Message::RecvPacket {
+            packet: packet(3, b"late", 18),
+            proof_height: 17,
+            proof_commitment,
+        }])
+        .unwrap_err();
+    assert!(err.to_string().contains("has timed out"));
+
+    // the counterparty must have reached the timeout height
+    let timeout = |proof_height: u64| Message::Timeout {
+        packet: packet(3, b"late", 18),
+        next_sequence_recv: 0,
+        proof_height,
+        proof_unreceived: Json::Null,
+    };
+    let err = app.execute(&alice, vec![timeout(17)]).unwrap_err();
+    assert!(err.to_string().contains("not timed out"));
+
+    app.execute(&alice, vec![timeout(18)]).unwrap();
+    assert_eq!(port_state(&app).2, [3]);
+}
+
+// The counterparty must be able to prove a new channel's next receive sequence
+// before any packet is received, so all three sequences are stored as soon as
+// the channel is opened, on both ends.
+#[test]
+fn channel_sequences_start_at_one() {
+    let mut app = MockApp::new();
+
+    let (bank_code, bank_code_hash) = register(&app, "bank", bank());
+    let bank = genesis_address(&bank_code_hash, b"bank");
+
+    let (account_code, account_code_hash) = register(&app, "account", account());
+    let alice = genesis_address(&account_code_hash, b"alice");
+
+    let (client_code, client_code_hash) = register(&app, "client", client());
+    let client = genesis_address(&client_code_hash, b"client");
+
+    let (port_code, port_code_hash) = register(&app, "port", port());
+    let port = genesis_address(&port_code_hash, b"port");
+
+    let mut config = mock_config(bank);
+    config.allowed_clients = BTreeSet::from([client_code_hash.clone()]);
+    app.init_chain("dev-1", GenesisState {
+        config,
+        msgs: vec![
+            upload(bank_code),
+            upload(account_code),
+            upload(client_code),
+            upload(port_code),
+            instantiate(&bank_code_hash, b"bank"),
+            instantiate(&account_code_hash, b"alice"),
+            instantiate(&port_code_hash, b"port"),
+            create_client(&client_code_hash, b"client"),
+        ],
+    });
+
+    open_channel(&mut app, &alice, &client, &port);
+
+    for channel_id in ["channel-0", "channel-1"] {
+        for kind in ["Send", "Recv", "Ack"] {
+            let path = format!("nextSequence{kind}/ports/{port}/channels/{channel_id}");
+            let (value, _) = app.query_store(&[b"ibc", path.as_bytes()].concat(), 0, false);
+            assert_eq!(value, Some(1_u64.to_be_bytes().to_vec()), "{path}");
+        }
+    }
+}
@@ -0,0 +1,306 @@
+mod common;
+
+use {
+    common::{
+        account, bank, cometbft_header, genesis_address, instantiate, mock_config, port,
+        query_empty, register, upload, GrugLightClient,
+    },
+    grug_ibc_client_tendermint::{ClientState, Misbehavior},
+    grug_testing::{
+        LightClient, MockClientState, MockHeader, MockIbc, MockLightClient, MockMisbehavior, Side,
+    },
+    grug_types::{
+        from_json_slice, hash, to_json_value, Addr, Binary, Coins, GenesisState,
+        IbcAcknowledgement, IbcChannelOrder, IbcChannelState, Message, Permission, QueryRequest,
+    },
+    std::collections::BTreeSet,
+};
+
+/// Set up two chains with a relayer account, a user account, and a port
+/// contract each, connected by a pair of clients of the given light client and
+/// a connection. Return the addresses of the relayer, the user and the port
+/// (same on both chains), and the IDs of the connection on chain A and B.
+fn setup(
+    light_client: impl LightClient + 'static,
+) -> (MockIbc, Addr, Addr, Addr, (String, String)) {
+    let mut ibc = MockIbc::new().with_light_client(light_client);
+    let mut addresses = None;
+
+    for side in [Side::A, Side::B] {
+        let (bank_code, bank_code_hash) = register(ibc.chain(side), "bank", bank());
+        let (account_code, account_code_hash) = register(ibc.chain(side), "account", account());
+        let (port_code, port_code_hash) = register(ibc.chain(side), "port", port());
+
+        let relayer = genesis_address(&account_code_hash, b"relayer");
+        let alice = genesis_address(&account_code_hash, b"alice");
+        let port = genesis_address(&port_code_hash, b"port");
+
+        let mut config = mock_config(genesis_address(&bank_code_hash, b"bank"));
+        let only_relayer = Permission::Somebodies(BTreeSet::from([relayer.clone()]));
+        config.permissions.create_client = only_relayer.clone();
+        config.permissions.create_connection = only_relayer.clone();
+        config.permissions.create_channel = only_relayer;
+        let msgs = vec![
+            upload(bank_code),
+            upload(account_code),
+            upload(port_code),
+            instantiate(&bank_code_hash, b"bank"),
+            instantiate(&account_code_hash, b"relayer"),
+            instantiate(&account_code_hash, b"alice"),
+            instantiate(&port_code_hash, b"port"),
+        ];
+
+        let chain_id = match side {
+            Side::A => "chain-a",
+            Side::B => "chain-b",
+        };
+        ibc.init_chain(
+            side,
+            chain_id,
+            GenesisState { config, msgs },
+            relayer.clone(),
+        );
+
+        addresses = Some((relayer, alice, port));
+    }
+
+    let (relayer, alice, port) = addresses.unwrap();
+
+    ibc.create_clients().unwrap();
+    let connections = ibc.open_connection().unwrap();
+
+    (ibc, relayer, alice, port, connections)
+}
+
+fn port_state(ibc: &MockIbc, side: Side, port: &Addr) -> (Vec<Binary>, Vec<Binary>, Vec<u64>) {
+    query_empty(ibc.chain(side), port)
+}
+
+fn send(
+    ibc: &mut MockIbc,
+    side: Side,
+    sender: &Addr,
+    port: &Addr,
+    channel_id: &str,
+    data: &[u8],
+    timeout_height: u64,
+) {
+    ibc.execute(side, sender, vec![Message::Execute {
+        contract: port.clone(),
+        msg: to_json_value(&(channel_id, Binary::from(data.to_vec()), timeout_height)).unwrap(),
+        funds: Coins::new_empty(),
+    }])
+    .unwrap();
+}
+
+#[test]
+fn relaying_packets_works() {
+    relay_packets(MockLightClient);
+}
+
+#[test]
+fn relaying_packets_through_grug_client_works() {
+    relay_packets(GrugLightClient);
+}
+
+#[test]
+fn timing_out_ordered_packets_works() {
+    time_out_ordered_packets(MockLightClient);
+}
+
+#[test]
+fn timing_out_ordered_packets_through_grug_client_works() {
+    time_out_ordered_packets(GrugLightClient);
+}
+
+fn relay_packets(light_client: impl LightClient + 'static) {
+    let (mut ibc, _, alice, port, (connection_a, connection_b)) = setup(light_client);
+    let (channel_a, channel_b) = ibc
+        .open_channel(
+            (&connection_a, &connection_b),
+            (&port, &port),
+            IbcChannelOrder::Unordered,
+            "mock-1",
+        )
+        .unwrap();
+
+    // a packet from A to B, and one back that B rejects
+    send(&mut ibc, Side::A, &alice, &port, &channel_a, b"hello", 1000);
+    send(&mut ibc, Side::B, &alice, &port, &channel_b, b"fail", 1000);
+    ibc.relay().unwrap();
+
+    let (received, _, timed_out) = port_state(&ibc, Side::B, &port);
+    assert_eq!(received, vec![Binary::from(b"hello".to_vec())]);
+    assert!(timed_out.is_empty());
+
+    let (received, acks, _) = port_state(&ibc, Side::A, &port);
+    assert!(received.is_empty());
+    assert_eq!(acks.len(), 1);
+    assert_eq!(
+        from_json_slice::<IbcAcknowledgement>(&acks[0]).unwrap(),
+        IbcAcknowledgement::Result(b"ok".to_vec().into())
+    );
+
+    let (_, acks, _) = port_state(&ibc, Side::B, &port);
+    assert_eq!(acks.len(), 1);
+    assert!(matches!(
+        from_json_slice::<IbcAcknowledgement>(&acks[0]).unwrap(),
+        IbcAcknowledgement::Error(_)
+    ));
+
+    // a packet that times out as of B's next block
+    let timeout_height = ibc.header(Side::B).height + 1;
+    send(
+        &mut ibc,
+        Side::A,
+        &alice,
+        &port,
+        &channel_a,
+        b"late",
+        timeout_height,
+    );
+    ibc.relay().unwrap();
+
+    let (received, ..) = port_state(&ibc, Side::B, &port);
+    assert_eq!(received.len(), 1);
+    let (_, acks, timed_out) = port_state(&ibc, Side::A, &port);
+    assert_eq!(acks.len(), 1);
+    assert_eq!(timed_out, vec![2]);
+}
+
+fn time_out_ordered_packets(light_client: impl LightClient + 'static) {
+    let (mut ibc, _, alice, port, (connection_a, connection_b)) = setup(light_client);
+    let (channel_a, _) = ibc
+        .open_channel(
+            (&connection_a, &connection_b),
+            (&port, &port),
+            IbcChannelOrder::Ordered,
+            "mock-1",
+        )
+        .unwrap();
+
+    let timeout_height = ibc.header(Side::B).height + 1;
+    send(
+        &mut ibc,
+        Side::A,
+        &alice,
+        &port,
+        &channel_a,
+        b"late",
+        timeout_height,
+    );
+    ibc.relay().unwrap();
+
+    let (_, _, timed_out) = port_state(&ibc, Side::A, &port);
+    assert_eq!(timed_out, vec![1]);
+
+    // timing out a packet on an ordered channel closes it
+    let channel = ibc
+        .chain(Side::A)
+        .query(QueryRequest::Channel {
+            port_id: port.clone(),
+            channel_id: channel_a,
+        })
+        .as_channel();
+    assert_eq!(channel.state, IbcChannelState::Closed);
+}
+
+#[test]
+fn misbehavior_freezes_client() {
+    let (mut ibc, relayer, ..) = setup(MockLightClient);
+
+    // two different headers of chain B at the same height
+    let header_1 = ibc.header(Side::B);
+    let header_2 = MockHeader {
+        app_hash: hash(b"forged"),
+        ..header_1.clone()
+    };
+
+    let client_id = ibc.client_id(Side::A).clone();
+    ibc.execute(Side::A, &relayer, vec![Message::SubmitMisbehavior {
+        client_id: client_id.clone(),
+        misbehavior: to_json_value(&MockMisbehavior { header_1, header_2 }).unwrap(),
+    }])
+    .unwrap();
+
+    let client = ibc
+        .chain(Side::A)
+        .query(QueryRequest::Client { client_id })
+        .as_client();
+    let client_state: MockClientState = from_json_slice(client.client_state).unwrap();
+    assert!(client_state.frozen);
+
+    // a frozen client can no longer be updated
+    ibc.chain_mut(Side::B).make_block(vec![]);
+    let err = ibc.update_client(Side::A).unwrap_err();
+    assert!(err.to_string().contains("client is frozen"));
+}
+
+#[test]
+fn grug_client_rejects_forged_headers() {
+    let (mut ibc, relayer, ..) = setup(GrugLightClient);
+    ibc.chain_mut(Side::B).make_block(vec![]);
+
+    // a commit of chain B's latest block, with the app hash changed after it
+    // was signed
+    let trusted_height = query_grug_client(&ibc, Side::A).latest_height;
+    let mut header = cometbft_header(&ibc.header(Side::B), trusted_height);
+    header.signed_header.header.app_hash = hash(b"forged").to_vec().into();
+
+    let err = ibc
+        .execute(Side::A, &relayer, vec![Message::UpdateClient {
+            client_id: ibc.client_id(Side::A).clone(),
+            header: to_json_value(&header).unwrap(),
+        }])
+        .unwrap_err();
+    assert!(err.to_string().contains("commit is not for the header"));
+
+    // the genuine commit is accepted
+    ibc.update_client(Side::A).unwrap();
+    assert_eq!(
+        query_grug_client(&ibc, Side::A).latest_height,
+        ibc.header(Side::B).height
+    );
+}
+
+#[test]
+fn grug_client_misbehavior_freezes_client() {
+    let (mut ibc, relayer, ..) = setup(GrugLightClient);
+    ibc.chain_mut(Side::B).make_block(vec![]);
+
+    // two commits of different blocks at the same height, both signed by
+    // chain B's validators
+    let trusted_height = query_grug_client(&ibc, Side::A).latest_height;
+    let header = ibc.header(Side::B);
+    let misbehavior = Misbehavior {
+        header_1: cometbft_header(&header, trusted_height),
+        header_2: cometbft_header(
+            &MockHeader {
+                app_hash: hash(b"forged"),
+                ..header.clone()
+            },
+            trusted_height,
+        ),
+    };
+
+    ibc.execute(Side::A, &relayer, vec![Message::SubmitMisbehavior {
+        client_id: ibc.client_id(Side::A).clone(),
+        misbehavior: to_json_value(&misbehavior).unwrap(),
+    }])
+    .unwrap();
+
+    assert_eq!(
+        query_grug_client(&ibc, Side::A).frozen_height,
+        Some(header.height)
+    );
+}
+
+fn query_grug_client(ibc: &MockIbc, side: Side) -> ClientState {
+    let client = ibc
+        .chain(side)
+        .query(QueryRequest::Client {
+            client_id: ibc.client_id(side).clone(),
+        })
+        .as_client();
+    from_json_slice(client.client_state).unwrap()
+}
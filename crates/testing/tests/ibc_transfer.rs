@@ -0,0 +1,464 @@
+mod common;
+
+use {
+    common::{account, genesis_address, instantiate, mock_config, register, upload},
+    grug_bank::ExecuteMsg as BankExecuteMsg,
+    grug_ibc_transfer::{DenomTrace, ExecuteMsg, QueryMsg, ICS20_VERSION},
+    grug_testing::{MockIbc, Side},
+    grug_types::{
+        from_json_value, to_json_value, Addr, BankMsg, BankQuery, BankQueryResponse, Coins, Empty,
+        GenesisState, Hash, IbcChannelOrder, IbcTimeout, ImmutableCtx, Json, Message, MutableCtx,
+        NumberConst, QueryRequest, Response, StdError, StdResult, SudoCtx, Uint128,
+    },
+    grug_vm_rust::{ContractBuilder, ContractWrapper},
+    std::collections::BTreeMap,
+};
+
+const DENOM: &str = "uatom";
+
+// ------------------------------ native contracts -----------------------------
+
+// The bank and the ICS-20 contract run natively, reusing the logics of the Wasm
+// contracts.
+
+fn bank() -> ContractWrapper {
+    ContractBuilder::new(Box::new(bank_instantiate))
+        .with_execute(Box::new(bank_execute_msg))
+        .with_bank_execute(Box::new(bank_execute))
+        .with_bank_query(Box::new(bank_query))
+        .build()
+}
+
+fn bank_instantiate(ctx: MutableCtx, msg: grug_bank::InstantiateMsg) -> StdResult<Response> {
+    grug_bank::initialize(ctx.storage, msg.initial_balances, msg.namespaces)
+}
+
+fn bank_execute_msg(ctx: MutableCtx, msg: BankExecuteMsg) -> anyhow::Result<Response> {
+    match msg {
+        BankExecuteMsg::Mint { to, denom, amount } => grug_bank::mint(ctx, to, denom, amount),
+        BankExecuteMsg::Burn {
+            from,
+            denom,
+            amount,
+        } => grug_bank::burn(ctx, from, denom, amount),
+        _ => anyhow::bail!("unsupported bank message"),
+    }
+}
+
+fn bank_execute(ctx: SudoCtx, msg: BankMsg) -> StdResult<Response> {
+    grug_bank::transfer(ctx.storage, &msg.from, &msg.to, &msg.coins)
+}
+
+fn bank_query(ctx: ImmutableCtx, msg: BankQuery) -> StdResult<BankQueryResponse> {
+    match msg {
+        BankQuery::Balance { address, denom } => {
+            grug_bank::query_balance(ctx.storage, address, denom).map(BankQueryResponse::Balance)
+        },
+        BankQuery::Supply { denom } => {
+            grug_bank::query_supply(ctx.storage, denom).map(BankQueryResponse::Supply)
+        },
+        _ => Err(StdError::generic_err("unsupported bank query")),
+    }
+}
+
+fn ics20() -> ContractWrapper {
+    ContractBuilder::new(Box::new(ics20_instantiate))
+        .with_execute(Box::new(ics20_execute))
+        .with_query(Box::new(ics20_query))
+        .with_ibc_channel_open(Box::new(|_ctx, msg| grug_ibc_transfer::open_channel(msg)))
+        .with_ibc_channel_connect(Box::new(|_ctx, msg| {
+            grug_ibc_transfer::connect_channel(msg)
+        }))
+        .with_ibc_packet_receive(Box::new(grug_ibc_transfer::receive_packet))
+        .with_ibc_packet_ack(Box::new(grug_ibc_transfer::acknowledge_packet))
+        .with_ibc_packet_timeout(Box::new(grug_ibc_transfer::timeout_packet))
+        .build()
+}
+
+fn ics20_instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn ics20_execute(ctx: MutableCtx, msg: ExecuteMsg) -> anyhow::Result<Response> {
+    match msg {
+        ExecuteMsg::Transfer {
+            channel_id,
+            receiver,
+            timeout,
+            memo,
+        } => grug_ibc_transfer::transfer(ctx, channel_id, receiver, timeout, memo),
+    }
+}
+
+fn ics20_query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
+    match msg {
+        QueryMsg::DenomTrace { hash } => {
+            to_json_value(&grug_ibc_transfer::query_denom_trace(ctx.storage, hash)?)
+        },
+        QueryMsg::Escrow {
+            channel_id,
+            start_after,
+            limit,
+        } => to_json_value(&grug_ibc_transfer::query_escrow(
+            ctx.storage,
+            channel_id,
+            start_after,
+            limit,
+        )?),
+        _ => Err(StdError::generic_err("unsupported query")),
+    }
+}
+
+// --------------------------------- helpers ----------------------------------
+
+/// Set up two chains, each with a bank that assigns the `ibc` namespace to the
+/// ICS-20 contract, and a user holding 100 `uatom`, connected by a pair of
+/// clients and a connection. Return the addresses of the user and the ICS-20
+/// contract (same on both chains), and the IDs of the connection on chain A
+/// and B.
+fn setup() -> (MockIbc, Addr, Addr, (String, String)) {
+    let mut ibc = MockIbc::new();
+    let mut addresses = None;
+
+    for side in [Side::A, Side::B] {
+        let (bank_code, bank_code_hash) = register(ibc.chain(side), "bank", bank());
+        let (account_code, account_code_hash) = register(ibc.chain(side), "account", account());
+        let (ics20_code, ics20_code_hash) = register(ibc.chain(side), "ics20", ics20());
+
+        let relayer = genesis_address(&account_code_hash, b"relayer");
+        let alice = genesis_address(&account_code_hash, b"alice");
+        let ics20 = genesis_address(&ics20_code_hash, b"ics20");
+
+        let bank_msg = grug_bank::InstantiateMsg {
+            initial_balances: BTreeMap::from([(
+                alice.clone(),
+                Coins::new_one(DENOM, Uint128::new(100)),
+            )]),
+            namespaces: BTreeMap::from([("ibc".to_string(), ics20.clone())]),
+        };
+        let msgs = vec![
+            upload(bank_code),
+            upload(account_code),
+            upload(ics20_code),
+            instantiate_with(&bank_code_hash, b"bank", &bank_msg),
+            instantiate(&account_code_hash, b"relayer"),
+            instantiate(&account_code_hash, b"alice"),
+            instantiate(&ics20_code_hash, b"ics20"),
+        ];
+
+        let chain_id = match side {
+            Side::A => "chain-a",
+            Side::B => "chain-b",
+        };
+        ibc.init_chain(
+            side,
+            chain_id,
+            GenesisState {
+                config: mock_config(genesis_address(&bank_code_hash, b"bank")),
+                msgs,
+            },
+            relayer,
+        );
+
+        addresses = Some((alice, ics20));
+    }
+
+    let (alice, ics20) = addresses.unwrap();
+
+    ibc.create_clients().unwrap();
+    let connections = ibc.open_connection().unwrap();
+
+    (ibc, alice, ics20, connections)
+}
+
+/// Set up the two chains, and open an ICS-20 channel between them. Return the
+/// IDs of the channel on chain A and B.
+fn setup_with_channel() -> (MockIbc, Addr, Addr, (String, String)) {
+    let (mut ibc, alice, ics20, (connection_a, connection_b)) = setup();
+    let channels = ibc
+        .open_channel(
+            (&connection_a, &connection_b),
+            (&ics20, &ics20),
+            IbcChannelOrder::Unordered,
+            ICS20_VERSION,
+        )
+        .unwrap();
+
+    (ibc, alice, ics20, channels)
+}
+
+fn instantiate_with(code_hash: &Hash, salt: &[u8], msg: &grug_bank::InstantiateMsg) -> Message {
+    Message::Instantiate {
+        code_hash: code_hash.clone(),
+        msg: to_json_value(msg).unwrap(),
+        salt: salt.to_vec().into(),
+        funds: Coins::new_empty(),
+        admin: None,
+    }
+}
+
+/// Send tokens from the given chain through the ICS-20 contract, timing out at
+/// the given height of the counterparty chain.
+fn send(
+    ibc: &mut MockIbc,
+    side: Side,
+    (sender, ics20): (&Addr, &Addr),
+    channel_id: &str,
+    receiver: &str,
+    (denom, amount): (&str, u128),
+    timeout_height: u64,
+) {
+    ibc.execute(side, sender, vec![Message::Execute {
+        contract: ics20.clone(),
+        msg: to_json_value(&ExecuteMsg::Transfer {
+            channel_id: channel_id.into(),
+            receiver: receiver.into(),
+            timeout: IbcTimeout {
+                height: Some(timeout_height),
+                timestamp: None,
+            },
+            memo: None,
+        })
+        .unwrap(),
+        funds: Coins::new_one(denom, Uint128::new(amount)),
+    }])
+    .unwrap();
+}
+
+fn balance(ibc: &MockIbc, side: Side, address: &Addr, denom: &str) -> Uint128 {
+    ibc.chain(side)
+        .query(QueryRequest::Balance {
+            address: address.clone(),
+            denom: denom.into(),
+        })
+        .as_balance()
+        .amount
+}
+
+fn supply(ibc: &MockIbc, side: Side, denom: &str) -> Uint128 {
+    ibc.chain(side)
+        .query(QueryRequest::Supply {
+            denom: denom.into(),
+        })
+        .as_supply()
+        .amount
+}
+
+fn escrow(ibc: &MockIbc, side: Side, ics20: &Addr, channel_id: &str) -> Coins {
+    let data = ibc
+        .chain(side)
+        .query(QueryRequest::WasmSmart {
+            contract: ics20.clone(),
+            msg: to_json_value(&QueryMsg::Escrow {
+                channel_id: channel_id.into(),
+                start_after: None,
+                limit: None,
+            })
+            .unwrap(),
+        })
+        .as_wasm_smart()
+        .data;
+    from_json_value(data).unwrap()
+}
+
+/// The trace of the vouchers minted on chain B for `uatom` coming from chain A.
+fn voucher_trace(ics20: &Addr, channel_b: &str) -> DenomTrace {
+    DenomTrace::parse(&format!("{ics20}/{channel_b}/{DENOM}"))
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[test]
+fn escrowing_and_releasing_native_tokens_works() {
+    let (mut ibc, alice, ics20, (channel_a, channel_b)) = setup_with_channel();
+    let voucher = voucher_trace(&ics20, &channel_b).local_denom();
+
+    // A is the source of the tokens, so they're held in escrow on A
+    send(
+        &mut ibc,
+        Side::A,
+        (&alice, &ics20),
+        &channel_a,
+        &alice.to_string(),
+        (DENOM, 30),
+        1000,
+    );
+    ibc.relay().unwrap();
+
+    assert_eq!(balance(&ibc, Side::A, &alice, DENOM), Uint128::new(70));
+    assert_eq!(
+        escrow(&ibc, Side::A, &ics20, &channel_a),
+        Coins::new_one(DENOM, Uint128::new(30))
+    );
+
+    // when the tokens come back, they're released from escrow
+    send(
+        &mut ibc,
+        Side::B,
+        (&alice, &ics20),
+        &channel_b,
+        &alice.to_string(),
+        (&voucher, 30),
+        1000,
+    );
+    ibc.relay().unwrap();
+
+    assert_eq!(balance(&ibc, Side::A, &alice, DENOM), Uint128::new(100));
+    assert!(escrow(&ibc, Side::A, &ics20, &channel_a).is_empty());
+    assert_eq!(balance(&ibc, Side::A, &ics20, DENOM), Uint128::ZERO);
+}
+
+#[test]
+fn minting_and_burning_back_vouchers_works() {
+    let (mut ibc, alice, ics20, (channel_a, channel_b)) = setup_with_channel();
+    let trace = voucher_trace(&ics20, &channel_b);
+    let voucher = trace.local_denom();
+
+    // B isn't the source of the tokens, so vouchers are minted on B
+    send(
+        &mut ibc,
+        Side::A,
+        (&alice, &ics20),
+        &channel_a,
+        &alice.to_string(),
+        (DENOM, 30),
+        1000,
+    );
+    ibc.relay().unwrap();
+
+    assert_eq!(voucher, format!("ibc/{}", trace.hash()));
+    assert_eq!(balance(&ibc, Side::B, &alice, &voucher), Uint128::new(30));
+    assert_eq!(supply(&ibc, Side::B, &voucher), Uint128::new(30));
+
+    let data = ibc
+        .chain(Side::B)
+        .query(QueryRequest::WasmSmart {
+            contract: ics20.clone(),
+            msg: to_json_value(&QueryMsg::DenomTrace { hash: trace.hash() }).unwrap(),
+        })
+        .as_wasm_smart()
+        .data;
+    assert_eq!(from_json_value::<DenomTrace>(data).unwrap(), trace);
+
+    // sending the vouchers back to A burns them
+    send(
+        &mut ibc,
+        Side::B,
+        (&alice, &ics20),
+        &channel_b,
+        &alice.to_string(),
+        (&voucher, 10),
+        1000,
+    );
+    ibc.relay().unwrap();
+
+    assert_eq!(balance(&ibc, Side::B, &alice, &voucher), Uint128::new(20));
+    assert_eq!(balance(&ibc, Side::B, &ics20, &voucher), Uint128::ZERO);
+    assert_eq!(supply(&ibc, Side::B, &voucher), Uint128::new(20));
+    assert_eq!(balance(&ibc, Side::A, &alice, DENOM), Uint128::new(80));
+}
+
+#[test]
+fn refunding_on_error_acknowledgement_works() {
+    let (mut ibc, alice, ics20, (channel_a, channel_b)) = setup_with_channel();
+    let voucher = voucher_trace(&ics20, &channel_b).local_denom();
+
+    // B fails to receive the packet, since the receiver isn't a valid address
+    send(
+        &mut ibc,
+        Side::A,
+        (&alice, &ics20),
+        &channel_a,
+        "larry",
+        (DENOM, 30),
+        1000,
+    );
+    ibc.relay().unwrap();
+
+    assert_eq!(supply(&ibc, Side::B, &voucher), Uint128::ZERO);
+    assert_eq!(balance(&ibc, Side::A, &alice, DENOM), Uint128::new(100));
+    assert!(escrow(&ibc, Side::A, &ics20, &channel_a).is_empty());
+}
+
+#[test]
+fn refunding_on_timeout_works() {
+    let (mut ibc, alice, ics20, (channel_a, channel_b)) = setup_with_channel();
+    let voucher = voucher_trace(&ics20, &channel_b).local_denom();
+
+    send(
+        &mut ibc,
+        Side::A,
+        (&alice, &ics20),
+        &channel_a,
+        &alice.to_string(),
+        (DENOM, 30),
+        1000,
+    );
+    ibc.relay().unwrap();
+
+    // vouchers sent back to A that time out as of A's next block are minted
+    // again on B
+    let timeout_height = ibc.header(Side::A).height + 1;
+    send(
+        &mut ibc,
+        Side::B,
+        (&alice, &ics20),
+        &channel_b,
+        &alice.to_string(),
+        (&voucher, 10),
+        timeout_height,
+    );
+    ibc.relay().unwrap();
+
+    assert_eq!(balance(&ibc, Side::B, &alice, &voucher), Uint128::new(30));
+    assert_eq!(supply(&ibc, Side::B, &voucher), Uint128::new(30));
+    assert_eq!(balance(&ibc, Side::A, &alice, DENOM), Uint128::new(70));
+
+    // native tokens that time out are released from escrow
+    let timeout_height = ibc.header(Side::B).height + 1;
+    send(
+        &mut ibc,
+        Side::A,
+        (&alice, &ics20),
+        &channel_a,
+        &alice.to_string(),
+        (DENOM, 20),
+        timeout_height,
+    );
+    ibc.relay().unwrap();
+
+    assert_eq!(balance(&ibc, Side::A, &alice, DENOM), Uint128::new(70));
+    assert_eq!(
+        escrow(&ibc, Side::A, &ics20, &channel_a),
+        Coins::new_one(DENOM, Uint128::new(30))
+    );
+    assert_eq!(supply(&ibc, Side::B, &voucher), Uint128::new(30));
+}
+
+#[test]
+fn rejecting_non_ics20_channels_works() {
+    let (mut ibc, _, ics20, (connection_a, connection_b)) = setup();
+
+    for (ordering, version) in [
+        (IbcChannelOrder::Ordered, ICS20_VERSION),
+        (IbcChannelOrder::Unordered, "ics20-2"),
+    ] {
+        let err = ibc
+            .open_channel(
+                (&connection_a, &connection_b),
+                (&ics20, &ics20),
+                ordering,
+                version,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid channel"));
+    }
+
+    ibc.open_channel(
+        (&connection_a, &connection_b),
+        (&ics20, &ics20),
+        IbcChannelOrder::Unordered,
+        ICS20_VERSION,
+    )
+    .unwrap();
+}
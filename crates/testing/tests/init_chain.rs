@@ -0,0 +1,41 @@
+mod common;
+
+use {
+    common::{bank, genesis_address, instantiate, mock_config, register, upload},
+    grug_testing::MockApp,
+    grug_types::{GenesisState, QueryRequest},
+};
+
+#[test]
+fn init_chain_works() {
+    let mut app = MockApp::new();
+
+    let (bank_code, bank_code_hash) = register(&app, "bank", bank());
+    let bank = genesis_address(&bank_code_hash, b"bank");
+
+    app.init_chain("dev-1", GenesisState {
+        config: mock_config(bank.clone()),
+        msgs: vec![upload(bank_code), instantiate(&bank_code_hash, b"bank")],
+    });
+
+    let info = app.query(QueryRequest::Info {}).as_info();
+    assert_eq!(info.chain_id, "dev-1");
+    assert_eq!(info.config.bank, bank);
+
+    let code_hashes = app
+        .query(QueryRequest::Codes {
+            start_after: None,
+            limit: None,
+        })
+        .as_codes();
+    assert_eq!(code_hashes, [bank_code_hash]);
+
+    let accounts = app
+        .query(QueryRequest::Accounts {
+            start_after: None,
+            limit: None,
+        })
+        .as_accounts();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].address, bank);
+}
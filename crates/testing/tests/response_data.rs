@@ -0,0 +1,100 @@
+mod common;
+
+use {
+    common::{
+        account, bank, counter, empty_instantiate, genesis_address, instantiate, mock_config,
+        query_empty, register, upload,
+    },
+    grug_storage::Item,
+    grug_testing::MockApp,
+    grug_types::{
+        to_json_value, to_json_vec, Addr, Binary, Coins, Empty, GenesisState, ImmutableCtx, Json,
+        Message, MutableCtx, Response, StdResult, SubMessage, SubMsgResult, SudoCtx,
+    },
+    grug_vm_rust::ContractBuilder,
+};
+
+const LAST_DATA: Item<Binary> = Item::new("last_data");
+
+fn relay_execute(_ctx: MutableCtx, target: Addr) -> StdResult<Response> {
+    let msg = Message::Execute {
+        contract: target,
+        msg: to_json_value(&false)?,
+        funds: Coins::new_empty(),
+    };
+    Ok(Response::new()
+        .add_submessage(SubMessage::reply_on_success(msg, &Empty {})?)
+        .set_data(b"relayed".to_vec()))
+}
+
+fn relay_reply(ctx: SudoCtx, _msg: Empty, res: SubMsgResult) -> StdResult<Response> {
+    if let Some(data) = res.as_ok().data {
+        LAST_DATA.save(ctx.storage, &data)?;
+    }
+    Ok(Response::new())
+}
+
+fn relay_query(ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    to_json_value(&LAST_DATA.load(ctx.storage)?)
+}
+
+#[test]
+fn response_data_is_returned() {
+    let mut app = MockApp::new();
+
+    let (bank_code, bank_code_hash) = register(&app, "bank", bank());
+    let bank = genesis_address(&bank_code_hash, b"bank");
+
+    let (account_code, account_code_hash) = register(&app, "account", account());
+    let alice = genesis_address(&account_code_hash, b"alice");
+
+    let (counter_code, counter_code_hash) = register(&app, "counter", counter());
+    let counter = genesis_address(&counter_code_hash, b"counter");
+
+    let relay_contract = ContractBuilder::new(Box::new(empty_instantiate))
+        .with_execute(Box::new(relay_execute))
+        .with_reply(Box::new(relay_reply))
+        .with_query(Box::new(relay_query))
+        .build();
+    let (relay_code, relay_code_hash) = register(&app, "relay", relay_contract);
+    let relay = genesis_address(&relay_code_hash, b"relay");
+
+    app.init_chain("dev-1", GenesisState {
+        config: mock_config(bank),
+        msgs: vec![
+            upload(bank_code),
+            upload(account_code),
+            upload(counter_code),
+            upload(relay_code),
+            instantiate(&bank_code_hash, b"bank"),
+            instantiate(&account_code_hash, b"alice"),
+            instantiate(&counter_code_hash, b"counter"),
+            instantiate(&relay_code_hash, b"relay"),
+        ],
+    });
+
+    let res = app
+        .execute(&alice, vec![
+            Message::Execute {
+                contract: counter.clone(),
+                msg: to_json_value(&false).unwrap(),
+                funds: Coins::new_empty(),
+            },
+            Message::Execute {
+                contract: relay.clone(),
+                msg: to_json_value(&counter).unwrap(),
+                funds: Coins::new_empty(),
+            },
+        ])
+        .unwrap();
+
+    // each message's data is returned in the tx result, in order
+    assert_eq!(res.data, vec![
+        Some(to_json_vec(&1).unwrap().into()),
+        Some(b"relayed".to_vec().into()),
+    ]);
+
+    // the submessage's data is passed to the emitting contract's reply
+    let data: Binary = query_empty(&app, &relay);
+    assert_eq!(data, Binary::from(to_json_vec(&2).unwrap()));
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use {
+    common::{account, bank, genesis_address, instantiate, mock_config, register, upload},
+    grug_app::MAX_SAVEPOINT_DEPTH,
+    grug_storage::Item,
+    grug_testing::MockApp,
+    grug_types::{
+        to_json_value, Coins, Empty, GenesisState, Hash, Message, MutableCtx, ReplyOn, Response,
+        StdResult, SubMessage, Timestamp, Tx,
+    },
+    grug_vm_rust::ContractBuilder,
+};
+
+const LEVELS: Item<Vec<u32>> = Item::new("levels");
+
+fn nester_instantiate(ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+    LEVELS.save(ctx.storage, &vec![])?;
+    Ok(Response::new())
+}
+
+// Record the level, then execute itself one level down as a submessage, until
+// level zero is reached.
+fn nester_execute(ctx: MutableCtx, level: u32) -> StdResult<Response> {
+    let mut levels = LEVELS.load(ctx.storage)?;
+    levels.push(level);
+    LEVELS.save(ctx.storage, &levels)?;
+
+    if level == 0 {
+        return Ok(Response::new());
+    }
+
+    Ok(Response::new().add_submessage(SubMessage {
+        msg: Message::Execute {
+            contract: ctx.contract,
+            msg: to_json_value(&(level - 1))?,
+            funds: Coins::new_empty(),
+        },
+        reply_on: ReplyOn::Never,
+    }))
+}
+
+/// Execute chains of submessages nested as deeply as allowed, and one level
+/// deeper, and return whether each succeeded, along with the app hash after
+/// each block.
+fn run_nested(track_state_diffs: bool) -> Vec<(Result<(), String>, bool, Hash)> {
+    let mut app = MockApp::new()
+        .with_state_diffs(track_state_diffs)
+        .with_genesis_time(Timestamp::from_seconds(1));
+
+    let (bank_code, bank_code_hash) = register(&app, "bank", bank());
+    let bank = genesis_address(&bank_code_hash, b"bank");
+
+    let (account_code, account_code_hash) = register(&app, "account", account());
+    let alice = genesis_address(&account_code_hash, b"alice");
+
+    let nester_contract = ContractBuilder::new(Box::new(nester_instantiate))
+        .with_execute(Box::new(nester_execute))
+        .build();
+    let (nester_code, nester_code_hash) = register(&app, "nester", nester_contract);
+    let nester = genesis_address(&nester_code_hash, b"nester");
+
+    app.init_chain("dev-1", GenesisState {
+        config: mock_config(bank),
+        msgs: vec![
+            upload(bank_code),
+            upload(account_code),
+            upload(nester_code),
+            instantiate(&bank_code_hash, b"bank"),
+            instantiate(&account_code_hash, b"alice"),
+            instantiate(&nester_code_hash, b"nester"),
+        ],
+    });
+
+    // one savepoint is taken for the tx's messages, and one for each of the
+    // submessages, i.e. one for each level below the top one
+    let max_level = (MAX_SAVEPOINT_DEPTH - 1) as u32;
+
+    [max_level, max_level + 1]
+        .into_iter()
+        .map(|level| {
+            let tx = Tx {
+                sender: alice.clone(),
+                msgs: vec![Message::Execute {
+                    contract: nester.clone(),
+                    msg: to_json_value(&level).unwrap(),
+                    funds: Coins::new_empty(),
+                }],
+                credential: Vec::new().into(),
+            };
+            let (_, app_hash, outcomes) = app.make_block(vec![tx]);
+            let outcome = outcomes.into_iter().next().unwrap();
+            let result = outcome.result.map(|_| ()).map_err(|err| err.to_string());
+            (result, outcome.state_diff.is_some(), app_hash)
+        })
+        .collect()
+}
+
+// Tracking state diffs must not take up any of the savepoint depth available
+// to transactions, or change their results in any other way.
+#[test]
+fn state_diffs_dont_affect_execution() {
+    let without_diffs = run_nested(false);
+    let with_diffs = run_nested(true);
+
+    // submessages nested as deeply as allowed succeed, one level deeper fail
+    assert!(with_diffs[0].0.is_ok());
+    assert!(with_diffs[1]
+        .0
+        .as_ref()
+        .is_err_and(|err| err.contains("too many nested savepoints")));
+
+    for ((result, diff_tracked, app_hash), (expect_result, _, expect_app_hash)) in
+        with_diffs.into_iter().zip(without_diffs)
+    {
+        assert!(diff_tracked);
+        assert_eq!(result, expect_result);
+        assert_eq!(app_hash, expect_app_hash);
+    }
+}
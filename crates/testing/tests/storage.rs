@@ -0,0 +1,96 @@
+mod common;
+
+use {
+    common::{bank, genesis_address, instantiate, mock_config, register, upload},
+    grug_storage::Map,
+    grug_testing::MockApp,
+    grug_types::{
+        from_json_value, to_json_value, Addr, Binary, Empty, GenesisState, ImmutableCtx, Json,
+        MutableCtx, Order, QueryRequest, Response, StdResult,
+    },
+    grug_vm_rust::ContractBuilder,
+};
+
+const NAMES: Map<u32, String> = Map::new("names");
+
+fn store_instantiate(ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+    for i in 1..=150 {
+        NAMES.save(ctx.storage, i, &format!("name_{i}"))?;
+    }
+    Ok(Response::new())
+}
+
+// With a `null` message, iterate the contract's own names. Otherwise, iterate
+// those of the given contract via the querier, requesting more than the host
+// returns in one page.
+fn store_query(ctx: ImmutableCtx, other: Option<Addr>) -> StdResult<Json> {
+    let names = match other {
+        None => NAMES
+            .range(ctx.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?,
+        Some(contract) => NAMES.query_range(&ctx.querier, contract, None, None, Some(1000))?,
+    };
+    to_json_value(&names)
+}
+
+// Contracts iterate their storage without the host's `wasm` and address prefix
+// in the keys, both their own and, via `WasmScan`, other contracts'.
+#[test]
+fn storage_scans_strip_namespace() {
+    let mut app = MockApp::new();
+
+    let (bank_code, bank_code_hash) = register(&app, "bank", bank());
+    let bank = genesis_address(&bank_code_hash, b"bank");
+
+    let store_contract = ContractBuilder::new(Box::new(store_instantiate))
+        .with_query(Box::new(store_query))
+        .build();
+    let (store_code, store_code_hash) = register(&app, "store", store_contract);
+    let alice = genesis_address(&store_code_hash, b"alice");
+    let bob = genesis_address(&store_code_hash, b"bob");
+
+    app.init_chain("dev-1", GenesisState {
+        config: mock_config(bank),
+        msgs: vec![
+            upload(bank_code),
+            upload(store_code),
+            instantiate(&bank_code_hash, b"bank"),
+            instantiate(&store_code_hash, b"alice"),
+            instantiate(&store_code_hash, b"bob"),
+        ],
+    });
+
+    let expected = (1..=150)
+        .map(|i| (i, format!("name_{i}")))
+        .collect::<Vec<_>>();
+
+    let query_names = |other: Option<&Addr>| -> Vec<(u32, String)> {
+        let data = app
+            .query(QueryRequest::WasmSmart {
+                contract: alice.clone(),
+                msg: to_json_value(&other).unwrap(),
+            })
+            .as_wasm_smart()
+            .data;
+        from_json_value(data).unwrap()
+    };
+
+    // the contract's own storage
+    assert_eq!(query_names(None), expected);
+
+    // another contract's storage, at most one page of 100 records
+    assert_eq!(query_names(Some(&bob)), expected[..100]);
+
+    // the raw keys returned to clients are those the contract wrote
+    let records = app
+        .query(QueryRequest::WasmScan {
+            contract: bob,
+            min: None,
+            max: None,
+            limit: Some(1),
+        })
+        .as_wasm_scan();
+    assert_eq!(records.into_keys().collect::<Vec<_>>(), [Binary::from(
+        [b"\x00\x05names".as_slice(), &1_u32.to_be_bytes()].concat()
+    )]);
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use {
+    common::{
+        bank, counter, empty_instantiate, genesis_address, instantiate, mock_config, query_empty,
+        register, upload,
+    },
+    grug_storage::Item,
+    grug_testing::MockApp,
+    grug_types::{
+        from_json_slice, to_json_string, to_json_value, Addr, Coins, Empty, ErrorCategory,
+        ExecutionError, GenesisState, ImmutableCtx, Json, Message, MutableCtx, Response, StdError,
+        StdResult,
+    },
+    grug_vm_rust::ContractBuilder,
+};
+
+const CALL_RESULTS: Item<Vec<String>> = Item::new("call_results");
+
+fn sync_caller_execute(ctx: MutableCtx, (target, fail): (Addr, bool)) -> StdResult<Response> {
+    let result = match ctx.call_and_decode::<_, u32>(target.clone(), &fail, Coins::new_empty()) {
+        // the callee's state changes are visible as soon as the call returns
+        Ok(returned) => {
+            let count: u32 = ctx.querier.query_wasm_smart(target, &Empty {})?;
+            format!("returned {returned}, count = {count}")
+        },
+        // errors of the callee keep their structure
+        Err(StdError::Execution(err)) => to_json_string(&err)?,
+        Err(err) => err.to_string(),
+    };
+
+    let mut results = CALL_RESULTS.may_load(ctx.storage)?.unwrap_or_default();
+    results.push(result);
+    CALL_RESULTS.save(ctx.storage, &results)?;
+
+    Ok(Response::new())
+}
+
+fn sync_caller_query(ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    to_json_value(&CALL_RESULTS.load(ctx.storage)?)
+}
+
+#[test]
+fn synchronous_calls_work() {
+    let mut app = MockApp::new();
+
+    let (bank_code, bank_code_hash) = register(&app, "bank", bank());
+    let bank = genesis_address(&bank_code_hash, b"bank");
+
+    let (counter_code, counter_code_hash) = register(&app, "counter", counter());
+    let counter = genesis_address(&counter_code_hash, b"counter");
+
+    let caller_contract = ContractBuilder::new(Box::new(empty_instantiate))
+        .with_execute(Box::new(sync_caller_execute))
+        .with_query(Box::new(sync_caller_query))
+        .build();
+    let (caller_code, caller_code_hash) = register(&app, "sync_caller", caller_contract);
+    let caller = genesis_address(&caller_code_hash, b"caller");
+
+    let execute = |target: &Addr, fail: bool| Message::Execute {
+        contract: caller.clone(),
+        msg: to_json_value(&(target, fail)).unwrap(),
+        funds: Coins::new_empty(),
+    };
+    app.init_chain("dev-1", GenesisState {
+        config: mock_config(bank),
+        msgs: vec![
+            upload(bank_code),
+            upload(counter_code),
+            upload(caller_code),
+            instantiate(&bank_code_hash, b"bank"),
+            instantiate(&counter_code_hash, b"counter"),
+            instantiate(&caller_code_hash, b"caller"),
+            // the call succeeds
+            execute(&counter, false),
+            // the call fails, and the counter's write is reverted
+            execute(&counter, true),
+            // the caller attempts to reenter itself
+            execute(&caller, false),
+        ],
+    });
+
+    let count: u32 = query_empty(&app, &counter);
+    assert_eq!(count, 1);
+
+    let results: Vec<String> = query_empty(&app, &caller);
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0], "returned 1, count = 1");
+
+    let err: ExecutionError = from_json_slice(&results[1]).unwrap();
+    assert_eq!(err, ExecutionError {
+        category: ErrorCategory::Contract,
+        contract: Some(counter),
+        entry_point: Some("execute".into()),
+        msg: StdError::generic_err("something went wrong").to_string(),
+    });
+
+    let err: ExecutionError = from_json_slice(&results[2]).unwrap();
+    assert_eq!(err.category, ErrorCategory::Host);
+    assert!(err.msg.contains("can't be reentered"));
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use {
+    common::{
+        account, bank, caller, failer, genesis_address, instantiate, mock_config, query_empty,
+        register, upload,
+    },
+    grug_testing::MockApp,
+    grug_types::{to_json_value, CallKind, Coins, ErrorCategory, GenesisState, Message},
+};
+
+#[test]
+fn simulating_tx_records_trace() {
+    let mut app = MockApp::new();
+
+    let (bank_code, bank_code_hash) = register(&app, "bank", bank());
+    let bank = genesis_address(&bank_code_hash, b"bank");
+
+    let (account_code, account_code_hash) = register(&app, "account", account());
+    let alice = genesis_address(&account_code_hash, b"alice");
+
+    let (failer_code, failer_code_hash) = register(&app, "failer", failer());
+    let failer = genesis_address(&failer_code_hash, b"failer");
+
+    let (caller_code, caller_code_hash) = register(&app, "caller", caller());
+    let caller = genesis_address(&caller_code_hash, b"caller");
+
+    app.init_chain("dev-1", GenesisState {
+        config: mock_config(bank),
+        msgs: vec![
+            upload(bank_code),
+            upload(account_code),
+            upload(failer_code),
+            upload(caller_code),
+            instantiate(&bank_code_hash, b"bank"),
+            instantiate(&account_code_hash, b"alice"),
+            instantiate(&failer_code_hash, b"failer"),
+            instantiate(&caller_code_hash, b"caller"),
+        ],
+    });
+
+    let (result, trace) = app.simulate(&alice, vec![Message::Execute {
+        contract: caller.clone(),
+        msg: to_json_value(&failer).unwrap(),
+        funds: Coins::new_empty(),
+    }]);
+    assert!(result.is_ok());
+
+    // before_tx -> execute -> after_tx, with the failing submessage and the
+    // reply nested in execute
+    let [before_tx, execute, after_tx] = trace.calls.as_slice() else {
+        panic!("unexpected calls: {:?}", trace.calls);
+    };
+    assert_eq!(before_tx.contract.as_ref(), Some(&alice));
+    assert_eq!(before_tx.entry_point.as_deref(), Some("before_tx"));
+    assert_eq!(before_tx.storage.writes, 1);
+    assert_eq!(after_tx.entry_point.as_deref(), Some("after_tx"));
+
+    assert_eq!(execute.contract.as_ref(), Some(&caller));
+    assert_eq!(execute.entry_point.as_deref(), Some("execute"));
+    assert_eq!(execute.msg, Some(to_json_value(&failer).unwrap()));
+    assert!(execute.error.is_none());
+
+    let [submsg] = execute.calls.as_slice() else {
+        panic!("unexpected calls: {:?}", execute.calls);
+    };
+    assert_eq!(submsg.kind, CallKind::Submessage);
+    assert_eq!(submsg.contract.as_ref(), Some(&caller));
+
+    let [failed, reply] = submsg.calls.as_slice() else {
+        panic!("unexpected calls: {:?}", submsg.calls);
+    };
+    assert_eq!(failed.contract.as_ref(), Some(&failer));
+    assert_eq!(
+        failed.error.as_ref().map(|err| err.category),
+        Some(ErrorCategory::Contract)
+    );
+    assert_eq!(reply.entry_point.as_deref(), Some("reply"));
+    assert_eq!(reply.storage.writes, 1);
+
+    let folded = trace.to_folded();
+    assert!(folded.contains(&format!(
+        "{caller}:execute;submsg:execute;{failer}:execute "
+    )));
+
+    // simulation doesn't persist any change
+    let sequence: u32 = query_empty(&app, &alice);
+    assert_eq!(sequence, 0);
+}
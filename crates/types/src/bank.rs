@@ -29,8 +29,10 @@
 
 use {
     crate::{Addr, Coin, Coins},
+    borsh::{BorshDeserialize, BorshSerialize},
     serde::{Deserialize, Serialize},
     serde_with::skip_serializing_none,
+    std::collections::BTreeMap,
 };
 
 /// The execute message that the host provides the bank contract during the
@@ -64,6 +66,26 @@ pub enum BankQuery {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    Metadata {
+        denom: String,
+    },
+    Metadatas {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+/// Descriptive information about a token, for display purposes.
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct DenomMetadata {
+    /// The token's display name, e.g. `Cosmos Hub Atom`.
+    pub name: String,
+    /// The token's ticker symbol, e.g. `ATOM`.
+    pub symbol: String,
+    /// The number of decimal places between the denom and the display unit,
+    /// e.g. 6 for `uatom`, of which 10^6 make one ATOM.
+    pub decimals: u8,
 }
 
 /// The query response that the bank contract must return during the `bank_query`
@@ -83,6 +105,9 @@ pub enum BankQueryResponse {
     Balances(Coins),
     Supply(Coin),
     Supplies(Coins),
+    /// `None` if the token has no metadata.
+    Metadata(Option<DenomMetadata>),
+    Metadatas(BTreeMap<String, DenomMetadata>),
 }
 
 impl BankQueryResponse {
@@ -113,4 +138,18 @@ impl BankQueryResponse {
         };
         coins
     }
+
+    pub fn as_metadata(self) -> Option<DenomMetadata> {
+        let BankQueryResponse::Metadata(metadata) = self else {
+            panic!("BankQueryResponse is not Metadata");
+        };
+        metadata
+    }
+
+    pub fn as_metadatas(self) -> BTreeMap<String, DenomMetadata> {
+        let BankQueryResponse::Metadatas(metadatas) = self else {
+            panic!("BankQueryResponse is not Metadatas");
+        };
+        metadatas
+    }
 }
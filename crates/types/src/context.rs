@@ -3,8 +3,12 @@
 #![cfg_attr(rustfmt, rustfmt::skip)]
 
 use {
-    crate::{Addr, Api, BlockInfo, Coins, QuerierWrapper, Storage},
+    crate::{
+        from_json_slice, to_json_value, Addr, Api, BlockInfo, Caller, Coins, MsgResponse,
+        QuerierWrapper, StdError, StdResult, Storage,
+    },
     borsh::{BorshDeserialize, BorshSerialize},
+    serde::{de::DeserializeOwned, ser::Serialize},
 };
 
 /// This is a union of all context types. When doing a Wasm function call, the
@@ -36,6 +40,7 @@ pub struct MutableCtx<'a> {
     pub storage:  &'a mut dyn Storage,
     pub api:      &'a dyn Api,
     pub querier:  QuerierWrapper<'a>,
+    pub caller:   &'a dyn Caller,
     pub chain_id: String,
     pub block:    BlockInfo,
     pub contract: Addr,
@@ -43,6 +48,34 @@ pub struct MutableCtx<'a> {
     pub funds:    Coins,
 }
 
+impl<'a> MutableCtx<'a> {
+    /// Execute another contract synchronously, sending it the given funds from
+    /// this contract, and return the events it has emitted and the data it has
+    /// returned.
+    ///
+    /// Unlike a submessage, the call is made right away, so its state changes
+    /// are visible to this contract once it returns. If it fails, its changes
+    /// are reverted and the error is returned for this contract to handle.
+    /// This contract can't be reentered while the call is in progress.
+    pub fn call<M: Serialize>(&self, contract: Addr, msg: &M, funds: Coins) -> StdResult<MsgResponse> {
+        self.caller.call_contract(contract, to_json_value(msg)?, funds)
+    }
+
+    /// Same as [`MutableCtx::call`], but deserialize the data returned by the
+    /// contract from JSON. Errors if the contract returns no data.
+    pub fn call_and_decode<M, R>(&self, contract: Addr, msg: &M, funds: Coins) -> StdResult<R>
+    where
+        M: Serialize,
+        R: DeserializeOwned,
+    {
+        let Some(data) = self.call(contract, msg, funds)?.data else {
+            return Err(StdError::generic_err("contract returned no data"));
+        };
+
+        from_json_slice(data)
+    }
+}
+
 /// Sudo context is a state-mutable context. This is used when a contract is
 /// called by the chain, instead of by a message sent by another account.
 /// Therefore, compared to `MutableCtx`, it lacks the `sender` and `funds` fields.
@@ -54,11 +87,33 @@ pub struct SudoCtx<'a> {
     pub storage:  &'a mut dyn Storage,
     pub api:      &'a dyn Api,
     pub querier:  QuerierWrapper<'a>,
+    pub caller:   &'a dyn Caller,
     pub chain_id: String,
     pub block:    BlockInfo,
     pub contract: Addr,
 }
 
+impl<'a> SudoCtx<'a> {
+    /// Execute another contract synchronously. See [`MutableCtx::call`].
+    pub fn call<M: Serialize>(&self, contract: Addr, msg: &M, funds: Coins) -> StdResult<MsgResponse> {
+        self.caller.call_contract(contract, to_json_value(msg)?, funds)
+    }
+
+    /// Same as [`SudoCtx::call`], but deserialize the data returned by the
+    /// contract from JSON. Errors if the contract returns no data.
+    pub fn call_and_decode<M, R>(&self, contract: Addr, msg: &M, funds: Coins) -> StdResult<R>
+    where
+        M: Serialize,
+        R: DeserializeOwned,
+    {
+        let Some(data) = self.call(contract, msg, funds)?.data else {
+            return Err(StdError::generic_err("contract returned no data"));
+        };
+
+        from_json_slice(data)
+    }
+}
+
 /// Similar to `SudoCtx`, but with an additional parameter `simulate` which
 /// designates whether the contract call is done in the simulation mode (e.g.
 /// during the `CheckTx` ABCI call).
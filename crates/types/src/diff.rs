@@ -0,0 +1,127 @@
+use {
+    crate::{Addr, Binary},
+    serde::{Deserialize, Serialize},
+    serde_with::skip_serializing_none,
+    std::{collections::BTreeMap, fmt, iter},
+};
+
+/// A change to a single key-value pair in the state.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub key: Binary,
+    /// The value before the change. `None` if the key didn't exist.
+    pub old: Option<Binary>,
+    /// The value after the change. `None` if the key has been deleted.
+    pub new: Option<Binary>,
+}
+
+/// Changes made to the state, in the order they were made, grouped by the
+/// contracts whose substores they belong to.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct StateDiff {
+    /// Changes to the chain-level state, i.e. not in any contract's substore.
+    /// Keys are full keys in the state.
+    pub chain: Vec<Change>,
+    /// Changes to contract substores, indexed by contract addresses.
+    /// Keys are relative to the contract's substore.
+    pub contracts: BTreeMap<Addr, Vec<Change>>,
+}
+
+impl StateDiff {
+    pub fn is_empty(&self) -> bool {
+        self.chain.is_empty() && self.contracts.is_empty()
+    }
+}
+
+/// All changes made to the state by a transaction, split by the stage of the
+/// transaction that made them.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct TxStateDiff {
+    /// Changes made by the sender account's `before_tx` call.
+    pub before_tx: StateDiff,
+    /// Changes made by each message, in the same order as the messages. Empty
+    /// if any of the messages or `after_tx` failed, since their changes are
+    /// then discarded altogether.
+    pub msgs: Vec<StateDiff>,
+    /// Changes made by the sender account's `after_tx` call.
+    pub after_tx: StateDiff,
+}
+
+impl TxStateDiff {
+    pub fn is_empty(&self) -> bool {
+        self.before_tx.is_empty()
+            && self.msgs.iter().all(StateDiff::is_empty)
+            && self.after_tx.is_empty()
+    }
+}
+
+impl fmt::Display for StateDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.chain.is_empty() {
+            writeln!(f, "chain:")?;
+            for change in &self.chain {
+                writeln!(f, "  {change}")?;
+            }
+        }
+
+        for (contract, changes) in &self.contracts {
+            writeln!(f, "contract {contract}:")?;
+            for change in changes {
+                writeln!(f, "  {change}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for TxStateDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let stages = iter::once(("before_tx".to_string(), &self.before_tx))
+            .chain(
+                self.msgs
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, diff)| (format!("message {idx}"), diff)),
+            )
+            .chain(iter::once(("after_tx".to_string(), &self.after_tx)));
+
+        for (stage, diff) in stages {
+            if diff.is_empty() {
+                continue;
+            }
+
+            writeln!(f, "{stage}:")?;
+            for line in diff.to_string().lines() {
+                writeln!(f, "  {line}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let key = Readable(&self.key);
+        match (&self.old, &self.new) {
+            (None, Some(new)) => write!(f, "+ {key}: {}", Readable(new)),
+            (Some(old), None) => write!(f, "- {key}: {}", Readable(old)),
+            (Some(old), Some(new)) => write!(f, "~ {key}: {} -> {}", Readable(old), Readable(new)),
+            (None, None) => write!(f, "  {key}: (none)"),
+        }
+    }
+}
+
+/// Displays bytes as a UTF-8 string if they are printable, otherwise in hex.
+struct Readable<'a>(&'a [u8]);
+
+impl<'a> fmt::Display for Readable<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match std::str::from_utf8(self.0) {
+            Ok(s) if s.chars().all(|c| !c.is_control()) => write!(f, "{s:?}"),
+            _ => write!(f, "0x{}", hex::encode(self.0)),
+        }
+    }
+}
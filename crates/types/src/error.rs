@@ -1,4 +1,5 @@
 use {
+    crate::ExecutionError,
     data_encoding::BASE64,
     hex::FromHexError,
     std::{any::type_name, array::TryFromSliceError},
@@ -18,6 +19,11 @@ pub enum StdError {
     #[error("generic error: {0}")]
     Generic(String),
 
+    /// An error in a query or contract call made through the host, with the
+    /// party at fault and the contract call where it occurred preserved.
+    #[error(transparent)]
+    Execution(#[from] ExecutionError),
+
     // TODO: add more details to this
     #[error("signature verification failed")]
     VerificationFailed,
@@ -1,4 +1,41 @@
-use serde::{Deserialize, Serialize};
+use {
+    crate::{from_json_slice, from_json_value, to_json_value, Json, StdError, StdResult},
+    serde::{de::DeserializeOwned, Deserialize, Serialize},
+};
+
+/// A structured event that contracts can emit with [`Response::add_event`],
+/// and clients can decode with [`Event::decode`].
+///
+/// The event must serialize to a JSON object. Each of its fields becomes an
+/// attribute of the event indexed by the chain, so field names must not start
+/// with an underscore, which is reserved for attributes added by the host. The
+/// event type must not be one of those emitted by the host itself, such as
+/// `execute` or `send_packet`.
+///
+/// Typically implemented with `#[derive(TypedEvent)]`, which uses the struct's
+/// name in snake case as the event type.
+///
+/// [`Response::add_event`]: crate::Response::add_event
+pub trait TypedEvent: Serialize + DeserializeOwned {
+    const EVENT_TYPE: &'static str;
+}
+
+/// An event emitted by a contract, before it's namespaced by the host with the
+/// contract address and entry point.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ContractEvent {
+    pub r#type: String,
+    pub data: Json,
+}
+
+impl ContractEvent {
+    pub fn new<E: TypedEvent>(event: &E) -> StdResult<Self> {
+        Ok(Self {
+            r#type: E::EVENT_TYPE.to_string(),
+            data: to_json_value(event)?,
+        })
+    }
+}
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
 pub struct Event {
@@ -23,6 +60,31 @@ impl Event {
         self.attributes.extend(attrs);
         self
     }
+
+    /// Decode an event emitted by a contract with [`Response::add_event`].
+    ///
+    /// Attributes whose keys start with an underscore, such as the contract
+    /// address added by the host, are ignored.
+    ///
+    /// [`Response::add_event`]: crate::Response::add_event
+    pub fn decode<E: TypedEvent>(&self) -> StdResult<E> {
+        if self.r#type != E::EVENT_TYPE {
+            return Err(StdError::generic_err(format!(
+                "expecting event of type `{}`, found `{}`",
+                E::EVENT_TYPE,
+                self.r#type
+            )));
+        }
+
+        let fields = self
+            .attributes
+            .iter()
+            .filter(|attr| !attr.key.starts_with('_'))
+            .map(|attr| Ok((attr.key.clone(), from_json_slice(&attr.value)?)))
+            .collect::<StdResult<serde_json::Map<_, _>>>()?;
+
+        from_json_value(Json::Object(fields))
+    }
 }
 
 #[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
@@ -39,3 +101,38 @@ impl Attribute {
         }
     }
 }
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::Addr};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+    struct Swap {
+        pool: Addr,
+        route: Vec<String>,
+        memo: Option<String>,
+    }
+
+    impl TypedEvent for Swap {
+        const EVENT_TYPE: &'static str = "swap";
+    }
+
+    #[test]
+    fn decoding_event_works() {
+        let swap = Swap {
+            pool: Addr::mock(1),
+            route: vec!["uatom".into(), "uosmo".into()],
+            memo: None,
+        };
+        let event = Event::new("swap")
+            .add_attribute("_contract_address", Addr::mock(2))
+            .add_attribute("pool", to_json_value(&swap.pool).unwrap())
+            .add_attribute("route", r#"["uatom","uosmo"]"#)
+            .add_attribute("memo", "null");
+        assert_eq!(event.decode::<Swap>().unwrap(), swap);
+
+        assert!(Event::new("transfer").decode::<Swap>().is_err());
+    }
+}
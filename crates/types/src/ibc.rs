@@ -1,7 +1,8 @@
 use {
-    crate::{Binary, Json},
+    crate::{Addr, Binary, Json, Timestamp},
     borsh::{BorshDeserialize, BorshSerialize},
     serde::{Deserialize, Serialize},
+    serde_with::skip_serializing_none,
 };
 
 /// The possible statuses that an IBC client can be in.
@@ -28,19 +29,27 @@ pub enum IbcClientStatus {
 pub enum IbcClientQuery {
     /// Query the client's status,
     Status {},
+    /// Query the client's current client state and latest consensus state.
+    State {},
     /// Verify a Merkle memership proof of the given key and value.
     ///
+    /// The key is to be prefixed with the commitment prefix of the connection
+    /// the proof is verified for, i.e. the counterparty's `prefix` in the
+    /// connection end.
+    ///
     /// Returns `Ok(true)` if verification succeeds; `Ok(false)` if fails; `Err`
     /// if an error happened during the verification process.
     VerifyMembership {
         height: u64,
         delay_time_period: u64,
         delay_block_period: u64,
+        prefix: Binary,
         key: Binary,
         value: Binary,
         proof: Json,
     },
-    /// Verify a Merkle non-membership proof of the given key.
+    /// Verify a Merkle non-membership proof of the given key, prefixed the same
+    /// way as for `VerifyMembership`.
     ///
     /// Returns `Ok(true)` if verification succeeds; `Ok(false)` if fails; `Err`
     /// if an error happened during the verification process.
@@ -48,9 +57,36 @@ pub enum IbcClientQuery {
         height: u64,
         delay_time_period: u64,
         delay_block_period: u64,
+        prefix: Binary,
         key: Binary,
         proof: Json,
     },
+    /// Query the counterparty chain's block time at the given height, which is
+    /// needed to tell whether a packet with a timeout timestamp has timed out.
+    TimestampAtHeight { height: u64 },
+}
+
+/// The response data that an IBC client contract must set in the `Response`
+/// of the `ibc_client_create`, `ibc_client_update` and `ibc_client_misbehavior`
+/// entry points, encoded as JSON.
+///
+/// The host includes it in the `create_client`, `update_client` and
+/// `client_misbehaviour` events, which relayers rely on.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IbcClientResponseData {
+    /// Height of the consensus state that the client was created with or
+    /// updated with, or, in the case of misbehavior, at which it was frozen.
+    pub consensus_height: u64,
+}
+
+/// The client and consensus states of an IBC client, as returned by the client
+/// contract in response to a [`IbcClientQuery::State`] query.
+///
+/// The encoding of the states is up to the client contract.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IbcClientStateResponse {
+    pub client_state: Binary,
+    pub consensus_state: Binary,
 }
 
 /// The query response that the IBC client contract must return during the
@@ -62,8 +98,10 @@ pub enum IbcClientQuery {
 #[serde(rename_all = "snake_case")]
 pub enum IbcClientQueryResponse {
     Status(IbcClientStatus),
+    State(IbcClientStateResponse),
     VerifyMembership(bool),
     VerifyNonMembership(bool),
+    TimestampAtHeight(Timestamp),
 }
 
 impl IbcClientQueryResponse {
@@ -74,6 +112,13 @@ impl IbcClientQueryResponse {
         status
     }
 
+    pub fn as_state(self) -> IbcClientStateResponse {
+        let IbcClientQueryResponse::State(state) = self else {
+            panic!("IbcClientQueryResponse is not State");
+        };
+        state
+    }
+
     pub fn as_verify_membership(self) -> bool {
         let IbcClientQueryResponse::VerifyMembership(success) = self else {
             panic!("IbcClientQueryResponse is not VerifyMembership");
@@ -87,4 +132,208 @@ impl IbcClientQueryResponse {
         };
         success
     }
+
+    pub fn as_timestamp_at_height(self) -> Timestamp {
+        let IbcClientQueryResponse::TimestampAtHeight(timestamp) = self else {
+            panic!("IbcClientQueryResponse is not TimestampAtHeight");
+        };
+        timestamp
+    }
+}
+
+// -------------------------------- connections --------------------------------
+
+/// The state of an IBC connection end, as defined in ICS-03.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcConnectionState {
+    /// `ConnOpenInit` has been executed on this chain.
+    Init,
+    /// `ConnOpenTry` has been executed on this chain.
+    TryOpen,
+    /// The handshake is complete.
+    Open,
+}
+
+/// A version of the IBC connection protocol, and the channel orderings that
+/// connections of this version support.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IbcConnectionVersion {
+    pub identifier: String,
+    pub features: Vec<String>,
+}
+
+impl Default for IbcConnectionVersion {
+    /// The only version we support, which is also ibc-go's default: version
+    /// `1`, supporting both ordered and unordered channels.
+    fn default() -> Self {
+        Self {
+            identifier: "1".to_string(),
+            features: vec!["ORDER_ORDERED".to_string(), "ORDER_UNORDERED".to_string()],
+        }
+    }
+}
+
+/// The counterparty chain's end of an IBC connection.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IbcConnectionCounterparty {
+    /// ID of the client on the counterparty chain that tracks our chain.
+    pub client_id: String,
+    /// ID of the connection on the counterparty chain. Unknown until the
+    /// counterparty has executed `ConnOpenTry` or `ConnOpenInit`.
+    pub connection_id: Option<String>,
+    /// Prefix of the keys in the counterparty's state under which IBC state is
+    /// kept, e.g. `ibc` for Cosmos SDK chains. Proofs of the counterparty's IBC
+    /// state are verified under this prefix.
+    pub prefix: Binary,
+}
+
+/// Our chain's end of an IBC connection.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IbcConnectionEnd {
+    /// The client on our chain that tracks the counterparty chain.
+    pub client_id: Addr,
+    /// The versions that can be used with this connection. Only one is left
+    /// once the handshake has progressed past `Init`.
+    pub versions: Vec<IbcConnectionVersion>,
+    pub state: IbcConnectionState,
+    pub counterparty: IbcConnectionCounterparty,
+    /// How long, in nanoseconds, a consensus state must have been known to the
+    /// client before packet proofs can be verified against it.
+    pub delay_period: u64,
+}
+
+// --------------------------------- channels ----------------------------------
+
+/// The state of an IBC channel end, as defined in ICS-04.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcChannelState {
+    /// `ChanOpenInit` has been executed on this chain.
+    Init,
+    /// `ChanOpenTry` has been executed on this chain.
+    TryOpen,
+    /// The handshake is complete; packets can be sent and received.
+    Open,
+    /// The channel has been closed; no more packets can be sent or received.
+    Closed,
+}
+
+/// Whether packets sent over an IBC channel must be received in the order
+/// they are sent.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcChannelOrder {
+    Unordered,
+    Ordered,
+}
+
+/// The counterparty chain's end of an IBC channel.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IbcChannelCounterparty {
+    pub port_id: String,
+    /// ID of the channel on the counterparty chain. Unknown until the
+    /// counterparty has executed `ChanOpenTry` or `ChanOpenInit`.
+    pub channel_id: Option<String>,
+}
+
+/// Our chain's end of an IBC channel.
+///
+/// On our chain, ports are contracts, identified by their addresses.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IbcChannelEnd {
+    pub state: IbcChannelState,
+    pub ordering: IbcChannelOrder,
+    pub counterparty: IbcChannelCounterparty,
+    /// The connections the channel goes through. Multi-hop channels aren't
+    /// supported, so this always consists of exactly one connection.
+    pub connection_hops: Vec<String>,
+    /// The application-level version, e.g. `ics20-1` for token transfers.
+    pub version: String,
+}
+
+/// The message that the host sends to the port contract in the
+/// `ibc_channel_open` call, during the first step of a channel's handshake on
+/// our chain, same as ibc-go's `OnChanOpenInit` and `OnChanOpenTry` callbacks.
+///
+/// The contract rejects the channel by returning an error, e.g. if it doesn't
+/// support the ordering or the version, in which case the handshake fails.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcChannelOpenMsg {
+    /// `ChanOpenInit` is being executed on this chain.
+    Init {
+        channel_id: String,
+        channel: IbcChannelEnd,
+    },
+    /// `ChanOpenTry` is being executed on this chain, in response to the
+    /// counterparty's `ChanOpenInit`.
+    Try {
+        channel_id: String,
+        channel: IbcChannelEnd,
+        /// The version the counterparty proposed in `ChanOpenInit`.
+        counterparty_version: String,
+    },
+}
+
+/// The message that the host sends to the port contract in the
+/// `ibc_channel_connect` call, once a channel is open on our chain, same as
+/// ibc-go's `OnChanOpenAck` and `OnChanOpenConfirm` callbacks.
+///
+/// The contract can still reject the channel by returning an error, in which
+/// case the channel end stays in the `Init` or `TryOpen` state.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcChannelConnectMsg {
+    /// `ChanOpenAck` is being executed on this chain. The channel's version is
+    /// the one the counterparty agreed to in `ChanOpenTry`.
+    Ack {
+        channel_id: String,
+        channel: IbcChannelEnd,
+    },
+    /// `ChanOpenConfirm` is being executed on this chain.
+    Confirm {
+        channel_id: String,
+        channel: IbcChannelEnd,
+    },
+}
+
+// ---------------------------------- packets ----------------------------------
+
+/// When a packet times out, i.e. can no longer be received by the counterparty
+/// chain: once the counterparty chain reaches the height or the timestamp,
+/// whichever comes first. At least one of the two must be set.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct IbcTimeout {
+    pub height: Option<u64>,
+    pub timestamp: Option<Timestamp>,
+}
+
+/// A packet sent over an IBC channel, as defined in ICS-04.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IbcPacket {
+    /// Packets sent over a channel are numbered starting from 1.
+    pub sequence: u64,
+    pub source_port: String,
+    pub source_channel: String,
+    pub destination_port: String,
+    pub destination_channel: String,
+    pub data: Binary,
+    pub timeout: IbcTimeout,
+}
+
+/// The acknowledgement that the host writes after a packet is received, in the
+/// JSON format used by ibc-go, i.e. `{"result":"..."}` or `{"error":"..."}`.
+///
+/// If the receiving contract's `ibc_packet_receive` call succeeds, the result
+/// is the data it returns; otherwise, its state changes are discarded and an
+/// error is returned to the sender. Same as in ibc-go, the error only includes
+/// the error's category, as a code, so that it's the same on every node; the
+/// error itself is emitted in a `recv_packet_error` event.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcAcknowledgement {
+    Result(Binary),
+    Error(String),
 }
@@ -4,18 +4,20 @@
 //!
 //! - database reads/writes,
 //! - cryptography methods, and
-//! - a method for querying the chain.
+//! - methods for querying the chain and calling other contracts.
 //!
-//! These functions are abstracted into the `Storage`, `Api`, and `Querier`
-//! traits.
+//! These functions are abstracted into the `Storage`, `Api`, `Querier`, and
+//! `Caller` traits.
 
 use {
     crate::{
-        from_json_value, to_json_value, AccountResponse, Addr, Batch, Binary, Coins, Hash,
-        InfoResponse, Op, Order, QueryRequest, QueryResponse, Record, StdResult, Uint128,
+        from_json_value, prefix_upper_bound, to_json_value, AccountResponse, Addr, Batch, Binary,
+        ClientResponse, Coins, DenomMetadata, Hash, IbcChannelEnd, IbcConnectionEnd, InfoResponse,
+        Json, MsgResponse, Op, Order, QueryRequest, QueryResponse, Record, StdResult, Uint128,
     },
     dyn_clone::DynClone,
     serde::{de::DeserializeOwned, ser::Serialize},
+    std::collections::BTreeMap,
 };
 
 // ---------------------------------- storage ----------------------------------
@@ -79,6 +81,36 @@ pub trait Storage: DynClone + Send + Sync {
     /// No-op if `min` > `max`.
     fn remove_range(&mut self, min: Option<&[u8]>, max: Option<&[u8]>);
 
+    /// Return whether a key exists in the storage.
+    ///
+    /// The default implementation reads the value and discards it. Overwrite
+    /// this implementation if the underlying store can check existence without
+    /// loading the value.
+    fn has(&self, key: &[u8]) -> bool {
+        self.read(key).is_some()
+    }
+
+    /// Read multiple keys at once. The values are returned in the same order as
+    /// the keys; `None` if the key doesn't exist.
+    ///
+    /// The default implementation is just calling `read` once for each key.
+    /// Overwrite this implementation if there are more efficient approaches.
+    fn read_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        keys.iter().map(|key| self.read(key)).collect()
+    }
+
+    /// Iterate over all key-value pairs whose keys start with the given prefix,
+    /// in the given order. The keys are returned in full (i.e. including the
+    /// prefix).
+    fn scan_prefix<'a>(
+        &'a self,
+        prefix: &[u8],
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        let max = prefix_upper_bound(prefix);
+        self.scan(Some(prefix), max.as_deref(), order)
+    }
+
     /// Perform a batch of writes and removes altogether, ideally atomically.
     ///
     /// The batch is provided by value instead of by reference (unlike other
@@ -88,7 +120,7 @@ pub trait Storage: DynClone + Send + Sync {
     /// The default implementation here is just looping through the ops and
     /// applying them one by one, which is inefficient and not atomic.
     /// Overwrite this implementation if there are more efficient approaches.
-    fn flush(&mut self, batch: Batch) {
+    fn write_batch(&mut self, batch: Batch) {
         for (key, op) in batch {
             if let Op::Insert(value) = op {
                 self.write(&key, &value);
@@ -97,6 +129,15 @@ pub trait Storage: DynClone + Send + Sync {
             }
         }
     }
+
+    /// Perform a batch of writes and removes altogether.
+    ///
+    /// This is the former name of `write_batch`, kept for compatibility with
+    /// existing callers. It simply forwards the batch to `write_batch`.
+    #[deprecated(note = "use `write_batch` instead")]
+    fn flush(&mut self, batch: Batch) {
+        self.write_batch(batch)
+    }
 }
 
 // A boxed `Storage` is also a `Storage`.
@@ -151,6 +192,27 @@ impl Storage for Box<dyn Storage> {
         self.as_mut().remove_range(min, max)
     }
 
+    fn has(&self, key: &[u8]) -> bool {
+        self.as_ref().has(key)
+    }
+
+    fn read_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        self.as_ref().read_many(keys)
+    }
+
+    fn scan_prefix<'a>(
+        &'a self,
+        prefix: &[u8],
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'a> {
+        self.as_ref().scan_prefix(prefix, order)
+    }
+
+    fn write_batch(&mut self, batch: Batch) {
+        self.as_mut().write_batch(batch)
+    }
+
+    #[allow(deprecated)]
     fn flush(&mut self, batch: Batch) {
         self.as_mut().flush(batch)
     }
@@ -320,6 +382,22 @@ impl<'a> QuerierWrapper<'a> {
             .map(|res| res.as_supplies())
     }
 
+    pub fn query_metadata(&self, denom: String) -> StdResult<Option<DenomMetadata>> {
+        self.inner
+            .query_chain(QueryRequest::Metadata { denom })
+            .map(|res| res.as_metadata())
+    }
+
+    pub fn query_metadatas(
+        &self,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<BTreeMap<String, DenomMetadata>> {
+        self.inner
+            .query_chain(QueryRequest::Metadatas { start_after, limit })
+            .map(|res| res.as_metadatas())
+    }
+
     pub fn query_code(&self, hash: Hash) -> StdResult<Binary> {
         self.inner
             .query_chain(QueryRequest::Code { hash })
@@ -358,6 +436,23 @@ impl<'a> QuerierWrapper<'a> {
             .map(|res| res.as_wasm_raw().value)
     }
 
+    pub fn query_wasm_scan(
+        &self,
+        contract: Addr,
+        min: Option<Binary>,
+        max: Option<Binary>,
+        limit: Option<u32>,
+    ) -> StdResult<BTreeMap<Binary, Binary>> {
+        self.inner
+            .query_chain(QueryRequest::WasmScan {
+                contract,
+                min,
+                max,
+                limit,
+            })
+            .map(|res| res.as_wasm_scan())
+    }
+
     pub fn query_wasm_smart<M: Serialize, R: DeserializeOwned>(
         &self,
         contract: Addr,
@@ -370,4 +465,42 @@ impl<'a> QuerierWrapper<'a> {
             })
             .and_then(|res| from_json_value(res.as_wasm_smart().data))
     }
+
+    pub fn query_client(&self, client_id: Addr) -> StdResult<ClientResponse> {
+        self.inner
+            .query_chain(QueryRequest::Client { client_id })
+            .map(|res| res.as_client())
+    }
+
+    pub fn query_connection(&self, connection_id: String) -> StdResult<IbcConnectionEnd> {
+        self.inner
+            .query_chain(QueryRequest::Connection { connection_id })
+            .map(|res| res.as_connection())
+    }
+
+    pub fn query_channel(&self, port_id: Addr, channel_id: String) -> StdResult<IbcChannelEnd> {
+        self.inner
+            .query_chain(QueryRequest::Channel {
+                port_id,
+                channel_id,
+            })
+            .map(|res| res.as_channel())
+    }
+}
+
+// ----------------------------------- caller ----------------------------------
+
+/// Describes the ability to execute other contracts synchronously.
+///
+/// This is separate from `Querier` because the calls may mutate the state, so
+/// it's only provided in contexts where the contract itself is allowed to do
+/// so, i.e. `MutableCtx` and `SudoCtx`.
+pub trait Caller {
+    /// Execute another contract synchronously, on behalf of the contract that
+    /// is currently being executed, and return the events it has emitted and
+    /// the data it has returned.
+    ///
+    /// If the call fails, its state changes are reverted and the error is
+    /// returned, while the calling contract's own changes are kept.
+    fn call_contract(&self, contract: Addr, msg: Json, funds: Coins) -> StdResult<MsgResponse>;
 }
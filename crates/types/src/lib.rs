@@ -5,6 +5,7 @@ mod binary;
 mod coin;
 mod context;
 mod db;
+mod diff;
 mod empty;
 mod error;
 mod event;
@@ -21,15 +22,16 @@ mod result;
 mod serde;
 mod signed;
 mod timestamp;
+mod trace;
 mod tx;
 mod udec;
 mod uint;
 mod utils;
 
 pub use {
-    address::*, app::*, bank::*, binary::*, coin::*, context::*, db::*, empty::*, error::*,
-    event::*, hash::*, ibc::*, imports::*, math::*, query::*, response::*, result::*, serde::*,
-    signed::*, timestamp::*, tx::*, udec::*, uint::*, utils::*,
+    address::*, app::*, bank::*, binary::*, coin::*, context::*, db::*, diff::*, empty::*,
+    error::*, event::*, hash::*, ibc::*, imports::*, math::*, query::*, response::*, result::*,
+    serde::*, signed::*, timestamp::*, trace::*, tx::*, udec::*, uint::*, utils::*,
 };
 
 // Mocks need to be excluded in Wasm builds because they depend on k256/p256
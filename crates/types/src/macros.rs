@@ -49,7 +49,7 @@ macro_rules! make_immutable_ctx {
 #[macro_export]
 #[rustfmt::skip]
 macro_rules! make_mutable_ctx {
-    ($ctx:ident, $storage:expr, $api:expr, $querier:expr) => {
+    ($ctx:ident, $storage:expr, $api:expr, $querier:expr, $caller:expr) => {
         {
             debug_assert!($ctx.simulate.is_none());
 
@@ -57,6 +57,7 @@ macro_rules! make_mutable_ctx {
                 storage:  $storage,
                 api:      $api,
                 querier:  QuerierWrapper::new($querier),
+                caller:   $caller,
                 chain_id: $ctx.chain_id,
                 block:    $ctx.block,
                 contract: $ctx.contract,
@@ -70,7 +71,7 @@ macro_rules! make_mutable_ctx {
 #[macro_export]
 #[rustfmt::skip]
 macro_rules! make_sudo_ctx {
-    ($ctx:ident, $storage:expr, $api:expr, $querier:expr) => {
+    ($ctx:ident, $storage:expr, $api:expr, $querier:expr, $caller:expr) => {
         {
             debug_assert!($ctx.sender.is_none());
             debug_assert!($ctx.funds.is_none());
@@ -80,6 +81,7 @@ macro_rules! make_sudo_ctx {
                 storage:  $storage,
                 api:      $api,
                 querier:  QuerierWrapper::new($querier),
+                caller:   $caller,
                 chain_id: $ctx.chain_id,
                 block:    $ctx.block,
                 contract: $ctx.contract,
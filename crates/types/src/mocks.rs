@@ -1,5 +1,8 @@
 use {
-    crate::{Addr, Api, Order, Record, StdError, StdResult, Storage},
+    crate::{
+        Addr, Api, Caller, Coins, Json, MsgResponse, Order, Querier, QueryRequest, QueryResponse,
+        Record, StdError, StdResult, Storage,
+    },
     std::{collections::BTreeMap, iter, ops::Bound},
 };
 
@@ -195,3 +198,27 @@ impl Api for MockApi {
         grug_crypto::blake3(data)
     }
 }
+
+// ---------------------------------- querier ----------------------------------
+
+/// A querier that rejects all queries, for testing contract functions that
+/// don't need to query the chain.
+pub struct MockQuerier;
+
+impl Querier for MockQuerier {
+    fn query_chain(&self, _req: QueryRequest) -> StdResult<QueryResponse> {
+        Err(StdError::generic_err("mock querier can't make queries"))
+    }
+}
+
+// ----------------------------------- caller ----------------------------------
+
+/// A caller that rejects all calls, for testing contract functions that don't
+/// need to call other contracts.
+pub struct MockCaller;
+
+impl Caller for MockCaller {
+    fn call_contract(&self, _contract: Addr, _msg: Json, _funds: Coins) -> StdResult<MsgResponse> {
+        Err(StdError::generic_err("mock caller can't call contracts"))
+    }
+}
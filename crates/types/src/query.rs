@@ -1,7 +1,11 @@
 use {
-    crate::{Addr, Binary, BlockInfo, Coin, Coins, Config, Hash, Json},
+    crate::{
+        Addr, Binary, BlockInfo, Coin, Coins, Config, DenomMetadata, Hash, IbcChannelEnd,
+        IbcConnectionEnd, Json,
+    },
     serde::{Deserialize, Serialize},
     serde_with::skip_serializing_none,
+    std::collections::BTreeMap,
 };
 
 #[skip_serializing_none]
@@ -30,6 +34,15 @@ pub enum QueryRequest {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    /// A token's metadata, if it has any.
+    /// Returns: `Option<DenomMetadata>`
+    Metadata { denom: String },
+    /// Enumerate all tokens' metadata.
+    /// Returns: `BTreeMap<String, DenomMetadata>`
+    Metadatas {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     /// A single Wasm byte code.
     /// Returns: `Binary`
     Code { hash: Hash },
@@ -53,9 +66,27 @@ pub enum QueryRequest {
     /// A raw key-value pair in a contract's internal state.
     /// Returns: `WasmRawResponse`
     WasmRaw { contract: Addr, key: Binary },
+    /// Enumerate raw key-value pairs in a contract's internal state, in
+    /// ascending order. `min` is inclusive and `max` is exclusive.
+    /// Returns: `BTreeMap<Binary, Binary>`
+    WasmScan {
+        contract: Addr,
+        min: Option<Binary>,
+        max: Option<Binary>,
+        limit: Option<u32>,
+    },
     /// Call the contract's query entry point with the given message.
     /// Returns: `WasmSmartResponse`
     WasmSmart { contract: Addr, msg: Json },
+    /// The client and consensus states of an IBC light client.
+    /// Returns: `ClientResponse`
+    Client { client_id: Addr },
+    /// An IBC connection end.
+    /// Returns: `IbcConnectionEnd`
+    Connection { connection_id: String },
+    /// An IBC channel end.
+    /// Returns: `IbcChannelEnd`
+    Channel { port_id: Addr, channel_id: String },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -103,12 +134,18 @@ pub enum QueryResponse {
     Balances(Coins),
     Supply(Coin),
     Supplies(Coins),
+    Metadata(Option<DenomMetadata>),
+    Metadatas(BTreeMap<String, DenomMetadata>),
     Code(Binary),
     Codes(Vec<Hash>),
     Account(AccountResponse),
     Accounts(Vec<AccountResponse>),
     WasmRaw(WasmRawResponse),
+    WasmScan(BTreeMap<Binary, Binary>),
     WasmSmart(WasmSmartResponse),
+    Client(ClientResponse),
+    Connection(IbcConnectionEnd),
+    Channel(IbcChannelEnd),
 }
 
 // TODO: can we use a macro to implement these?
@@ -148,6 +185,20 @@ impl QueryResponse {
         coins
     }
 
+    pub fn as_metadata(self) -> Option<DenomMetadata> {
+        let Self::Metadata(metadata) = self else {
+            panic!("QueryResponse is not Metadata");
+        };
+        metadata
+    }
+
+    pub fn as_metadatas(self) -> BTreeMap<String, DenomMetadata> {
+        let Self::Metadatas(metadatas) = self else {
+            panic!("QueryResponse is not Metadatas");
+        };
+        metadatas
+    }
+
     pub fn as_code(self) -> Binary {
         let Self::Code(wasm_byte_code) = self else {
             panic!("QueryResponse is not Code");
@@ -183,10 +234,38 @@ impl QueryResponse {
         resp
     }
 
+    pub fn as_wasm_scan(self) -> BTreeMap<Binary, Binary> {
+        let Self::WasmScan(records) = self else {
+            panic!("QueryResponse is not WasmScan");
+        };
+        records
+    }
+
     pub fn as_wasm_smart(self) -> WasmSmartResponse {
         let Self::WasmSmart(resp) = self else {
             panic!("QueryResponse is not WasmSmart");
         };
         resp
     }
+
+    pub fn as_client(self) -> ClientResponse {
+        let Self::Client(resp) = self else {
+            panic!("QueryResponse is not Client");
+        };
+        resp
+    }
+
+    pub fn as_connection(self) -> IbcConnectionEnd {
+        let Self::Connection(resp) = self else {
+            panic!("QueryResponse is not Connection");
+        };
+        resp
+    }
+
+    pub fn as_channel(self) -> IbcChannelEnd {
+        let Self::Channel(resp) = self else {
+            panic!("QueryResponse is not Channel");
+        };
+        resp
+    }
 }
@@ -1,5 +1,7 @@
 use {
-    crate::{to_json_value, Attribute, Json, Message, StdResult},
+    crate::{
+        to_json_value, Attribute, Binary, ContractEvent, Json, Message, StdResult, TypedEvent,
+    },
     serde::{Deserialize, Serialize},
 };
 
@@ -7,6 +9,13 @@ use {
 pub struct Response {
     pub submsgs: Vec<SubMessage>,
     pub attributes: Vec<Attribute>,
+    /// Structured events, each of which the host emits as a separate event,
+    /// along with the one containing the attributes above.
+    #[serde(default)]
+    pub events: Vec<ContractEvent>,
+    /// Data to be returned to the caller: the contract that emitted the
+    /// submessage this is a response to, or the transaction sender.
+    pub data: Option<Binary>,
 }
 
 impl Response {
@@ -39,6 +48,16 @@ impl Response {
         self.attributes.push(Attribute::new(key, value));
         self
     }
+
+    pub fn add_event<E: TypedEvent>(mut self, event: &E) -> StdResult<Self> {
+        self.events.push(ContractEvent::new(event)?);
+        Ok(self)
+    }
+
+    pub fn set_data(mut self, data: impl Into<Binary>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
 }
 
 /// Indicates that after a submessage has been executed, whether the host should
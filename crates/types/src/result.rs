@@ -1,11 +1,39 @@
 use {
-    crate::{Event, StdError, StdResult},
+    crate::{Addr, Binary, Event, StdError, StdResult},
     serde::{Deserialize, Serialize},
+    std::fmt,
 };
 
 /// The result for executing a submessage, provided to the contract in the `reply`
 /// entry point.
-pub type SubMsgResult = GenericResult<Vec<Event>>;
+pub type SubMsgResult = GenericResult<MsgResponse, ExecutionError>;
+
+/// The outcome of successfully executing a message.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct MsgResponse {
+    /// Events emitted during the execution, including those of submessages.
+    pub events: Vec<Event>,
+    /// Data returned by the contract that the message was sent to, if any.
+    pub data: Option<Binary>,
+}
+
+impl MsgResponse {
+    /// Create a response with the given events and no data, for messages that
+    /// aren't handled by a contract, such as uploading code.
+    pub fn from_events(events: Vec<Event>) -> Self {
+        Self { events, data: None }
+    }
+}
+
+/// The outcome of successfully executing a transaction.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct TxResponse {
+    /// Events emitted during the execution of the transaction, including those
+    /// of the sender account's `before_tx` and `after_tx` calls.
+    pub events: Vec<Event>,
+    /// Data returned by each of the transaction's messages, in order.
+    pub data: Vec<Option<Binary>>,
+}
 
 /// A result type that can be serialized into a string and thus passed over the
 /// FFI boundary.
@@ -15,9 +43,9 @@ pub type SubMsgResult = GenericResult<Vec<Event>>;
 /// - the Wasm module calls an import function provided by the host
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
-pub enum GenericResult<T> {
+pub enum GenericResult<T, E = String> {
     Ok(T),
-    Err(String),
+    Err(E),
 }
 
 impl<T, E> From<Result<T, E>> for GenericResult<T>
@@ -32,13 +60,46 @@ where
     }
 }
 
+/// Used by the host to pass the result of a query or contract call made by a
+/// contract back to it, without losing the structure of the error.
+impl<T> From<StdResult<T>> for GenericResult<T, ExecutionError> {
+    fn from(res: StdResult<T>) -> Self {
+        match res {
+            Result::Ok(data) => Self::Ok(data),
+            Result::Err(StdError::Execution(err)) => Self::Err(err),
+            Result::Err(err) => Self::Err(ExecutionError::new(ErrorCategory::Host, err)),
+        }
+    }
+}
+
 impl<T> GenericResult<T> {
+    /// Convert the GenericResult, returned by a call to a contract, to a Result
+    /// whose error is attributed to that contract and entry point.
+    pub fn into_contract_result(
+        self,
+        contract: &Addr,
+        entry_point: &str,
+    ) -> Result<T, ExecutionError> {
+        match self {
+            GenericResult::Ok(data) => Ok(data),
+            GenericResult::Err(err) => {
+                Err(ExecutionError::new(ErrorCategory::Contract, err)
+                    .attribute(contract, entry_point))
+            },
+        }
+    }
+}
+
+impl<T, E> GenericResult<T, E>
+where
+    E: ToString,
+{
     /// Convert the GenericResult to a StdResult, so that it can be unwrapped
     /// with the `?` operator.
     pub fn into_std_result(self) -> StdResult<T> {
         match self {
             GenericResult::Ok(data) => Ok(data),
-            GenericResult::Err(err) => Err(StdError::Generic(err)),
+            GenericResult::Err(err) => Err(StdError::Generic(err.to_string())),
         }
     }
 
@@ -59,10 +120,114 @@ impl<T> GenericResult<T> {
     /// This is useful if you're sure the result is an Err, e.g. when handling a
     /// submessage result in the `reply` entry point, when you have configured
     /// it to reply only on error.
-    pub fn as_err(self) -> String {
+    pub fn as_err(self) -> E {
         match self {
             GenericResult::Ok(_) => unreachable!(),
             GenericResult::Err(err) => err,
         }
     }
 }
+
+// ------------------------------ execution error ------------------------------
+
+/// The category of an [`ExecutionError`], i.e. which party is at fault.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// The contract returned an error.
+    Contract,
+    /// The host failed to carry out the call, e.g. the sender isn't authorized,
+    /// or a storage error.
+    Host,
+    /// The VM aborted the execution of the contract, e.g. it ran out of memory
+    /// or made an invalid call to a host function.
+    Trap,
+    /// Data passed between the host and the contract can't be serialized or
+    /// deserialized.
+    Deserialize,
+    /// The contract, code, or entry point being called doesn't exist.
+    NotFound,
+}
+
+impl ErrorCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorCategory::Contract => "contract",
+            ErrorCategory::Host => "host",
+            ErrorCategory::Trap => "trap",
+            ErrorCategory::Deserialize => "deserialize",
+            ErrorCategory::NotFound => "not_found",
+        }
+    }
+
+    /// A non-zero numerical code for the category, e.g. to be used as the ABCI
+    /// result code of a failed transaction.
+    pub fn code(self) -> u32 {
+        match self {
+            ErrorCategory::Contract => 1,
+            ErrorCategory::Host => 2,
+            ErrorCategory::Trap => 3,
+            ErrorCategory::Deserialize => 4,
+            ErrorCategory::NotFound => 5,
+        }
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An error that occurred while executing a message, structured such that the
+/// party at fault and the contract call where it occurred can be told apart.
+///
+/// This is provided to contracts in the `reply` entry point when a submessage
+/// fails, and to clients in the result of failed transactions.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionError {
+    pub category: ErrorCategory,
+    /// The contract being called when the error occurred, if any.
+    pub contract: Option<Addr>,
+    /// The entry point being called when the error occurred, if any.
+    pub entry_point: Option<String>,
+    /// The original error message.
+    pub msg: String,
+}
+
+impl ExecutionError {
+    pub fn new(category: ErrorCategory, msg: impl ToString) -> Self {
+        Self {
+            category,
+            contract: None,
+            entry_point: None,
+            msg: msg.to_string(),
+        }
+    }
+
+    /// Attribute the error to a call to the given contract and entry point,
+    /// unless it's already been attributed to one, e.g. because it occurred in
+    /// a call nested inside this one.
+    pub fn attribute(mut self, contract: &Addr, entry_point: &str) -> Self {
+        if self.contract.is_none() {
+            self.contract = Some(contract.clone());
+            self.entry_point = Some(entry_point.to_string());
+        }
+        self
+    }
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.contract, &self.entry_point) {
+            (Some(contract), Some(entry_point)) => write!(
+                f,
+                "{} error in contract `{contract}` at entry point `{entry_point}`: {}",
+                self.category, self.msg
+            ),
+            _ => write!(f, "{} error: {}", self.category, self.msg),
+        }
+    }
+}
+
+impl std::error::Error for ExecutionError {}
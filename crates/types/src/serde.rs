@@ -40,6 +40,14 @@ where
     serde_json::to_vec(data).map_err(StdError::serialize::<T>)
 }
 
+/// Serialize a Rust value into a string using the JSON encoding scheme.
+pub fn to_json_string<T>(data: &T) -> StdResult<String>
+where
+    T: Serialize,
+{
+    serde_json::to_string(data).map_err(StdError::serialize::<T>)
+}
+
 /// Deserialize a slice of bytes into Rust value of a given type `T` using the
 /// [Borsh](https://crates.io/crates/borsh) encoding scheme.
 pub fn from_borsh_slice<T>(bytes: impl AsRef<[u8]>) -> StdResult<T>
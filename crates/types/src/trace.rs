@@ -0,0 +1,208 @@
+use {
+    crate::{Addr, Coins, ExecutionError, GenericResult, Json, TxResponse},
+    serde::{Deserialize, Serialize},
+    serde_with::skip_serializing_none,
+    std::fmt::Write,
+};
+
+/// The kind of a node in a call tree.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CallKind {
+    /// A call to an entry point of a contract.
+    Contract,
+    /// A submessage emitted by a contract.
+    Submessage,
+    /// A query made by a contract to the chain.
+    Query,
+}
+
+/// Storage operations made by a call, not including those made by the calls
+/// nested in it.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageStats {
+    /// Number of keys read, including existence checks.
+    pub reads: u64,
+    /// Total length of the values read.
+    pub read_bytes: u64,
+    /// Number of iterators created.
+    pub scans: u64,
+    /// Number of keys written.
+    pub writes: u64,
+    /// Total length of the keys and values written.
+    pub write_bytes: u64,
+    /// Number of keys or key ranges removed.
+    pub removes: u64,
+}
+
+/// A node in the call tree of a transaction.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CallTrace {
+    pub kind: CallKind,
+    /// For contract calls, the contract being called. For submessages, the
+    /// contract that emitted it.
+    pub contract: Option<Addr>,
+    /// For contract calls, the entry point being called.
+    pub entry_point: Option<String>,
+    /// The message, submessage, or query request.
+    pub msg: Option<Json>,
+    /// For contract calls, the coins sent along with the call.
+    pub funds: Option<Coins>,
+    /// Wall-clock time spent in the call, including nested calls, in
+    /// nanoseconds.
+    pub duration: u64,
+    pub storage: StorageStats,
+    /// The error, if the call failed.
+    pub error: Option<ExecutionError>,
+    /// Calls made during this one, in the order they were made.
+    pub calls: Vec<CallTrace>,
+}
+
+impl CallTrace {
+    pub fn new(kind: CallKind) -> Self {
+        Self {
+            kind,
+            contract: None,
+            entry_point: None,
+            msg: None,
+            funds: None,
+            duration: 0,
+            storage: StorageStats::default(),
+            error: None,
+            calls: vec![],
+        }
+    }
+
+    /// Wall-clock time spent in the call itself, excluding nested calls, in
+    /// nanoseconds.
+    pub fn self_duration(&self) -> u64 {
+        let nested = self.calls.iter().map(|call| call.duration).sum();
+        self.duration.saturating_sub(nested)
+    }
+
+    /// A short name for the call, to be used as a frame in flamegraphs.
+    pub fn label(&self) -> String {
+        // The name of a message or query is the name of its enum variant, which
+        // is the only key of the JSON object it's serialized to.
+        let variant = || {
+            self.msg
+                .as_ref()
+                .and_then(Json::as_object)
+                .and_then(|obj| obj.keys().next().cloned())
+                .unwrap_or_default()
+        };
+
+        match self.kind {
+            CallKind::Contract => format!(
+                "{}:{}",
+                self.contract
+                    .as_ref()
+                    .map(Addr::to_string)
+                    .unwrap_or_default(),
+                self.entry_point.as_deref().unwrap_or_default()
+            ),
+            CallKind::Submessage => format!("submsg:{}", variant()),
+            CallKind::Query => format!("query:{}", variant()),
+        }
+    }
+}
+
+/// The call tree of a transaction: contract calls, submessages, and queries,
+/// each with the calls nested in it.
+///
+/// There is no gas metering yet, so calls are profiled by wall-clock time and
+/// the storage operations they make.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    /// The outermost calls, in the order they were made.
+    pub calls: Vec<CallTrace>,
+}
+
+impl Trace {
+    /// Render the trace in the "folded stacks" format, which can be turned
+    /// into a flamegraph by tools such as `flamegraph.pl` or `inferno`.
+    ///
+    /// Each line contains a stack of frames, separated by semicolons, and the
+    /// time spent in the innermost frame itself, in nanoseconds.
+    pub fn to_folded(&self) -> String {
+        fn fold(call: &CallTrace, stack: &mut Vec<String>, out: &mut String) {
+            stack.push(call.label());
+
+            // Writing to a string can't fail, so it's safe to ignore the error.
+            let _ = writeln!(out, "{} {}", stack.join(";"), call.self_duration());
+
+            for nested in &call.calls {
+                fold(nested, stack, out);
+            }
+
+            stack.pop();
+        }
+
+        let mut out = String::new();
+        for call in &self.calls {
+            fold(call, &mut vec![], &mut out);
+        }
+        out
+    }
+}
+
+/// The outcome of simulating a transaction.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SimulateResponse {
+    /// Events emitted and data returned if the transaction succeeded, or the
+    /// error if it failed.
+    pub result: GenericResult<TxResponse, ExecutionError>,
+    pub trace: Trace,
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    fn mock_call(
+        kind: CallKind,
+        msg: Option<Json>,
+        duration: u64,
+        calls: Vec<CallTrace>,
+    ) -> CallTrace {
+        CallTrace {
+            contract: Some(Addr::mock(1)),
+            entry_point: (kind == CallKind::Contract).then(|| "execute".to_string()),
+            msg,
+            duration,
+            calls,
+            ..CallTrace::new(kind)
+        }
+    }
+
+    #[test]
+    fn folding_trace_works() {
+        let trace = Trace {
+            calls: vec![mock_call(CallKind::Contract, None, 100, vec![
+                mock_call(CallKind::Query, Some(json!({ "info": {} })), 30, vec![]),
+                mock_call(
+                    CallKind::Submessage,
+                    Some(json!({ "transfer": { "to": "", "coins": [] } })),
+                    50,
+                    vec![mock_call(CallKind::Contract, None, 45, vec![])],
+                ),
+            ])],
+        };
+
+        let contract = format!("{}:execute", Addr::mock(1));
+        assert_eq!(
+            trace.to_folded(),
+            [
+                format!("{contract} 20"),
+                format!("{contract};query:info 30"),
+                format!("{contract};submsg:transfer 5"),
+                format!("{contract};submsg:transfer;{contract} 45"),
+                String::new(),
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use {
-    crate::{Addr, Binary, Coins, Config, Hash, Json},
+    crate::{
+        Addr, Binary, Coins, Config, Hash, IbcChannelCounterparty, IbcChannelOrder,
+        IbcConnectionCounterparty, IbcConnectionVersion, IbcPacket, IbcTimeout, Json,
+    },
     serde::{Deserialize, Serialize},
     serde_with::skip_serializing_none,
 };
@@ -47,4 +50,128 @@ pub enum Message {
         new_code_hash: Hash,
         msg: Json,
     },
+    /// Create a new IBC light client.
+    ///
+    /// The client is an account running the given code, which must be one of
+    /// the `allowed_clients` in the chain's config. Its address, which serves
+    /// as the client ID, is derived the same way as a contract's.
+    CreateClient {
+        code_hash: Hash,
+        client_state: Json,
+        consensus_state: Json,
+        salt: Binary,
+    },
+    /// Update an IBC light client with a new header from the counterparty chain.
+    UpdateClient { client_id: Addr, header: Json },
+    /// Submit evidence that the counterparty chain has misbehaved, e.g. signed
+    /// two different headers at the same height. If the evidence is valid, the
+    /// client is frozen.
+    SubmitMisbehavior { client_id: Addr, misbehavior: Json },
+    /// Start the handshake to open an IBC connection with the counterparty
+    /// chain tracked by the client (ICS-03 `ConnOpenInit`).
+    ///
+    /// This is the only step of the handshake that requires the permission to
+    /// create connections; the others can be relayed by anyone.
+    ConnOpenInit {
+        client_id: Addr,
+        counterparty: IbcConnectionCounterparty,
+        delay_period: u64,
+    },
+    /// Respond to the counterparty chain's `ConnOpenInit`, proving that its
+    /// connection end is in the `Init` state (ICS-03 `ConnOpenTry`).
+    ConnOpenTry {
+        client_id: Addr,
+        counterparty: IbcConnectionCounterparty,
+        counterparty_versions: Vec<IbcConnectionVersion>,
+        delay_period: u64,
+        proof_height: u64,
+        proof_init: Json,
+    },
+    /// Respond to the counterparty chain's `ConnOpenTry`, proving that its
+    /// connection end is in the `TryOpen` state (ICS-03 `ConnOpenAck`).
+    ConnOpenAck {
+        connection_id: String,
+        counterparty_connection_id: String,
+        version: IbcConnectionVersion,
+        proof_height: u64,
+        proof_try: Json,
+    },
+    /// Finish the handshake, proving that the counterparty chain's connection
+    /// end is in the `Open` state (ICS-03 `ConnOpenConfirm`).
+    ConnOpenConfirm {
+        connection_id: String,
+        proof_height: u64,
+        proof_ack: Json,
+    },
+    /// Start the handshake to open an IBC channel between the port contract and
+    /// a port on the counterparty chain (ICS-04 `ChanOpenInit`).
+    ChanOpenInit {
+        port_id: Addr,
+        connection_id: String,
+        counterparty_port_id: String,
+        ordering: IbcChannelOrder,
+        version: String,
+    },
+    /// Respond to the counterparty chain's `ChanOpenInit`, proving that its
+    /// channel end is in the `Init` state (ICS-04 `ChanOpenTry`).
+    ChanOpenTry {
+        port_id: Addr,
+        connection_id: String,
+        counterparty: IbcChannelCounterparty,
+        ordering: IbcChannelOrder,
+        version: String,
+        counterparty_version: String,
+        proof_height: u64,
+        proof_init: Json,
+    },
+    /// Respond to the counterparty chain's `ChanOpenTry`, proving that its
+    /// channel end is in the `TryOpen` state (ICS-04 `ChanOpenAck`).
+    ChanOpenAck {
+        port_id: Addr,
+        channel_id: String,
+        counterparty_channel_id: String,
+        counterparty_version: String,
+        proof_height: u64,
+        proof_try: Json,
+    },
+    /// Finish the handshake, proving that the counterparty chain's channel end
+    /// is in the `Open` state (ICS-04 `ChanOpenConfirm`).
+    ChanOpenConfirm {
+        port_id: Addr,
+        channel_id: String,
+        proof_height: u64,
+        proof_ack: Json,
+    },
+    /// Send a packet over an IBC channel whose port is the sender contract.
+    /// The packet's sequence is returned as the message's data.
+    SendPacket {
+        channel_id: String,
+        data: Binary,
+        timeout: IbcTimeout,
+    },
+    /// Deliver a packet sent by the counterparty chain to the receiving
+    /// contract, proving that the counterparty committed to it.
+    RecvPacket {
+        packet: IbcPacket,
+        proof_height: u64,
+        proof_commitment: Json,
+    },
+    /// Deliver the counterparty chain's acknowledgement of a packet to the
+    /// sending contract, proving that the counterparty wrote it.
+    Acknowledgement {
+        packet: IbcPacket,
+        acknowledgement: Binary,
+        proof_height: u64,
+        proof_acked: Json,
+    },
+    /// Notify the sending contract that a packet has timed out, proving that
+    /// the counterparty chain hasn't received it. For ordered channels, the
+    /// proof is of the counterparty's next receive sequence instead, and the
+    /// channel is closed.
+    Timeout {
+        packet: IbcPacket,
+        next_sequence_recv: u64,
+        proof_height: u64,
+        proof_unreceived: Json,
+    },
 }
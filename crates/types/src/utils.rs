@@ -1,7 +1,7 @@
 //! This file contains helper functions for use in implementing database- or
 //! math- related functionalities. Generally they involve manipulating raw bytes.
 
-use crate::{StdError, StdResult};
+use crate::{Batch, Op, StdError, StdResult};
 
 // --------------------------------- database ----------------------------------
 
@@ -85,6 +85,19 @@ pub fn increment_last_byte(mut bytes: Vec<u8>) -> Vec<u8> {
     bytes
 }
 
+/// Given a prefix, return the smallest byte array that is greater than all keys
+/// that start with this prefix. This is useful as the exclusive upper bound when
+/// iterating all keys under a prefix.
+///
+/// Return `None` if such a byte array doesn't exist, i.e. the prefix is empty or
+/// consists entirely of 255 bytes. In this case, the iteration is unbounded.
+pub fn prefix_upper_bound(prefix: &[u8]) -> Option<Vec<u8>> {
+    let end = prefix.iter().rposition(|byte| *byte != u8::MAX)?;
+    let mut bound = prefix[..=end].to_vec();
+    bound[end] += 1;
+    Some(bound)
+}
+
 /// Given an extendable byte slice, append a zero byte to the end of it.
 /// This is useful for dealing with iterator bounds.
 pub fn extend_one_byte(mut bytes: Vec<u8>) -> Vec<u8> {
@@ -191,6 +204,81 @@ pub fn decode_sections(data: &[u8]) -> Vec<&[u8]> {
     result
 }
 
+/// Encodes a batch of DB ops into one vector, so that it can be passed through
+/// the FFI boundary in one go.
+///
+/// Each op is encoded as two sections: the key, followed by either a `1` byte
+/// and the value (for insertions), or a single `0` byte (for deletions).
+pub fn encode_batch(batch: &Batch) -> StdResult<Vec<u8>> {
+    let ops = batch
+        .iter()
+        .map(|(key, op)| (key.as_slice(), encode_op(op.as_ref())))
+        .collect::<Vec<_>>();
+    let sections = ops
+        .iter()
+        .flat_map(|(key, op)| [*key, op.as_slice()])
+        .collect::<Vec<_>>();
+    encode_sections(&sections)
+}
+
+/// Decodes a batch of DB ops. The reverse of what `encode_batch` does.
+pub fn decode_batch(data: &[u8]) -> StdResult<Batch> {
+    let sections = decode_sections(data);
+    if sections.len() % 2 != 0 {
+        return Err(StdError::deserialize::<Batch>("odd number of sections"));
+    }
+
+    sections
+        .chunks_exact(2)
+        .map(|chunk| Ok((chunk[0].to_vec(), decode_op(chunk[1])?)))
+        .collect()
+}
+
+/// Encodes a list of optional values into one vector, so that it can be passed
+/// through the FFI boundary in one go.
+///
+/// Each value is encoded as one section: either a `1` byte followed by the
+/// value (if it exists), or a single `0` byte (if it doesn't).
+pub fn encode_optional_values(values: &[Option<Vec<u8>>]) -> StdResult<Vec<u8>> {
+    let values = values
+        .iter()
+        .map(|value| encode_op(value.as_ref().map_or(Op::Delete, Op::Insert)))
+        .collect::<Vec<_>>();
+    let sections = values.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    encode_sections(&sections)
+}
+
+/// Decodes a list of optional values. The reverse of what
+/// `encode_optional_values` does.
+pub fn decode_optional_values(data: &[u8]) -> StdResult<Vec<Option<Vec<u8>>>> {
+    decode_sections(data)
+        .into_iter()
+        .map(|section| decode_op(section).map(Op::into_option))
+        .collect()
+}
+
+#[inline]
+fn encode_op(op: Op<&Vec<u8>>) -> Vec<u8> {
+    match op {
+        Op::Insert(value) => {
+            let mut bytes = Vec::with_capacity(value.len() + 1);
+            bytes.push(1);
+            bytes.extend_from_slice(value);
+            bytes
+        },
+        Op::Delete => vec![0],
+    }
+}
+
+#[inline]
+fn decode_op(bytes: &[u8]) -> StdResult<Op> {
+    match bytes.split_first() {
+        Some((1, value)) => Ok(Op::Insert(value.to_vec())),
+        Some((0, [])) => Ok(Op::Delete),
+        _ => Err(StdError::deserialize::<Op>("invalid op encoding")),
+    }
+}
+
 // ----------------------------------- math ------------------------------------
 
 pub const fn grow_be_int<const INPUT_SIZE: usize, const OUTPUT_SIZE: usize>(
@@ -281,7 +369,13 @@ pub const fn grow_le_uint<const INPUT_SIZE: usize, const OUTPUT_SIZE: usize>(
 
 #[cfg(test)]
 mod tests {
-    use crate::{decode_sections, encode_sections};
+    use {
+        crate::{
+            decode_batch, decode_optional_values, decode_sections, encode_batch,
+            encode_optional_values, encode_sections, prefix_upper_bound, Batch, Op,
+        },
+        test_case::test_case,
+    };
 
     #[test]
     fn encode_decode() {
@@ -289,4 +383,30 @@ mod tests {
         let encoded = encode_sections(data).unwrap();
         assert_eq!(data, decode_sections(&encoded));
     }
+
+    #[test]
+    fn encode_decode_batch() {
+        let batch = Batch::from([
+            (b"larry".to_vec(), Op::Insert(b"engineer".to_vec())),
+            (b"jake".to_vec(), Op::Delete),
+            (b"pumpkin".to_vec(), Op::Insert(vec![])),
+        ]);
+        let encoded = encode_batch(&batch).unwrap();
+        assert_eq!(decode_batch(&encoded).unwrap(), batch);
+    }
+
+    #[test]
+    fn encode_decode_optional_values() {
+        let values = vec![Some(b"engineer".to_vec()), None, Some(vec![])];
+        let encoded = encode_optional_values(&values).unwrap();
+        assert_eq!(decode_optional_values(&encoded).unwrap(), values);
+    }
+
+    #[test_case(b"", None; "empty")]
+    #[test_case(b"abc", Some(b"abd".to_vec()); "no trailing max")]
+    #[test_case(&[1, 255, 255], Some(vec![2]); "trailing max")]
+    #[test_case(&[255, 255], None; "all max")]
+    fn prefix_upper_bound_works(prefix: &[u8], expect: Option<Vec<u8>>) {
+        assert_eq!(prefix_upper_bound(prefix), expect);
+    }
 }
@@ -0,0 +1,35 @@
+use {
+    grug_app::AppError,
+    grug_types::{ErrorCategory, ExecutionError},
+    thiserror::Error,
+};
+
+#[derive(Debug, Error)]
+pub enum VmError {
+    #[error(transparent)]
+    Rust(#[from] grug_vm_rust::VmError),
+
+    #[error(transparent)]
+    Wasm(#[from] grug_vm_wasm::VmError),
+
+    #[error("exceeded the maximum call depth! max: {max}")]
+    ExceedMaxCallDepth { max: usize },
+}
+
+impl VmError {
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            VmError::Rust(err) => err.category(),
+            VmError::Wasm(err) => err.category(),
+            VmError::ExceedMaxCallDepth { .. } => ErrorCategory::Trap,
+        }
+    }
+}
+
+impl From<VmError> for AppError {
+    fn from(err: VmError) -> Self {
+        AppError::Execution(ExecutionError::new(err.category(), err))
+    }
+}
+
+pub type VmResult<T> = core::result::Result<T, VmError>;
@@ -0,0 +1,4 @@
+mod error;
+mod vm;
+
+pub use {error::*, vm::*};
@@ -0,0 +1,153 @@
+use {
+    crate::{VmError, VmResult},
+    grug_app::{Instance, QuerierProvider, StorageProvider, Vm},
+    grug_types::Context,
+    grug_vm_rust::{RustInstance, RustVm},
+    grug_vm_wasm::{WasmInstance, WasmVm},
+};
+
+/// The first four bytes of any valid Wasm module.
+pub const WASM_MAGIC: &[u8] = b"\0asm";
+
+// ------------------------------------ vm -------------------------------------
+
+/// A VM that runs both native Rust contracts and Wasm contracts in the same
+/// chain.
+///
+/// Which backend a contract runs in is decided by its code: code that starts
+/// with the Wasm magic bytes is run by the Wasm VM, while anything else is
+/// looked up in the Rust VM's registry.
+///
+/// Instances of either backend are given a querier that dispatches to this
+/// hybrid VM, so contracts can query and message each other regardless of
+/// which backend the other contract runs in.
+///
+/// The call depth is tracked here rather than by the backends, since a call
+/// stack may alternate between them. It's capped at the Wasm VM's
+/// `max_call_depth`, regardless of which backend each frame runs in.
+#[derive(Default, Clone)]
+pub struct HybridVm {
+    rust: RustVm,
+    wasm: WasmVm,
+    /// The number of instances above the ones built by this VM in the call
+    /// stack.
+    depth: usize,
+}
+
+impl HybridVm {
+    pub fn new(rust: RustVm, wasm: WasmVm) -> Self {
+        Self {
+            rust,
+            wasm,
+            depth: 0,
+        }
+    }
+
+    /// Return the Rust VM, which native contracts need to be registered with
+    /// before they can be uploaded.
+    pub fn rust(&self) -> &RustVm {
+        &self.rust
+    }
+}
+
+impl Vm for HybridVm {
+    type Error = VmError;
+    type Instance = HybridInstance;
+
+    fn build_instance(
+        &mut self,
+        storage: StorageProvider,
+        mut querier: QuerierProvider<Self>,
+        code: &[u8],
+    ) -> VmResult<HybridInstance> {
+        let max_call_depth = self.wasm.limits().max_call_depth;
+        if self.depth >= max_call_depth {
+            return Err(VmError::ExceedMaxCallDepth {
+                max: max_call_depth,
+            });
+        }
+
+        // instances built for queries made by this instance are one level
+        // deeper in the call stack
+        querier.vm_mut().depth = self.depth + 1;
+
+        if code.starts_with(WASM_MAGIC) {
+            self.wasm
+                .build_instance_with_querier(
+                    storage,
+                    Box::new(querier.clone()),
+                    Box::new(querier),
+                    code,
+                )
+                .map(HybridInstance::Wasm)
+                .map_err(Into::into)
+        } else {
+            self.rust
+                .build_instance_with_querier(
+                    storage,
+                    Box::new(querier.clone()),
+                    Box::new(querier),
+                    code,
+                )
+                .map(HybridInstance::Rust)
+                .map_err(Into::into)
+        }
+    }
+
+    fn validate_code(&self, code: &[u8]) -> VmResult<()> {
+        if code.starts_with(WASM_MAGIC) {
+            Ok(self.wasm.validate_code(code)?)
+        } else {
+            Ok(self.rust.validate_code(code)?)
+        }
+    }
+}
+
+// --------------------------------- instance ----------------------------------
+
+pub enum HybridInstance {
+    Rust(RustInstance),
+    Wasm(WasmInstance),
+}
+
+impl Instance for HybridInstance {
+    type Error = VmError;
+
+    fn call_in_0_out_1(self, name: &str, ctx: &Context) -> VmResult<Vec<u8>> {
+        match self {
+            HybridInstance::Rust(instance) => Ok(instance.call_in_0_out_1(name, ctx)?),
+            HybridInstance::Wasm(instance) => Ok(instance.call_in_0_out_1(name, ctx)?),
+        }
+    }
+
+    fn call_in_1_out_1<P>(self, name: &str, ctx: &Context, param: &P) -> VmResult<Vec<u8>>
+    where
+        P: AsRef<[u8]>,
+    {
+        match self {
+            HybridInstance::Rust(instance) => Ok(instance.call_in_1_out_1(name, ctx, param)?),
+            HybridInstance::Wasm(instance) => Ok(instance.call_in_1_out_1(name, ctx, param)?),
+        }
+    }
+
+    fn call_in_2_out_1<P1, P2>(
+        self,
+        name: &str,
+        ctx: &Context,
+        param1: &P1,
+        param2: &P2,
+    ) -> VmResult<Vec<u8>>
+    where
+        P1: AsRef<[u8]>,
+        P2: AsRef<[u8]>,
+    {
+        match self {
+            HybridInstance::Rust(instance) => {
+                Ok(instance.call_in_2_out_1(name, ctx, param1, param2)?)
+            },
+            HybridInstance::Wasm(instance) => {
+                Ok(instance.call_in_2_out_1(name, ctx, param1, param2)?)
+            },
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use {
+    anyhow::ensure,
+    grug_account::{make_sign_bytes, PublicKey, StateResponse},
+    grug_app::{App, AppResult},
+    grug_crypto::{sha2_256, Identity256},
+    grug_db_memory::MemDb,
+    grug_types::{
+        from_json_value, to_json_value, Addr, BankMsg, BankQuery, BankQueryResponse, BlockInfo,
+        Coin, Coins, Config, Empty, GenesisState, Hash, ImmutableCtx, Json, Message, MutableCtx,
+        NumberConst, Permission, Permissions, QueryRequest, QueryResponse, Response, StdError,
+        StdResult, SudoCtx, Timestamp, Tx, Uint64, GENESIS_SENDER,
+    },
+    grug_vm_hybrid::HybridVm,
+    grug_vm_rust::ContractBuilder,
+    k256::ecdsa::{signature::DigestSigner, Signature, SigningKey},
+    rand::rngs::OsRng,
+    serde::{de::DeserializeOwned, ser::Serialize},
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        fs, io, vec,
+    },
+};
+
+const MOCK_CHAIN_ID: &str = "grug-1";
+const MOCK_DENOM: &str = "ugrug";
+const MOCK_BANK_SALT: &[u8] = b"bank";
+const MOCK_READER_SALT: &[u8] = b"reader";
+const MOCK_SENDER_SALT: &[u8] = b"sender";
+const MOCK_RECEIVER_SALT: &[u8] = b"receiver";
+const MOCK_RECURSER_SALT: &[u8] = b"recurser";
+
+fn read_wasm_file(filename: &str) -> io::Result<Vec<u8>> {
+    let path = format!("{}/../wasm/testdata/{filename}", env!("CARGO_MANIFEST_DIR"));
+    fs::read(path)
+}
+
+// ------------------------------ native contracts -----------------------------
+
+// The bank runs natively, reusing the logics of the Wasm bank contract.
+
+fn bank_instantiate(ctx: MutableCtx, msg: grug_bank::InstantiateMsg) -> StdResult<Response> {
+    grug_bank::initialize(ctx.storage, msg.initial_balances, msg.namespaces)
+}
+
+fn bank_execute(ctx: SudoCtx, msg: BankMsg) -> StdResult<Response> {
+    grug_bank::transfer(ctx.storage, &msg.from, &msg.to, &msg.coins)
+}
+
+fn bank_query(ctx: ImmutableCtx, msg: BankQuery) -> StdResult<BankQueryResponse> {
+    match msg {
+        BankQuery::Balance { address, denom } => {
+            grug_bank::query_balance(ctx.storage, address, denom).map(BankQueryResponse::Balance)
+        },
+        _ => Err(StdError::generic_err("unsupported bank query")),
+    }
+}
+
+// A native contract that forwards queries to a Wasm account, to test that
+// contracts in one VM can query contracts in the other.
+
+fn reader_instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn reader_query(ctx: ImmutableCtx, account: Addr) -> StdResult<Json> {
+    let res: StateResponse = ctx
+        .querier
+        .query_wasm_smart(account, &grug_account::QueryMsg::State {})?;
+    to_json_value(&res)
+}
+
+// A native contract that queries itself the given number of times, to test
+// that the call depth is capped even if the call stack never enters Wasm.
+
+fn recurser_query(ctx: ImmutableCtx, times: u32) -> StdResult<Json> {
+    if times > 0 {
+        ctx.querier
+            .query_wasm_smart::<_, Json>(ctx.contract.clone(), &(times - 1))?;
+    }
+
+    Ok(Json::Null)
+}
+
+// --------------------------------- test suite --------------------------------
+
+struct TestSuite {
+    app: App<MemDb, HybridVm>,
+    vm: HybridVm,
+    block: BlockInfo,
+}
+
+impl TestSuite {
+    fn new() -> Self {
+        let vm = HybridVm::default();
+        Self {
+            app: App::new(MemDb::new(), vm.clone()),
+            vm,
+            block: BlockInfo {
+                height: Uint64::ZERO,
+                timestamp: Timestamp::from_nanos(0),
+                hash: Hash::ZERO,
+            },
+        }
+    }
+
+    fn init_chain(&mut self, genesis_state: GenesisState) -> AppResult<Hash> {
+        self.app
+            .do_init_chain(MOCK_CHAIN_ID.to_string(), self.block.clone(), genesis_state)
+    }
+
+    fn query(&self, req: QueryRequest) -> AppResult<QueryResponse> {
+        self.app.do_query_app(req, self.block.height.into(), false)
+    }
+
+    fn query_wasm_smart<M: Serialize, R: DeserializeOwned>(
+        &self,
+        contract: Addr,
+        msg: &M,
+    ) -> anyhow::Result<R> {
+        let msg_raw = to_json_value(&msg)?;
+        let res_raw = self
+            .query(QueryRequest::WasmSmart {
+                contract,
+                msg: msg_raw,
+            })?
+            .as_wasm_smart()
+            .data;
+        Ok(from_json_value(res_raw)?)
+    }
+
+    fn query_account_sequence(&self, address: Addr) -> anyhow::Result<u32> {
+        self.query_wasm_smart(address, &grug_account::QueryMsg::State {})
+            .map(|res: StateResponse| res.sequence)
+    }
+
+    fn send_messages(
+        &mut self,
+        sender: Addr,
+        sk: &SigningKey,
+        msgs: Vec<Message>,
+    ) -> anyhow::Result<()> {
+        // Sign the transaction
+        let sequence = self.query_account_sequence(sender.clone())?;
+        let sign_bytes = make_sign_bytes(sha2_256, &msgs, &sender, MOCK_CHAIN_ID, sequence)?;
+        let signature: Signature = sk.sign_digest(Identity256::from(sign_bytes));
+        let tx = Tx {
+            sender,
+            msgs,
+            credential: signature.to_vec().into(),
+        };
+
+        // Increment block height and block time
+        self.block.height += Uint64::ONE;
+        self.block.timestamp = self.block.timestamp.plus_nanos(1);
+
+        // Finalize block + commit
+        self.app
+            .do_finalize_block(self.block.clone(), vec![(Hash::ZERO, tx)])?;
+        self.app.do_commit()?;
+
+        Ok(())
+    }
+
+    fn assert_balance(&self, address: Addr, denom: &str, expect: u128) -> anyhow::Result<()> {
+        let actual = self
+            .query(QueryRequest::Balance {
+                address,
+                denom: denom.to_string(),
+            })?
+            .as_balance()
+            .amount
+            .number();
+
+        ensure!(actual == expect);
+
+        Ok(())
+    }
+}
+
+#[test]
+fn hybrid_vm_works() -> anyhow::Result<()> {
+    let mut suite = TestSuite::new();
+
+    // Generate private keys for the accounts
+    let sender_sk = SigningKey::random(&mut OsRng);
+    let sender_pk = sender_sk.verifying_key().to_encoded_point(true).to_bytes();
+    let receiver_sk = SigningKey::random(&mut OsRng);
+    let receiver_pk = receiver_sk
+        .verifying_key()
+        .to_encoded_point(true)
+        .to_bytes();
+
+    // Load account contract byte code, and predict account addresses.
+    // The accounts run in the Wasm VM.
+    let account_code = read_wasm_file("grug_account.wasm")?;
+    let account_code_hash = Hash::from_slice(sha2_256(&account_code));
+    let sender = Addr::compute(&GENESIS_SENDER, &account_code_hash, MOCK_SENDER_SALT);
+    let receiver = Addr::compute(&GENESIS_SENDER, &account_code_hash, MOCK_RECEIVER_SALT);
+
+    // Register the bank and reader contracts, and predict their addresses.
+    // These run in the Rust VM.
+    let bank_code = suite.vm.rust().register(
+        "bank",
+        "0.0.0",
+        ContractBuilder::new(Box::new(bank_instantiate))
+            .with_bank_execute(Box::new(bank_execute))
+            .with_bank_query(Box::new(bank_query))
+            .build(),
+    )?;
+    let bank_code_hash = Hash::from_slice(sha2_256(&bank_code));
+    let bank = Addr::compute(&GENESIS_SENDER, &bank_code_hash, MOCK_BANK_SALT);
+
+    let reader_code = suite.vm.rust().register(
+        "reader",
+        "0.0.0",
+        ContractBuilder::new(Box::new(reader_instantiate))
+            .with_query(Box::new(reader_query))
+            .build(),
+    )?;
+    let reader_code_hash = Hash::from_slice(sha2_256(&reader_code));
+    let reader = Addr::compute(&GENESIS_SENDER, &reader_code_hash, MOCK_READER_SALT);
+
+    // Genesis the chain. This deploys the contracts and gives the "sender"
+    // account 100 ugrug.
+    suite.init_chain(GenesisState {
+        config: Config {
+            owner: None,
+            bank: bank.clone(),
+            begin_blockers: vec![],
+            end_blockers: vec![],
+            permissions: Permissions {
+                upload: Permission::Everybody,
+                instantiate: Permission::Everybody,
+                create_client: Permission::Everybody,
+                create_connection: Permission::Everybody,
+                create_channel: Permission::Everybody,
+            },
+            allowed_clients: BTreeSet::new(),
+        },
+        msgs: vec![
+            Message::Upload {
+                code: account_code.into(),
+            },
+            Message::Upload {
+                code: bank_code.into(),
+            },
+            Message::Upload {
+                code: reader_code.into(),
+            },
+            Message::Instantiate {
+                code_hash: account_code_hash.clone(),
+                msg: to_json_value(&grug_account::InstantiateMsg {
+                    public_key: PublicKey::Secp256k1(sender_pk.to_vec().into()),
+                })?,
+                salt: MOCK_SENDER_SALT.to_vec().into(),
+                funds: Coins::new_empty(),
+                admin: Some(sender.clone()),
+            },
+            Message::Instantiate {
+                code_hash: account_code_hash.clone(),
+                msg: to_json_value(&grug_account::InstantiateMsg {
+                    public_key: PublicKey::Secp256k1(receiver_pk.to_vec().into()),
+                })?,
+                salt: MOCK_RECEIVER_SALT.to_vec().into(),
+                funds: Coins::new_empty(),
+                admin: Some(receiver.clone()),
+            },
+            Message::Instantiate {
+                code_hash: bank_code_hash,
+                msg: to_json_value(&grug_bank::InstantiateMsg {
+                    initial_balances: BTreeMap::from([(
+                        sender.clone(),
+                        Coins::new_one(MOCK_DENOM, 100_u128),
+                    )]),
+                    namespaces: BTreeMap::new(),
+                })?,
+                salt: MOCK_BANK_SALT.to_vec().into(),
+                funds: Coins::new_empty(),
+                admin: None,
+            },
+            Message::Instantiate {
+                code_hash: reader_code_hash,
+                msg: to_json_value(&Empty {})?,
+                salt: MOCK_READER_SALT.to_vec().into(),
+                funds: Coins::new_empty(),
+                admin: None,
+            },
+        ],
+    })?;
+
+    // Check that sender has been given 100 ugrug.
+    suite.assert_balance(sender.clone(), MOCK_DENOM, 100)?;
+
+    // Sender sends 25 ugrug to the receiver. The Wasm account authenticates
+    // the tx, the native bank makes the transfer, then the Wasm receiver is
+    // notified of the funds.
+    suite.send_messages(sender.clone(), &sender_sk, vec![Message::Transfer {
+        to: receiver.clone(),
+        coins: vec![Coin::new(MOCK_DENOM, 25_u128)].try_into().unwrap(),
+    }])?;
+
+    // Check balances again.
+    suite.assert_balance(sender.clone(), MOCK_DENOM, 75)?;
+    suite.assert_balance(receiver, MOCK_DENOM, 25)?;
+
+    // The native reader can query the Wasm account.
+    let state: StateResponse = suite.query_wasm_smart(reader, &sender)?;
+    ensure!(state.sequence == 1);
+
+    Ok(())
+}
+
+#[test]
+fn call_depth_is_capped_across_native_contracts() -> anyhow::Result<()> {
+    let mut suite = TestSuite::new();
+
+    let recurser_code = suite.vm.rust().register(
+        "recurser",
+        "0.0.0",
+        ContractBuilder::new(Box::new(reader_instantiate))
+            .with_query(Box::new(recurser_query))
+            .build(),
+    )?;
+    let recurser_code_hash = Hash::from_slice(sha2_256(&recurser_code));
+    let recurser = Addr::compute(&GENESIS_SENDER, &recurser_code_hash, MOCK_RECURSER_SALT);
+
+    suite.init_chain(GenesisState {
+        config: Config {
+            owner: None,
+            bank: recurser.clone(),
+            begin_blockers: vec![],
+            end_blockers: vec![],
+            permissions: Permissions {
+                upload: Permission::Everybody,
+                instantiate: Permission::Everybody,
+                create_client: Permission::Everybody,
+                create_connection: Permission::Everybody,
+                create_channel: Permission::Everybody,
+            },
+            allowed_clients: BTreeSet::new(),
+        },
+        msgs: vec![
+            Message::Upload {
+                code: recurser_code.into(),
+            },
+            Message::Instantiate {
+                code_hash: recurser_code_hash,
+                msg: to_json_value(&Empty {})?,
+                salt: MOCK_RECURSER_SALT.to_vec().into(),
+                funds: Coins::new_empty(),
+                admin: None,
+            },
+        ],
+    })?;
+
+    // The default limit is 16, and the outermost query counts as one level.
+    suite.query_wasm_smart::<_, Json>(recurser.clone(), &15)?;
+
+    let err = suite
+        .query_wasm_smart::<_, Json>(recurser, &16)
+        .unwrap_err();
+    ensure!(err.to_string().contains("exceeded the maximum call depth"));
+
+    Ok(())
+}
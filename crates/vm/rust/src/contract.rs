@@ -1,25 +1,36 @@
 use {
-    crate::{Contract, ExecuteFn, InstantiateFn, MigrateFn, QueryFn, ReceiveFn, ReplyFn},
-    elsa::sync::FrozenVec,
+    crate::{
+        AfterBlockFn, AfterTxFn, BankQueryFn, BankTransferFn, BeforeBlockFn, BeforeTxFn, Contract,
+        ExecuteFn, IbcChannelConnectFn, IbcChannelOpenFn, IbcClientCreateFn,
+        IbcClientMisbehaviorFn, IbcClientQueryFn, IbcClientUpdateFn, IbcPacketAckFn,
+        IbcPacketReceiveFn, IbcPacketTimeoutFn, InstantiateFn, MigrateFn, QueryFn, ReceiveFn,
+        ReplyFn,
+    },
     grug_types::{
-        from_json_value, make_immutable_ctx, make_mutable_ctx, make_sudo_ctx,
-        return_into_generic_result, unwrap_into_generic_result, Api, Context, GenericResult,
+        from_json_value, make_auth_ctx, make_immutable_ctx, make_mutable_ctx, make_sudo_ctx, Api,
+        AuthCtx, BankMsg, BankQuery, BankQueryResponse, Binary, Caller, Context, GenericResult,
+        IbcChannelConnectMsg, IbcChannelOpenMsg, IbcClientQuery, IbcClientQueryResponse, IbcPacket,
         ImmutableCtx, Json, MutableCtx, Querier, QuerierWrapper, Response, Storage, SubMsgResult,
-        SudoCtx,
+        SudoCtx, Tx,
     },
     serde::de::DeserializeOwned,
-    std::sync::OnceLock,
+    std::sync::Arc,
 };
 
-pub(crate) static CONTRACTS: OnceLock<FrozenVec<Box<dyn Contract + Send + Sync>>> = OnceLock::new();
-
 // ---------------------------------- wrapper ----------------------------------
 
+/// A contract made of native Rust functions, ready to be registered with a
+/// [`RustVm`](crate::RustVm).
+#[derive(Clone)]
 pub struct ContractWrapper {
-    pub(crate) index: usize,
+    pub(crate) inner: Arc<dyn Contract + Send + Sync>,
 }
 
 impl ContractWrapper {
+    /// Create a contract with the basic entry points.
+    ///
+    /// For other entry points, such as `before_tx` or `bank_execute`, use
+    /// [`ContractBuilder`] instead.
     pub fn new<M1, M2, M3, M5, M6, E1, E2, E3, E4, E5, E6>(
         instantiate_fn: InstantiateFn<M1, E1>,
         execute_fn: Option<ExecuteFn<M2, E2>>,
@@ -41,67 +52,363 @@ impl ContractWrapper {
         E5: ToString + 'static,
         E6: ToString + 'static,
     {
-        let contracts = CONTRACTS.get_or_init(Default::default);
-        let index = contracts.len();
-        contracts.push(Box::new(ContractImpl {
-            instantiate_fn,
-            execute_fn,
-            migrate_fn,
-            receive_fn,
-            reply_fn,
-            query_fn,
+        let mut builder = ContractBuilder::new(instantiate_fn);
+        if let Some(execute_fn) = execute_fn {
+            builder = builder.with_execute(execute_fn);
+        }
+        if let Some(migrate_fn) = migrate_fn {
+            builder = builder.with_migrate(migrate_fn);
+        }
+        if let Some(receive_fn) = receive_fn {
+            builder = builder.with_receive(receive_fn);
+        }
+        if let Some(reply_fn) = reply_fn {
+            builder = builder.with_reply(reply_fn);
+        }
+        if let Some(query_fn) = query_fn {
+            builder = builder.with_query(query_fn);
+        }
+        builder.build()
+    }
+}
+
+// ---------------------------------- builder ----------------------------------
+
+/// Builds a contract out of native Rust functions, one for each entry point.
+///
+/// Only `instantiate` is mandatory. Calling an entry point that hasn't been
+/// provided results in an error.
+///
+/// Messages and errors are type-erased when the functions are provided: the
+/// messages are deserialized from JSON, and the errors are stringified, the
+/// same way the `grug-wasm` exports handle them.
+pub struct ContractBuilder {
+    instantiate_fn: InstantiateFn<Json, String>,
+    execute_fn: Option<ExecuteFn<Json, String>>,
+    migrate_fn: Option<MigrateFn<Json, String>>,
+    receive_fn: Option<ReceiveFn<String>>,
+    reply_fn: Option<ReplyFn<Json, String>>,
+    query_fn: Option<QueryFn<Json, String>>,
+    before_tx_fn: Option<BeforeTxFn<String>>,
+    after_tx_fn: Option<AfterTxFn<String>>,
+    before_block_fn: Option<BeforeBlockFn<String>>,
+    after_block_fn: Option<AfterBlockFn<String>>,
+    bank_execute_fn: Option<BankTransferFn<String>>,
+    bank_query_fn: Option<BankQueryFn<String>>,
+    ibc_client_create_fn: Option<IbcClientCreateFn<Json, Json, String>>,
+    ibc_client_update_fn: Option<IbcClientUpdateFn<Json, String>>,
+    ibc_client_misbehavior_fn: Option<IbcClientMisbehaviorFn<Json, String>>,
+    ibc_client_query_fn: Option<IbcClientQueryFn<String>>,
+    ibc_channel_open_fn: Option<IbcChannelOpenFn<String>>,
+    ibc_channel_connect_fn: Option<IbcChannelConnectFn<String>>,
+    ibc_packet_receive_fn: Option<IbcPacketReceiveFn<String>>,
+    ibc_packet_ack_fn: Option<IbcPacketAckFn<String>>,
+    ibc_packet_timeout_fn: Option<IbcPacketTimeoutFn<String>>,
+}
+
+impl ContractBuilder {
+    pub fn new<M, E>(instantiate_fn: InstantiateFn<M, E>) -> Self
+    where
+        M: DeserializeOwned + 'static,
+        E: ToString + 'static,
+    {
+        Self {
+            instantiate_fn: Box::new(move |ctx, msg| {
+                instantiate_fn(ctx, from_json_value(msg).map_err(|err| err.to_string())?)
+                    .map_err(|err| err.to_string())
+            }),
+            execute_fn: None,
+            migrate_fn: None,
+            receive_fn: None,
+            reply_fn: None,
+            query_fn: None,
+            before_tx_fn: None,
+            after_tx_fn: None,
+            before_block_fn: None,
+            after_block_fn: None,
+            bank_execute_fn: None,
+            bank_query_fn: None,
+            ibc_client_create_fn: None,
+            ibc_client_update_fn: None,
+            ibc_client_misbehavior_fn: None,
+            ibc_client_query_fn: None,
+            ibc_channel_open_fn: None,
+            ibc_channel_connect_fn: None,
+            ibc_packet_receive_fn: None,
+            ibc_packet_ack_fn: None,
+            ibc_packet_timeout_fn: None,
+        }
+    }
+
+    pub fn with_execute<M, E>(mut self, execute_fn: ExecuteFn<M, E>) -> Self
+    where
+        M: DeserializeOwned + 'static,
+        E: ToString + 'static,
+    {
+        self.execute_fn = Some(Box::new(move |ctx, msg| {
+            execute_fn(ctx, from_json_value(msg).map_err(|err| err.to_string())?)
+                .map_err(|err| err.to_string())
         }));
-        Self { index }
+        self
     }
 
-    pub fn into_bytes(self) -> Vec<u8> {
-        self.index.to_le_bytes().to_vec()
+    pub fn with_migrate<M, E>(mut self, migrate_fn: MigrateFn<M, E>) -> Self
+    where
+        M: DeserializeOwned + 'static,
+        E: ToString + 'static,
+    {
+        self.migrate_fn = Some(Box::new(move |ctx, msg| {
+            migrate_fn(ctx, from_json_value(msg).map_err(|err| err.to_string())?)
+                .map_err(|err| err.to_string())
+        }));
+        self
     }
 
-    pub fn from_bytes(bytes: &[u8]) -> Self {
-        Self {
-            index: usize::from_le_bytes(bytes.try_into().unwrap()),
+    pub fn with_receive<E>(mut self, receive_fn: ReceiveFn<E>) -> Self
+    where
+        E: ToString + 'static,
+    {
+        self.receive_fn = Some(Box::new(move |ctx| {
+            receive_fn(ctx).map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_reply<M, E>(mut self, reply_fn: ReplyFn<M, E>) -> Self
+    where
+        M: DeserializeOwned + 'static,
+        E: ToString + 'static,
+    {
+        self.reply_fn = Some(Box::new(move |ctx, msg, submsg_res| {
+            reply_fn(
+                ctx,
+                from_json_value(msg).map_err(|err| err.to_string())?,
+                submsg_res,
+            )
+            .map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_query<M, E>(mut self, query_fn: QueryFn<M, E>) -> Self
+    where
+        M: DeserializeOwned + 'static,
+        E: ToString + 'static,
+    {
+        self.query_fn = Some(Box::new(move |ctx, msg| {
+            query_fn(ctx, from_json_value(msg).map_err(|err| err.to_string())?)
+                .map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_before_tx<E>(mut self, before_tx_fn: BeforeTxFn<E>) -> Self
+    where
+        E: ToString + 'static,
+    {
+        self.before_tx_fn = Some(Box::new(move |ctx, tx| {
+            before_tx_fn(ctx, tx).map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_after_tx<E>(mut self, after_tx_fn: AfterTxFn<E>) -> Self
+    where
+        E: ToString + 'static,
+    {
+        self.after_tx_fn = Some(Box::new(move |ctx, tx| {
+            after_tx_fn(ctx, tx).map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_before_block<E>(mut self, before_block_fn: BeforeBlockFn<E>) -> Self
+    where
+        E: ToString + 'static,
+    {
+        self.before_block_fn = Some(Box::new(move |ctx| {
+            before_block_fn(ctx).map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_after_block<E>(mut self, after_block_fn: AfterBlockFn<E>) -> Self
+    where
+        E: ToString + 'static,
+    {
+        self.after_block_fn = Some(Box::new(move |ctx| {
+            after_block_fn(ctx).map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_bank_execute<E>(mut self, bank_execute_fn: BankTransferFn<E>) -> Self
+    where
+        E: ToString + 'static,
+    {
+        self.bank_execute_fn = Some(Box::new(move |ctx, msg| {
+            bank_execute_fn(ctx, msg).map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_bank_query<E>(mut self, bank_query_fn: BankQueryFn<E>) -> Self
+    where
+        E: ToString + 'static,
+    {
+        self.bank_query_fn = Some(Box::new(move |ctx, msg| {
+            bank_query_fn(ctx, msg).map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_ibc_client_create<M1, M2, E>(
+        mut self,
+        ibc_client_create_fn: IbcClientCreateFn<M1, M2, E>,
+    ) -> Self
+    where
+        M1: DeserializeOwned + 'static,
+        M2: DeserializeOwned + 'static,
+        E: ToString + 'static,
+    {
+        self.ibc_client_create_fn = Some(Box::new(move |ctx, client_state, consensus_state| {
+            ibc_client_create_fn(
+                ctx,
+                from_json_value(client_state).map_err(|err| err.to_string())?,
+                from_json_value(consensus_state).map_err(|err| err.to_string())?,
+            )
+            .map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_ibc_client_update<M, E>(
+        mut self,
+        ibc_client_update_fn: IbcClientUpdateFn<M, E>,
+    ) -> Self
+    where
+        M: DeserializeOwned + 'static,
+        E: ToString + 'static,
+    {
+        self.ibc_client_update_fn = Some(Box::new(move |ctx, header| {
+            ibc_client_update_fn(ctx, from_json_value(header).map_err(|err| err.to_string())?)
+                .map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_ibc_client_misbehavior<M, E>(
+        mut self,
+        ibc_client_misbehavior_fn: IbcClientMisbehaviorFn<M, E>,
+    ) -> Self
+    where
+        M: DeserializeOwned + 'static,
+        E: ToString + 'static,
+    {
+        self.ibc_client_misbehavior_fn = Some(Box::new(move |ctx, misbehavior| {
+            ibc_client_misbehavior_fn(
+                ctx,
+                from_json_value(misbehavior).map_err(|err| err.to_string())?,
+            )
+            .map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_ibc_client_query<E>(mut self, ibc_client_query_fn: IbcClientQueryFn<E>) -> Self
+    where
+        E: ToString + 'static,
+    {
+        self.ibc_client_query_fn = Some(Box::new(move |ctx, msg| {
+            ibc_client_query_fn(ctx, msg).map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_ibc_channel_open<E>(mut self, ibc_channel_open_fn: IbcChannelOpenFn<E>) -> Self
+    where
+        E: ToString + 'static,
+    {
+        self.ibc_channel_open_fn = Some(Box::new(move |ctx, msg| {
+            ibc_channel_open_fn(ctx, msg).map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_ibc_channel_connect<E>(
+        mut self,
+        ibc_channel_connect_fn: IbcChannelConnectFn<E>,
+    ) -> Self
+    where
+        E: ToString + 'static,
+    {
+        self.ibc_channel_connect_fn = Some(Box::new(move |ctx, msg| {
+            ibc_channel_connect_fn(ctx, msg).map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_ibc_packet_receive<E>(
+        mut self,
+        ibc_packet_receive_fn: IbcPacketReceiveFn<E>,
+    ) -> Self
+    where
+        E: ToString + 'static,
+    {
+        self.ibc_packet_receive_fn = Some(Box::new(move |ctx, packet| {
+            ibc_packet_receive_fn(ctx, packet).map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_ibc_packet_ack<E>(mut self, ibc_packet_ack_fn: IbcPacketAckFn<E>) -> Self
+    where
+        E: ToString + 'static,
+    {
+        self.ibc_packet_ack_fn = Some(Box::new(move |ctx, packet, acknowledgement| {
+            ibc_packet_ack_fn(ctx, packet, acknowledgement).map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn with_ibc_packet_timeout<E>(
+        mut self,
+        ibc_packet_timeout_fn: IbcPacketTimeoutFn<E>,
+    ) -> Self
+    where
+        E: ToString + 'static,
+    {
+        self.ibc_packet_timeout_fn = Some(Box::new(move |ctx, packet| {
+            ibc_packet_timeout_fn(ctx, packet).map_err(|err| err.to_string())
+        }));
+        self
+    }
+
+    pub fn build(self) -> ContractWrapper {
+        ContractWrapper {
+            inner: Arc::new(self),
         }
     }
 }
 
-// ----------------------------------- impl ------------------------------------
-
-pub struct ContractImpl<M1, M2, M3, M5, M6, E1, E2, E3, E4, E5, E6> {
-    instantiate_fn: InstantiateFn<M1, E1>,
-    execute_fn: Option<ExecuteFn<M2, E2>>,
-    migrate_fn: Option<MigrateFn<M3, E3>>,
-    receive_fn: Option<ReceiveFn<E4>>,
-    reply_fn: Option<ReplyFn<M5, E5>>,
-    query_fn: Option<QueryFn<M6, E6>>,
+fn not_implemented<T>(name: &str) -> GenericResult<T> {
+    GenericResult::Err(format!("contract doesn't implement entry point `{name}`"))
 }
 
-impl<M1, M2, M3, M5, M6, E1, E2, E3, E4, E5, E6> Contract
-    for ContractImpl<M1, M2, M3, M5, M6, E1, E2, E3, E4, E5, E6>
-where
-    M1: DeserializeOwned,
-    M2: DeserializeOwned,
-    M3: DeserializeOwned,
-    M5: DeserializeOwned,
-    M6: DeserializeOwned,
-    E1: ToString,
-    E2: ToString,
-    E3: ToString,
-    E4: ToString,
-    E5: ToString,
-    E6: ToString,
-{
+// ----------------------------------- impl ------------------------------------
+
+impl Contract for ContractBuilder {
     fn instantiate(
         &self,
         ctx: Context,
         storage: &mut dyn Storage,
         api: &dyn Api,
         querier: &dyn Querier,
+        caller: &dyn Caller,
         msg: Json,
     ) -> GenericResult<Response> {
-        let mutable_ctx = make_mutable_ctx!(ctx, storage, api, querier);
-        let msg = unwrap_into_generic_result!(from_json_value(msg));
-        return_into_generic_result!((self.instantiate_fn)(mutable_ctx, msg))
+        let mutable_ctx = make_mutable_ctx!(ctx, storage, api, querier, caller);
+        (self.instantiate_fn)(mutable_ctx, msg).into()
     }
 
     fn execute(
@@ -110,11 +417,14 @@ where
         storage: &mut dyn Storage,
         api: &dyn Api,
         querier: &dyn Querier,
+        caller: &dyn Caller,
         msg: Json,
     ) -> GenericResult<Response> {
-        let mutable_ctx = make_mutable_ctx!(ctx, storage, api, querier);
-        let msg = unwrap_into_generic_result!(from_json_value(msg));
-        return_into_generic_result!(self.execute_fn.as_ref().unwrap()(mutable_ctx, msg))
+        let Some(execute_fn) = &self.execute_fn else {
+            return not_implemented("execute");
+        };
+        let mutable_ctx = make_mutable_ctx!(ctx, storage, api, querier, caller);
+        execute_fn(mutable_ctx, msg).into()
     }
 
     fn migrate(
@@ -123,11 +433,14 @@ where
         storage: &mut dyn Storage,
         api: &dyn Api,
         querier: &dyn Querier,
+        caller: &dyn Caller,
         msg: Json,
     ) -> GenericResult<Response> {
-        let mutable_ctx = make_mutable_ctx!(ctx, storage, api, querier);
-        let msg = unwrap_into_generic_result!(from_json_value(msg));
-        return_into_generic_result!(self.migrate_fn.as_ref().unwrap()(mutable_ctx, msg))
+        let Some(migrate_fn) = &self.migrate_fn else {
+            return not_implemented("migrate");
+        };
+        let mutable_ctx = make_mutable_ctx!(ctx, storage, api, querier, caller);
+        migrate_fn(mutable_ctx, msg).into()
     }
 
     fn receive(
@@ -136,9 +449,13 @@ where
         storage: &mut dyn Storage,
         api: &dyn Api,
         querier: &dyn Querier,
+        caller: &dyn Caller,
     ) -> GenericResult<Response> {
-        let mutable_ctx = make_mutable_ctx!(ctx, storage, api, querier);
-        return_into_generic_result!(self.receive_fn.as_ref().unwrap()(mutable_ctx))
+        let Some(receive_fn) = &self.receive_fn else {
+            return not_implemented("receive");
+        };
+        let mutable_ctx = make_mutable_ctx!(ctx, storage, api, querier, caller);
+        receive_fn(mutable_ctx).into()
     }
 
     fn reply(
@@ -147,12 +464,15 @@ where
         storage: &mut dyn Storage,
         api: &dyn Api,
         querier: &dyn Querier,
+        caller: &dyn Caller,
         msg: Json,
         submsg_res: SubMsgResult,
     ) -> GenericResult<Response> {
-        let sudo_ctx = make_sudo_ctx!(ctx, storage, api, querier);
-        let msg = unwrap_into_generic_result!(from_json_value(msg));
-        return_into_generic_result!(self.reply_fn.as_ref().unwrap()(sudo_ctx, msg, submsg_res))
+        let Some(reply_fn) = &self.reply_fn else {
+            return not_implemented("reply");
+        };
+        let sudo_ctx = make_sudo_ctx!(ctx, storage, api, querier, caller);
+        reply_fn(sudo_ctx, msg, submsg_res).into()
     }
 
     fn query(
@@ -163,8 +483,246 @@ where
         querier: &dyn Querier,
         msg: Json,
     ) -> GenericResult<Json> {
+        let Some(query_fn) = &self.query_fn else {
+            return not_implemented("query");
+        };
+        let immutable_ctx = make_immutable_ctx!(ctx, storage, api, querier);
+        query_fn(immutable_ctx, msg).into()
+    }
+
+    fn before_tx(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        tx: Tx,
+    ) -> GenericResult<Response> {
+        let Some(before_tx_fn) = &self.before_tx_fn else {
+            return not_implemented("before_tx");
+        };
+        let auth_ctx = make_auth_ctx!(ctx, storage, api, querier);
+        before_tx_fn(auth_ctx, tx).into()
+    }
+
+    fn after_tx(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        tx: Tx,
+    ) -> GenericResult<Response> {
+        let Some(after_tx_fn) = &self.after_tx_fn else {
+            return not_implemented("after_tx");
+        };
+        let auth_ctx = make_auth_ctx!(ctx, storage, api, querier);
+        after_tx_fn(auth_ctx, tx).into()
+    }
+
+    fn before_block(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+    ) -> GenericResult<Response> {
+        let Some(before_block_fn) = &self.before_block_fn else {
+            return not_implemented("before_block");
+        };
+        let sudo_ctx = make_sudo_ctx!(ctx, storage, api, querier, caller);
+        before_block_fn(sudo_ctx).into()
+    }
+
+    fn after_block(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+    ) -> GenericResult<Response> {
+        let Some(after_block_fn) = &self.after_block_fn else {
+            return not_implemented("after_block");
+        };
+        let sudo_ctx = make_sudo_ctx!(ctx, storage, api, querier, caller);
+        after_block_fn(sudo_ctx).into()
+    }
+
+    fn bank_execute(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        msg: BankMsg,
+    ) -> GenericResult<Response> {
+        let Some(bank_execute_fn) = &self.bank_execute_fn else {
+            return not_implemented("bank_execute");
+        };
+        let sudo_ctx = make_sudo_ctx!(ctx, storage, api, querier, caller);
+        bank_execute_fn(sudo_ctx, msg).into()
+    }
+
+    fn bank_query(
+        &self,
+        ctx: Context,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        msg: BankQuery,
+    ) -> GenericResult<BankQueryResponse> {
+        let Some(bank_query_fn) = &self.bank_query_fn else {
+            return not_implemented("bank_query");
+        };
         let immutable_ctx = make_immutable_ctx!(ctx, storage, api, querier);
-        let msg = unwrap_into_generic_result!(from_json_value(msg));
-        return_into_generic_result!(self.query_fn.as_ref().unwrap()(immutable_ctx, msg))
+        bank_query_fn(immutable_ctx, msg).into()
+    }
+
+    fn ibc_client_create(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        client_state: Json,
+        consensus_state: Json,
+    ) -> GenericResult<Response> {
+        let Some(ibc_client_create_fn) = &self.ibc_client_create_fn else {
+            return not_implemented("ibc_client_create");
+        };
+        let sudo_ctx = make_sudo_ctx!(ctx, storage, api, querier, caller);
+        ibc_client_create_fn(sudo_ctx, client_state, consensus_state).into()
+    }
+
+    fn ibc_client_update(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        header: Json,
+    ) -> GenericResult<Response> {
+        let Some(ibc_client_update_fn) = &self.ibc_client_update_fn else {
+            return not_implemented("ibc_client_update");
+        };
+        let sudo_ctx = make_sudo_ctx!(ctx, storage, api, querier, caller);
+        ibc_client_update_fn(sudo_ctx, header).into()
+    }
+
+    fn ibc_client_misbehavior(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        misbehavior: Json,
+    ) -> GenericResult<Response> {
+        let Some(ibc_client_misbehavior_fn) = &self.ibc_client_misbehavior_fn else {
+            return not_implemented("ibc_client_misbehavior");
+        };
+        let sudo_ctx = make_sudo_ctx!(ctx, storage, api, querier, caller);
+        ibc_client_misbehavior_fn(sudo_ctx, misbehavior).into()
+    }
+
+    fn ibc_client_query(
+        &self,
+        ctx: Context,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        msg: IbcClientQuery,
+    ) -> GenericResult<IbcClientQueryResponse> {
+        let Some(ibc_client_query_fn) = &self.ibc_client_query_fn else {
+            return not_implemented("ibc_client_query");
+        };
+        let immutable_ctx = make_immutable_ctx!(ctx, storage, api, querier);
+        ibc_client_query_fn(immutable_ctx, msg).into()
+    }
+
+    fn ibc_channel_open(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        msg: IbcChannelOpenMsg,
+    ) -> GenericResult<Response> {
+        let Some(ibc_channel_open_fn) = &self.ibc_channel_open_fn else {
+            return not_implemented("ibc_channel_open");
+        };
+        let sudo_ctx = make_sudo_ctx!(ctx, storage, api, querier, caller);
+        ibc_channel_open_fn(sudo_ctx, msg).into()
+    }
+
+    fn ibc_channel_connect(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        msg: IbcChannelConnectMsg,
+    ) -> GenericResult<Response> {
+        let Some(ibc_channel_connect_fn) = &self.ibc_channel_connect_fn else {
+            return not_implemented("ibc_channel_connect");
+        };
+        let sudo_ctx = make_sudo_ctx!(ctx, storage, api, querier, caller);
+        ibc_channel_connect_fn(sudo_ctx, msg).into()
+    }
+
+    fn ibc_packet_receive(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        packet: IbcPacket,
+    ) -> GenericResult<Response> {
+        let Some(ibc_packet_receive_fn) = &self.ibc_packet_receive_fn else {
+            return not_implemented("ibc_packet_receive");
+        };
+        let sudo_ctx = make_sudo_ctx!(ctx, storage, api, querier, caller);
+        ibc_packet_receive_fn(sudo_ctx, packet).into()
+    }
+
+    fn ibc_packet_ack(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        packet: IbcPacket,
+        acknowledgement: Binary,
+    ) -> GenericResult<Response> {
+        let Some(ibc_packet_ack_fn) = &self.ibc_packet_ack_fn else {
+            return not_implemented("ibc_packet_ack");
+        };
+        let sudo_ctx = make_sudo_ctx!(ctx, storage, api, querier, caller);
+        ibc_packet_ack_fn(sudo_ctx, packet, acknowledgement).into()
+    }
+
+    fn ibc_packet_timeout(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        packet: IbcPacket,
+    ) -> GenericResult<Response> {
+        let Some(ibc_packet_timeout_fn) = &self.ibc_packet_timeout_fn else {
+            return not_implemented("ibc_packet_timeout");
+        };
+        let sudo_ctx = make_sudo_ctx!(ctx, storage, api, querier, caller);
+        ibc_packet_timeout_fn(sudo_ctx, packet).into()
     }
 }
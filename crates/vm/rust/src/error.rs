@@ -1,4 +1,8 @@
-use {grug_app::AppError, grug_types::StdError, thiserror::Error};
+use {
+    grug_app::AppError,
+    grug_types::{ErrorCategory, ExecutionError, StdError},
+    thiserror::Error,
+};
 
 #[derive(Debug, Error)]
 pub enum VmError {
@@ -7,11 +11,39 @@ pub enum VmError {
 
     #[error("attempting to call `{name}` with {num} inputs, but this function takes a different number of inputs")]
     IncorrectNumberOfInputs { name: String, num: usize },
+
+    #[error("contract with code `{code}` is already registered")]
+    ContractExists { code: String },
+
+    #[error("no contract is registered with code `{code}`")]
+    ContractNotFound { code: String },
+}
+
+impl VmError {
+    pub fn contract_exists(code: &[u8]) -> Self {
+        Self::ContractExists {
+            code: String::from_utf8_lossy(code).into(),
+        }
+    }
+
+    pub fn contract_not_found(code: &[u8]) -> Self {
+        Self::ContractNotFound {
+            code: String::from_utf8_lossy(code).into(),
+        }
+    }
+
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            VmError::Std(_) => ErrorCategory::Deserialize,
+            VmError::ContractNotFound { .. } => ErrorCategory::NotFound,
+            _ => ErrorCategory::Host,
+        }
+    }
 }
 
 impl From<VmError> for AppError {
     fn from(err: VmError) -> Self {
-        AppError::Vm(err.to_string())
+        AppError::Execution(ExecutionError::new(err.category(), err))
     }
 }
 
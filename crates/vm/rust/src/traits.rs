@@ -3,9 +3,10 @@
 #![cfg_attr(rustfmt, rustfmt::skip)]
 
 use grug_types::{
-    Api, AuthCtx, BankMsg, BankQuery, BankQueryResponse, Context, Empty, GenericResult,
-    ImmutableCtx, Json, MutableCtx, Querier, Response, StdError, Storage, SubMsgResult, SudoCtx,
-    Tx,
+    Api, AuthCtx, BankMsg, BankQuery, BankQueryResponse, Binary, Caller, Context, Empty,
+    GenericResult, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcClientQuery, IbcClientQueryResponse,
+    IbcPacket, ImmutableCtx, Json, MutableCtx, Querier, Response, StdError, Storage, SubMsgResult,
+    SudoCtx, Tx,
 };
 
 pub trait Contract {
@@ -15,6 +16,7 @@ pub trait Contract {
         storage: &mut dyn Storage,
         api: &dyn Api,
         querier: &dyn Querier,
+        caller: &dyn Caller,
         msg: Json,
     ) -> GenericResult<Response>;
 
@@ -24,6 +26,7 @@ pub trait Contract {
         storage: &mut dyn Storage,
         api: &dyn Api,
         querier: &dyn Querier,
+        caller: &dyn Caller,
         msg: Json,
     ) -> GenericResult<Response>;
 
@@ -33,6 +36,7 @@ pub trait Contract {
         storage: &mut dyn Storage,
         api: &dyn Api,
         querier: &dyn Querier,
+        caller: &dyn Caller,
         msg: Json,
     ) -> GenericResult<Response>;
 
@@ -42,6 +46,7 @@ pub trait Contract {
         storage: &mut dyn Storage,
         api: &dyn Api,
         querier: &dyn Querier,
+        caller: &dyn Caller,
     ) -> GenericResult<Response>;
 
     fn reply(
@@ -50,6 +55,7 @@ pub trait Contract {
         storage: &mut dyn Storage,
         api: &dyn Api,
         querier: &dyn Querier,
+        caller: &dyn Caller,
         msg: Json,
         submsg_res: SubMsgResult,
     ) -> GenericResult<Response>;
@@ -62,6 +68,152 @@ pub trait Contract {
         querier: &dyn Querier,
         msg: Json,
     ) -> GenericResult<Json>;
+
+    fn before_tx(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        tx: Tx,
+    ) -> GenericResult<Response>;
+
+    fn after_tx(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        tx: Tx,
+    ) -> GenericResult<Response>;
+
+    fn before_block(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+    ) -> GenericResult<Response>;
+
+    fn after_block(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+    ) -> GenericResult<Response>;
+
+    fn bank_execute(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        msg: BankMsg,
+    ) -> GenericResult<Response>;
+
+    fn bank_query(
+        &self,
+        ctx: Context,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        msg: BankQuery,
+    ) -> GenericResult<BankQueryResponse>;
+
+    fn ibc_client_create(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        client_state: Json,
+        consensus_state: Json,
+    ) -> GenericResult<Response>;
+
+    fn ibc_client_update(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        header: Json,
+    ) -> GenericResult<Response>;
+
+    fn ibc_client_misbehavior(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        misbehavior: Json,
+    ) -> GenericResult<Response>;
+
+    fn ibc_client_query(
+        &self,
+        ctx: Context,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        msg: IbcClientQuery,
+    ) -> GenericResult<IbcClientQueryResponse>;
+
+    fn ibc_channel_open(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        msg: IbcChannelOpenMsg,
+    ) -> GenericResult<Response>;
+
+    fn ibc_channel_connect(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        msg: IbcChannelConnectMsg,
+    ) -> GenericResult<Response>;
+
+    fn ibc_packet_receive(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        packet: IbcPacket,
+    ) -> GenericResult<Response>;
+
+    fn ibc_packet_ack(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        packet: IbcPacket,
+        acknowledgement: Binary,
+    ) -> GenericResult<Response>;
+
+    fn ibc_packet_timeout(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        caller: &dyn Caller,
+        packet: IbcPacket,
+    ) -> GenericResult<Response>;
 }
 
 // Trait aliases are unstable:
@@ -91,3 +243,21 @@ pub type AfterBlockFn<E = StdError> = Box<dyn Fn(SudoCtx) -> Result<Response, E>
 pub type BankTransferFn<E = StdError> = Box<dyn Fn(SudoCtx, BankMsg) -> Result<Response, E> + Send + Sync>;
 
 pub type BankQueryFn<E = StdError> = Box<dyn Fn(ImmutableCtx, BankQuery) -> Result<BankQueryResponse, E> + Send + Sync>;
+
+pub type IbcClientCreateFn<M1 = Empty, M2 = Empty, E = StdError> = Box<dyn Fn(SudoCtx, M1, M2) -> Result<Response, E> + Send + Sync>;
+
+pub type IbcClientUpdateFn<M = Empty, E = StdError> = Box<dyn Fn(SudoCtx, M) -> Result<Response, E> + Send + Sync>;
+
+pub type IbcClientMisbehaviorFn<M = Empty, E = StdError> = Box<dyn Fn(SudoCtx, M) -> Result<Response, E> + Send + Sync>;
+
+pub type IbcClientQueryFn<E = StdError> = Box<dyn Fn(ImmutableCtx, IbcClientQuery) -> Result<IbcClientQueryResponse, E> + Send + Sync>;
+
+pub type IbcChannelOpenFn<E = StdError> = Box<dyn Fn(SudoCtx, IbcChannelOpenMsg) -> Result<Response, E> + Send + Sync>;
+
+pub type IbcChannelConnectFn<E = StdError> = Box<dyn Fn(SudoCtx, IbcChannelConnectMsg) -> Result<Response, E> + Send + Sync>;
+
+pub type IbcPacketReceiveFn<E = StdError> = Box<dyn Fn(SudoCtx, IbcPacket) -> Result<Response, E> + Send + Sync>;
+
+pub type IbcPacketAckFn<E = StdError> = Box<dyn Fn(SudoCtx, IbcPacket, Binary) -> Result<Response, E> + Send + Sync>;
+
+pub type IbcPacketTimeoutFn<E = StdError> = Box<dyn Fn(SudoCtx, IbcPacket) -> Result<Response, E> + Send + Sync>;
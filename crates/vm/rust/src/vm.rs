@@ -1,23 +1,81 @@
 use {
-    crate::{ContractWrapper, VmError, VmResult, CONTRACTS},
+    crate::{ContractWrapper, VmError, VmResult},
     grug_app::{Instance, QuerierProvider, StorageProvider, Vm},
-    grug_types::{from_json_slice, to_json_vec, Context, MockApi},
+    grug_types::{from_json_slice, to_json_vec, Caller, Context, MockApi, Querier},
+    std::{
+        collections::BTreeMap,
+        sync::{Arc, RwLock},
+    },
 };
 
-macro_rules! get_contract {
-    ($index:expr) => {
-        CONTRACTS.get().and_then(|contracts| contracts.get($index)).unwrap_or_else(|| {
-            panic!("can't find contract with index {}", $index); // TODO: throw an VmError instead of panicking?
-        })
-    }
-}
-
+/// A VM that runs contracts made of native Rust functions.
+///
+/// Contracts must be registered with the VM before they can be uploaded.
+/// Cloning the VM gives another handle to the same registry, so contracts can
+/// be registered after the VM has been handed to an `App`.
 #[derive(Default, Clone)]
-pub struct RustVm;
+pub struct RustVm {
+    contracts: Arc<RwLock<BTreeMap<Vec<u8>, ContractWrapper>>>,
+}
 
 impl RustVm {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Register a contract under the given name and version, and return the
+    /// bytes to be uploaded as its code.
+    ///
+    /// The code bytes only depend on the name and version, so the resulting
+    /// code hash is the same regardless of which other contracts have been
+    /// registered, or in what order.
+    pub fn register(
+        &self,
+        name: &str,
+        version: &str,
+        contract: ContractWrapper,
+    ) -> VmResult<Vec<u8>> {
+        let code = format!("{name}@{version}").into_bytes();
+        let mut contracts = self
+            .contracts
+            .write()
+            .unwrap_or_else(|err| panic!("poisoned lock: {err:?}"));
+
+        if contracts.contains_key(&code) {
+            return Err(VmError::contract_exists(&code));
+        }
+
+        contracts.insert(code.clone(), contract);
+
+        Ok(code)
+    }
+
+    /// Build an instance that makes queries and calls with the given querier
+    /// and caller, instead of ones that dispatch to this VM.
+    ///
+    /// This allows VMs that wrap around this one, such as a hybrid VM, to have
+    /// queries and calls made by contracts go through the wrapper.
+    pub fn build_instance_with_querier(
+        &self,
+        storage: StorageProvider,
+        querier: Box<dyn Querier>,
+        caller: Box<dyn Caller>,
+        code: &[u8],
+    ) -> VmResult<RustInstance> {
+        let contract = self
+            .contracts
+            .read()
+            .unwrap_or_else(|err| panic!("poisoned lock: {err:?}"))
+            .get(code)
+            .cloned()
+            .ok_or_else(|| VmError::contract_not_found(code))?;
+
+        Ok(RustInstance {
+            storage,
+            querier,
+            caller,
+            contract,
+        })
     }
 }
 
@@ -31,28 +89,56 @@ impl Vm for RustVm {
         querier: QuerierProvider<Self>,
         code: &[u8],
     ) -> VmResult<RustInstance> {
-        Ok(RustInstance {
+        self.build_instance_with_querier(
             storage,
-            querier,
-            wrapper: ContractWrapper::from_bytes(code),
-        })
+            Box::new(querier.clone()),
+            Box::new(querier),
+            code,
+        )
     }
 }
 
 pub struct RustInstance {
     storage: StorageProvider,
-    querier: QuerierProvider<RustVm>,
-    wrapper: ContractWrapper,
+    querier: Box<dyn Querier>,
+    caller: Box<dyn Caller>,
+    contract: ContractWrapper,
 }
 
 impl Instance for RustInstance {
     type Error = VmError;
 
     fn call_in_0_out_1(mut self, name: &str, ctx: &Context) -> VmResult<Vec<u8>> {
-        let contract = get_contract!(self.wrapper.index);
+        let contract = &self.contract.inner;
         let out = match name {
             "receive" => {
-                let res = contract.receive(ctx.clone(), &mut self.storage, &MockApi, &self.querier);
+                let res = contract.receive(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
+                );
+                to_json_vec(&res)?
+            },
+            "before_block" => {
+                let res = contract.before_block(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
+                );
+                to_json_vec(&res)?
+            },
+            "after_block" => {
+                let res = contract.after_block(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
+                );
                 to_json_vec(&res)?
             },
             _ => {
@@ -69,7 +155,7 @@ impl Instance for RustInstance {
     where
         P: AsRef<[u8]>,
     {
-        let contract = get_contract!(self.wrapper.index);
+        let contract = &self.contract.inner;
         let out = match name {
             "instantiate" => {
                 let msg = from_json_slice(param)?;
@@ -77,26 +163,173 @@ impl Instance for RustInstance {
                     ctx.clone(),
                     &mut self.storage,
                     &MockApi,
-                    &self.querier,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
                     msg,
                 );
                 to_json_vec(&res)?
             },
             "execute" => {
                 let msg = from_json_slice(param)?;
-                let res =
-                    contract.execute(ctx.clone(), &mut self.storage, &MockApi, &self.querier, msg);
+                let res = contract.execute(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
+                    msg,
+                );
                 to_json_vec(&res)?
             },
             "migrate" => {
                 let msg = from_json_slice(param)?;
-                let res =
-                    contract.migrate(ctx.clone(), &mut self.storage, &MockApi, &self.querier, msg);
+                let res = contract.migrate(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
+                    msg,
+                );
                 to_json_vec(&res)?
             },
             "query" => {
                 let msg = from_json_slice(param)?;
-                let res = contract.query(ctx.clone(), &self.storage, &MockApi, &self.querier, msg);
+                let res = contract.query(
+                    ctx.clone(),
+                    &self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    msg,
+                );
+                to_json_vec(&res)?
+            },
+            "before_tx" => {
+                let tx = from_json_slice(param)?;
+                let res = contract.before_tx(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    tx,
+                );
+                to_json_vec(&res)?
+            },
+            "after_tx" => {
+                let tx = from_json_slice(param)?;
+                let res = contract.after_tx(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    tx,
+                );
+                to_json_vec(&res)?
+            },
+            "bank_execute" => {
+                let msg = from_json_slice(param)?;
+                let res = contract.bank_execute(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
+                    msg,
+                );
+                to_json_vec(&res)?
+            },
+            "bank_query" => {
+                let msg = from_json_slice(param)?;
+                let res = contract.bank_query(
+                    ctx.clone(),
+                    &self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    msg,
+                );
+                to_json_vec(&res)?
+            },
+            "ibc_client_update" => {
+                let header = from_json_slice(param)?;
+                let res = contract.ibc_client_update(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
+                    header,
+                );
+                to_json_vec(&res)?
+            },
+            "ibc_client_misbehavior" => {
+                let misbehavior = from_json_slice(param)?;
+                let res = contract.ibc_client_misbehavior(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
+                    misbehavior,
+                );
+                to_json_vec(&res)?
+            },
+            "ibc_channel_open" => {
+                let msg = from_json_slice(param)?;
+                let res = contract.ibc_channel_open(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
+                    msg,
+                );
+                to_json_vec(&res)?
+            },
+            "ibc_channel_connect" => {
+                let msg = from_json_slice(param)?;
+                let res = contract.ibc_channel_connect(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
+                    msg,
+                );
+                to_json_vec(&res)?
+            },
+            "ibc_packet_receive" => {
+                let packet = from_json_slice(param)?;
+                let res = contract.ibc_packet_receive(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
+                    packet,
+                );
+                to_json_vec(&res)?
+            },
+            "ibc_packet_timeout" => {
+                let packet = from_json_slice(param)?;
+                let res = contract.ibc_packet_timeout(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
+                    packet,
+                );
+                to_json_vec(&res)?
+            },
+            "ibc_client_query" => {
+                let msg = from_json_slice(param)?;
+                let res = contract.ibc_client_query(
+                    ctx.clone(),
+                    &self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    msg,
+                );
                 to_json_vec(&res)?
             },
             _ => {
@@ -120,7 +353,7 @@ impl Instance for RustInstance {
         P1: AsRef<[u8]>,
         P2: AsRef<[u8]>,
     {
-        let contract = get_contract!(self.wrapper.index);
+        let contract = &self.contract.inner;
         let out = match name {
             "reply" => {
                 let msg = from_json_slice(param1)?;
@@ -129,12 +362,41 @@ impl Instance for RustInstance {
                     ctx.clone(),
                     &mut self.storage,
                     &MockApi,
-                    &self.querier,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
                     msg,
                     submsg_res,
                 );
                 to_json_vec(&res)?
             },
+            "ibc_packet_ack" => {
+                let packet = from_json_slice(param1)?;
+                let acknowledgement = from_json_slice(param2)?;
+                let res = contract.ibc_packet_ack(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
+                    packet,
+                    acknowledgement,
+                );
+                to_json_vec(&res)?
+            },
+            "ibc_client_create" => {
+                let client_state = from_json_slice(param1)?;
+                let consensus_state = from_json_slice(param2)?;
+                let res = contract.ibc_client_create(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &MockApi,
+                    self.querier.as_ref(),
+                    self.caller.as_ref(),
+                    client_state,
+                    consensus_state,
+                );
+                to_json_vec(&res)?
+            },
             _ => {
                 return Err(VmError::IncorrectNumberOfInputs {
                     name: name.into(),
@@ -145,3 +407,64 @@ impl Instance for RustInstance {
         Ok(out)
     }
 }
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::ContractBuilder,
+        grug_types::{
+            BlockInfo, Empty, Hash, MockStorage, MutableCtx, Response, StdResult, Timestamp, Uint64,
+        },
+    };
+
+    fn instantiate(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn mock_contract() -> ContractWrapper {
+        ContractBuilder::new(Box::new(instantiate)).build()
+    }
+
+    fn build_instance(vm: &mut RustVm, code: &[u8]) -> VmResult<RustInstance> {
+        let block = BlockInfo {
+            height: Uint64::new(0),
+            timestamp: Timestamp::from_nanos(0),
+            hash: Hash::ZERO,
+        };
+        let querier = QuerierProvider::new(vm.clone(), Box::new(MockStorage::new()), block);
+        let storage = StorageProvider::new(Box::new(MockStorage::new()), &[]);
+        vm.build_instance(storage, querier, code)
+    }
+
+    #[test]
+    fn registry_is_deterministic_and_scoped() {
+        let vm1 = RustVm::new();
+        let bank1 = vm1.register("bank", "1.0.0", mock_contract()).unwrap();
+        let account1 = vm1.register("account", "1.0.0", mock_contract()).unwrap();
+
+        // registering the same contracts in a different order in another VM
+        // gives the same code bytes
+        let mut vm2 = RustVm::new();
+        let account2 = vm2.register("account", "1.0.0", mock_contract()).unwrap();
+        let bank2 = vm2.register("bank", "1.0.0", mock_contract()).unwrap();
+        assert_eq!(bank1, bank2);
+        assert_eq!(account1, account2);
+
+        // a name and version can only be registered once per VM
+        assert!(matches!(
+            vm2.register("bank", "1.0.0", mock_contract()),
+            Err(VmError::ContractExists { .. })
+        ));
+
+        // contracts registered in one VM aren't visible in another
+        let taxman = vm1.register("taxman", "1.0.0", mock_contract()).unwrap();
+        assert!(build_instance(&mut vm2, &bank2).is_ok());
+        assert!(matches!(
+            build_instance(&mut vm2, &taxman),
+            Err(VmError::ContractNotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,213 @@
+use wasmer::{
+    sys::Features, wasmparser::Operator, CpuFeature, FunctionMiddleware, LocalFunctionIndex,
+    MiddlewareError, MiddlewareReaderState, ModuleMiddleware, Target, Triple,
+};
+
+/// How strictly the VM guards against Wasm features whose results may differ
+/// between validators.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DeterminismMode {
+    /// Use Wasmer's default features. Not safe for use in consensus.
+    Off,
+    /// Reject modules that use threads or SIMD. Floating point instructions
+    /// are allowed, with NaN canonicalization enabled, so that all NaNs
+    /// produced have the same bit pattern.
+    ///
+    /// This is the default, since contracts that use `serde_json` contain
+    /// floating point instructions even if they never deal with floats.
+    #[default]
+    AllowFloats,
+    /// Same as `AllowFloats`, but also reject modules that contain any
+    /// floating point instruction.
+    Strict,
+}
+
+impl DeterminismMode {
+    /// Return the Wasm features that modules are allowed to use.
+    ///
+    /// Every feature that can affect determinism is explicitly set, instead of
+    /// relying on Wasmer's defaults, so that upgrading Wasmer doesn't silently
+    /// change which modules are accepted.
+    ///
+    /// Bulk memory, reference types, and multi-value are allowed: their
+    /// instructions are deterministic, and rustc enables them by default when
+    /// targeting `wasm32-unknown-unknown` (reference types and multi-value
+    /// since Rust 1.82, bulk memory since 1.87), so rejecting them would reject
+    /// any contract built with a recent toolchain that doesn't pass
+    /// `-C target-feature=-bulk-memory,-reference-types,-multivalue`.
+    pub fn features(self) -> Features {
+        if self == DeterminismMode::Off {
+            return Features::default();
+        }
+
+        let mut features = Features::new();
+        features
+            .threads(false)
+            .simd(false)
+            .bulk_memory(true)
+            .reference_types(true)
+            .multi_value(true)
+            .tail_call(false)
+            .module_linking(false)
+            .multi_memory(false)
+            .memory64(false);
+        features.exceptions = false;
+        features.relaxed_simd = false;
+        features.extended_const = false;
+        features
+    }
+
+    /// Return the target that modules are compiled for.
+    ///
+    /// The CPU features are pinned to a fixed baseline instead of being
+    /// detected from the host, so that all nodes of the same architecture
+    /// compile a module to the same machine code regardless of their CPUs. On
+    /// x86_64 the baseline is SSE4.2 with POPCNT, which the singlepass compiler
+    /// requires anyways, and which every node must support.
+    pub fn target(self) -> Target {
+        if self == DeterminismMode::Off {
+            return Target::default();
+        }
+
+        let cpu_features = if cfg!(target_arch = "x86_64") {
+            CpuFeature::SSE2
+                | CpuFeature::SSE3
+                | CpuFeature::SSSE3
+                | CpuFeature::SSE41
+                | CpuFeature::SSE42
+                | CpuFeature::POPCNT
+        } else {
+            CpuFeature::set()
+        };
+
+        Target::new(Triple::host(), cpu_features)
+    }
+}
+
+/// A compiler middleware that rejects modules containing floating point
+/// instructions.
+///
+/// Other non-deterministic instructions (threads, SIMD) are rejected by the
+/// module validator per [`DeterminismMode::features`], so we don't need to
+/// check for them here.
+#[derive(Debug, Default)]
+pub struct Gatekeeper;
+
+impl ModuleMiddleware for Gatekeeper {
+    fn generate_function_middleware(&self, _: LocalFunctionIndex) -> Box<dyn FunctionMiddleware> {
+        Box::new(FunctionGatekeeper)
+    }
+}
+
+#[derive(Debug)]
+struct FunctionGatekeeper;
+
+impl FunctionMiddleware for FunctionGatekeeper {
+    fn feed<'a>(
+        &mut self,
+        operator: Operator<'a>,
+        state: &mut MiddlewareReaderState<'a>,
+    ) -> Result<(), MiddlewareError> {
+        if is_float(&operator) {
+            return Err(MiddlewareError::new(
+                "Gatekeeper",
+                format!("floating point operator not allowed: {operator:?}"),
+            ));
+        }
+
+        state.push_operator(operator);
+
+        Ok(())
+    }
+}
+
+/// Return whether the operator takes or produces a floating point value.
+///
+/// SIMD operators, including the floating point lanes ones, aren't listed here
+/// since the validator already rejects them per [`DeterminismMode::features`].
+fn is_float(operator: &Operator) -> bool {
+    matches!(
+        operator,
+        // memory
+        Operator::F32Load { .. }
+            | Operator::F64Load { .. }
+            | Operator::F32Store { .. }
+            | Operator::F64Store { .. }
+            // constants
+            | Operator::F32Const { .. }
+            | Operator::F64Const { .. }
+            // comparisons
+            | Operator::F32Eq
+            | Operator::F32Ne
+            | Operator::F32Lt
+            | Operator::F32Gt
+            | Operator::F32Le
+            | Operator::F32Ge
+            | Operator::F64Eq
+            | Operator::F64Ne
+            | Operator::F64Lt
+            | Operator::F64Gt
+            | Operator::F64Le
+            | Operator::F64Ge
+            // arithmetic
+            | Operator::F32Abs
+            | Operator::F32Neg
+            | Operator::F32Ceil
+            | Operator::F32Floor
+            | Operator::F32Trunc
+            | Operator::F32Nearest
+            | Operator::F32Sqrt
+            | Operator::F32Add
+            | Operator::F32Sub
+            | Operator::F32Mul
+            | Operator::F32Div
+            | Operator::F32Min
+            | Operator::F32Max
+            | Operator::F32Copysign
+            | Operator::F64Abs
+            | Operator::F64Neg
+            | Operator::F64Ceil
+            | Operator::F64Floor
+            | Operator::F64Trunc
+            | Operator::F64Nearest
+            | Operator::F64Sqrt
+            | Operator::F64Add
+            | Operator::F64Sub
+            | Operator::F64Mul
+            | Operator::F64Div
+            | Operator::F64Min
+            | Operator::F64Max
+            | Operator::F64Copysign
+            // conversions
+            | Operator::I32TruncF32S
+            | Operator::I32TruncF32U
+            | Operator::I32TruncF64S
+            | Operator::I32TruncF64U
+            | Operator::I64TruncF32S
+            | Operator::I64TruncF32U
+            | Operator::I64TruncF64S
+            | Operator::I64TruncF64U
+            | Operator::I32TruncSatF32S
+            | Operator::I32TruncSatF32U
+            | Operator::I32TruncSatF64S
+            | Operator::I32TruncSatF64U
+            | Operator::I64TruncSatF32S
+            | Operator::I64TruncSatF32U
+            | Operator::I64TruncSatF64S
+            | Operator::I64TruncSatF64U
+            | Operator::F32ConvertI32S
+            | Operator::F32ConvertI32U
+            | Operator::F32ConvertI64S
+            | Operator::F32ConvertI64U
+            | Operator::F32DemoteF64
+            | Operator::F64ConvertI32S
+            | Operator::F64ConvertI32U
+            | Operator::F64ConvertI64S
+            | Operator::F64ConvertI64U
+            | Operator::F64PromoteF32
+            | Operator::I32ReinterpretF32
+            | Operator::I64ReinterpretF64
+            | Operator::F32ReinterpretI32
+            | Operator::F64ReinterpretI64
+    )
+}
@@ -1,6 +1,7 @@
 use {
-    crate::{Iterator, VmError, VmResult, WasmVm},
-    grug_app::{QuerierProvider, StorageProvider},
+    crate::{Iterator, VmError, VmResult, WasmLimits},
+    grug_app::StorageProvider,
+    grug_types::{Caller, Querier},
     std::{
         borrow::{Borrow, BorrowMut},
         collections::HashMap,
@@ -14,7 +15,9 @@ use {
 // it has to do with the procedure how we create the ContextData when building the instance
 pub struct ContextData {
     pub storage: StorageProvider,
-    pub querier: QuerierProvider<WasmVm>,
+    pub querier: Box<dyn Querier>,
+    pub caller: Box<dyn Caller>,
+    pub limits: WasmLimits,
     pub iterators: HashMap<i32, Iterator>,
     pub next_iterator_id: i32,
     /// A non-owning link to the wasmer instance. Need this for doing function
@@ -35,12 +38,19 @@ pub struct Environment {
 }
 
 impl Environment {
-    pub fn new(storage: StorageProvider, querier: QuerierProvider<WasmVm>) -> Self {
+    pub fn new(
+        storage: StorageProvider,
+        querier: Box<dyn Querier>,
+        caller: Box<dyn Caller>,
+        limits: WasmLimits,
+    ) -> Self {
         Self {
             memory: None,
             data: Arc::new(RwLock::new(ContextData {
                 storage,
                 querier,
+                caller,
+                limits,
                 iterators: HashMap::new(),
                 next_iterator_id: 0,
                 wasmer_instance: None,
@@ -1,7 +1,7 @@
 use {
     grug_app::AppError,
     grug_crypto::CryptoError,
-    grug_types::StdError,
+    grug_types::{ErrorCategory, ExecutionError, StdError},
     std::string::FromUtf8Error,
     thiserror::Error,
     wasmer::{CompileError, ExportError, InstantiationError, MemoryAccessError, RuntimeError},
@@ -30,6 +30,9 @@ pub enum VmError {
     // The wasmer `CompileError` and `InstantiateError` are big (56 and 128 bytes,
     // respectively). We get a clippy warning if we wrap them directly here in
     // VmError (result_large_err). To avoid this, we cast them to strings instead.
+    #[error("failed to compile Wasm module: {0}")]
+    Compile(String),
+
     #[error("failed to instantiate Wasm module: {0}")]
     Instantiation(String),
 
@@ -67,11 +70,51 @@ pub enum VmError {
 
     #[error("unexpected return type: {0}")]
     ReturnType(&'static str),
+
+    #[error("memory exceeds the maximum number of pages! requested: {requested}, max: {max}")]
+    ExceedMaxMemoryPages { requested: u32, max: u32 },
+
+    #[error("exceeded the maximum call depth! max: {max}")]
+    ExceedMaxCallDepth { max: usize },
+
+    #[error("exceeded the maximum number of iterators! max: {max}")]
+    ExceedMaxIterators { max: usize },
+
+    #[error("key is too long! length: {len}, max: {max}")]
+    KeyTooLong { len: usize, max: usize },
+
+    #[error("value is too long! length: {len}, max: {max}")]
+    ValueTooLong { len: usize, max: usize },
+}
+
+impl VmError {
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            VmError::Std(StdError::Serialize { .. } | StdError::Deserialize { .. })
+            | VmError::FromUtf8(_) => ErrorCategory::Deserialize,
+            VmError::Export(_) => ErrorCategory::NotFound,
+            // the contract misbehaved at the Wasm level: it trapped, is invalid,
+            // violated the ABI, or exceeded a resource limit
+            VmError::Runtime(_)
+            | VmError::MemoryAccess(_)
+            | VmError::Compile(_)
+            | VmError::Instantiation(_)
+            | VmError::RegionTooSmall { .. }
+            | VmError::ReturnCount { .. }
+            | VmError::ReturnType(_)
+            | VmError::ExceedMaxMemoryPages { .. }
+            | VmError::ExceedMaxCallDepth { .. }
+            | VmError::ExceedMaxIterators { .. }
+            | VmError::KeyTooLong { .. }
+            | VmError::ValueTooLong { .. } => ErrorCategory::Trap,
+            _ => ErrorCategory::Host,
+        }
+    }
 }
 
 impl From<CompileError> for VmError {
     fn from(err: CompileError) -> Self {
-        Self::Instantiation(err.to_string())
+        Self::Compile(err.to_string())
     }
 }
 
@@ -90,7 +133,7 @@ impl From<VmError> for RuntimeError {
 
 impl From<VmError> for AppError {
     fn from(err: VmError) -> Self {
-        AppError::Vm(err.to_string())
+        AppError::Execution(ExecutionError::new(err.category(), err))
     }
 }
 
@@ -1,9 +1,12 @@
 use {
     crate::{
         read_from_memory, write_to_memory, ContextData, Environment, Iterator, VmError, VmResult,
+        WasmLimits,
     },
     grug_types::{
-        decode_sections, from_json_slice, to_json_vec, Addr, Querier, QueryRequest, Record, Storage,
+        decode_batch, decode_sections, encode_optional_values, from_json_slice, to_json_vec, Addr,
+        Coins, ExecutionError, GenericResult, Json, MsgResponse, Op, QueryRequest, QueryResponse,
+        Record, Storage,
     },
     tracing::info,
     wasmer::FunctionEnvMut,
@@ -22,6 +25,17 @@ pub fn db_read(mut fe: FunctionEnvMut<Environment>, key_ptr: u32) -> VmResult<u3
     write_to_memory(env, &mut wasm_store, &value)
 }
 
+pub fn db_read_many(mut fe: FunctionEnvMut<Environment>, keys_ptr: u32) -> VmResult<u32> {
+    let (env, mut wasm_store) = fe.data_and_store_mut();
+
+    let keys = read_from_memory(env, &wasm_store, keys_ptr)?;
+    let keys = decode_sections(&keys);
+
+    let values = env.with_context_data(|ctx| VmResult::Ok(ctx.storage.read_many(&keys)))?;
+
+    write_to_memory(env, &mut wasm_store, &encode_optional_values(&values)?)
+}
+
 pub fn db_scan(
     mut fe: FunctionEnvMut<Environment>,
     min_ptr: u32,
@@ -46,6 +60,12 @@ pub fn db_scan(
 
     // Insert the iterator into the `ContextData`, incrementing the next ID.
     env.with_context_data_mut(|ctx| -> VmResult<_> {
+        if ctx.iterators.len() >= ctx.limits.max_iterators {
+            return Err(VmError::ExceedMaxIterators {
+                max: ctx.limits.max_iterators,
+            });
+        }
+
         let iterator_id = ctx.next_iterator_id;
         ctx.iterators.insert(iterator_id, iterator);
         ctx.next_iterator_id += 1;
@@ -86,6 +106,18 @@ pub fn db_next_value(mut fe: FunctionEnvMut<Environment>, iterator_id: i32) -> V
     write_to_memory(env, &mut wasm_store, &value)
 }
 
+pub fn db_iterator_close(mut fe: FunctionEnvMut<Environment>, iterator_id: i32) -> VmResult<()> {
+    let (env, _) = fe.data_and_store_mut();
+
+    // Removing the iterator frees up room for a new one under the limit.
+    env.with_context_data_mut(|ctx| -> VmResult<_> {
+        ctx.iterators
+            .remove(&iterator_id)
+            .ok_or(VmError::IteratorNotFound { iterator_id })?;
+        Ok(())
+    })
+}
+
 pub fn db_write(mut fe: FunctionEnvMut<Environment>, key_ptr: u32, value_ptr: u32) -> VmResult<()> {
     let (env, wasm_store) = fe.data_and_store_mut();
 
@@ -93,11 +125,29 @@ pub fn db_write(mut fe: FunctionEnvMut<Environment>, key_ptr: u32, value_ptr: u3
     let value = read_from_memory(env, &wasm_store, value_ptr)?;
 
     env.with_context_data_mut(|ctx| -> VmResult<_> {
+        validate_write(&ctx.limits, &key, &value)?;
         ctx.storage.write(&key, &value);
         Ok(())
     })
 }
 
+pub fn db_write_batch(mut fe: FunctionEnvMut<Environment>, batch_ptr: u32) -> VmResult<()> {
+    let (env, wasm_store) = fe.data_and_store_mut();
+
+    let batch = read_from_memory(env, &wasm_store, batch_ptr)?;
+    let batch = decode_batch(&batch)?;
+
+    env.with_context_data_mut(|ctx| -> VmResult<_> {
+        for (key, op) in &batch {
+            if let Op::Insert(value) = op {
+                validate_write(&ctx.limits, key, value)?;
+            }
+        }
+        ctx.storage.write_batch(batch);
+        Ok(())
+    })
+}
+
 pub fn db_remove(mut fe: FunctionEnvMut<Environment>, key_ptr: u32) -> VmResult<()> {
     let (env, wasm_store) = fe.data_and_store_mut();
 
@@ -155,7 +205,37 @@ pub fn query_chain(mut fe: FunctionEnvMut<Environment>, req_ptr: u32) -> VmResul
     let req_bytes = read_from_memory(env, &wasm_store, req_ptr)?;
     let req: QueryRequest = from_json_slice(req_bytes)?;
 
-    let res = env.with_context_data(|ctx| ctx.querier.query_chain(req))?;
+    // A failed query is not an error on the host's side; it's returned to the
+    // contract to handle.
+    let res = env.with_context_data(|ctx| {
+        VmResult::Ok(GenericResult::<QueryResponse, ExecutionError>::from(
+            ctx.querier.query_chain(req),
+        ))
+    })?;
+    let res_bytes = to_json_vec(&res)?;
+
+    write_to_memory(env, &mut wasm_store, &res_bytes)
+}
+
+pub fn call_contract(
+    mut fe: FunctionEnvMut<Environment>,
+    contract_ptr: u32,
+    msg_ptr: u32,
+    funds_ptr: u32,
+) -> VmResult<u32> {
+    let (env, mut wasm_store) = fe.data_and_store_mut();
+
+    let contract = Addr::try_from(read_from_memory(env, &wasm_store, contract_ptr)?.as_slice())?;
+    let msg: Json = from_json_slice(read_from_memory(env, &wasm_store, msg_ptr)?)?;
+    let funds: Coins = from_json_slice(read_from_memory(env, &wasm_store, funds_ptr)?)?;
+
+    // A failed call is not an error on the host's side; it's returned to the
+    // contract to handle.
+    let res = env.with_context_data(|ctx| {
+        VmResult::Ok(GenericResult::<MsgResponse, ExecutionError>::from(
+            ctx.caller.call_contract(contract, msg, funds),
+        ))
+    })?;
     let res_bytes = to_json_vec(&res)?;
 
     write_to_memory(env, &mut wasm_store, &res_bytes)
@@ -285,6 +365,24 @@ impl_hash_method!(blake2s_256);
 impl_hash_method!(blake2b_512);
 impl_hash_method!(blake3);
 
+fn validate_write(limits: &WasmLimits, key: &[u8], value: &[u8]) -> VmResult<()> {
+    if key.len() > limits.max_key_len {
+        return Err(VmError::KeyTooLong {
+            len: key.len(),
+            max: limits.max_key_len,
+        });
+    }
+
+    if value.len() > limits.max_value_len {
+        return Err(VmError::ValueTooLong {
+            len: value.len(),
+            max: limits.max_value_len,
+        });
+    }
+
+    Ok(())
+}
+
 fn next_record(ctx: &mut ContextData, iterator_id: i32) -> VmResult<Option<Record>> {
     ctx.iterators
         .get_mut(&iterator_id)
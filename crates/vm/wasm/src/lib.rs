@@ -1,9 +1,15 @@
+mod determinism;
 mod environment;
 mod error;
 mod imports;
 mod iterator;
+mod limits;
 mod memory;
 mod region;
+mod tunables;
 mod vm;
 
-pub use {environment::*, error::*, imports::*, iterator::*, memory::*, region::*, vm::*};
+pub use {
+    determinism::*, environment::*, error::*, imports::*, iterator::*, limits::*, memory::*,
+    region::*, tunables::*, vm::*,
+};
@@ -0,0 +1,38 @@
+/// Limits on the resources a single Wasm contract call can consume.
+///
+/// These exist so that a contract can't exhaust the node's memory, either
+/// directly by growing its linear memory, or indirectly by making the host
+/// allocate on its behalf.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmLimits {
+    /// Maximum number of 64 KiB pages an instance's linear memory can have.
+    pub max_memory_pages: u32,
+    /// Maximum number of Wasm instances that can be nested in the call stack,
+    /// e.g. by a contract querying another contract, which queries a third
+    /// contract, and so on.
+    pub max_call_depth: usize,
+    /// Maximum number of iterators a single instance can have open at the same
+    /// time. Contracts close iterators with the `db_iterator_close` import once
+    /// they're dropped.
+    pub max_iterators: usize,
+    /// Maximum length, in bytes, of a key written to storage.
+    pub max_key_len: usize,
+    /// Maximum length, in bytes, of a value written to storage.
+    pub max_value_len: usize,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        Self {
+            // 32 MiB
+            max_memory_pages: 512,
+            max_call_depth: 16,
+            max_iterators: 256,
+            // 4 KiB; must fit in a u16, as keys are length-prefixed that way
+            // when returned to the contract by iterators
+            max_key_len: 4 * 1024,
+            // 128 KiB
+            max_value_len: 128 * 1024,
+        }
+    }
+}
@@ -1,14 +1,20 @@
 use {
     crate::{
-        blake2b_512, blake2s_256, blake3, db_next, db_next_key, db_next_value, db_read, db_remove,
-        db_remove_range, db_scan, db_write, debug, ed25519_batch_verify, ed25519_verify, keccak256,
-        query_chain, read_then_wipe, secp256k1_pubkey_recover, secp256k1_verify, secp256r1_verify,
-        sha2_256, sha2_512, sha2_512_truncated, sha3_256, sha3_512, sha3_512_truncated,
-        write_to_memory, Environment, VmError, VmResult,
+        blake2b_512, blake2s_256, blake3, call_contract, db_iterator_close, db_next, db_next_key,
+        db_next_value, db_read, db_read_many, db_remove, db_remove_range, db_scan, db_write,
+        db_write_batch, debug, ed25519_batch_verify, ed25519_verify, keccak256, query_chain,
+        read_then_wipe, secp256k1_pubkey_recover, secp256k1_verify, secp256r1_verify, sha2_256,
+        sha2_512, sha2_512_truncated, sha3_256, sha3_512, sha3_512_truncated, write_to_memory,
+        DeterminismMode, Environment, Gatekeeper, LimitingTunables, VmError, VmResult, WasmLimits,
     },
     grug_app::{Instance, QuerierProvider, StorageProvider, Vm},
-    grug_types::{to_borsh_vec, Context},
-    wasmer::{imports, Function, FunctionEnv, Module, Singlepass, Store},
+    grug_types::{to_borsh_vec, Caller, Context, Querier},
+    std::sync::Arc,
+    wasmer::{
+        imports,
+        sys::{BaseTunables, EngineBuilder},
+        CompilerConfig, ExternType, Function, FunctionEnv, Module, Pages, Singlepass, Store,
+    },
 };
 
 // ------------------------------------ vm -------------------------------------
@@ -16,42 +22,85 @@ use {
 #[derive(Default, Clone)]
 pub struct WasmVm {
     // TODO: add module cache (note: the cache must be clone-able)
+    limits: WasmLimits,
+    determinism: DeterminismMode,
+    /// The number of Wasm instances above the ones built by this VM in the
+    /// call stack.
+    depth: usize,
 }
 
 impl WasmVm {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
     }
-}
 
-impl Vm for WasmVm {
-    type Error = VmError;
-    type Instance = WasmInstance;
+    pub fn with_limits(mut self, limits: WasmLimits) -> Self {
+        self.limits = limits;
+        self
+    }
 
-    fn build_instance(
-        &mut self,
+    pub fn with_determinism(mut self, determinism: DeterminismMode) -> Self {
+        self.determinism = determinism;
+        self
+    }
+
+    pub fn limits(&self) -> &WasmLimits {
+        &self.limits
+    }
+
+    pub fn determinism(&self) -> DeterminismMode {
+        self.determinism
+    }
+
+    /// Return the VM that instances called from within an instance built by
+    /// this VM should be built with, i.e. one level deeper in the call stack.
+    pub fn nested(&self) -> Self {
+        Self {
+            limits: self.limits,
+            determinism: self.determinism,
+            depth: self.depth + 1,
+        }
+    }
+
+    /// Build an instance that makes queries and calls with the given querier
+    /// and caller, instead of ones that dispatch to this VM.
+    ///
+    /// This allows VMs that wrap around this one, such as a hybrid VM, to have
+    /// queries and calls made by contracts go through the wrapper.
+    pub fn build_instance_with_querier(
+        &self,
         storage: StorageProvider,
-        querier: QuerierProvider<Self>,
+        querier: Box<dyn Querier>,
+        caller: Box<dyn Caller>,
         code: &[u8],
     ) -> VmResult<WasmInstance> {
-        // create Wasm store
-        // for now we use the singlepass compiler
-        let mut store = Store::new(Singlepass::default());
+        if self.depth >= self.limits.max_call_depth {
+            return Err(VmError::ExceedMaxCallDepth {
+                max: self.limits.max_call_depth,
+            });
+        }
 
-        // compile Wasm byte code into module
-        let module = Module::new(&store, code)?;
+        // create Wasm store and compile Wasm byte code into module
+        let mut store = self.make_store();
+        let module = self.compile(&store, code)?;
 
         // create function environment and register imports
         // note: memory/store/instance in the env hasn't been set yet at this point
-        let fe = FunctionEnv::new(&mut store, Environment::new(storage, querier));
+        let fe = FunctionEnv::new(
+            &mut store,
+            Environment::new(storage, querier, caller, self.limits),
+        );
         let import_obj = imports! {
             "env" => {
                 "db_read"                  => Function::new_typed_with_env(&mut store, &fe, db_read),
+                "db_read_many"             => Function::new_typed_with_env(&mut store, &fe, db_read_many),
                 "db_scan"                  => Function::new_typed_with_env(&mut store, &fe, db_scan),
                 "db_next"                  => Function::new_typed_with_env(&mut store, &fe, db_next),
                 "db_next_key"              => Function::new_typed_with_env(&mut store, &fe, db_next_key),
                 "db_next_value"            => Function::new_typed_with_env(&mut store, &fe, db_next_value),
+                "db_iterator_close"        => Function::new_typed_with_env(&mut store, &fe, db_iterator_close),
                 "db_write"                 => Function::new_typed_with_env(&mut store, &fe, db_write),
+                "db_write_batch"           => Function::new_typed_with_env(&mut store, &fe, db_write_batch),
                 "db_remove"                => Function::new_typed_with_env(&mut store, &fe, db_remove),
                 "db_remove_range"          => Function::new_typed_with_env(&mut store, &fe, db_remove_range),
                 "secp256k1_verify"         => Function::new_typed_with_env(&mut store, &fe, secp256k1_verify),
@@ -71,6 +120,7 @@ impl Vm for WasmVm {
                 "blake3"                   => Function::new_typed_with_env(&mut store, &fe, blake3),
                 "debug"                    => Function::new_typed_with_env(&mut store, &fe, debug),
                 "query_chain"              => Function::new_typed_with_env(&mut store, &fe, query_chain),
+                "call_contract"            => Function::new_typed_with_env(&mut store, &fe, call_contract),
             }
         };
 
@@ -89,6 +139,84 @@ impl Vm for WasmVm {
             fe,
         })
     }
+
+    /// Create a Wasmer store, with the compiler configured according to the
+    /// determinism mode, and tunables that cap the size of linear memory.
+    ///
+    /// For now we use the singlepass compiler. The compilation target is
+    /// pinned per [`DeterminismMode::target`], rather than detected from the
+    /// host CPU.
+    fn make_store(&self) -> Store {
+        let mut compiler = Singlepass::default();
+        match self.determinism {
+            DeterminismMode::Off => {},
+            DeterminismMode::AllowFloats => {
+                compiler.canonicalize_nans(true);
+            },
+            DeterminismMode::Strict => {
+                compiler.push_middleware(Arc::new(Gatekeeper));
+            },
+        }
+
+        let target = self.determinism.target();
+        let mut engine = EngineBuilder::new(compiler)
+            .set_features(Some(self.determinism.features()))
+            .set_target(Some(target.clone()))
+            .engine();
+        engine.set_tunables(LimitingTunables::new(
+            BaseTunables::for_target(&target),
+            Pages(self.limits.max_memory_pages),
+        ));
+
+        Store::new(engine)
+    }
+
+    fn compile(&self, store: &Store, code: &[u8]) -> VmResult<Module> {
+        let module = Module::new(store, code)?;
+
+        // reject modules that ask for more memory than allowed upfront. the
+        // tunables would reject them as well, but only at instantiation and
+        // with a less helpful error.
+        for export in module.exports() {
+            if let ExternType::Memory(ty) = export.ty() {
+                if ty.minimum.0 > self.limits.max_memory_pages {
+                    return Err(VmError::ExceedMaxMemoryPages {
+                        requested: ty.minimum.0,
+                        max: self.limits.max_memory_pages,
+                    });
+                }
+            }
+        }
+
+        Ok(module)
+    }
+}
+
+impl Vm for WasmVm {
+    type Error = VmError;
+    type Instance = WasmInstance;
+
+    fn build_instance(
+        &mut self,
+        storage: StorageProvider,
+        mut querier: QuerierProvider<Self>,
+        code: &[u8],
+    ) -> VmResult<WasmInstance> {
+        // instances built for queries made by this instance are one level
+        // deeper in the call stack
+        *querier.vm_mut() = self.nested();
+
+        self.build_instance_with_querier(
+            storage,
+            Box::new(querier.clone()),
+            Box::new(querier),
+            code,
+        )
+    }
+
+    fn validate_code(&self, code: &[u8]) -> VmResult<()> {
+        self.compile(&self.make_store(), code).map(|_| ())
+    }
 }
 
 // --------------------------------- instance ----------------------------------
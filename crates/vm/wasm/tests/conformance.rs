@@ -0,0 +1,287 @@
+//! Conformance tests for the determinism guarantees of `WasmVm`.
+//!
+//! The corpus under `tests/conformance` consists of small modules, grouped in
+//! folders by how the VM is expected to treat them:
+//!
+//! - `deterministic`: accepted in all determinism modes;
+//! - `float`: accepted if floats are allowed, rejected in strict mode;
+//! - `nondeterministic`: rejected in all modes that guard against
+//!   non-determinism.
+//!
+//! Additionally, we replay the same chain twice from scratch, and check that
+//! both runs produce byte-identical outputs and app hashes.
+
+use {
+    anyhow::{ensure, Context as _},
+    grug_account::{make_sign_bytes, PublicKey, StateResponse},
+    grug_app::{App, Instance, QuerierProvider, StorageProvider, Vm},
+    grug_crypto::{sha2_256, Identity256},
+    grug_db_memory::MemDb,
+    grug_types::{
+        from_json_value, to_json_value, to_json_vec, Addr, BlockInfo, Coins, Config, Context,
+        GenesisState, Hash, Message, MockStorage, Permission, Permissions, QueryRequest, Timestamp,
+        Tx, Uint64, GENESIS_SENDER,
+    },
+    grug_vm_wasm::{DeterminismMode, VmError, WasmVm},
+    k256::ecdsa::{signature::DigestSigner, Signature, SigningKey},
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        fs, io,
+        path::PathBuf,
+    },
+    wasmer::wat2wasm,
+};
+
+const MOCK_CHAIN_ID: &str = "grug-1";
+const MOCK_DENOM: &str = "ugrug";
+
+fn corpus_path(group: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("conformance")
+        .join(group)
+}
+
+/// Read all modules in a group of the corpus, in alphabetical order, compiling
+/// them from text to binary format.
+fn read_corpus(group: &str) -> anyhow::Result<Vec<(String, Vec<u8>)>> {
+    let mut paths = fs::read_dir(corpus_path(group))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let name = path.display().to_string();
+            let wat = fs::read(&path)?;
+            let wasm = wat2wasm(&wat).with_context(|| format!("invalid module: {name}"))?;
+            Ok((name, wasm.into_owned()))
+        })
+        .collect()
+}
+
+fn read_wasm_file(filename: &str) -> io::Result<Vec<u8>> {
+    let path = format!("{}/testdata/{filename}", env!("CARGO_MANIFEST_DIR"));
+    fs::read(path)
+}
+
+fn mock_block(height: u64) -> BlockInfo {
+    BlockInfo {
+        height: Uint64::new(height),
+        timestamp: Timestamp::from_nanos(height),
+        hash: Hash::ZERO,
+    }
+}
+
+#[test]
+fn corpus_is_validated_according_to_determinism_mode() -> anyhow::Result<()> {
+    let allow_floats = WasmVm::new().with_determinism(DeterminismMode::AllowFloats);
+    let strict = WasmVm::new().with_determinism(DeterminismMode::Strict);
+
+    for (name, code) in read_corpus("deterministic")? {
+        ensure!(
+            allow_floats.validate_code(&code).is_ok(),
+            "{name} is rejected"
+        );
+        ensure!(strict.validate_code(&code).is_ok(), "{name} is rejected");
+    }
+
+    for (name, code) in read_corpus("float")? {
+        ensure!(
+            allow_floats.validate_code(&code).is_ok(),
+            "{name} is rejected"
+        );
+        ensure!(
+            matches!(strict.validate_code(&code), Err(VmError::Compile(_))),
+            "{name} is accepted in strict mode"
+        );
+    }
+
+    for (name, code) in read_corpus("nondeterministic")? {
+        ensure!(
+            matches!(allow_floats.validate_code(&code), Err(VmError::Compile(_))),
+            "{name} is accepted"
+        );
+        ensure!(
+            matches!(strict.validate_code(&code), Err(VmError::Compile(_))),
+            "{name} is accepted in strict mode"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn nans_are_canonicalized() -> anyhow::Result<()> {
+    let wat = fs::read(corpus_path("float").join("nan_bits.wat"))?;
+    let code = wat2wasm(&wat)?;
+
+    let vm = WasmVm::new().with_determinism(DeterminismMode::AllowFloats);
+    let storage = StorageProvider::new(Box::new(MockStorage::new()), &[]);
+    let querier = QuerierProvider::new(vm.clone(), Box::new(MockStorage::new()), mock_block(0));
+    let instance = vm.build_instance_with_querier(
+        storage,
+        Box::new(querier.clone()),
+        Box::new(querier),
+        &code,
+    )?;
+
+    let bits = instance.call_in_0_out_1("nan_bits", &Context {
+        chain_id: MOCK_CHAIN_ID.to_string(),
+        block: mock_block(0),
+        contract: Addr::mock(1),
+        sender: None,
+        funds: None,
+        simulate: None,
+    })?;
+
+    // The canonical NaN: positive, quiet, with an empty payload.
+    ensure!(bits == 0x7fc00000_u32.to_le_bytes());
+
+    Ok(())
+}
+
+/// Run a chain consisting of the account and bank contracts from scratch, and
+/// return the app hash and the outputs of each block.
+fn replay_chain() -> anyhow::Result<Vec<(Hash, Vec<u8>)>> {
+    let app = App::new(MemDb::new(), WasmVm::new());
+    let mut blocks = vec![];
+
+    // Use fixed private keys so that every run produces the same transactions.
+    // ECDSA signatures are deterministic per RFC 6979.
+    let sender_sk = SigningKey::from_slice(&[1; 32])?;
+    let sender_pk = sender_sk.verifying_key().to_encoded_point(true).to_bytes();
+    let receiver_sk = SigningKey::from_slice(&[2; 32])?;
+    let receiver_pk = receiver_sk
+        .verifying_key()
+        .to_encoded_point(true)
+        .to_bytes();
+
+    let account_code = read_wasm_file("grug_account.wasm")?;
+    let account_code_hash = Hash::from_slice(sha2_256(&account_code));
+    let sender = Addr::compute(&GENESIS_SENDER, &account_code_hash, b"sender");
+    let receiver = Addr::compute(&GENESIS_SENDER, &account_code_hash, b"receiver");
+
+    let bank_code = read_wasm_file("grug_bank.wasm")?;
+    let bank_code_hash = Hash::from_slice(sha2_256(&bank_code));
+    let bank = Addr::compute(&GENESIS_SENDER, &bank_code_hash, b"bank");
+
+    let instantiate_account = |pk: &[u8], salt: &[u8], address: &Addr| {
+        to_json_value(&grug_account::InstantiateMsg {
+            public_key: PublicKey::Secp256k1(pk.to_vec().into()),
+        })
+        .map(|msg| Message::Instantiate {
+            code_hash: account_code_hash.clone(),
+            msg,
+            salt: salt.to_vec().into(),
+            funds: Coins::new_empty(),
+            admin: Some(address.clone()),
+        })
+    };
+
+    let genesis_hash =
+        app.do_init_chain(MOCK_CHAIN_ID.to_string(), mock_block(0), GenesisState {
+            config: Config {
+                owner: None,
+                bank: bank.clone(),
+                begin_blockers: vec![],
+                end_blockers: vec![],
+                permissions: Permissions {
+                    upload: Permission::Everybody,
+                    instantiate: Permission::Everybody,
+                    create_client: Permission::Everybody,
+                    create_connection: Permission::Everybody,
+                    create_channel: Permission::Everybody,
+                },
+                allowed_clients: BTreeSet::new(),
+            },
+            msgs: vec![
+                Message::Upload {
+                    code: account_code.into(),
+                },
+                Message::Upload {
+                    code: bank_code.into(),
+                },
+                instantiate_account(&sender_pk, b"sender", &sender)?,
+                instantiate_account(&receiver_pk, b"receiver", &receiver)?,
+                Message::Instantiate {
+                    code_hash: bank_code_hash,
+                    msg: to_json_value(&grug_bank::InstantiateMsg {
+                        initial_balances: BTreeMap::from([(
+                            sender.clone(),
+                            Coins::new_one(MOCK_DENOM, 100_u128),
+                        )]),
+                        namespaces: BTreeMap::new(),
+                    })?,
+                    salt: b"bank".to_vec().into(),
+                    funds: Coins::new_empty(),
+                    admin: None,
+                },
+            ],
+        })?;
+    blocks.push((genesis_hash, vec![]));
+
+    // A successful transfer, a failed one (insufficient balance), then one in
+    // the opposite direction.
+    let transfers = [
+        (&sender, &sender_sk, &receiver, 25_u128),
+        (&sender, &sender_sk, &receiver, 1000),
+        (&receiver, &receiver_sk, &sender, 10),
+    ];
+
+    for (height, (from, sk, to, amount)) in (1..).zip(transfers) {
+        let sequence = app
+            .do_query_app(
+                QueryRequest::WasmSmart {
+                    contract: from.clone(),
+                    msg: to_json_value(&grug_account::QueryMsg::State {})?,
+                },
+                height - 1,
+                false,
+            )
+            .map(|res| from_json_value::<StateResponse>(res.as_wasm_smart().data))??
+            .sequence;
+
+        let msgs = vec![Message::Transfer {
+            to: to.clone(),
+            coins: Coins::new_one(MOCK_DENOM, amount),
+        }];
+        let sign_bytes = make_sign_bytes(sha2_256, &msgs, from, MOCK_CHAIN_ID, sequence)?;
+        let signature: Signature = sk.sign_digest(Identity256::from(sign_bytes));
+        let tx = Tx {
+            sender: from.clone(),
+            msgs,
+            credential: signature.to_vec().into(),
+        };
+
+        let (app_hash, events, outcomes) =
+            app.do_finalize_block(mock_block(height), vec![(Hash::ZERO, tx)])?;
+        app.do_commit()?;
+
+        let mut output = to_json_vec(&events)?;
+        for outcome in outcomes {
+            match outcome.result {
+                Ok(res) => output.extend(to_json_vec(&res)?),
+                Err(err) => output.extend(err.to_string().into_bytes()),
+            }
+        }
+        blocks.push((app_hash, output));
+    }
+
+    Ok(blocks)
+}
+
+#[test]
+fn replaying_chain_is_deterministic() -> anyhow::Result<()> {
+    let first = replay_chain()?;
+    let second = replay_chain()?;
+
+    ensure!(first.len() == second.len());
+    for (height, (first, second)) in first.iter().zip(&second).enumerate() {
+        ensure!(first.0 == second.0, "app hash mismatch at height {height}");
+        ensure!(first.1 == second.1, "output mismatch at height {height}");
+    }
+
+    Ok(())
+}
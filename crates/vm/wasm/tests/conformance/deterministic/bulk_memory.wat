@@ -0,0 +1,6 @@
+;; Bulk memory instructions are accepted: they are deterministic, and rustc emits
+;; them by default.
+(module
+  (memory (export "memory") 1)
+  (func (export "fill") (param $len i32)
+    (memory.fill (i32.const 0) (i32.const 0) (local.get $len))))
@@ -0,0 +1,15 @@
+;; Integer arithmetic, memory access, and control flow are deterministic.
+(module
+  (memory (export "memory") 1)
+  (func (export "collatz_steps") (param $n i64) (result i32)
+    (local $steps i32)
+    (block $done
+      (loop $next
+        (br_if $done (i64.le_u (local.get $n) (i64.const 1)))
+        (if (i64.eqz (i64.rem_u (local.get $n) (i64.const 2)))
+          (then (local.set $n (i64.div_u (local.get $n) (i64.const 2))))
+          (else (local.set $n (i64.add (i64.mul (local.get $n) (i64.const 3)) (i64.const 1)))))
+        (local.set $steps (i32.add (local.get $steps) (i32.const 1)))
+        (br $next)))
+    (i32.store (i32.const 0) (local.get $steps))
+    (i32.load (i32.const 0))))
@@ -0,0 +1,6 @@
+;; Reference types are accepted: they are deterministic, and rustc enables them
+;; by default.
+(module
+  (table $t 1 funcref)
+  (func (export "is_null") (result i32)
+    (ref.is_null (table.get $t (i32.const 0)))))
@@ -0,0 +1,4 @@
+;; Sign extension operators are part of the Wasm MVP feature set we accept.
+(module
+  (func (export "extend") (param $x i32) (result i64)
+    (i64.extend_i32_s (i32.extend8_s (local.get $x)))))
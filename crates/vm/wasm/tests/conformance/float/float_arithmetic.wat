@@ -0,0 +1,8 @@
+;; Floating point arithmetic. Accepted with NaN canonicalization, unless floats
+;; are disallowed entirely.
+(module
+  (func (export "hypot") (param $x f64) (param $y f64) (result f64)
+    (f64.sqrt
+      (f64.add
+        (f64.mul (local.get $x) (local.get $x))
+        (f64.mul (local.get $y) (local.get $y))))))
@@ -0,0 +1,4 @@
+;; Conversions between integers and floats count as float operators too.
+(module
+  (func (export "round_trip") (param $x i32) (result i32)
+    (i32.trunc_f32_s (f32.convert_i32_s (local.get $x)))))
@@ -0,0 +1,28 @@
+;; A minimal contract that returns the bit pattern of the NaN produced by 0/0,
+;; which differs between CPU architectures unless NaNs are canonicalized.
+;;
+;; Implements just enough of the guest ABI to be called by the VM: a bump
+;; allocator exported as `allocate`, a no-op `deallocate`, and an entry point
+;; that takes a context and returns a region.
+(module
+  (memory (export "memory") 1)
+  (global $heap (mut i32) (i32.const 1024))
+  (func $allocate (export "allocate") (param $capacity i32) (result i32)
+    (local $region i32)
+    (local.set $region (global.get $heap))
+    ;; region layout: offset (u32), capacity (u32), length (u32)
+    (i32.store (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+    (i32.store offset=4 (local.get $region) (local.get $capacity))
+    (i32.store offset=8 (local.get $region) (i32.const 0))
+    (global.set $heap
+      (i32.add (i32.add (local.get $region) (i32.const 12)) (local.get $capacity)))
+    (local.get $region))
+  (func (export "deallocate") (param $region i32))
+  (func (export "nan_bits") (param $ctx i32) (result i32)
+    (local $region i32)
+    (local.set $region (call $allocate (i32.const 4)))
+    (i32.store
+      (i32.load (local.get $region))
+      (i32.reinterpret_f32 (f32.div (f32.const 0) (f32.const 0))))
+    (i32.store offset=8 (local.get $region) (i32.const 4))
+    (local.get $region)))
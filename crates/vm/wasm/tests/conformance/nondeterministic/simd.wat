@@ -0,0 +1,4 @@
+;; SIMD instructions are rejected.
+(module
+  (func (export "splat") (param $x i32) (result i32)
+    (i32x4.extract_lane 0 (i32x4.splat (local.get $x)))))
@@ -0,0 +1,5 @@
+;; Shared memories and atomic instructions are rejected.
+(module
+  (memory (export "memory") 1 1 shared)
+  (func (export "load") (result i32)
+    (i32.atomic.load (i32.const 0))))
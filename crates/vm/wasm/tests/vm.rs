@@ -1,15 +1,16 @@
 use {
     anyhow::ensure,
     grug_account::{make_sign_bytes, PublicKey, StateResponse},
-    grug_app::{App, AppResult},
+    grug_app::{App, AppResult, Instance, QuerierProvider, Shared, StorageProvider},
     grug_crypto::{sha2_256, Identity256},
     grug_db_memory::MemDb,
     grug_types::{
-        from_json_value, to_json_value, Addr, BlockInfo, Coin, Coins, Config, GenesisState, Hash,
-        Message, NumberConst, Permission, Permissions, QueryRequest, QueryResponse, Timestamp, Tx,
-        Uint64, GENESIS_SENDER,
+        decode_optional_values, encode_batch, encode_sections, from_json_value, to_json_value,
+        Addr, Batch, BlockInfo, Coin, Coins, Config, Context, GenesisState, Hash, Message,
+        MockStorage, NumberConst, Op, Permission, Permissions, QueryRequest, QueryResponse,
+        Storage, Timestamp, Tx, Uint64, GENESIS_SENDER,
     },
-    grug_vm_wasm::WasmVm,
+    grug_vm_wasm::{VmError, VmResult, WasmInstance, WasmLimits, WasmVm},
     k256::ecdsa::{signature::DigestSigner, Signature, SigningKey},
     rand::rngs::OsRng,
     serde::{de::DeserializeOwned, ser::Serialize},
@@ -194,6 +195,7 @@ fn wasm_vm_works() -> anyhow::Result<()> {
                         sender.clone(),
                         Coins::new_one(MOCK_DENOM, 100_u128),
                     )]),
+                    namespaces: BTreeMap::new(),
                 })?,
                 salt: MOCK_BANK_SALT.to_vec().into(),
                 funds: Coins::new_empty(),
@@ -217,3 +219,180 @@ fn wasm_vm_works() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn wasm_limits_are_enforced() -> anyhow::Result<()> {
+    let code = read_wasm_file("grug_account.wasm")?;
+    let block = BlockInfo {
+        height: Uint64::ZERO,
+        timestamp: Timestamp::from_nanos(0),
+        hash: Hash::ZERO,
+    };
+    let build = |limits: WasmLimits| {
+        let vm = WasmVm::new().with_limits(limits);
+        let storage = StorageProvider::new(Box::new(MockStorage::new()), &[]);
+        let querier = QuerierProvider::new(vm.clone(), Box::new(MockStorage::new()), block.clone());
+        vm.build_instance_with_querier(storage, Box::new(querier.clone()), Box::new(querier), &code)
+    };
+
+    // The default limits allow the contract to be instantiated.
+    ensure!(build(WasmLimits::default()).is_ok());
+
+    // The contract's memory needs more than one page.
+    ensure!(matches!(
+        build(WasmLimits {
+            max_memory_pages: 1,
+            ..Default::default()
+        }),
+        Err(VmError::ExceedMaxMemoryPages { max: 1, .. })
+    ));
+
+    // A VM that's already as deep in the call stack as allowed refuses to
+    // build more instances.
+    ensure!(matches!(
+        build(WasmLimits {
+            max_call_depth: 0,
+            ..Default::default()
+        }),
+        Err(VmError::ExceedMaxCallDepth { max: 0 })
+    ));
+
+    Ok(())
+}
+
+// A bump allocator that never frees memory, which is all the host needs to pass
+// parameters to, and read results from, the test modules below.
+const ALLOCATOR_WAT: &str = r#"
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 1024))
+  (func (export "allocate") (param $size i32) (result i32)
+    (local $region i32)
+    (local.set $region (global.get $next))
+    (i32.store (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+    (i32.store offset=4 (local.get $region) (local.get $size))
+    (i32.store offset=8 (local.get $region) (i32.const 0))
+    (global.set $next
+      (i32.add (local.get $region) (i32.add (local.get $size) (i32.const 12))))
+    (local.get $region))
+  (func (export "deallocate") (param i32))
+"#;
+
+// A module that passes its parameter straight to `db_read_many` or
+// `db_write_batch`.
+const BATCH_OPS_WAT: &str = r#"
+  (import "env" "db_read_many" (func $db_read_many (param i32) (result i32)))
+  (import "env" "db_write_batch" (func $db_write_batch (param i32)))
+  (func (export "read_many") (param i32 i32) (result i32)
+    (call $db_read_many (local.get 1)))
+  (func (export "write_batch") (param i32 i32) (result i32)
+    (call $db_write_batch (local.get 1))
+    (local.get 1))
+"#;
+
+// A module that opens three iterators, either closing each one before opening
+// the next, or keeping them all open.
+const ITERATORS_WAT: &str = r#"
+  (import "env" "db_scan" (func $db_scan (param i32 i32 i32) (result i32)))
+  (import "env" "db_iterator_close" (func $db_iterator_close (param i32)))
+  (func (export "scan_and_close") (param i32 i32) (result i32)
+    (call $db_iterator_close (call $db_scan (i32.const 0) (i32.const 0) (i32.const 1)))
+    (call $db_iterator_close (call $db_scan (i32.const 0) (i32.const 0) (i32.const 1)))
+    (call $db_iterator_close (call $db_scan (i32.const 0) (i32.const 0) (i32.const 1)))
+    (local.get 1))
+  (func (export "scan") (param i32 i32) (result i32)
+    (drop (call $db_scan (i32.const 0) (i32.const 0) (i32.const 1)))
+    (drop (call $db_scan (i32.const 0) (i32.const 0) (i32.const 1)))
+    (drop (call $db_scan (i32.const 0) (i32.const 0) (i32.const 1)))
+    (local.get 1))
+"#;
+
+fn mock_context() -> Context {
+    Context {
+        chain_id: MOCK_CHAIN_ID.to_string(),
+        block: BlockInfo {
+            height: Uint64::ZERO,
+            timestamp: Timestamp::from_nanos(0),
+            hash: Hash::ZERO,
+        },
+        contract: Addr::mock(1),
+        sender: None,
+        funds: None,
+        simulate: None,
+    }
+}
+
+/// Build an instance of a module consisting of the given WAT items and the
+/// allocator, with the given limits.
+fn build_wat_instance(
+    limits: WasmLimits,
+    storage: StorageProvider,
+    items: &str,
+) -> VmResult<WasmInstance> {
+    let vm = WasmVm::new().with_limits(limits);
+    let querier = QuerierProvider::new(
+        vm.clone(),
+        Box::new(MockStorage::new()),
+        mock_context().block,
+    );
+    let code = format!("(module {items} {ALLOCATOR_WAT})");
+    vm.build_instance_with_querier(
+        storage,
+        Box::new(querier.clone()),
+        Box::new(querier),
+        code.as_bytes(),
+    )
+}
+
+#[test]
+fn wasm_batch_ops_work() -> anyhow::Result<()> {
+    let ctx = mock_context();
+
+    let mut base = Shared::new(MockStorage::new());
+    base.write(b"contract/a", b"1");
+    base.write(b"contract/b", b"2");
+
+    let build = || {
+        let storage = StorageProvider::new(Box::new(base.clone()), &[b"contract/"]);
+        build_wat_instance(WasmLimits::default(), storage, BATCH_OPS_WAT)
+    };
+
+    // Writes and removes in the batch land in the contract's namespace.
+    let batch = Batch::from([
+        (b"b".to_vec(), Op::Delete),
+        (b"c".to_vec(), Op::Insert(b"3".to_vec())),
+    ]);
+    build()?.call_in_1_out_1("write_batch", &ctx, &encode_batch(&batch)?)?;
+    ensure!(base.read(b"contract/b").is_none());
+    ensure!(base.read(b"contract/c") == Some(b"3".to_vec()));
+
+    // Values are read in the order of the keys, with missing ones as `None`.
+    let keys = encode_sections(&[b"c".as_slice(), b"b", b"a"])?;
+    let values = build()?.call_in_1_out_1("read_many", &ctx, &keys)?;
+    ensure!(decode_optional_values(&values)? == [Some(b"3".to_vec()), None, Some(b"1".to_vec())]);
+
+    Ok(())
+}
+
+#[test]
+fn closed_iterators_dont_count_against_limit() -> anyhow::Result<()> {
+    let ctx = mock_context();
+    let build = || {
+        let storage = StorageProvider::new(Box::new(MockStorage::new()), &[]);
+        let limits = WasmLimits {
+            max_iterators: 2,
+            ..Default::default()
+        };
+        build_wat_instance(limits, storage, ITERATORS_WAT)
+    };
+
+    // Only two iterators can be open at once, but any number can be opened
+    // one after another as long as each is closed first.
+    build()?.call_in_1_out_1("scan_and_close", &ctx, b"")?;
+    // Errors in host functions reach the caller as Wasm runtime errors.
+    let err = build()?.call_in_1_out_1("scan", &ctx, b"").unwrap_err();
+    ensure!(err
+        .to_string()
+        .contains(&VmError::ExceedMaxIterators { max: 2 }.to_string()));
+
+    Ok(())
+}
@@ -1,9 +1,10 @@
 use {
-    crate::{ExternalApi, ExternalQuerier, ExternalStorage, Region},
+    crate::{ExternalApi, ExternalCaller, ExternalQuerier, ExternalStorage, Region},
     grug_types::{
         from_borsh_slice, from_json_slice, make_auth_ctx, make_immutable_ctx, make_mutable_ctx,
         make_sudo_ctx, to_json_vec, unwrap_into_generic_result, AuthCtx, BankMsg, BankQuery,
-        BankQueryResponse, Context, GenericResult, IbcClientQuery, ImmutableCtx, Json, MutableCtx,
+        BankQueryResponse, Binary, Context, GenericResult, IbcChannelConnectMsg, IbcChannelOpenMsg,
+        IbcClientQuery, IbcClientQueryResponse, IbcPacket, ImmutableCtx, Json, MutableCtx,
         QuerierWrapper, Response, SubMsgResult, SudoCtx, Tx,
     },
     serde::de::DeserializeOwned,
@@ -59,7 +60,13 @@ where
     E: ToString,
 {
     let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
-    let mutable_ctx = make_mutable_ctx!(ctx, &mut ExternalStorage, &ExternalApi, &ExternalQuerier);
+    let mutable_ctx = make_mutable_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
     let msg = unwrap_into_generic_result!(from_json_slice(msg_bytes));
 
     instantiate_fn(mutable_ctx, msg).into()
@@ -95,7 +102,13 @@ where
     E: ToString,
 {
     let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
-    let mutable_ctx = make_mutable_ctx!(ctx, &mut ExternalStorage, &ExternalApi, &ExternalQuerier);
+    let mutable_ctx = make_mutable_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
     let msg = unwrap_into_generic_result!(from_json_slice(msg_bytes));
 
     execute_fn(mutable_ctx, msg).into()
@@ -167,7 +180,13 @@ where
     E: ToString,
 {
     let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
-    let mutable_ctx = make_mutable_ctx!(ctx, &mut ExternalStorage, &ExternalApi, &ExternalQuerier);
+    let mutable_ctx = make_mutable_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
     let msg = unwrap_into_generic_result!(from_json_slice(msg_bytes));
 
     migrate_fn(mutable_ctx, msg).into()
@@ -206,7 +225,13 @@ where
     E: ToString,
 {
     let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
-    let sudo_ctx = make_sudo_ctx!(ctx, &mut ExternalStorage, &ExternalApi, &ExternalQuerier);
+    let sudo_ctx = make_sudo_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
     let msg = unwrap_into_generic_result!(from_json_slice(msg_bytes));
     let events = unwrap_into_generic_result!(from_json_slice(events_bytes));
 
@@ -238,7 +263,13 @@ where
     E: ToString,
 {
     let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
-    let mutable_ctx = make_mutable_ctx!(ctx, &mut ExternalStorage, &ExternalApi, &ExternalQuerier);
+    let mutable_ctx = make_mutable_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
 
     receive_fn(mutable_ctx).into()
 }
@@ -268,7 +299,13 @@ where
     E: ToString,
 {
     let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
-    let sudo_ctx = make_sudo_ctx!(ctx, &mut ExternalStorage, &ExternalApi, &ExternalQuerier);
+    let sudo_ctx = make_sudo_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
 
     before_block_fn(sudo_ctx).into()
 }
@@ -298,7 +335,13 @@ where
     E: ToString,
 {
     let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
-    let sudo_ctx = make_sudo_ctx!(ctx, &mut ExternalStorage, &ExternalApi, &ExternalQuerier);
+    let sudo_ctx = make_sudo_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
 
     after_block_fn(sudo_ctx).into()
 }
@@ -399,7 +442,13 @@ where
     E: ToString,
 {
     let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
-    let sudo_ctx = make_sudo_ctx!(ctx, &mut ExternalStorage, &ExternalApi, &ExternalQuerier);
+    let sudo_ctx = make_sudo_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
     let msg = unwrap_into_generic_result!(from_json_slice(msg_bytes));
 
     transfer_fn(sudo_ctx, msg).into()
@@ -439,10 +488,147 @@ where
     query_fn(immutable_ctx, msg).into()
 }
 
+// ----------------------------- ibc client create -----------------------------
+
+pub fn do_ibc_client_create<M1, M2, E>(
+    create_fn: &dyn Fn(SudoCtx, M1, M2) -> Result<Response, E>,
+    ctx_ptr: usize,
+    client_state_ptr: usize,
+    consensus_state_ptr: usize,
+) -> usize
+where
+    M1: DeserializeOwned,
+    M2: DeserializeOwned,
+    E: ToString,
+{
+    let ctx_bytes = unsafe { Region::consume(ctx_ptr as *mut Region) };
+    let client_state_bytes = unsafe { Region::consume(client_state_ptr as *mut Region) };
+    let consensus_state_bytes = unsafe { Region::consume(consensus_state_ptr as *mut Region) };
+
+    let res = _do_ibc_client_create(
+        create_fn,
+        &ctx_bytes,
+        &client_state_bytes,
+        &consensus_state_bytes,
+    );
+    let res_bytes = to_json_vec(&res).unwrap();
+
+    Region::release_buffer(res_bytes) as usize
+}
+
+fn _do_ibc_client_create<M1, M2, E>(
+    create_fn: &dyn Fn(SudoCtx, M1, M2) -> Result<Response, E>,
+    ctx_bytes: &[u8],
+    client_state_bytes: &[u8],
+    consensus_state_bytes: &[u8],
+) -> GenericResult<Response>
+where
+    M1: DeserializeOwned,
+    M2: DeserializeOwned,
+    E: ToString,
+{
+    let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
+    let sudo_ctx = make_sudo_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
+    let client_state = unwrap_into_generic_result!(from_json_slice(client_state_bytes));
+    let consensus_state = unwrap_into_generic_result!(from_json_slice(consensus_state_bytes));
+
+    create_fn(sudo_ctx, client_state, consensus_state).into()
+}
+
+// ----------------------------- ibc client update -----------------------------
+
+pub fn do_ibc_client_update<M, E>(
+    update_fn: &dyn Fn(SudoCtx, M) -> Result<Response, E>,
+    ctx_ptr: usize,
+    header_ptr: usize,
+) -> usize
+where
+    M: DeserializeOwned,
+    E: ToString,
+{
+    let ctx_bytes = unsafe { Region::consume(ctx_ptr as *mut Region) };
+    let header_bytes = unsafe { Region::consume(header_ptr as *mut Region) };
+
+    let res = _do_ibc_client_update(update_fn, &ctx_bytes, &header_bytes);
+    let res_bytes = to_json_vec(&res).unwrap();
+
+    Region::release_buffer(res_bytes) as usize
+}
+
+fn _do_ibc_client_update<M, E>(
+    update_fn: &dyn Fn(SudoCtx, M) -> Result<Response, E>,
+    ctx_bytes: &[u8],
+    header_bytes: &[u8],
+) -> GenericResult<Response>
+where
+    M: DeserializeOwned,
+    E: ToString,
+{
+    let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
+    let sudo_ctx = make_sudo_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
+    let header = unwrap_into_generic_result!(from_json_slice(header_bytes));
+
+    update_fn(sudo_ctx, header).into()
+}
+
+// -------------------------- ibc client misbehavior ---------------------------
+
+pub fn do_ibc_client_misbehavior<M, E>(
+    misbehavior_fn: &dyn Fn(SudoCtx, M) -> Result<Response, E>,
+    ctx_ptr: usize,
+    misbehavior_ptr: usize,
+) -> usize
+where
+    M: DeserializeOwned,
+    E: ToString,
+{
+    let ctx_bytes = unsafe { Region::consume(ctx_ptr as *mut Region) };
+    let misbehavior_bytes = unsafe { Region::consume(misbehavior_ptr as *mut Region) };
+
+    let res = _do_ibc_client_misbehavior(misbehavior_fn, &ctx_bytes, &misbehavior_bytes);
+    let res_bytes = to_json_vec(&res).unwrap();
+
+    Region::release_buffer(res_bytes) as usize
+}
+
+fn _do_ibc_client_misbehavior<M, E>(
+    misbehavior_fn: &dyn Fn(SudoCtx, M) -> Result<Response, E>,
+    ctx_bytes: &[u8],
+    misbehavior_bytes: &[u8],
+) -> GenericResult<Response>
+where
+    M: DeserializeOwned,
+    E: ToString,
+{
+    let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
+    let sudo_ctx = make_sudo_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
+    let misbehavior = unwrap_into_generic_result!(from_json_slice(misbehavior_bytes));
+
+    misbehavior_fn(sudo_ctx, misbehavior).into()
+}
+
 // ----------------------------- ibc client query ------------------------------
 
 pub fn do_ibc_client_query<E>(
-    verify_fn: &dyn Fn(ImmutableCtx, IbcClientQuery) -> Result<(), E>,
+    query_fn: &dyn Fn(ImmutableCtx, IbcClientQuery) -> Result<IbcClientQueryResponse, E>,
     ctx_ptr: usize,
     msg_ptr: usize,
 ) -> usize
@@ -452,17 +638,17 @@ where
     let ctx_bytes = unsafe { Region::consume(ctx_ptr as *mut Region) };
     let msg_bytes = unsafe { Region::consume(msg_ptr as *mut Region) };
 
-    let res = _do_ibc_client_query(verify_fn, &ctx_bytes, &msg_bytes);
+    let res = _do_ibc_client_query(query_fn, &ctx_bytes, &msg_bytes);
     let res_bytes = to_json_vec(&res).unwrap();
 
     Region::release_buffer(res_bytes) as usize
 }
 
 fn _do_ibc_client_query<E>(
-    verify_fn: &dyn Fn(ImmutableCtx, IbcClientQuery) -> Result<(), E>,
+    query_fn: &dyn Fn(ImmutableCtx, IbcClientQuery) -> Result<IbcClientQueryResponse, E>,
     ctx_bytes: &[u8],
     msg_bytes: &[u8],
-) -> GenericResult<()>
+) -> GenericResult<IbcClientQueryResponse>
 where
     E: ToString,
 {
@@ -470,5 +656,209 @@ where
     let immutable_ctx = make_immutable_ctx!(ctx, &ExternalStorage, &ExternalApi, &ExternalQuerier);
     let msg = unwrap_into_generic_result!(from_json_slice(msg_bytes));
 
-    verify_fn(immutable_ctx, msg).into()
+    query_fn(immutable_ctx, msg).into()
+}
+
+// ----------------------------- ibc channel open ------------------------------
+
+pub fn do_ibc_channel_open<E>(
+    open_fn: &dyn Fn(SudoCtx, IbcChannelOpenMsg) -> Result<Response, E>,
+    ctx_ptr: usize,
+    msg_ptr: usize,
+) -> usize
+where
+    E: ToString,
+{
+    let ctx_bytes = unsafe { Region::consume(ctx_ptr as *mut Region) };
+    let msg_bytes = unsafe { Region::consume(msg_ptr as *mut Region) };
+
+    let res = _do_ibc_channel_open(open_fn, &ctx_bytes, &msg_bytes);
+    let res_bytes = to_json_vec(&res).unwrap();
+
+    Region::release_buffer(res_bytes) as usize
+}
+
+fn _do_ibc_channel_open<E>(
+    open_fn: &dyn Fn(SudoCtx, IbcChannelOpenMsg) -> Result<Response, E>,
+    ctx_bytes: &[u8],
+    msg_bytes: &[u8],
+) -> GenericResult<Response>
+where
+    E: ToString,
+{
+    let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
+    let sudo_ctx = make_sudo_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
+    let msg = unwrap_into_generic_result!(from_json_slice(msg_bytes));
+
+    open_fn(sudo_ctx, msg).into()
+}
+
+// ---------------------------- ibc channel connect ----------------------------
+
+pub fn do_ibc_channel_connect<E>(
+    connect_fn: &dyn Fn(SudoCtx, IbcChannelConnectMsg) -> Result<Response, E>,
+    ctx_ptr: usize,
+    msg_ptr: usize,
+) -> usize
+where
+    E: ToString,
+{
+    let ctx_bytes = unsafe { Region::consume(ctx_ptr as *mut Region) };
+    let msg_bytes = unsafe { Region::consume(msg_ptr as *mut Region) };
+
+    let res = _do_ibc_channel_connect(connect_fn, &ctx_bytes, &msg_bytes);
+    let res_bytes = to_json_vec(&res).unwrap();
+
+    Region::release_buffer(res_bytes) as usize
+}
+
+fn _do_ibc_channel_connect<E>(
+    connect_fn: &dyn Fn(SudoCtx, IbcChannelConnectMsg) -> Result<Response, E>,
+    ctx_bytes: &[u8],
+    msg_bytes: &[u8],
+) -> GenericResult<Response>
+where
+    E: ToString,
+{
+    let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
+    let sudo_ctx = make_sudo_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
+    let msg = unwrap_into_generic_result!(from_json_slice(msg_bytes));
+
+    connect_fn(sudo_ctx, msg).into()
+}
+
+// ---------------------------- ibc packet receive -----------------------------
+
+pub fn do_ibc_packet_receive<E>(
+    receive_fn: &dyn Fn(SudoCtx, IbcPacket) -> Result<Response, E>,
+    ctx_ptr: usize,
+    packet_ptr: usize,
+) -> usize
+where
+    E: ToString,
+{
+    let ctx_bytes = unsafe { Region::consume(ctx_ptr as *mut Region) };
+    let packet_bytes = unsafe { Region::consume(packet_ptr as *mut Region) };
+
+    let res = _do_ibc_packet_receive(receive_fn, &ctx_bytes, &packet_bytes);
+    let res_bytes = to_json_vec(&res).unwrap();
+
+    Region::release_buffer(res_bytes) as usize
+}
+
+fn _do_ibc_packet_receive<E>(
+    receive_fn: &dyn Fn(SudoCtx, IbcPacket) -> Result<Response, E>,
+    ctx_bytes: &[u8],
+    packet_bytes: &[u8],
+) -> GenericResult<Response>
+where
+    E: ToString,
+{
+    let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
+    let sudo_ctx = make_sudo_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
+    let packet = unwrap_into_generic_result!(from_json_slice(packet_bytes));
+
+    receive_fn(sudo_ctx, packet).into()
+}
+
+// ------------------------------ ibc packet ack -------------------------------
+
+pub fn do_ibc_packet_ack<E>(
+    ack_fn: &dyn Fn(SudoCtx, IbcPacket, Binary) -> Result<Response, E>,
+    ctx_ptr: usize,
+    packet_ptr: usize,
+    ack_ptr: usize,
+) -> usize
+where
+    E: ToString,
+{
+    let ctx_bytes = unsafe { Region::consume(ctx_ptr as *mut Region) };
+    let packet_bytes = unsafe { Region::consume(packet_ptr as *mut Region) };
+    let ack_bytes = unsafe { Region::consume(ack_ptr as *mut Region) };
+
+    let res = _do_ibc_packet_ack(ack_fn, &ctx_bytes, &packet_bytes, &ack_bytes);
+    let res_bytes = to_json_vec(&res).unwrap();
+
+    Region::release_buffer(res_bytes) as usize
+}
+
+fn _do_ibc_packet_ack<E>(
+    ack_fn: &dyn Fn(SudoCtx, IbcPacket, Binary) -> Result<Response, E>,
+    ctx_bytes: &[u8],
+    packet_bytes: &[u8],
+    ack_bytes: &[u8],
+) -> GenericResult<Response>
+where
+    E: ToString,
+{
+    let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
+    let sudo_ctx = make_sudo_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
+    let packet = unwrap_into_generic_result!(from_json_slice(packet_bytes));
+    let ack = unwrap_into_generic_result!(from_json_slice(ack_bytes));
+
+    ack_fn(sudo_ctx, packet, ack).into()
+}
+
+// ---------------------------- ibc packet timeout -----------------------------
+
+pub fn do_ibc_packet_timeout<E>(
+    timeout_fn: &dyn Fn(SudoCtx, IbcPacket) -> Result<Response, E>,
+    ctx_ptr: usize,
+    packet_ptr: usize,
+) -> usize
+where
+    E: ToString,
+{
+    let ctx_bytes = unsafe { Region::consume(ctx_ptr as *mut Region) };
+    let packet_bytes = unsafe { Region::consume(packet_ptr as *mut Region) };
+
+    let res = _do_ibc_packet_timeout(timeout_fn, &ctx_bytes, &packet_bytes);
+    let res_bytes = to_json_vec(&res).unwrap();
+
+    Region::release_buffer(res_bytes) as usize
+}
+
+fn _do_ibc_packet_timeout<E>(
+    timeout_fn: &dyn Fn(SudoCtx, IbcPacket) -> Result<Response, E>,
+    ctx_bytes: &[u8],
+    packet_bytes: &[u8],
+) -> GenericResult<Response>
+where
+    E: ToString,
+{
+    let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
+    let sudo_ctx = make_sudo_ctx!(
+        ctx,
+        &mut ExternalStorage,
+        &ExternalApi,
+        &ExternalQuerier,
+        &ExternalCaller
+    );
+    let packet = unwrap_into_generic_result!(from_json_slice(packet_bytes));
+
+    timeout_fn(sudo_ctx, packet).into()
 }
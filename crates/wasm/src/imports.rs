@@ -1,8 +1,9 @@
 use {
     crate::Region,
     grug_types::{
-        encode_sections, from_json_slice, to_json_vec, Addr, Api, GenericResult, Order, Querier,
-        QueryRequest, QueryResponse, Record, StdError, StdResult, Storage,
+        decode_optional_values, encode_batch, encode_sections, from_json_slice, to_json_vec, Addr,
+        Api, Batch, Caller, Coins, ExecutionError, GenericResult, Json, MsgResponse, Order,
+        Querier, QueryRequest, QueryResponse, Record, StdError, StdResult, Storage,
     },
 };
 
@@ -16,11 +17,14 @@ extern "C" {
     // from the Wasm module's perspective, if a response is received, the DB op
     // must have succeeded.
     fn db_read(key_ptr: usize) -> usize;
+    fn db_read_many(keys_ptr: usize) -> usize;
     fn db_scan(min_ptr: usize, max_ptr: usize, order: i32) -> i32;
     fn db_next(iterator_id: i32) -> usize;
     fn db_next_key(iterator_id: i32) -> usize;
     fn db_next_value(iterator_id: i32) -> usize;
+    fn db_iterator_close(iterator_id: i32);
     fn db_write(key_ptr: usize, value_ptr: usize);
+    fn db_write_batch(batch_ptr: usize);
     fn db_remove(key_ptr: usize);
     fn db_remove_range(min_ptr: usize, max_ptr: usize);
 
@@ -55,6 +59,9 @@ extern "C" {
     // Send a query request to the chain.
     // Not to be confused with the `query` export.
     fn query_chain(req: usize) -> usize;
+
+    // Execute another contract synchronously.
+    fn call_contract(contract_ptr: usize, msg_ptr: usize, funds_ptr: usize) -> usize;
 }
 
 // ---------------------------------- storage ----------------------------------
@@ -147,6 +154,31 @@ impl Storage for ExternalStorage {
 
         unsafe { db_remove_range(min_ptr, max_ptr) }
     }
+
+    fn read_many(&self, keys: &[&[u8]]) -> Vec<Option<Vec<u8>>> {
+        let keys = encode_sections(keys).unwrap_or_else(|err| {
+            panic!("[ExternalStorage]: failed to encode keys: {err}");
+        });
+        let keys_region = Region::build(&keys);
+        let keys_ptr = &*keys_region as *const Region;
+
+        let values_ptr = unsafe { db_read_many(keys_ptr as usize) };
+        let values = unsafe { Region::consume(values_ptr as *mut Region) };
+
+        decode_optional_values(&values).unwrap_or_else(|err| {
+            panic!("[ExternalStorage]: failed to decode values: {err}");
+        })
+    }
+
+    fn write_batch(&mut self, batch: Batch) {
+        let batch = encode_batch(&batch).unwrap_or_else(|err| {
+            panic!("[ExternalStorage]: failed to encode batch: {err}");
+        });
+        let batch_region = Region::build(&batch);
+        let batch_ptr = &*batch_region as *const Region;
+
+        unsafe { db_write_batch(batch_ptr as usize) }
+    }
 }
 
 /// Iterator wrapper over the `db_next` import, which iterates over both the
@@ -170,6 +202,14 @@ impl Iterator for ExternalIterator {
     }
 }
 
+// Close the iterator on the host once it's dropped, so that it no longer
+// counts against the limit of open iterators.
+impl Drop for ExternalIterator {
+    fn drop(&mut self) {
+        unsafe { db_iterator_close(self.iterator_id) }
+    }
+}
+
 /// Iterator wrapper over either the `db_next_key` or `db_next_value` imports,
 /// which iterates over either only the raw keys, or only the raw values.
 pub struct ExternalPartialIterator {
@@ -200,6 +240,12 @@ impl Iterator for ExternalPartialIterator {
     }
 }
 
+impl Drop for ExternalPartialIterator {
+    fn drop(&mut self) {
+        unsafe { db_iterator_close(self.iterator_id) }
+    }
+}
+
 unsafe fn register_iterator(min: Option<&[u8]>, max: Option<&[u8]>, order: Order) -> i32 {
     // IMPORTANT: We must to keep the `Region`s in scope until end of the func.
     // Make sure to use `as_ref` so that the `Region`s don't get consumed.
@@ -440,9 +486,42 @@ impl Querier for ExternalQuerier {
 
         let res_ptr = unsafe { query_chain(req_ptr as usize) };
         let res_bytes = unsafe { Region::consume(res_ptr as *mut Region) };
-        let res: GenericResult<QueryResponse> = from_json_slice(res_bytes)?;
+        let res: GenericResult<QueryResponse, ExecutionError> = from_json_slice(res_bytes)?;
+
+        match res {
+            GenericResult::Ok(res) => Ok(res),
+            GenericResult::Err(err) => Err(err.into()),
+        }
+    }
+}
+
+// ----------------------------------- caller ----------------------------------
+
+/// A zero-size wrapper over the `call_contract` FFI function.
+pub struct ExternalCaller;
+
+impl Caller for ExternalCaller {
+    fn call_contract(&self, contract: Addr, msg: Json, funds: Coins) -> StdResult<MsgResponse> {
+        let contract_region = Region::build(&contract);
+        let contract_ptr = &*contract_region as *const Region;
+
+        let msg_bytes = to_json_vec(&msg)?;
+        let msg_region = Region::build(&msg_bytes);
+        let msg_ptr = &*msg_region as *const Region;
+
+        let funds_bytes = to_json_vec(&funds)?;
+        let funds_region = Region::build(&funds_bytes);
+        let funds_ptr = &*funds_region as *const Region;
+
+        let res_ptr =
+            unsafe { call_contract(contract_ptr as usize, msg_ptr as usize, funds_ptr as usize) };
+        let res_bytes = unsafe { Region::consume(res_ptr as *mut Region) };
+        let res: GenericResult<MsgResponse, ExecutionError> = from_json_slice(res_bytes)?;
 
-        res.into_std_result()
+        match res {
+            GenericResult::Ok(res) => Ok(res),
+            GenericResult::Err(err) => Err(err.into()),
+        }
     }
 }
 
@@ -3,13 +3,16 @@ use {
     anyhow::{bail, ensure},
     grug::{
         from_json_slice, from_json_value, hash, to_json_value, to_json_vec, AccountResponse, Addr,
-        Binary, Coin, Coins, Config, Hash, InfoResponse, Message, QueryRequest, QueryResponse, Tx,
-        WasmRawResponse,
+        Attribute, Binary, ClientResponse, Coin, Coins, Config, DenomMetadata, Event, Hash,
+        IbcChannelEnd, IbcConnectionEnd, InfoResponse, Message, QueryRequest, QueryResponse,
+        SimulateResponse, Tx, TypedEvent, WasmRawResponse,
     },
     grug_account::{QueryMsg, StateResponse},
-    grug_jmt::Proof,
+    grug_ics23::CommitmentProof,
+    grug_jmt::ICS23_PROOF_TYPE,
+    prost::Message as _,
     serde::{de::DeserializeOwned, ser::Serialize},
-    std::any::type_name,
+    std::collections::BTreeMap,
     tendermint::block::Height,
     tendermint_rpc::{
         endpoint::{abci_query::AbciQuery, block, block_results, broadcast::tx_sync, status, tx},
@@ -45,6 +48,39 @@ impl Client {
         Ok(self.inner.tx(hash_bytes.try_into()?, false).await?)
     }
 
+    /// Query a transaction by hash, and decode the data returned by each of its
+    /// messages, in the same order as the messages. Errors if the transaction
+    /// failed.
+    pub async fn tx_data(&self, hash_str: &str) -> anyhow::Result<Vec<Option<Binary>>> {
+        let res = self.tx(hash_str).await?;
+        ensure!(
+            res.tx_result.code.is_ok(),
+            "transaction failed! codespace = {}, code = {}, log = {}",
+            res.tx_result.codespace,
+            res.tx_result.code.value(),
+            res.tx_result.log
+        );
+        Ok(from_json_slice(res.tx_result.data)?)
+    }
+
+    /// Query a transaction by hash, and decode the events of the given type
+    /// emitted by contracts during it, in the order they were emitted.
+    pub async fn tx_events<E: TypedEvent>(&self, hash_str: &str) -> anyhow::Result<Vec<E>> {
+        let res = self.tx(hash_str).await?;
+        res.tx_result
+            .events
+            .into_iter()
+            .filter(|event| event.kind == E::EVENT_TYPE)
+            .map(|event| {
+                let attrs = event
+                    .attributes
+                    .into_iter()
+                    .map(|attr| Attribute::new(attr.key, attr.value));
+                Ok(Event::new(event.kind).add_attributes(attrs).decode()?)
+            })
+            .collect()
+    }
+
     pub async fn block(&self, height: Option<u64>) -> anyhow::Result<block::Response> {
         match height {
             Some(height) => Ok(self.inner.block(Height::try_from(height)?).await?),
@@ -92,7 +128,7 @@ impl Client {
         key: Vec<u8>,
         height: Option<u64>,
         prove: bool,
-    ) -> anyhow::Result<(Option<Vec<u8>>, Option<Proof>)> {
+    ) -> anyhow::Result<(Option<Vec<u8>>, Option<CommitmentProof>)> {
         let res = self.query("/store", key.clone(), height, prove).await?;
         let value = if res.value.is_empty() {
             None
@@ -103,9 +139,9 @@ impl Client {
             ensure!(res.proof.is_some());
             let proof = res.proof.unwrap();
             ensure!(proof.ops.len() == 1);
-            ensure!(proof.ops[0].field_type == type_name::<Proof>());
+            ensure!(proof.ops[0].field_type == ICS23_PROOF_TYPE);
             ensure!(proof.ops[0].key == key);
-            Some(from_json_slice(&proof.ops[0].data)?)
+            Some(CommitmentProof::decode(proof.ops[0].data.as_slice())?)
         } else {
             None
         };
@@ -179,6 +215,29 @@ impl Client {
         Ok(res.as_supplies())
     }
 
+    pub async fn query_metadata(
+        &self,
+        denom: String,
+        height: Option<u64>,
+    ) -> anyhow::Result<Option<DenomMetadata>> {
+        let res = self
+            .query_app(&QueryRequest::Metadata { denom }, height)
+            .await?;
+        Ok(res.as_metadata())
+    }
+
+    pub async fn query_metadatas(
+        &self,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        height: Option<u64>,
+    ) -> anyhow::Result<BTreeMap<String, DenomMetadata>> {
+        let res = self
+            .query_app(&QueryRequest::Metadatas { start_after, limit }, height)
+            .await?;
+        Ok(res.as_metadatas())
+    }
+
     pub async fn query_code(&self, hash: Hash, height: Option<u64>) -> anyhow::Result<Binary> {
         let res = self.query_app(&QueryRequest::Code { hash }, height).await?;
         Ok(res.as_code())
@@ -231,6 +290,28 @@ impl Client {
         Ok(res.as_wasm_raw())
     }
 
+    pub async fn query_wasm_scan(
+        &self,
+        contract: Addr,
+        min: Option<Binary>,
+        max: Option<Binary>,
+        limit: Option<u32>,
+        height: Option<u64>,
+    ) -> anyhow::Result<BTreeMap<Binary, Binary>> {
+        let res = self
+            .query_app(
+                &QueryRequest::WasmScan {
+                    contract,
+                    min,
+                    max,
+                    limit,
+                },
+                height,
+            )
+            .await?;
+        Ok(res.as_wasm_scan())
+    }
+
     pub async fn query_wasm_smart<M: Serialize, R: DeserializeOwned>(
         &self,
         contract: Addr,
@@ -244,29 +325,55 @@ impl Client {
         Ok(from_json_value(res.as_wasm_smart().data)?)
     }
 
-    // ------------------------------ tx methods -------------------------------
+    pub async fn query_client(
+        &self,
+        client_id: Addr,
+        height: Option<u64>,
+    ) -> anyhow::Result<ClientResponse> {
+        let res = self
+            .query_app(&QueryRequest::Client { client_id }, height)
+            .await?;
+        Ok(res.as_client())
+    }
 
-    /// Create, sign, and broadcast a transaction without confirmation.
-    ///
-    /// If you need the user to provide a confirmation (e.g. via CLI) before
-    /// broadcasting, use `send_tx_with_confirmation`.
-    pub async fn send_tx(
+    pub async fn query_connection(
         &self,
-        msgs: Vec<Message>,
-        sign_opts: &SigningOptions,
-    ) -> anyhow::Result<tx_sync::Response> {
-        let maybe_res = self
-            .send_tx_with_confirmation(msgs, sign_opts, |_| Ok(true))
+        connection_id: String,
+        height: Option<u64>,
+    ) -> anyhow::Result<IbcConnectionEnd> {
+        let res = self
+            .query_app(&QueryRequest::Connection { connection_id }, height)
             .await?;
-        Ok(maybe_res.unwrap())
+        Ok(res.as_connection())
     }
 
-    pub async fn send_tx_with_confirmation(
+    pub async fn query_channel(
+        &self,
+        port_id: Addr,
+        channel_id: String,
+        height: Option<u64>,
+    ) -> anyhow::Result<IbcChannelEnd> {
+        let res = self
+            .query_app(
+                &QueryRequest::Channel {
+                    port_id,
+                    channel_id,
+                },
+                height,
+            )
+            .await?;
+        Ok(res.as_channel())
+    }
+
+    // ------------------------------ tx methods -------------------------------
+
+    /// Create and sign a transaction, querying the chain ID and the sender's
+    /// sequence number from the chain if they aren't provided.
+    pub async fn create_and_sign_tx(
         &self,
         msgs: Vec<Message>,
         sign_opts: &SigningOptions,
-        confirm_fn: fn(&Tx) -> anyhow::Result<bool>,
-    ) -> anyhow::Result<Option<tx_sync::Response>> {
+    ) -> anyhow::Result<Tx> {
         let chain_id = match &sign_opts.chain_id {
             None => self.query_info(None).await?.chain_id,
             Some(id) => id.to_string(),
@@ -285,12 +392,45 @@ impl Client {
             Some(seq) => seq,
         };
 
-        let tx = sign_opts.signing_key.create_and_sign_tx(
+        sign_opts.signing_key.create_and_sign_tx(
             msgs,
             sign_opts.sender.clone(),
             &chain_id,
             sequence,
-        )?;
+        )
+    }
+
+    /// Simulate a transaction against the latest state, and return its result
+    /// along with a trace of the contract calls it made.
+    pub async fn simulate(&self, tx: &Tx) -> anyhow::Result<SimulateResponse> {
+        let res = self
+            .query("/simulate", to_json_vec(tx)?, None, false)
+            .await?;
+        Ok(from_json_slice(res.value)?)
+    }
+
+    /// Create, sign, and broadcast a transaction without confirmation.
+    ///
+    /// If you need the user to provide a confirmation (e.g. via CLI) before
+    /// broadcasting, use `send_tx_with_confirmation`.
+    pub async fn send_tx(
+        &self,
+        msgs: Vec<Message>,
+        sign_opts: &SigningOptions,
+    ) -> anyhow::Result<tx_sync::Response> {
+        let maybe_res = self
+            .send_tx_with_confirmation(msgs, sign_opts, |_| Ok(true))
+            .await?;
+        Ok(maybe_res.unwrap())
+    }
+
+    pub async fn send_tx_with_confirmation(
+        &self,
+        msgs: Vec<Message>,
+        sign_opts: &SigningOptions,
+        confirm_fn: fn(&Tx) -> anyhow::Result<bool>,
+    ) -> anyhow::Result<Option<tx_sync::Response>> {
+        let tx = self.create_and_sign_tx(msgs, sign_opts).await?;
 
         if confirm_fn(&tx)? {
             let tx_bytes = to_json_vec(&tx)?;